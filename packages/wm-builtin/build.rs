@@ -1,17 +1,149 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(feature = "build_zebar")]
-use std::process::Command;
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 
-/// Helper function to run a command via cmd.exe on Windows.
+#[cfg(feature = "build_zebar")]
+use anyhow::{bail, Context};
+
+/// Environment variable for overriding how long each step of the zebar
+/// build may take, in seconds. A value of 0 disables the timeout.
+#[cfg(feature = "build_zebar")]
+const BUILD_TIMEOUT_ENV: &str = "GLAZEWM_BUILD_TIMEOUT_SECS";
+
+#[cfg(feature = "build_zebar")]
+const DEFAULT_BUILD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Max number of output lines of a failed step to surface as warnings.
+#[cfg(feature = "build_zebar")]
+const MAX_ERROR_LINES: usize = 20;
+
+/// Helper function to create a command that runs via cmd.exe on Windows.
 /// This is necessary because pnpm is typically installed as a .cmd script,
 /// which cannot be executed directly by Command::new().
 #[cfg(feature = "build_zebar")]
-fn run_cmd(program: &str, args: &[&str], current_dir: &PathBuf) -> std::io::Result<std::process::ExitStatus> {
+fn cmd(program: &str, args: &[&str], current_dir: &Path) -> Command {
     let full_command = format!("{} {}", program, args.join(" "));
-    Command::new("cmd")
-        .current_dir(current_dir)
-        .args(["/C", &full_command])
-        .status()
+    let mut command = Command::new("cmd");
+    command.current_dir(current_dir).args(["/C", &full_command]);
+    command
+}
+
+/// Runs a command to completion, killing it if it exceeds the timeout.
+///
+/// The command's output is captured and forwarded to the build script's
+/// stderr. If the command fails, the error includes the tail of its
+/// stderr (or stdout, if nothing was written to stderr).
+#[cfg(feature = "build_zebar")]
+fn run_command(mut command: Command, timeout: Option<Duration>) -> anyhow::Result<()> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start command")?;
+
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let started_at = Instant::now();
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if let Some(timeout) = timeout.filter(|timeout| started_at.elapsed() >= *timeout) {
+            kill_process_tree(&mut child);
+            bail!(
+                "Timed out after {}s. Set {} to change the timeout.",
+                timeout.as_secs(),
+                BUILD_TIMEOUT_ENV
+            );
+        }
+
+        thread::sleep(Duration::from_millis(200));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    eprint!("{stdout}{stderr}");
+
+    if !status.success() {
+        let output = if stderr.trim().is_empty() { &stdout } else { &stderr };
+        bail!("Command failed with {}:\n{}", status, output_tail(output, MAX_ERROR_LINES));
+    }
+
+    Ok(())
+}
+
+/// Reads a child's output stream to the end on a separate thread, such
+/// that a full pipe never blocks the child.
+#[cfg(feature = "build_zebar")]
+fn read_in_background(stream: Option<impl Read + Send + 'static>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut buffer);
+        }
+
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// Kills a child and all of its descendants. Killing only the child would
+/// leave behind e.g. the node processes spawned via cmd.exe.
+#[cfg(feature = "build_zebar")]
+fn kill_process_tree(child: &mut Child) {
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Gets the last non-empty lines of a command's output.
+#[cfg(feature = "build_zebar")]
+fn output_tail(output: &str, max_lines: usize) -> String {
+    let lines = output.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(max_lines)..].join("\n")
+}
+
+/// Gets the timeout for each step of the zebar build.
+#[cfg(feature = "build_zebar")]
+fn build_timeout() -> Option<Duration> {
+    let Ok(value) = std::env::var(BUILD_TIMEOUT_ENV) else {
+        return Some(DEFAULT_BUILD_TIMEOUT);
+    };
+
+    match value.trim().parse::<u64>() {
+        Ok(0) => None,
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => {
+            warn(&format!(
+                "Invalid {}={:?}, using the default of {}s.",
+                BUILD_TIMEOUT_ENV,
+                value,
+                DEFAULT_BUILD_TIMEOUT.as_secs()
+            ));
+            Some(DEFAULT_BUILD_TIMEOUT)
+        }
+    }
+}
+
+/// Emits a message as cargo warnings, one per line.
+#[cfg(feature = "build_zebar")]
+fn warn(message: &str) {
+    for line in message.lines() {
+        println!("cargo:warning={}", line);
+    }
 }
 
 fn main() {
@@ -43,64 +175,38 @@ fn main() {
 }
 
 #[cfg(feature = "build_zebar")]
-fn build_zebar(zebar_dir: &PathBuf, out_dir: &PathBuf) {
-    use std::fs;
-
+fn build_zebar(zebar_dir: &Path, out_dir: &Path) {
     println!("cargo:rerun-if-changed={}", zebar_dir.join("packages/desktop/src").display());
     println!("cargo:rerun-if-changed={}", zebar_dir.join("packages/settings-ui/src").display());
+    println!("cargo:rerun-if-env-changed={}", BUILD_TIMEOUT_ENV);
+
+    if let Err(err) = try_build_zebar(zebar_dir, out_dir, build_timeout()) {
+        warn(&format!("{:#}", err));
+        warn("Falling back to a placeholder zebar.exe.");
+        create_placeholder(out_dir);
+    }
+}
+
+#[cfg(feature = "build_zebar")]
+fn try_build_zebar(zebar_dir: &Path, out_dir: &Path, timeout: Option<Duration>) -> anyhow::Result<()> {
+    use std::fs;
 
     let profile = std::env::var("PROFILE").unwrap_or_else(|_| "debug".to_string());
     let release_flag = if profile == "release" { "--release" } else { "" };
 
     // Install pnpm dependencies
     println!("cargo:warning=Installing pnpm dependencies for zebar...");
-    let pnpm_install = run_cmd("pnpm", &["install"], zebar_dir);
-
-    if let Err(e) = pnpm_install {
-        println!("cargo:warning=Failed to run pnpm install: {}. Make sure pnpm is installed.", e);
-        create_placeholder(out_dir);
-        return;
-    }
-
-    if !pnpm_install.unwrap().success() {
-        println!("cargo:warning=pnpm install failed");
-        create_placeholder(out_dir);
-        return;
-    }
+    run_command(cmd("pnpm", &["install"], zebar_dir), timeout).context("pnpm install failed")?;
 
     // Build the client-api first (required by settings-ui)
     println!("cargo:warning=Building zebar client-api...");
     let client_api_dir = zebar_dir.join("packages").join("client-api");
-    let client_api_build = run_cmd("pnpm", &["run", "build"], &client_api_dir);
-
-    if let Err(e) = client_api_build {
-        println!("cargo:warning=Failed to build client-api: {}", e);
-        create_placeholder(out_dir);
-        return;
-    }
-
-    if !client_api_build.unwrap().success() {
-        println!("cargo:warning=client-api build failed");
-        create_placeholder(out_dir);
-        return;
-    }
+    run_command(cmd("pnpm", &["run", "build"], &client_api_dir), timeout).context("client-api build failed")?;
 
     // Build the settings-ui
     println!("cargo:warning=Building zebar settings-ui...");
     let settings_ui_dir = zebar_dir.join("packages").join("settings-ui");
-    let ui_build = run_cmd("pnpm", &["run", "build"], &settings_ui_dir);
-
-    if let Err(e) = ui_build {
-        println!("cargo:warning=Failed to build settings-ui: {}", e);
-        create_placeholder(out_dir);
-        return;
-    }
-
-    if !ui_build.unwrap().success() {
-        println!("cargo:warning=settings-ui build failed");
-        create_placeholder(out_dir);
-        return;
-    }
+    run_command(cmd("pnpm", &["run", "build"], &settings_ui_dir), timeout).context("settings-ui build failed")?;
 
     // Build zebar using cargo
     println!("cargo:warning=Building zebar...");
@@ -120,39 +226,27 @@ fn build_zebar(zebar_dir: &PathBuf, out_dir: &PathBuf) {
         println!("cargo:warning=Note: Debug builds require the dev server running at http://localhost:4200");
     }
 
-    let cargo_build = Command::new("cargo")
-        .current_dir(&zebar_dir)
-        .args(&cargo_args)
-        .status();
+    let mut cargo_build = Command::new("cargo");
+    cargo_build.current_dir(zebar_dir).args(&cargo_args);
+    run_command(cargo_build, timeout).context("Cargo build of zebar failed")?;
 
-    match cargo_build {
-        Ok(status) if status.success() => {
-            // Copy the built binary
-            let target_dir = zebar_dir.join("target").join(&profile);
-            let zebar_exe = target_dir.join("zebar.exe");
-            let dest = out_dir.join("zebar.exe");
-
-            if zebar_exe.exists() {
-                fs::copy(&zebar_exe, &dest).expect("Failed to copy zebar.exe");
-                println!("cargo:warning=Successfully built and copied zebar.exe");
-            } else {
-                println!("cargo:warning=zebar.exe not found at {:?}", zebar_exe);
-                create_placeholder(out_dir);
-            }
-        }
-        Ok(status) => {
-            println!("cargo:warning=Cargo build failed with status: {}", status);
-            create_placeholder(out_dir);
-        }
-        Err(e) => {
-            println!("cargo:warning=Failed to run cargo build: {}", e);
-            create_placeholder(out_dir);
-        }
+    // Copy the built binary
+    let target_dir = zebar_dir.join("target").join(&profile);
+    let zebar_exe = target_dir.join("zebar.exe");
+    let dest = out_dir.join("zebar.exe");
+
+    if !zebar_exe.exists() {
+        bail!("zebar.exe not found at {:?}", zebar_exe);
     }
+
+    fs::copy(&zebar_exe, &dest).context("Failed to copy zebar.exe")?;
+    println!("cargo:warning=Successfully built and copied zebar.exe");
+
+    Ok(())
 }
 
 #[cfg(not(feature = "build_zebar"))]
-fn check_or_create_placeholder(zebar_dir: &PathBuf, out_dir: &PathBuf) {
+fn check_or_create_placeholder(zebar_dir: &Path, out_dir: &Path) {
     use std::fs;
 
     // Check for prebuilt binary in thirdparty/zebar/prebuilt/
@@ -169,7 +263,7 @@ fn check_or_create_placeholder(zebar_dir: &PathBuf, out_dir: &PathBuf) {
     }
 }
 
-fn create_placeholder(out_dir: &PathBuf) {
+fn create_placeholder(out_dir: &Path) {
     use std::fs;
     // Create an empty placeholder file
     let dest = out_dir.join("zebar.exe");
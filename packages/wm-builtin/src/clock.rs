@@ -0,0 +1,73 @@
+//! Time source abstraction for the process manager.
+
+use std::time::{Duration, Instant};
+
+/// Source of time used for scheduling restarts and timeouts.
+///
+/// Defaults to [`SystemClock`]. Tests can swap in a fake clock to advance
+/// time without sleeping.
+pub trait Clock: Send + Sync {
+    /// Get the current instant.
+    fn now(&self) -> Instant;
+
+    /// Block the current thread for the given duration.
+    fn sleep(&self, duration: Duration);
+
+    /// Get the instant at which a timer started now with the given delay
+    /// would fire.
+    fn deadline(&self, delay: Duration) -> Instant {
+        self.now() + delay
+    }
+
+    /// Check whether a deadline has been reached.
+    fn is_due(&self, deadline: Instant) -> bool {
+        self.now() >= deadline
+    }
+}
+
+/// Clock backed by the system's monotonic time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock that only moves forward when advanced manually.
+#[cfg(test)]
+pub(crate) struct FakeClock {
+    start: Instant,
+    elapsed: std::sync::Mutex<Duration>,
+}
+
+#[cfg(test)]
+impl FakeClock {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: std::sync::Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward by the given duration.
+    pub(crate) fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
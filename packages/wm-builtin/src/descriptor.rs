@@ -0,0 +1,134 @@
+//! Per-program settings for extracting and launching builtins.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{BuiltinError, BuiltinProgram};
+
+/// How the extracted executable of a builtin program is named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileNamePolicy {
+    /// Always use the configured file name as-is.
+    #[default]
+    Stable,
+    /// Stamp the file name with a hash of the embedded binary, so that a
+    /// new build never overwrites an executable that is still running.
+    Versioned,
+}
+
+/// Describes how a builtin program is extracted to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltinDescriptor {
+    pub program: BuiltinProgram,
+    file_name: String,
+    pub file_name_policy: FileNamePolicy,
+}
+
+impl BuiltinDescriptor {
+    /// Create a descriptor with the program's default file name.
+    pub fn new(program: BuiltinProgram) -> Self {
+        Self {
+            program,
+            file_name: program.exe_name().to_string(),
+            file_name_policy: FileNamePolicy::default(),
+        }
+    }
+
+    /// Use a custom file name for the extracted executable.
+    ///
+    /// Fails if the name is empty or contains path separators.
+    pub fn with_file_name(mut self, file_name: &str) -> Result<Self> {
+        validate_file_name(file_name)?;
+        self.file_name = file_name.to_string();
+        Ok(self)
+    }
+
+    /// Use the given policy for naming the extracted executable.
+    pub fn with_file_name_policy(mut self, policy: FileNamePolicy) -> Self {
+        self.file_name_policy = policy;
+        self
+    }
+
+    /// Get the configured file name, before applying the naming policy.
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Get the file name that the executable is extracted to.
+    pub fn resolved_file_name(&self) -> String {
+        match self.file_name_policy {
+            FileNamePolicy::Stable => self.file_name.clone(),
+            FileNamePolicy::Versioned => {
+                let stamp = content_hash(self.program.binary_data());
+                let path = Path::new(&self.file_name);
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(&self.file_name);
+
+                match path.extension().and_then(|e| e.to_str()) {
+                    Some(ext) => format!("{}-{:016x}.{}", stem, stamp, ext),
+                    None => format!("{}-{:016x}", stem, stamp),
+                }
+            }
+        }
+    }
+}
+
+/// Check that a file name refers to a single file in the builtin directory.
+fn validate_file_name(file_name: &str) -> Result<()> {
+    let is_valid = !file_name.is_empty()
+        && file_name != "."
+        && file_name != ".."
+        && !file_name.contains(['/', '\\', ':']);
+
+    if !is_valid {
+        return Err(BuiltinError::InvalidFileName(file_name.to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// FNV-1a hash of the binary, which is stable across builds of GlazeWM
+/// unlike `DefaultHasher`.
+pub(crate) fn content_hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_program_exe_name() {
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+        assert_eq!(descriptor.resolved_file_name(), "zebar.exe");
+    }
+
+    #[test]
+    fn rejects_names_with_path_separators() {
+        for name in ["", "..", "bin/zebar.exe", "..\\zebar.exe", "C:zebar.exe"] {
+            let err = BuiltinDescriptor::new(BuiltinProgram::Zebar)
+                .with_file_name(name)
+                .unwrap_err();
+
+            assert_eq!(
+                err.downcast_ref::<BuiltinError>(),
+                Some(&BuiltinError::InvalidFileName(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn versioned_name_keeps_extension() {
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar)
+            .with_file_name("bar.exe")
+            .unwrap()
+            .with_file_name_policy(FileNamePolicy::Versioned);
+
+        let name = descriptor.resolved_file_name();
+        assert!(name.starts_with("bar-"));
+        assert!(name.ends_with(".exe"));
+        assert_eq!(name.len(), "bar-.exe".len() + 16);
+    }
+}
@@ -1,24 +1,83 @@
 //! Embedded binary data and extraction utilities.
 
+use std::ffi::OsString;
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::{Context, Result};
-use tracing::info;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tracing::{info, warn};
+
+use crate::descriptor::content_hash;
+use crate::{BuiltinDescriptor, BuiltinError, Clock, SystemClock};
+
+/// Number of attempts at replacing an executable that is in use.
+const IN_USE_ATTEMPTS: u32 = 5;
+
+/// Delay between attempts at replacing an executable that is in use.
+/// Windows keeps the image of a process locked for a short while after
+/// it has exited.
+const IN_USE_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// OS error codes for a file that is locked by a running process.
+#[cfg(windows)]
+const IN_USE_ERROR_CODES: &[i32] = &[
+    32, // ERROR_SHARING_VIOLATION
+    33, // ERROR_LOCK_VIOLATION
+];
+
+#[cfg(not(windows))]
+const IN_USE_ERROR_CODES: &[i32] = &[
+    26, // ETXTBSY
+];
+
+/// Environment variable that overrides the directory where builtin
+/// binaries are extracted.
+pub const BUILTIN_DIR_ENV: &str = "GLAZEWM_BUILTIN_DIR";
+
+/// Minimum size of an embedded binary for it to be a real executable.
+/// Anything smaller is left over from a partial or broken build.
+const MIN_BINARY_SIZE: usize = 4096;
+
+/// Offset of the field in the DOS header that holds the offset of the PE
+/// header.
+const PE_HEADER_OFFSET_FIELD: usize = 0x3C;
 
 /// Embedded zebar binary data.
 /// This will be an empty file if zebar was not built.
 const ZEBAR_BINARY: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/zebar.exe"));
 
 /// List of available builtin programs.
+///
+/// Serialized as its lowercase name (e.g. `"zebar"`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BuiltinProgram {
     Zebar,
 }
 
+impl Serialize for BuiltinProgram {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for BuiltinProgram {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Self::from_str(&name)
+            .ok_or_else(|| serde::de::Error::custom(BuiltinError::UnknownBuiltin(name)))
+    }
+}
+
 impl BuiltinProgram {
+    /// All builtin programs, whether or not they were embedded.
+    pub const ALL: [Self; 1] = [Self::Zebar];
+
     /// Parse a builtin program name from a string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "zebar" => Some(Self::Zebar),
@@ -26,13 +85,27 @@ impl BuiltinProgram {
         }
     }
 
-    /// Get the executable name for this builtin program.
+    /// Get the name used to refer to this program in commands.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Zebar => "zebar",
+        }
+    }
+
+    /// Get the default executable name for this builtin program.
     pub fn exe_name(&self) -> &'static str {
         match self {
             Self::Zebar => "zebar.exe",
         }
     }
 
+    /// Get the Cargo feature that builds this program from source.
+    pub fn build_feature(&self) -> &'static str {
+        match self {
+            Self::Zebar => "build_zebar",
+        }
+    }
+
     /// Get the embedded binary data for this program.
     pub fn binary_data(&self) -> &'static [u8] {
         match self {
@@ -40,70 +113,700 @@ impl BuiltinProgram {
         }
     }
 
-    /// Check if this builtin program is available (was actually embedded).
+    /// Check if this builtin program is available (was actually embedded
+    /// and looks like a valid executable).
     pub fn is_available(&self) -> bool {
-        !self.binary_data().is_empty()
+        let data = self.binary_data();
+
+        match validate_binary(data) {
+            Ok(()) => true,
+            Err(err) => {
+                // An empty binary is the expected placeholder for programs
+                // that weren't built.
+                if !data.is_empty() {
+                    warn!("Embedded builtin {:?} is not a valid executable: {err}", self);
+                }
+
+                false
+            }
+        }
     }
 }
 
+/// Check that embedded binary data passes a minimal sanity check for
+/// being an executable, such that a corrupt build is never spawned.
+fn validate_binary(data: &[u8]) -> Result<()> {
+    if data.is_empty() {
+        bail!("No binary was embedded.");
+    }
+
+    if data.len() < MIN_BINARY_SIZE {
+        bail!("Binary is only {} bytes, expected at least {MIN_BINARY_SIZE}.", data.len());
+    }
+
+    if cfg!(windows) {
+        validate_pe_header(data)?;
+    }
+
+    Ok(())
+}
+
+/// Check for the `MZ` signature of the DOS header and the `PE` signature
+/// it points to.
+fn validate_pe_header(data: &[u8]) -> Result<()> {
+    if !data.starts_with(b"MZ") {
+        bail!("Binary is missing the MZ signature.");
+    }
+
+    let pe_offset = data
+        .get(PE_HEADER_OFFSET_FIELD..PE_HEADER_OFFSET_FIELD + 4)
+        .and_then(|field| field.try_into().ok())
+        .map(|field| u32::from_le_bytes(field) as usize)
+        .context("Binary is truncated within the DOS header.")?;
+
+    if data.get(pe_offset..pe_offset.saturating_add(4)) != Some(b"PE\0\0".as_slice()) {
+        bail!("Binary is missing the PE signature at offset {pe_offset:#x}.");
+    }
+
+    Ok(())
+}
+
 /// Get the directory where builtin binaries are extracted.
+///
+/// Defaults to `~/.glzr/glazewm/builtin`, and can be overridden via the
+/// `GLAZEWM_BUILTIN_DIR` environment variable.
 pub fn get_builtin_dir() -> Result<PathBuf> {
-    let home = home::home_dir().context("Unable to get home directory")?;
-    let builtin_dir = home.join(".glzr").join("glazewm").join("builtin");
+    let builtin_dir = resolve_builtin_dir(|name| std::env::var_os(name))?;
+
+    ensure_dir(&builtin_dir)?;
+    Ok(builtin_dir)
+}
 
-    if !builtin_dir.exists() {
-        fs::create_dir_all(&builtin_dir)
-            .context("Failed to create builtin directory")?;
+/// Resolve the builtin directory from the environment variables returned
+/// by `env`.
+///
+/// The home directory is resolved from `USERPROFILE` first, which is set
+/// for every Windows profile, followed by `HOMEDRIVE` + `HOMEPATH` and
+/// `HOME`. If none of them hold an absolute path, the directory is placed
+/// under `%APPDATA%` instead.
+///
+/// A relative `GLAZEWM_BUILTIN_DIR` is ignored with a warning, since it
+/// would depend on the working directory the WM was started from.
+fn resolve_builtin_dir(env: impl Fn(&str) -> Option<OsString>) -> Result<PathBuf> {
+    let env_path = |name: &str| env(name).map(PathBuf::from).filter(|path| path.is_absolute());
+
+    match env(BUILTIN_DIR_ENV).map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => return Ok(dir),
+        Some(dir) if !dir.as_os_str().is_empty() => {
+            warn!("Ignoring {BUILTIN_DIR_ENV} {:?}, since it isn't an absolute path.", dir);
+        }
+        _ => {}
     }
 
-    Ok(builtin_dir)
+    let home_drive_path = env("HOMEDRIVE").zip(env("HOMEPATH")).map(|(drive, path)| {
+        let mut home = drive;
+        home.push(path);
+        PathBuf::from(home)
+    });
+
+    let home = env_path("USERPROFILE")
+        .or(home_drive_path.filter(|path| path.is_absolute()))
+        .or_else(|| env_path("HOME"));
+
+    if let Some(home) = home {
+        return Ok(home.join(".glzr").join("glazewm").join("builtin"));
+    }
+
+    if let Some(app_data) = env_path("APPDATA") {
+        return Ok(app_data.join("glzr").join("glazewm").join("builtin"));
+    }
+
+    bail!(
+        "Unable to determine the home directory for builtin binaries. \
+         Set USERPROFILE, HOME or {BUILTIN_DIR_ENV} to an absolute path."
+    )
+}
+
+/// Create a directory and its parents, tolerating other processes
+/// creating it concurrently. Fails if the path exists but is not a
+/// directory.
+fn ensure_dir(dir: &Path) -> Result<()> {
+    match fs::create_dir_all(dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to create builtin directory {:?}", dir));
+        }
+    }
+
+    if !dir.is_dir() {
+        bail!("Builtin directory {:?} exists but is not a directory", dir);
+    }
+
+    Ok(())
 }
 
 /// Extract a builtin program to disk if needed.
 /// Returns the path to the extracted executable.
-pub fn extract_builtin(program: BuiltinProgram) -> Result<PathBuf> {
+pub fn extract_builtin(descriptor: &BuiltinDescriptor) -> Result<PathBuf> {
+    let program = descriptor.program;
+
+    // Check availability before creating the builtin directory.
     if !program.is_available() {
-        anyhow::bail!(
-            "Builtin program {:?} is not available. \
-            It may not have been built. Enable the 'build_zebar' feature \
-            or provide a prebuilt binary.",
-            program
-        );
+        return Err(unavailable_error(descriptor));
+    }
+
+    extract_data(descriptor, program.binary_data(), &get_builtin_dir()?)
+}
+
+/// Whether the executable at `exe_path` differs from the embedded binary
+/// of a builtin program, e.g. because the embedded binary was updated
+/// while a previously extracted one kept running.
+pub fn is_extraction_outdated(descriptor: &BuiltinDescriptor, exe_path: &Path) -> bool {
+    let program = descriptor.program;
+    program.is_available() && needs_extraction(exe_path, program.binary_data())
+}
+
+/// Extract the given binary data of a builtin program into `dir` if
+/// needed. Returns the path to the extracted executable.
+///
+/// Fails if `data` is empty, which is the placeholder used for programs
+/// that weren't built.
+pub fn extract_data(descriptor: &BuiltinDescriptor, data: &[u8], dir: &Path) -> Result<PathBuf> {
+    if data.is_empty() {
+        return Err(unavailable_error(descriptor));
+    }
+
+    let exe_path = dir.join(descriptor.resolved_file_name());
+
+    if extract_to(&exe_path, data)? {
+        info!("Successfully extracted builtin {:?} to {:?}", descriptor.program, exe_path);
+    }
+
+    Ok(exe_path)
+}
+
+/// Extract a builtin program to disk, replacing any previously extracted
+/// executable even if it appears to be up to date.
+/// Returns the path to the extracted executable.
+///
+/// Fails with [`BuiltinError::ExecutableInUse`] if the executable is
+/// locked by a process that is still running it.
+pub fn force_extract_builtin(descriptor: &BuiltinDescriptor) -> Result<PathBuf> {
+    let program = descriptor.program;
+
+    if !program.is_available() {
+        return Err(unavailable_error(descriptor));
     }
 
-    let builtin_dir = get_builtin_dir()?;
-    let exe_path = builtin_dir.join(program.exe_name());
+    force_extract_data(descriptor, program.binary_data(), &get_builtin_dir()?)
+}
+
+/// Extract the given binary data of a builtin program into `dir`,
+/// replacing any previously extracted executable.
+pub fn force_extract_data(descriptor: &BuiltinDescriptor, data: &[u8], dir: &Path) -> Result<PathBuf> {
+    if data.is_empty() {
+        return Err(unavailable_error(descriptor));
+    }
+
+    remove_extracted(&dir.join(descriptor.resolved_file_name()), &SystemClock)?;
+    extract_data(descriptor, data, dir)
+}
 
-    // Check if we need to extract (file doesn't exist or is different)
-    let needs_extraction = if exe_path.exists() {
-        // Compare file sizes first (quick check)
-        let existing_size = fs::metadata(&exe_path)
-            .map(|m| m.len())
-            .unwrap_or(0);
-        existing_size != program.binary_data().len() as u64
-    } else {
-        true
+/// Remove an extracted executable along with its marker.
+///
+/// The marker is removed first, such that the executable gets extracted
+/// again even if removing it is interrupted.
+fn remove_extracted(exe_path: &Path, clock: &dyn Clock) -> Result<()> {
+    let ignore_missing = |result: io::Result<()>| match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
     };
 
-    if needs_extraction {
-        info!("Extracting builtin {:?} to {:?}", program, exe_path);
+    ignore_missing(fs::remove_file(marker_path(exe_path)))
+        .context("Failed to remove builtin marker")?;
 
-        let mut file = fs::File::create(&exe_path)
-            .context("Failed to create builtin executable file")?;
+    retry_while_in_use(|| ignore_missing(fs::remove_file(exe_path)), clock).map_err(|err| {
+        if is_in_use(&err) {
+            BuiltinError::ExecutableInUse(exe_path.to_path_buf()).into()
+        } else {
+            anyhow::Error::new(err).context("Failed to remove builtin executable")
+        }
+    })
+}
+
+fn unavailable_error(descriptor: &BuiltinDescriptor) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Builtin program {:?} is not available. \
+        It may not have been built, or the build was incomplete. Enable the '{}' feature \
+        or provide a prebuilt binary.",
+        descriptor.program,
+        descriptor.program.build_feature()
+    )
+}
+
+/// Path of the sidecar marker that is written once an extraction has
+/// fully completed.
+fn marker_path(exe_path: &Path) -> PathBuf {
+    let mut path = exe_path.as_os_str().to_owned();
+    path.push(".meta");
+    PathBuf::from(path)
+}
+
+/// Contents of the marker for the given binary data.
+fn marker_contents(data: &[u8]) -> String {
+    format!("{} {:016x}\n", data.len(), content_hash(data))
+}
+
+/// Whether the executable at `exe_path` is missing, differs from `data`,
+/// or was left behind by an interrupted extraction.
+fn needs_extraction(exe_path: &Path, data: &[u8]) -> bool {
+    // The marker is written last, so its absence means the previous
+    // extraction never completed, even if the file size happens to match.
+    let marker_matches = fs::read_to_string(marker_path(exe_path))
+        .is_ok_and(|marker| marker == marker_contents(data));
 
-        file.write_all(program.binary_data())
-            .context("Failed to write builtin executable data")?;
+    let size_matches = fs::metadata(exe_path)
+        .is_ok_and(|metadata| metadata.len() == data.len() as u64);
 
-        file.flush()?;
+    !(marker_matches && size_matches)
+}
 
-        info!("Successfully extracted builtin {:?}", program);
+/// Extract `data` to `exe_path` if needed. Returns whether the file was
+/// (re-)extracted.
+///
+/// The binary is written to a temporary file and renamed into place, so
+/// the executable is never partially written. The marker is removed
+/// beforehand and written after the rename.
+fn extract_to(exe_path: &Path, data: &[u8]) -> Result<bool> {
+    if !needs_extraction(exe_path, data) {
+        return Ok(false);
     }
 
-    Ok(exe_path)
+    info!("Extracting builtin to {:?}", exe_path);
+
+    let marker_path = marker_path(exe_path);
+    if marker_path.exists() {
+        fs::remove_file(&marker_path)
+            .context("Failed to remove stale builtin marker")?;
+    }
+
+    let mut temp_path = exe_path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = fs::File::create(&temp_path)
+        .context("Failed to create builtin executable file")?;
+
+    file.write_all(data)
+        .context("Failed to write builtin executable data")?;
+
+    file.sync_all()
+        .context("Failed to flush builtin executable data")?;
+
+    drop(file);
+
+    if let Err(err) = replace_executable(&temp_path, exe_path, &SystemClock) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    let mut marker = fs::File::create(&marker_path)
+        .context("Failed to create builtin marker")?;
+
+    marker.write_all(marker_contents(data).as_bytes())
+        .context("Failed to write builtin marker")?;
+
+    marker.sync_all()
+        .context("Failed to flush builtin marker")?;
+
+    Ok(true)
+}
+
+/// Move the extracted binary at `temp_path` into place at `exe_path`.
+///
+/// Retries for a short while if the executable is locked by a running
+/// process, and fails with [`BuiltinError::ExecutableInUse`] if it stays
+/// locked.
+fn replace_executable(temp_path: &Path, exe_path: &Path, clock: &dyn Clock) -> Result<()> {
+    retry_while_in_use(|| fs::rename(temp_path, exe_path), clock).map_err(|err| {
+        if is_in_use(&err) {
+            BuiltinError::ExecutableInUse(exe_path.to_path_buf()).into()
+        } else {
+            anyhow::Error::new(err).context("Failed to move builtin executable into place")
+        }
+    })
+}
+
+/// Run `op`, retrying up to [`IN_USE_ATTEMPTS`] times while it fails
+/// because a file is in use.
+fn retry_while_in_use<T>(mut op: impl FnMut() -> io::Result<T>, clock: &dyn Clock) -> io::Result<T> {
+    let mut attempt = 1;
+
+    loop {
+        match op() {
+            Err(err) if is_in_use(&err) && attempt < IN_USE_ATTEMPTS => {
+                attempt += 1;
+                clock.sleep(IN_USE_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether an IO error was caused by a file being locked by a running
+/// process.
+fn is_in_use(err: &io::Error) -> bool {
+    err.raw_os_error()
+        .is_some_and(|code| IN_USE_ERROR_CODES.contains(&code))
 }
 
 /// Get all available builtin programs.
 pub fn available_builtins() -> Vec<BuiltinProgram> {
-    let all = [BuiltinProgram::Zebar];
-    all.into_iter().filter(|p| p.is_available()).collect()
+    BuiltinProgram::ALL
+        .into_iter()
+        .filter(|p| p.is_available())
+        .collect()
+}
+
+/// Log a warning for each builtin program that was left out of the
+/// build, such that it's clear why it's missing before it's started.
+///
+/// Meant to be called once at startup.
+pub fn warn_unavailable_builtins() {
+    let available = available_builtins();
+
+    // Embedded binaries that are invalid rather than empty are already
+    // warned about when checking their availability.
+    let placeholders = BuiltinProgram::ALL
+        .into_iter()
+        .filter(|program| !available.contains(program) && program.binary_data().is_empty());
+
+    for program in placeholders {
+        warn!("{}", placeholder_warning(program));
+    }
+}
+
+fn placeholder_warning(program: BuiltinProgram) -> String {
+    format!(
+        "{} builtin unavailable; built without {} and no prebuilt provided",
+        program.name(),
+        program.build_feature()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeClock;
+
+    /// Creates an empty directory that is unique to the given test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("wm-builtin-{}-{}", std::process::id(), name));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extracts_once_and_writes_marker() {
+        let exe_path = test_dir("extracts_once").join("zebar.exe");
+
+        assert!(extract_to(&exe_path, b"binary").unwrap());
+        assert_eq!(fs::read(&exe_path).unwrap(), b"binary");
+        assert!(marker_path(&exe_path).exists());
+
+        assert!(!extract_to(&exe_path, b"binary").unwrap());
+    }
+
+    #[test]
+    fn reextracts_interrupted_write_of_same_size() {
+        let exe_path = test_dir("interrupted").join("zebar.exe");
+
+        // Simulate an interrupted write that left a file of the correct
+        // size but no marker.
+        fs::write(&exe_path, b"\0\0\0\0\0\0").unwrap();
+        assert!(needs_extraction(&exe_path, b"binary"));
+
+        assert!(extract_to(&exe_path, b"binary").unwrap());
+        assert_eq!(fs::read(&exe_path).unwrap(), b"binary");
+    }
+
+    #[test]
+    fn reextracts_same_size_with_different_content() {
+        let exe_path = test_dir("changed").join("zebar.exe");
+
+        extract_to(&exe_path, b"binary").unwrap();
+        assert!(extract_to(&exe_path, b"BINARY").unwrap());
+        assert_eq!(fs::read(&exe_path).unwrap(), b"BINARY");
+    }
+
+    #[test]
+    fn extract_data_skips_matching_file() {
+        let dir = test_dir("skips_matching");
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+
+        let exe_path = extract_data(&descriptor, b"binary", &dir).unwrap();
+        assert_eq!(exe_path, dir.join("zebar.exe"));
+        let modified = fs::metadata(&exe_path).unwrap().modified().unwrap();
+
+        assert_eq!(extract_data(&descriptor, b"binary", &dir).unwrap(), exe_path);
+        assert_eq!(fs::metadata(&exe_path).unwrap().modified().unwrap(), modified);
+    }
+
+    #[test]
+    fn extract_data_reextracts_on_size_change() {
+        let dir = test_dir("size_change");
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+
+        extract_data(&descriptor, b"binary", &dir).unwrap();
+        let exe_path = extract_data(&descriptor, b"longer binary", &dir).unwrap();
+
+        assert_eq!(fs::read(exe_path).unwrap(), b"longer binary");
+    }
+
+    #[test]
+    fn force_extract_data_replaces_matching_file() {
+        let dir = test_dir("force_extract");
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+
+        let exe_path = extract_data(&descriptor, b"binary", &dir).unwrap();
+
+        // Simulate corruption that leaves the size and marker intact.
+        fs::write(&exe_path, b"BINARY").unwrap();
+        assert!(!needs_extraction(&exe_path, b"binary"));
+
+        assert_eq!(force_extract_data(&descriptor, b"binary", &dir).unwrap(), exe_path);
+        assert_eq!(fs::read(&exe_path).unwrap(), b"binary");
+        assert!(marker_path(&exe_path).exists());
+    }
+
+    #[test]
+    fn force_extract_data_without_previous_extraction() {
+        let dir = test_dir("force_extract_fresh");
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+
+        let exe_path = force_extract_data(&descriptor, b"binary", &dir).unwrap();
+        assert_eq!(fs::read(exe_path).unwrap(), b"binary");
+    }
+
+    #[test]
+    fn program_round_trips_as_name() {
+        let json = serde_json::to_string(&BuiltinProgram::Zebar).unwrap();
+        assert_eq!(json, "\"zebar\"");
+
+        let program: BuiltinProgram = serde_json::from_str(&json).unwrap();
+        assert_eq!(program, BuiltinProgram::Zebar);
+    }
+
+    #[test]
+    fn program_deserializes_case_insensitively() {
+        let program: BuiltinProgram = serde_json::from_str("\"Zebar\"").unwrap();
+        assert_eq!(program, BuiltinProgram::Zebar);
+    }
+
+    #[test]
+    fn program_rejects_unknown_name() {
+        let err = serde_json::from_str::<BuiltinProgram>("\"polybar\"").unwrap_err();
+        assert!(err.to_string().contains("Unknown builtin program 'polybar'"));
+    }
+
+    fn in_use_error() -> io::Error {
+        io::Error::from_raw_os_error(IN_USE_ERROR_CODES[0])
+    }
+
+    #[test]
+    fn retries_while_file_is_in_use() {
+        let clock = FakeClock::new();
+        let started_at = clock.now();
+        let mut failures = 2;
+
+        let result = retry_while_in_use(
+            || {
+                if failures > 0 {
+                    failures -= 1;
+                    Err(in_use_error())
+                } else {
+                    Ok(())
+                }
+            },
+            &clock,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(clock.now() - started_at, IN_USE_RETRY_DELAY * 2);
+    }
+
+    #[test]
+    fn gives_up_on_file_that_stays_in_use() {
+        let clock = FakeClock::new();
+        let mut attempts = 0;
+
+        let result = retry_while_in_use::<()>(
+            || {
+                attempts += 1;
+                Err(in_use_error())
+            },
+            &clock,
+        );
+
+        assert!(is_in_use(&result.unwrap_err()));
+        assert_eq!(attempts, IN_USE_ATTEMPTS);
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let clock = FakeClock::new();
+        let mut attempts = 0;
+
+        let result = retry_while_in_use::<()>(
+            || {
+                attempts += 1;
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            },
+            &clock,
+        );
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn ensure_dir_creates_nested_and_tolerates_existing() {
+        let dir = test_dir("ensure-dir").join("a").join("b");
+
+        ensure_dir(&dir).unwrap();
+        ensure_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+    }
+
+    #[test]
+    fn ensure_dir_rejects_file_at_path() {
+        let path = test_dir("ensure-dir-file").join("builtin");
+        fs::write(&path, b"").unwrap();
+
+        let err = ensure_dir(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("builtin"));
+    }
+
+    fn pe_binary(pe_offset: u32) -> Vec<u8> {
+        let mut data = vec![0; MIN_BINARY_SIZE];
+        data[..2].copy_from_slice(b"MZ");
+        data[PE_HEADER_OFFSET_FIELD..PE_HEADER_OFFSET_FIELD + 4].copy_from_slice(&pe_offset.to_le_bytes());
+        data[pe_offset as usize..pe_offset as usize + 4].copy_from_slice(b"PE\0\0");
+        data
+    }
+
+    #[test]
+    fn accepts_valid_pe_header() {
+        assert!(validate_pe_header(&pe_binary(0x80)).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_pe_headers() {
+        let mut missing_mz = pe_binary(0x80);
+        missing_mz[0] = 0;
+        assert!(validate_pe_header(&missing_mz).is_err());
+
+        let mut missing_pe = pe_binary(0x80);
+        missing_pe[0x80] = 0;
+        assert!(validate_pe_header(&missing_pe).is_err());
+
+        // PE header offset pointing past the end of the binary.
+        let mut out_of_bounds = pe_binary(0x80);
+        out_of_bounds[PE_HEADER_OFFSET_FIELD..PE_HEADER_OFFSET_FIELD + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(validate_pe_header(&out_of_bounds).is_err());
+
+        assert!(validate_pe_header(b"MZ").is_err());
+    }
+
+    #[test]
+    fn rejects_empty_and_truncated_binaries() {
+        assert!(validate_binary(b"").is_err());
+
+        let truncated = &pe_binary(0x80)[..MIN_BINARY_SIZE - 1];
+        assert!(validate_binary(truncated).unwrap_err().to_string().contains("only"));
+
+        assert!(validate_binary(&pe_binary(0x80)).is_ok());
+    }
+
+    #[test]
+    fn placeholder_warning_names_build_feature() {
+        assert_eq!(
+            placeholder_warning(BuiltinProgram::Zebar),
+            "zebar builtin unavailable; built without build_zebar and no prebuilt provided"
+        );
+    }
+
+    #[test]
+    fn extract_data_rejects_placeholder() {
+        let dir = test_dir("placeholder");
+        let descriptor = BuiltinDescriptor::new(BuiltinProgram::Zebar);
+
+        let err = extract_data(&descriptor, b"", &dir).unwrap_err();
+        assert!(err.to_string().contains("not available"));
+        assert!(!dir.join("zebar.exe").exists());
+    }
+
+    /// Creates an environment lookup from the given variables.
+    fn fake_env(vars: &[(&str, &Path)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.as_os_str().to_owned()))
+            .collect::<Vec<_>>();
+
+        move |name| vars.iter().find(|(var, _)| var == name).map(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn builtin_dir_respects_env_override() {
+        let home = test_dir("home_override");
+        let override_dir = home.join("custom");
+
+        let env = fake_env(&[("USERPROFILE", &home), (BUILTIN_DIR_ENV, &override_dir)]);
+        assert_eq!(resolve_builtin_dir(env).unwrap(), override_dir);
+    }
+
+    #[test]
+    fn builtin_dir_prefers_user_profile() {
+        let profile = test_dir("user_profile");
+        let home = test_dir("home");
+
+        let env = fake_env(&[("HOME", &home), ("USERPROFILE", &profile)]);
+        assert_eq!(
+            resolve_builtin_dir(env).unwrap(),
+            profile.join(".glzr").join("glazewm").join("builtin")
+        );
+    }
+
+    #[test]
+    fn builtin_dir_skips_relative_homes() {
+        let app_data = test_dir("app_data");
+
+        let env = fake_env(&[
+            ("USERPROFILE", Path::new("")),
+            ("HOME", Path::new("relative")),
+            ("APPDATA", &app_data),
+        ]);
+        assert_eq!(
+            resolve_builtin_dir(env).unwrap(),
+            app_data.join("glzr").join("glazewm").join("builtin")
+        );
+
+        let err = resolve_builtin_dir(fake_env(&[])).unwrap_err();
+        assert!(err.to_string().contains(BUILTIN_DIR_ENV));
+    }
+
+    #[test]
+    fn builtin_dir_falls_back_from_relative_override() {
+        let home = test_dir("home_relative_override");
+
+        let env = fake_env(&[("USERPROFILE", &home), (BUILTIN_DIR_ENV, Path::new("builtin"))]);
+        assert_eq!(
+            resolve_builtin_dir(env).unwrap(),
+            home.join(".glzr").join("glazewm").join("builtin")
+        );
+    }
 }
@@ -0,0 +1,47 @@
+//! Error types for builtin programs.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::BuiltinProgram;
+
+/// Errors that can occur when managing builtin programs by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuiltinError {
+    /// The given name does not match any builtin program.
+    UnknownBuiltin(String),
+    /// The file name for an extracted builtin is not a plain file name.
+    InvalidFileName(String),
+    /// The extracted executable is locked by a process that is still
+    /// running it, so it can't be replaced with the embedded binary.
+    ExecutableInUse(PathBuf),
+}
+
+impl fmt::Display for BuiltinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownBuiltin(name) => {
+                let known = BuiltinProgram::ALL
+                    .iter()
+                    .map(BuiltinProgram::name)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "Unknown builtin program '{}'. Expected one of: {}.", name, known)
+            }
+            Self::InvalidFileName(name) => {
+                write!(f, "Invalid builtin file name '{}'. It must not contain path separators.", name)
+            }
+            Self::ExecutableInUse(path) => {
+                write!(
+                    f,
+                    "Builtin executable {:?} is in use and can't be updated. \
+                    Close any running instance of it (e.g. one started outside of GlazeWM) and try again.",
+                    path
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuiltinError {}
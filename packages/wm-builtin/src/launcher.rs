@@ -0,0 +1,181 @@
+//! Process spawning abstraction for the process manager.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use anyhow::{Context, Result};
+
+use crate::{extract_builtin, is_extraction_outdated, BuiltinDescriptor};
+
+/// How the window of a builtin program is shown when it's launched.
+///
+/// Maps to `STARTUPINFO.wShowWindow` on Windows, which the program applies
+/// the first time it shows its window. Has no effect on other platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShowState {
+    /// Start with the window hidden (e.g. to the tray).
+    Hide,
+    /// Start with the window minimized, without activating it.
+    ShowMinimized,
+    /// Start with the window shown as the program decides.
+    #[default]
+    ShowNormal,
+}
+
+impl ShowState {
+    /// Get the `SW_*` value to set as `STARTUPINFO.wShowWindow`.
+    #[cfg(windows)]
+    fn show_window_cmd(self) -> u16 {
+        use windows::Win32::UI::WindowsAndMessaging::{SW_HIDE, SW_SHOWMINNOACTIVE, SW_SHOWNORMAL};
+
+        let cmd = match self {
+            Self::Hide => SW_HIDE,
+            Self::ShowMinimized => SW_SHOWMINNOACTIVE,
+            Self::ShowNormal => SW_SHOWNORMAL,
+        };
+
+        cmd.0 as u16
+    }
+}
+
+/// Spawns the processes of builtin programs.
+///
+/// Defaults to [`EmbeddedLauncher`]. Tests can swap in a launcher that
+/// spawns a stand-in process instead of the embedded binary.
+pub trait Launcher: Send + Sync {
+    /// Spawn a builtin program with its window shown as given by
+    /// `show_state`. Returns the child process along with the path of the
+    /// executable that was started.
+    fn launch(&self, descriptor: &BuiltinDescriptor, show_state: ShowState) -> Result<(Child, PathBuf)>;
+
+    /// Whether the executable that a running program was started from
+    /// differs from the one it would be launched from now. The program
+    /// then has to be stopped before its executable can be replaced.
+    fn is_outdated(&self, descriptor: &BuiltinDescriptor, exe_path: &Path) -> bool;
+}
+
+/// Launcher that extracts the embedded binary of a program and runs it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddedLauncher;
+
+impl Launcher for EmbeddedLauncher {
+    fn launch(&self, descriptor: &BuiltinDescriptor, show_state: ShowState) -> Result<(Child, PathBuf)> {
+        let exe_path = extract_builtin(descriptor)?;
+        let mut command = Command::new(&exe_path);
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.show_window(show_state.show_window_cmd());
+        }
+
+        #[cfg(not(windows))]
+        let _ = show_state;
+
+        let child = command
+            .spawn()
+            .with_context(|| format!("Failed to start builtin {:?}", descriptor.program))?;
+
+        Ok((child, exe_path))
+    }
+
+    fn is_outdated(&self, descriptor: &BuiltinDescriptor, exe_path: &Path) -> bool {
+        is_extraction_outdated(descriptor, exe_path)
+    }
+}
+
+/// Stand-in process that tests run in place of a builtin program.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestBuiltin {
+    /// Keeps running until it's stopped.
+    LongRunning,
+    /// Exits right away with a non-zero exit code.
+    Crashing,
+    /// Ignores requests to exit, and keeps running until it's forcibly
+    /// terminated.
+    IgnoringStop,
+}
+
+#[cfg(test)]
+impl TestBuiltin {
+    /// Get the shell and the arguments that run this stand-in.
+    #[cfg(unix)]
+    fn command(self) -> (&'static str, [&'static str; 2]) {
+        match self {
+            Self::LongRunning => ("sh", ["-c", "exec sleep 30"]),
+            Self::Crashing => ("sh", ["-c", "exit 1"]),
+            Self::IgnoringStop => ("sh", ["-c", "trap '' TERM; while :; do sleep 0.05; done"]),
+        }
+    }
+
+    /// Get the shell and the arguments that run this stand-in.
+    #[cfg(windows)]
+    fn command(self) -> (&'static str, [&'static str; 2]) {
+        match self {
+            // `timeout` needs an interactive console, so wait via `ping`.
+            Self::LongRunning => ("cmd", ["/C", "ping -n 31 127.0.0.1 >nul"]),
+            Self::Crashing => ("cmd", ["/C", "exit 1"]),
+            // Has no windows, so it can't be asked to close.
+            Self::IgnoringStop => ("cmd", ["/C", "ping -n 31 127.0.0.1 >nul"]),
+        }
+    }
+
+    /// Get the path that the stand-in's process is started from.
+    pub(crate) fn exe_path(self) -> PathBuf {
+        PathBuf::from(self.command().0)
+    }
+}
+
+/// Launcher that runs a [`TestBuiltin`] in place of the builtin program.
+#[cfg(test)]
+pub(crate) struct TestLauncher {
+    builtin: TestBuiltin,
+    launches: std::sync::atomic::AtomicUsize,
+    show_states: std::sync::Mutex<Vec<ShowState>>,
+    is_outdated: std::sync::atomic::AtomicBool,
+}
+
+#[cfg(test)]
+impl TestLauncher {
+    pub(crate) fn new(builtin: TestBuiltin) -> Self {
+        Self {
+            builtin,
+            launches: std::sync::atomic::AtomicUsize::new(0),
+            show_states: std::sync::Mutex::new(Vec::new()),
+            is_outdated: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Set whether running processes are reported as started from an
+    /// outdated executable.
+    pub(crate) fn set_outdated(&self, is_outdated: bool) {
+        self.is_outdated.store(is_outdated, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Number of processes spawned so far.
+    pub(crate) fn launches(&self) -> usize {
+        self.launches.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Show states that processes were spawned with so far.
+    pub(crate) fn show_states(&self) -> Vec<ShowState> {
+        self.show_states.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl Launcher for TestLauncher {
+    fn launch(&self, _descriptor: &BuiltinDescriptor, show_state: ShowState) -> Result<(Child, PathBuf)> {
+        let (shell, args) = self.builtin.command();
+        let child = Command::new(shell).args(args).spawn()?;
+        self.launches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.show_states.lock().unwrap().push(show_state);
+
+        Ok((child, self.builtin.exe_path()))
+    }
+
+    fn is_outdated(&self, _descriptor: &BuiltinDescriptor, _exe_path: &Path) -> bool {
+        self.is_outdated.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
@@ -3,8 +3,22 @@
 //! This module provides functionality to embed and manage builtin binaries
 //! (like zebar) within the GlazeWM executable.
 
+#![cfg_attr(windows, feature(windows_process_extensions_show_window))]
+
+mod clock;
+mod descriptor;
 mod embedded;
+mod error;
+mod launcher;
 mod process_manager;
+#[cfg(any(windows, test))]
+mod process_tree;
+mod stop;
 
+pub use clock::*;
+pub use descriptor::*;
 pub use embedded::*;
+pub use error::*;
+pub use launcher::*;
 pub use process_manager::*;
+pub use stop::*;
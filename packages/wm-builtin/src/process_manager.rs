@@ -1,134 +1,667 @@
 //! Process management for builtin programs.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::process::Child;
+use std::process::{Child, ExitStatus};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Result};
 use tracing::{error, info, warn};
 
-use crate::{extract_builtin, BuiltinProgram};
+use crate::{
+    available_builtins, force_extract_builtin, stop_child, BuiltinDescriptor, BuiltinError, BuiltinProgram, Clock,
+    EmbeddedLauncher, Launcher, ShowState, StopOutcome, SystemClock,
+};
+
+/// Max time to wait for a terminated program to be reaped when stopped.
+const DEFAULT_REAP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Max time a program is given to stop when the process manager is
+/// dropped, unless configured otherwise via
+/// [`ProcessManager::set_stop_timeout`].
+const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Global process manager for builtin programs.
 static PROCESS_MANAGER: std::sync::OnceLock<Arc<Mutex<ProcessManager>>> =
     std::sync::OnceLock::new();
 
 /// Get the global process manager instance.
+///
+/// This backs the free functions in this module (e.g. [`start_builtin`]).
+/// Callers that need an isolated instance can construct their own
+/// [`ProcessManager`] instead.
 pub fn process_manager() -> Arc<Mutex<ProcessManager>> {
     PROCESS_MANAGER
         .get_or_init(|| Arc::new(Mutex::new(ProcessManager::new())))
         .clone()
 }
 
+/// Current state of a builtin program.
+///
+/// Sent to IPC clients as `wm_common::BuiltinStatusData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltinStatus {
+    pub program: BuiltinProgram,
+    /// Whether the program was embedded in this build.
+    pub available: bool,
+    pub running: bool,
+    /// PID of the running process, if any.
+    pub pid: Option<u32>,
+    /// Path of the executable the running process was started from, if
+    /// any. Differs from the default location when an override is used.
+    pub exe_path: Option<PathBuf>,
+    /// Time remaining until a scheduled restart, if any.
+    pub restart_in: Option<Duration>,
+}
+
+/// Details of a running builtin program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessReport {
+    pub program: BuiltinProgram,
+    pub pid: u32,
+    /// Path of the executable that was started.
+    pub exe_path: PathBuf,
+    /// Time since the program was started.
+    pub uptime: Duration,
+    /// Number of consecutive restarts after unexpected exits that led up
+    /// to this run.
+    pub restarts: u32,
+}
+
+/// Policy for restarting builtin programs that exit unexpectedly.
+///
+/// The delay before a restart doubles with each consecutive failure, up to
+/// `max_delay`. A program that stays up for at least `stable_uptime` has
+/// its failure count reset.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Delay before the first restart attempt.
+    pub initial_delay: Duration,
+    /// Upper bound on the delay between restart attempts.
+    pub max_delay: Duration,
+    /// Uptime after which a program is considered stable.
+    pub stable_uptime: Duration,
+}
+
+impl RestartPolicy {
+    /// Get the delay before restarting after the given number of
+    /// consecutive failures.
+    pub fn delay_for(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(1).min(31);
+        self.initial_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            stable_uptime: Duration::from_secs(60),
+        }
+    }
+}
+
 /// Manages running builtin processes.
 pub struct ProcessManager {
     /// Map of running processes by program type.
     processes: HashMap<BuiltinProgram, ChildProcess>,
+    /// Restarts scheduled for programs that exited unexpectedly.
+    pending_restarts: HashMap<BuiltinProgram, PendingRestart>,
+    /// Programs whose process is being spawned.
+    starting: HashSet<BuiltinProgram>,
+    /// Extraction settings for programs that don't use the defaults.
+    descriptors: HashMap<BuiltinProgram, BuiltinDescriptor>,
+    restart_policy: RestartPolicy,
+    /// Time a program is given to exit gracefully when stopped.
+    stop_grace_period: Duration,
+    /// Max time to wait for a terminated program to be reaped.
+    reap_timeout: Duration,
+    /// Stop timeouts for programs that don't use the default.
+    stop_timeouts: HashMap<BuiltinProgram, Duration>,
+    /// Show states at launch for programs that don't use the default.
+    spawn_show_states: HashMap<BuiltinProgram, ShowState>,
+    clock: Arc<dyn Clock>,
+    launcher: Arc<dyn Launcher>,
 }
 
 struct ChildProcess {
     child: Child,
-    #[allow(dead_code)]
     exe_path: PathBuf,
+    started_at: Instant,
+    /// Consecutive failures prior to this run.
+    failures: u32,
+}
+
+struct PendingRestart {
+    failures: u32,
+    restart_at: Instant,
+}
+
+/// Start of a program that was staged while holding the manager's lock,
+/// such that the process can be spawned without holding it.
+struct StagedStart {
+    program: BuiltinProgram,
+    descriptor: BuiltinDescriptor,
+    show_state: ShowState,
+    launcher: Arc<dyn Launcher>,
+}
+
+impl StagedStart {
+    fn launch(&self) -> Result<(Child, PathBuf)> {
+        info!("Starting builtin {:?}", self.program);
+        self.launcher.launch(&self.descriptor, self.show_state)
+    }
+}
+
+/// Launch staged starts concurrently, returning the outcomes in the same
+/// order.
+///
+/// Programs are extracted to distinct paths, so their extractions can run
+/// at the same time. Should two descriptors resolve to the same file name,
+/// the programs are launched one after the other instead.
+fn launch_all(staged: &[StagedStart]) -> Vec<Result<(Child, PathBuf)>> {
+    let file_names = staged
+        .iter()
+        .map(|staged| staged.descriptor.resolved_file_name())
+        .collect::<HashSet<_>>();
+
+    if file_names.len() < staged.len() {
+        return staged.iter().map(StagedStart::launch).collect();
+    }
+
+    std::thread::scope(|scope| {
+        let handles = staged
+            .iter()
+            .map(|staged| scope.spawn(|| staged.launch()))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Builtin launch thread panicked")))
+            })
+            .collect()
+    })
+}
+
+/// Process that was removed from the manager while holding its lock,
+/// such that it can be waited on without holding it.
+struct StagedStop {
+    program: BuiltinProgram,
+    child: Child,
+    grace_period: Duration,
+    reap_timeout: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl StagedStop {
+    fn run(mut self) -> StopOutcome {
+        info!("Stopping builtin {:?} (PID {})", self.program, self.child.id());
+
+        let outcome = stop_child(&mut self.child, self.grace_period, self.reap_timeout, self.clock.as_ref());
+
+        info!("Stopped builtin {:?} ({:?})", self.program, outcome);
+        outcome
+    }
+
+    /// Stop the process, failing if it couldn't be confirmed to have
+    /// exited. Its executable may still be locked in that case.
+    fn run_to_exit(self) -> Result<()> {
+        let program = self.program;
+
+        if self.run() == StopOutcome::Unreaped {
+            bail!("Builtin {:?} did not exit, so its executable can't be removed.", program);
+        }
+
+        Ok(())
+    }
 }
 
 impl ProcessManager {
     /// Create a new process manager.
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Create a new process manager that uses the given clock for
+    /// scheduling restarts.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             processes: HashMap::new(),
+            pending_restarts: HashMap::new(),
+            starting: HashSet::new(),
+            descriptors: HashMap::new(),
+            restart_policy: RestartPolicy::default(),
+            stop_grace_period: Duration::from_secs(3),
+            reap_timeout: DEFAULT_REAP_TIMEOUT,
+            stop_timeouts: HashMap::new(),
+            spawn_show_states: HashMap::new(),
+            clock,
+            launcher: Arc::new(EmbeddedLauncher),
         }
     }
 
+    /// Use the given launcher for spawning programs.
+    pub fn with_launcher(mut self, launcher: Arc<dyn Launcher>) -> Self {
+        self.launcher = launcher;
+        self
+    }
+
+    /// Set the policy used for restarting programs that exit unexpectedly.
+    pub fn set_restart_policy(&mut self, policy: RestartPolicy) {
+        self.restart_policy = policy;
+    }
+
+    /// Set the time a program is given to exit gracefully when stopped,
+    /// before it gets forcibly terminated.
+    pub fn set_stop_grace_period(&mut self, grace_period: Duration) {
+        self.stop_grace_period = grace_period;
+    }
+
+    /// Set the max time a program is given to stop, including being
+    /// forcibly terminated, when the process manager is dropped.
+    ///
+    /// Dropping typically happens on exit, so this defaults to a short
+    /// timeout. Programs that need longer to shut down cleanly can opt
+    /// into a longer one.
+    pub fn set_stop_timeout(&mut self, program: BuiltinProgram, timeout: Duration) {
+        self.stop_timeouts.insert(program, timeout);
+    }
+
+    /// Get the max time a program is given to stop when the process
+    /// manager is dropped.
+    pub fn stop_timeout(&self, program: BuiltinProgram) -> Duration {
+        self.stop_timeouts.get(&program).copied().unwrap_or(DEFAULT_STOP_TIMEOUT)
+    }
+
+    /// Set how the window of a program is shown when it's launched, e.g.
+    /// to start it hidden to the tray without taking focus.
+    ///
+    /// Takes effect the next time the program is started, and applies to
+    /// all later starts, including automatic restarts.
+    pub fn set_spawn_show_state(&mut self, program: BuiltinProgram, show_state: ShowState) {
+        self.spawn_show_states.insert(program, show_state);
+    }
+
+    /// Get how the window of a program is shown when it's launched.
+    pub fn spawn_show_state(&self, program: BuiltinProgram) -> ShowState {
+        self.spawn_show_states.get(&program).copied().unwrap_or_default()
+    }
+
+    /// Set the extraction settings for a builtin program.
+    ///
+    /// Takes effect the next time the program is started.
+    pub fn set_descriptor(&mut self, descriptor: BuiltinDescriptor) {
+        self.descriptors.insert(descriptor.program, descriptor);
+    }
+
+    /// Get the extraction settings for a builtin program.
+    pub fn descriptor(&self, program: BuiltinProgram) -> BuiltinDescriptor {
+        self.descriptors
+            .get(&program)
+            .cloned()
+            .unwrap_or_else(|| BuiltinDescriptor::new(program))
+    }
+
     /// Start a builtin program.
+    ///
+    /// A program that is running from an outdated executable is stopped,
+    /// such that its executable can be replaced, and then started again.
     pub fn start(&mut self, program: BuiltinProgram) -> Result<()> {
-        // Check if already running
-        if self.is_running(program) {
+        if let Some(staged) = self.stage_outdated_stop(program) {
+            staged.run_to_exit()?;
+        }
+
+        match self.stage_start(program) {
+            Some(staged) => {
+                let launched = staged.launch();
+                self.finish_start(program, launched)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Start multiple builtin programs, extracting and spawning them
+    /// concurrently.
+    ///
+    /// Programs that are already running are skipped, unless they run
+    /// from an outdated executable. Fails if any of the programs couldn't
+    /// be started, after the others have been started.
+    pub fn start_all(&mut self, programs: &[BuiltinProgram]) -> Result<()> {
+        for program in programs {
+            if let Some(staged) = self.stage_outdated_stop(*program) {
+                staged.run_to_exit()?;
+            }
+        }
+
+        let staged = programs
+            .iter()
+            .filter_map(|program| self.stage_start(*program))
+            .collect::<Vec<_>>();
+
+        let launched = launch_all(&staged);
+        self.finish_starts(&staged, launched)
+    }
+
+    /// Stage stopping a program that is running from an outdated
+    /// executable, such that the executable can be replaced before the
+    /// program is started again.
+    ///
+    /// Returns `None` if the program isn't running, or if its executable
+    /// is up to date.
+    fn stage_outdated_stop(&mut self, program: BuiltinProgram) -> Option<StagedStop> {
+        let process = self.processes.get(&program)?;
+
+        if !self.launcher.is_outdated(&self.descriptor(program), &process.exe_path) {
+            return None;
+        }
+
+        info!("Builtin {:?} is running from an outdated executable, restarting it", program);
+        self.stage_stop(program, self.reap_timeout)
+    }
+
+    /// Stage the start of a program, to be launched without holding the
+    /// manager's lock and then passed to [`Self::finish_start`].
+    ///
+    /// Returns `None` if the program is already running or being started.
+    fn stage_start(&mut self, program: BuiltinProgram) -> Option<StagedStart> {
+        if self.is_running(program) || self.starting.contains(&program) {
             info!("Builtin {:?} is already running", program);
-            return Ok(());
+            return None;
         }
 
-        // Extract the binary
-        let exe_path = extract_builtin(program)?;
+        self.starting.insert(program);
+
+        Some(StagedStart {
+            program,
+            descriptor: self.descriptor(program),
+            show_state: self.spawn_show_state(program),
+            launcher: self.launcher.clone(),
+        })
+    }
 
-        info!("Starting builtin {:?} from {:?}", program, exe_path);
+    /// Record the outcome of launching a staged start.
+    fn finish_start(&mut self, program: BuiltinProgram, launched: Result<(Child, PathBuf)>) -> Result<()> {
+        self.starting.remove(&program);
+        let (child, exe_path) = launched?;
 
-        // Start the process
-        let child = std::process::Command::new(&exe_path)
-            .spawn()
-            .with_context(|| {
-                format!("Failed to start builtin {:?}", program)
-            })?;
+        info!("Started builtin {:?} from {:?} with PID {}", program, exe_path, child.id());
 
-        info!("Started builtin {:?} with PID {}", program, child.id());
+        let failures = self
+            .pending_restarts
+            .remove(&program)
+            .map_or(0, |restart| restart.failures);
 
         self.processes.insert(
             program,
             ChildProcess {
                 child,
                 exe_path,
+                started_at: self.clock.now(),
+                failures,
             },
         );
 
         Ok(())
     }
 
+    /// Record the outcomes of launching multiple staged starts, one at a
+    /// time. Failures are combined into a single error.
+    fn finish_starts(&mut self, staged: &[StagedStart], launched: Vec<Result<(Child, PathBuf)>>) -> Result<()> {
+        let failures = staged
+            .iter()
+            .zip(launched)
+            .filter_map(|(staged, launched)| {
+                self.finish_start(staged.program, launched)
+                    .err()
+                    .map(|err| format!("{}: {err:#}", staged.program.name()))
+            })
+            .collect::<Vec<_>>();
+
+        if !failures.is_empty() {
+            bail!("Failed to start builtins: {}", failures.join("; "));
+        }
+
+        Ok(())
+    }
+
     /// Stop a builtin program.
     pub fn stop(&mut self, program: BuiltinProgram) -> Result<()> {
-        if let Some(mut process) = self.processes.remove(&program) {
-            info!("Stopping builtin {:?} (PID {})", program, process.child.id());
-
-            // Try graceful termination first on Windows
-            #[cfg(windows)]
-            {
-                if let Err(e) = self.terminate_process_tree(process.child.id()) {
-                    warn!("Failed to terminate process tree: {}", e);
-                    // Fall back to kill
-                    if let Err(e) = process.child.kill() {
-                        error!("Failed to kill builtin {:?}: {}", program, e);
-                    }
-                }
-            }
+        if let Some(staged) = self.stage_stop(program, self.reap_timeout) {
+            staged.run();
+        }
 
-            #[cfg(not(windows))]
-            {
-                if let Err(e) = process.child.kill() {
-                    error!("Failed to kill builtin {:?}: {}", program, e);
-                }
-            }
+        Ok(())
+    }
 
-            // Wait for the process to exit
-            let _ = process.child.wait();
+    /// Stage stopping a program by removing it from the manager, such that
+    /// it can be waited on without holding the manager's lock.
+    fn stage_stop(&mut self, program: BuiltinProgram, reap_timeout: Duration) -> Option<StagedStop> {
+        // An explicit stop cancels any scheduled restart.
+        self.pending_restarts.remove(&program);
 
-            info!("Stopped builtin {:?}", program);
-        } else {
+        let Some(process) = self.processes.remove(&program) else {
             warn!("Builtin {:?} is not running", program);
+            return None;
+        };
+
+        Some(StagedStop {
+            program,
+            child: process.child,
+            grace_period: self.stop_grace_period,
+            reap_timeout,
+            clock: self.clock.clone(),
+        })
+    }
+
+    /// Restart a builtin program, starting it if it is not running.
+    pub fn restart(&mut self, program: BuiltinProgram) -> Result<()> {
+        if let Some(staged) = self.stage_restart(program) {
+            staged.run();
         }
 
-        Ok(())
+        self.start(program)
+    }
+
+    /// Stage stopping a program if it's running, such that it can be
+    /// started fresh afterwards.
+    fn stage_restart(&mut self, program: BuiltinProgram) -> Option<StagedStop> {
+        let staged = if self.is_running(program) {
+            self.stage_stop(program, self.reap_timeout)
+        } else {
+            None
+        };
+
+        // Start fresh rather than waiting on a scheduled restart.
+        self.pending_restarts.remove(&program);
+        staged
+    }
+
+    /// Re-extract the executable of a builtin program from scratch,
+    /// stopping the program first if it's running. Returns the path to
+    /// the extracted executable.
+    ///
+    /// Meant for recovering from a corrupted extraction. The program is
+    /// started again afterwards if `restart` is set.
+    pub fn reextract(&mut self, program: BuiltinProgram, restart: bool) -> Result<PathBuf> {
+        if let Some(staged) = self.stage_restart(program) {
+            staged.run_to_exit()?;
+        }
+
+        let exe_path = force_extract_builtin(&self.descriptor(program))?;
+
+        if restart {
+            self.start(program)?;
+        }
+
+        Ok(exe_path)
+    }
+
+    /// Get the current state of a builtin program.
+    pub fn status(&mut self, program: BuiltinProgram) -> BuiltinStatus {
+        let running = self.is_running(program);
+        let process = self.processes.get(&program);
+
+        BuiltinStatus {
+            program,
+            available: program.is_available(),
+            running,
+            pid: process.map(|process| process.child.id()),
+            exe_path: process.map(|process| process.exe_path.clone()),
+            restart_in: self.restart_delay(program),
+        }
+    }
+
+    /// Get all builtin programs that are currently running.
+    pub fn running(&mut self) -> Vec<BuiltinProgram> {
+        BuiltinProgram::ALL
+            .into_iter()
+            .filter(|program| self.is_running(*program))
+            .collect()
+    }
+
+    /// Get details of all builtin programs that are currently running.
+    ///
+    /// Programs that have exited are detected first, so the report only
+    /// includes live processes.
+    pub fn report(&mut self) -> Vec<ProcessReport> {
+        let now = self.clock.now();
+
+        self.running()
+            .into_iter()
+            .filter_map(|program| {
+                let process = self.processes.get(&program)?;
+
+                Some(ProcessReport {
+                    program,
+                    pid: process.child.id(),
+                    exe_path: process.exe_path.clone(),
+                    uptime: now.saturating_duration_since(process.started_at),
+                    restarts: process.failures,
+                })
+            })
+            .collect()
     }
 
     /// Check if a builtin program is running.
     pub fn is_running(&mut self, program: BuiltinProgram) -> bool {
-        if let Some(process) = self.processes.get_mut(&program) {
-            // Check if the process has exited
-            match process.child.try_wait() {
-                Ok(Some(_)) => {
-                    // Process has exited, remove it
-                    self.processes.remove(&program);
-                    false
-                }
-                Ok(None) => true,  // Still running
-                Err(_) => {
-                    // Error checking status, assume not running
-                    self.processes.remove(&program);
-                    false
+        self.reap_program(program);
+        self.processes.contains_key(&program)
+    }
+
+    /// Remove all programs whose process has exited, scheduling a restart
+    /// for each. Returns the exited programs along with their exit status.
+    ///
+    /// Exited programs are otherwise only detected when they're queried,
+    /// so this should be called periodically for crashes to be noticed.
+    pub fn reap(&mut self) -> Vec<(BuiltinProgram, ExitStatus)> {
+        let programs: Vec<_> = self.processes.keys().copied().collect();
+
+        programs
+            .into_iter()
+            .filter_map(|program| Some((program, self.reap_program(program)?)))
+            .collect()
+    }
+
+    /// Remove the program if its process has exited, scheduling a restart.
+    /// Returns the exit status if the process exited.
+    fn reap_program(&mut self, program: BuiltinProgram) -> Option<ExitStatus> {
+        let process = self.processes.get_mut(&program)?;
+
+        let status = match process.child.try_wait() {
+            Ok(None) => return None,
+            Ok(Some(status)) => {
+                warn!("Builtin {:?} exited unexpectedly with {}", program, status);
+                Some(status)
+            }
+            // Error checking status, assume not running.
+            Err(err) => {
+                warn!("Failed to check status of builtin {:?}: {}", program, err);
+                None
+            }
+        };
+
+        if let Some(process) = self.processes.remove(&program) {
+            self.schedule_restart(program, process.started_at, process.failures);
+        }
+
+        status
+    }
+
+    /// Get the time remaining until a scheduled restart of the program.
+    ///
+    /// Returns `None` if no restart is scheduled.
+    pub fn restart_delay(&self, program: BuiltinProgram) -> Option<Duration> {
+        self.pending_restarts
+            .get(&program)
+            .map(|restart| restart.restart_at.saturating_duration_since(self.clock.now()))
+    }
+
+    /// Restart any programs whose scheduled restart is due.
+    ///
+    /// Exited programs are detected first, so this can be called
+    /// periodically to keep builtins alive. Returns the programs that were
+    /// restarted.
+    pub fn poll_restarts(&mut self) -> Vec<BuiltinProgram> {
+        self.reap();
+
+        let mut restarted = Vec::new();
+
+        for program in self.due_restarts() {
+            let failures = self.pending_restarts[&program].failures;
+
+            match self.start(program) {
+                Ok(()) => restarted.push(program),
+                Err(e) => {
+                    error!("Failed to restart builtin {:?}: {}", program, e);
+                    // Count the failed attempt so that the next one is
+                    // delayed further.
+                    self.pending_restarts.remove(&program);
+                    self.schedule_restart(program, self.clock.now(), failures);
                 }
             }
-        } else {
-            false
         }
+
+        restarted
+    }
+
+    /// Get programs whose scheduled restart is due.
+    fn due_restarts(&self) -> Vec<BuiltinProgram> {
+        self.pending_restarts
+            .iter()
+            .filter(|(_, restart)| self.clock.is_due(restart.restart_at))
+            .map(|(program, _)| *program)
+            .collect()
+    }
+
+    /// Schedule a restart for a program that exited after starting at
+    /// `started_at` with `failures` prior consecutive failures.
+    fn schedule_restart(&mut self, program: BuiltinProgram, started_at: Instant, failures: u32) {
+        let uptime = self.clock.now().saturating_duration_since(started_at);
+
+        // Reset the failure count if the program had been stable.
+        let failures = if uptime >= self.restart_policy.stable_uptime {
+            1
+        } else {
+            failures + 1
+        };
+
+        let delay = self.restart_policy.delay_for(failures);
+        info!("Restarting builtin {:?} in {:?} (attempt {})", program, delay, failures);
+
+        self.pending_restarts.insert(
+            program,
+            PendingRestart {
+                failures,
+                restart_at: self.clock.deadline(delay),
+            },
+        );
     }
 
     /// Stop all running builtin programs.
@@ -141,55 +674,27 @@ impl ProcessManager {
         }
     }
 
-    /// Terminate a process and all its children on Windows.
-    #[cfg(windows)]
-    fn terminate_process_tree(&self, pid: u32) -> Result<()> {
-        use windows::Win32::Foundation::CloseHandle;
-        use windows::Win32::System::Diagnostics::ToolHelp::{
-            CreateToolhelp32Snapshot, Process32First, Process32Next,
-            PROCESSENTRY32, TH32CS_SNAPPROCESS,
-        };
-        use windows::Win32::System::Threading::{
-            OpenProcess, TerminateProcess, PROCESS_TERMINATE,
-        };
-
-        unsafe {
-            // Get all child processes
-            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
-
-            let mut entry = PROCESSENTRY32 {
-                dwSize: std::mem::size_of::<PROCESSENTRY32>() as u32,
-                ..Default::default()
-            };
-
-            let mut children = Vec::new();
-
-            if Process32First(snapshot, &mut entry).is_ok() {
-                loop {
-                    if entry.th32ParentProcessID == pid {
-                        children.push(entry.th32ProcessID);
-                    }
-                    if Process32Next(snapshot, &mut entry).is_err() {
-                        break;
-                    }
-                }
-            }
+    /// Stop all running builtin programs concurrently, such that each
+    /// stops within its stop timeout.
+    fn stop_all_within_timeouts(&mut self) {
+        let programs: Vec<_> = self.processes.keys().copied().collect();
 
-            let _ = CloseHandle(snapshot);
+        let staged = programs
+            .into_iter()
+            .filter_map(|program| {
+                let (grace_period, reap_timeout) = split_stop_timeout(self.stop_timeout(program), self.stop_grace_period);
 
-            // Recursively terminate children
-            for child_pid in children {
-                let _ = self.terminate_process_tree(child_pid);
-            }
+                let mut staged = self.stage_stop(program, reap_timeout)?;
+                staged.grace_period = grace_period;
+                Some(staged)
+            })
+            .collect::<Vec<_>>();
 
-            // Terminate the process itself
-            if let Ok(handle) = OpenProcess(PROCESS_TERMINATE, false, pid) {
-                let _ = TerminateProcess(handle, 0);
-                let _ = CloseHandle(handle);
+        std::thread::scope(|scope| {
+            for staged in staged {
+                scope.spawn(|| staged.run());
             }
-        }
-
-        Ok(())
+        });
     }
 }
 
@@ -201,30 +706,184 @@ impl Default for ProcessManager {
 
 impl Drop for ProcessManager {
     fn drop(&mut self) {
-        self.stop_all();
+        // Bound the time spent stopping, since dropping typically happens
+        // on exit.
+        self.stop_all_within_timeouts();
     }
 }
 
+/// Split a stop timeout into the grace period and the reap timeout.
+///
+/// The grace period is capped at half the timeout, so that a program that
+/// ignores the request to exit still has time to be reaped after being
+/// forcibly terminated.
+fn split_stop_timeout(stop_timeout: Duration, grace_period: Duration) -> (Duration, Duration) {
+    let grace_period = grace_period.min(stop_timeout / 2);
+    (grace_period, stop_timeout - grace_period)
+}
+
+/// Parse a builtin program name.
+///
+/// Fails with [`BuiltinError::UnknownBuiltin`] if the name does not match
+/// any builtin program.
+pub fn parse_builtin(name: &str) -> Result<BuiltinProgram> {
+    BuiltinProgram::from_str(name)
+        .ok_or_else(|| BuiltinError::UnknownBuiltin(name.to_string()).into())
+}
+
+/// Run a closure with the global process manager locked.
+fn with_process_manager<T>(f: impl FnOnce(&mut ProcessManager) -> T) -> Result<T> {
+    let manager = process_manager();
+    let mut manager = manager
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire process manager lock"))?;
+
+    Ok(f(&mut manager))
+}
+
+/// Start a program with the global process manager. The process is
+/// spawned without holding the lock, so that other queries aren't blocked.
+fn start_unlocked(program: BuiltinProgram) -> Result<()> {
+    if let Some(staged) = with_process_manager(|manager| manager.stage_outdated_stop(program))? {
+        staged.run_to_exit()?;
+    }
+
+    let Some(staged) = with_process_manager(|manager| manager.stage_start(program))? else {
+        return Ok(());
+    };
+
+    let launched = staged.launch();
+    with_process_manager(|manager| manager.finish_start(program, launched))?
+}
+
 /// Start a builtin program by name.
 pub fn start_builtin(name: &str) -> Result<()> {
-    let program = BuiltinProgram::from_str(name)
-        .with_context(|| format!("Unknown builtin program: {}", name))?;
+    let program = parse_builtin(name)?;
+    start_unlocked(program)
+}
 
-    process_manager()
-        .lock()
-        .map_err(|_| anyhow::anyhow!("Failed to acquire process manager lock"))?
-        .start(program)
+/// Start all available builtin programs with the global process manager.
+///
+/// Binaries are extracted and spawned concurrently without holding the
+/// lock. The started processes are then recorded one at a time, such
+/// that the manager's state is only ever modified under the lock.
+pub fn start_all_available_parallel() -> Result<()> {
+    let programs = available_builtins();
+
+    let outdated = with_process_manager(|manager| {
+        programs
+            .iter()
+            .filter_map(|program| manager.stage_outdated_stop(*program))
+            .collect::<Vec<_>>()
+    })?;
+
+    for staged in outdated {
+        staged.run_to_exit()?;
+    }
+
+    let staged = with_process_manager(|manager| {
+        programs
+            .iter()
+            .filter_map(|program| manager.stage_start(*program))
+            .collect::<Vec<_>>()
+    })?;
+
+    let launched = launch_all(&staged);
+    with_process_manager(|manager| manager.finish_starts(&staged, launched))?
 }
 
 /// Stop a builtin program by name.
+///
+/// The program is removed from the global process manager before waiting
+/// for it to exit, so the lock isn't held during the grace period.
 pub fn stop_builtin(name: &str) -> Result<()> {
-    let program = BuiltinProgram::from_str(name)
-        .with_context(|| format!("Unknown builtin program: {}", name))?;
+    let program = parse_builtin(name)?;
 
-    process_manager()
-        .lock()
-        .map_err(|_| anyhow::anyhow!("Failed to acquire process manager lock"))?
-        .stop(program)
+    if let Some(staged) = with_process_manager(|manager| manager.stage_stop(program, manager.reap_timeout))? {
+        staged.run();
+    }
+
+    Ok(())
+}
+
+/// Restart a builtin program by name.
+pub fn restart_builtin(name: &str) -> Result<()> {
+    let program = parse_builtin(name)?;
+
+    if let Some(staged) = with_process_manager(|manager| manager.stage_restart(program))? {
+        staged.run();
+    }
+
+    start_unlocked(program)
+}
+
+/// Re-extract the executable of a builtin program by name, stopping the
+/// program first if it's running. Returns the path to the extracted
+/// executable.
+///
+/// The program is removed from the global process manager before waiting
+/// for it to exit, so the lock isn't held during the grace period. It's
+/// started again afterwards if `restart` is set.
+pub fn reextract_builtin(name: &str, restart: bool) -> Result<PathBuf> {
+    let program = parse_builtin(name)?;
+
+    let (staged, descriptor) =
+        with_process_manager(|manager| (manager.stage_restart(program), manager.descriptor(program)))?;
+
+    if let Some(staged) = staged {
+        staged.run_to_exit()?;
+    }
+
+    let exe_path = force_extract_builtin(&descriptor)?;
+
+    if restart {
+        start_unlocked(program)?;
+    }
+
+    Ok(exe_path)
+}
+
+/// Remove builtin programs that have exited from the global process
+/// manager. Returns the exited programs along with their exit status.
+pub fn reap_builtins() -> Result<Vec<(BuiltinProgram, ExitStatus)>> {
+    with_process_manager(ProcessManager::reap)
+}
+
+/// Restart builtin programs whose scheduled restart is due, after
+/// detecting any that have exited. Returns the restarted programs.
+pub fn poll_builtin_restarts() -> Result<Vec<BuiltinProgram>> {
+    with_process_manager(ProcessManager::poll_restarts)
+}
+
+/// Get the current state of a builtin program by name.
+pub fn builtin_status(name: &str) -> Result<BuiltinStatus> {
+    let program = parse_builtin(name)?;
+    with_process_manager(|manager| manager.status(program))
+}
+
+/// Get the current state of all builtin programs.
+pub fn builtin_statuses() -> Result<Vec<BuiltinStatus>> {
+    with_process_manager(|manager| {
+        BuiltinProgram::ALL
+            .into_iter()
+            .map(|program| manager.status(program))
+            .collect()
+    })
+}
+
+/// Set the extraction settings for a builtin program.
+pub fn configure_builtin(descriptor: BuiltinDescriptor) -> Result<()> {
+    with_process_manager(|manager| manager.set_descriptor(descriptor))
+}
+
+/// Get all builtin programs that are currently running.
+pub fn running_builtins() -> Vec<BuiltinProgram> {
+    with_process_manager(ProcessManager::running).unwrap_or_default()
+}
+
+/// Get details of all builtin programs that are currently running.
+pub fn builtins_report() -> Result<Vec<ProcessReport>> {
+    with_process_manager(ProcessManager::report)
 }
 
 /// Stop all running builtin programs.
@@ -233,3 +892,445 @@ pub fn stop_all_builtins() {
         manager.stop_all();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FakeClock, TestBuiltin, TestLauncher};
+
+    fn manager_with_fake_clock() -> (ProcessManager, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new());
+        (ProcessManager::with_clock(clock.clone()), clock)
+    }
+
+    #[test]
+    fn delay_doubles_up_to_max() {
+        let policy = RestartPolicy::default();
+
+        assert_eq!(policy.delay_for(1), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(4));
+        assert_eq!(policy.delay_for(6), Duration::from_secs(30));
+        assert_eq!(policy.delay_for(u32::MAX), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn restart_becomes_due_after_backoff() {
+        let (mut manager, clock) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        manager.schedule_restart(program, clock.now(), 0);
+        assert_eq!(manager.restart_delay(program), Some(Duration::from_secs(1)));
+        assert!(manager.due_restarts().is_empty());
+
+        clock.advance(Duration::from_millis(999));
+        assert!(manager.due_restarts().is_empty());
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(manager.due_restarts(), vec![program]);
+    }
+
+    #[test]
+    fn repeated_crashes_increase_backoff() {
+        let (mut manager, clock) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        for (failures, expected_secs) in [(0, 1), (1, 2), (2, 4), (3, 8)] {
+            let started_at = clock.now();
+            clock.advance(Duration::from_secs(5));
+            manager.schedule_restart(program, started_at, failures);
+
+            assert_eq!(
+                manager.restart_delay(program),
+                Some(Duration::from_secs(expected_secs))
+            );
+        }
+    }
+
+    #[test]
+    fn stable_uptime_resets_backoff() {
+        let (mut manager, clock) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        let started_at = clock.now();
+        clock.advance(Duration::from_secs(60));
+        manager.schedule_restart(program, started_at, 4);
+
+        assert_eq!(manager.restart_delay(program), Some(Duration::from_secs(1)));
+        assert_eq!(manager.pending_restarts[&program].failures, 1);
+    }
+
+    #[test]
+    fn stop_cancels_scheduled_restart() {
+        let (mut manager, clock) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        manager.schedule_restart(program, clock.now(), 0);
+        manager.stop(program).unwrap();
+
+        assert_eq!(manager.restart_delay(program), None);
+    }
+
+    #[test]
+    fn status_reports_scheduled_restart() {
+        let (mut manager, clock) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        manager.schedule_restart(program, clock.now(), 0);
+        let status = manager.status(program);
+
+        assert!(!status.running);
+        assert_eq!(status.pid, None);
+        assert_eq!(status.exe_path, None);
+        assert_eq!(status.restart_in, Some(Duration::from_secs(1)));
+        assert!(manager.running().is_empty());
+    }
+
+    #[test]
+    fn unknown_builtin_is_rejected() {
+        assert_eq!(parse_builtin("ZEBAR").unwrap(), BuiltinProgram::Zebar);
+
+        let err = parse_builtin("polybar").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuiltinError>(),
+            Some(&BuiltinError::UnknownBuiltin("polybar".to_string()))
+        );
+    }
+
+    #[test]
+    fn descriptor_defaults_until_configured() {
+        let (mut manager, _) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        assert_eq!(manager.descriptor(program), BuiltinDescriptor::new(program));
+
+        let descriptor = BuiltinDescriptor::new(program)
+            .with_file_name("my-bar.exe")
+            .unwrap();
+        manager.set_descriptor(descriptor.clone());
+
+        assert_eq!(manager.descriptor(program), descriptor);
+    }
+
+    #[test]
+    fn stop_timeout_defaults_until_configured() {
+        let (mut manager, _) = manager_with_fake_clock();
+
+        assert_eq!(manager.stop_timeout(BuiltinProgram::Zebar), DEFAULT_STOP_TIMEOUT);
+
+        manager.set_stop_timeout(BuiltinProgram::Zebar, Duration::from_secs(10));
+        assert_eq!(manager.stop_timeout(BuiltinProgram::Zebar), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn stop_timeout_leaves_time_to_reap() {
+        let timeout = Duration::from_secs(2);
+
+        assert_eq!(
+            split_stop_timeout(timeout, Duration::from_millis(500)),
+            (Duration::from_millis(500), Duration::from_millis(1500))
+        );
+        assert_eq!(
+            split_stop_timeout(timeout, Duration::from_secs(3)),
+            (Duration::from_secs(1), Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn failed_launch_allows_another_start() {
+        let (mut manager, _) = manager_with_fake_clock();
+        let program = BuiltinProgram::Zebar;
+
+        assert!(manager.stage_start(program).is_some());
+        assert!(manager.stage_start(program).is_none());
+
+        let result = manager.finish_start(program, Err(anyhow::anyhow!("Failed to spawn")));
+        assert!(result.is_err());
+        assert!(manager.stage_start(program).is_some());
+    }
+
+    /// Create a process manager that runs the given stand-in in place of
+    /// the builtin program.
+    fn manager_with_test_builtin(builtin: TestBuiltin) -> (ProcessManager, Arc<FakeClock>, Arc<TestLauncher>) {
+        let clock = Arc::new(FakeClock::new());
+        let launcher = Arc::new(TestLauncher::new(builtin));
+
+        let mut manager = ProcessManager::with_clock(clock.clone()).with_launcher(launcher.clone());
+        manager.set_stop_grace_period(Duration::from_secs(1));
+
+        (manager, clock, launcher)
+    }
+
+    /// Wait (in real time) until the program is no longer running.
+    fn wait_until_exited(manager: &mut ProcessManager, program: BuiltinProgram) {
+        for _ in 0..200 {
+            if !manager.is_running(program) {
+                return;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        panic!("Builtin {:?} did not exit", program);
+    }
+
+    #[test]
+    fn start_and_stop_process() {
+        let (mut manager, _, launcher) = manager_with_test_builtin(TestBuiltin::LongRunning);
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+        assert!(manager.is_running(program));
+
+        let status = manager.status(program);
+        assert!(status.running);
+        assert!(status.pid.is_some());
+        assert_eq!(status.exe_path, Some(TestBuiltin::LongRunning.exe_path()));
+
+        // Starting an already running program is a no-op.
+        manager.start(program).unwrap();
+        assert_eq!(launcher.launches(), 1);
+
+        manager.stop(program).unwrap();
+        assert!(!manager.is_running(program));
+        assert_eq!(manager.restart_delay(program), None);
+    }
+
+    #[test]
+    fn start_all_skips_running_programs() {
+        let (mut manager, _, launcher) = manager_with_test_builtin(TestBuiltin::LongRunning);
+
+        manager.start_all(&BuiltinProgram::ALL).unwrap();
+        assert!(BuiltinProgram::ALL.iter().all(|program| manager.is_running(*program)));
+        assert_eq!(launcher.launches(), BuiltinProgram::ALL.len());
+
+        manager.start_all(&BuiltinProgram::ALL).unwrap();
+        assert_eq!(launcher.launches(), BuiltinProgram::ALL.len());
+
+        manager.stop_all();
+        assert!(manager.running().is_empty());
+    }
+
+    #[test]
+    fn start_restarts_program_with_outdated_executable() {
+        // The program has to have exited before it's started again, so
+        // it's waited on in real time.
+        let launcher = Arc::new(TestLauncher::new(TestBuiltin::LongRunning));
+        let mut manager = ProcessManager::new().with_launcher(launcher.clone());
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+        let pid = manager.status(program).pid;
+
+        // An up-to-date program is left running.
+        manager.start(program).unwrap();
+        assert_eq!(launcher.launches(), 1);
+        assert_eq!(manager.status(program).pid, pid);
+
+        // An outdated one is stopped and started again.
+        launcher.set_outdated(true);
+        manager.start(program).unwrap();
+        assert_eq!(launcher.launches(), 2);
+        assert!(manager.is_running(program));
+        assert_ne!(manager.status(program).pid, pid);
+
+        manager.stop_all();
+    }
+
+    #[test]
+    fn spawn_show_state_applies_to_every_start() {
+        let (mut manager, _, launcher) = manager_with_test_builtin(TestBuiltin::LongRunning);
+        let program = BuiltinProgram::Zebar;
+
+        assert_eq!(manager.spawn_show_state(program), ShowState::ShowNormal);
+
+        manager.set_spawn_show_state(program, ShowState::Hide);
+        manager.start(program).unwrap();
+        manager.restart(program).unwrap();
+        manager.stop(program).unwrap();
+
+        assert_eq!(launcher.show_states(), vec![ShowState::Hide, ShowState::Hide]);
+    }
+
+    #[test]
+    fn launch_all_launches_starts_sharing_a_file_name() {
+        let test_launcher = Arc::new(TestLauncher::new(TestBuiltin::LongRunning));
+        let launcher: Arc<dyn Launcher> = test_launcher.clone();
+
+        // Starts that share a file name are launched one at a time.
+        let staged = [BuiltinProgram::Zebar, BuiltinProgram::Zebar].map(|program| StagedStart {
+            program,
+            descriptor: BuiltinDescriptor::new(program),
+            show_state: ShowState::default(),
+            launcher: launcher.clone(),
+        });
+
+        let launched = launch_all(&staged);
+        assert_eq!(launched.len(), 2);
+        assert_eq!(test_launcher.launches(), 2);
+
+        let mut children = launched.into_iter().map(|outcome| outcome.unwrap()).collect::<Vec<_>>();
+        assert_ne!(children[0].0.id(), children[1].0.id());
+
+        for (child, exe_path) in &mut children {
+            assert_eq!(*exe_path, TestBuiltin::LongRunning.exe_path());
+            assert!(child.try_wait().unwrap().is_none());
+            child.kill().unwrap();
+            child.wait().unwrap();
+        }
+    }
+
+    #[test]
+    fn staged_start_and_stop_leave_manager_usable() {
+        let (mut manager, _, launcher) = manager_with_test_builtin(TestBuiltin::LongRunning);
+        let program = BuiltinProgram::Zebar;
+
+        let staged = manager.stage_start(program).unwrap();
+
+        // Queries are answered while the process is being spawned, and
+        // the program isn't started twice.
+        assert!(!manager.status(program).running);
+        assert!(manager.stage_start(program).is_none());
+
+        let launched = staged.launch();
+        manager.finish_start(program, launched).unwrap();
+        assert!(manager.is_running(program));
+        assert_eq!(launcher.launches(), 1);
+
+        let staged = manager.stage_stop(program, DEFAULT_REAP_TIMEOUT).unwrap();
+        assert!(!manager.is_running(program));
+
+        staged.run();
+        assert_eq!(manager.restart_delay(program), None);
+    }
+
+    #[test]
+    fn report_includes_running_processes() {
+        let (mut manager, clock, _) = manager_with_test_builtin(TestBuiltin::LongRunning);
+        let program = BuiltinProgram::Zebar;
+
+        assert!(manager.report().is_empty());
+
+        manager.start(program).unwrap();
+        clock.advance(Duration::from_secs(10));
+
+        let report = manager.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].program, program);
+        assert_eq!(report[0].exe_path, TestBuiltin::LongRunning.exe_path());
+        assert_eq!(report[0].uptime, Duration::from_secs(10));
+        assert_eq!(report[0].restarts, 0);
+
+        manager.stop(program).unwrap();
+        assert!(manager.report().is_empty());
+    }
+
+    #[test]
+    fn exited_process_is_reaped_and_restarted() {
+        let (mut manager, clock, launcher) = manager_with_test_builtin(TestBuiltin::Crashing);
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+        wait_until_exited(&mut manager, program);
+
+        assert!(manager.running().is_empty());
+        assert_eq!(manager.restart_delay(program), Some(Duration::from_secs(1)));
+        assert!(manager.poll_restarts().is_empty());
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(manager.poll_restarts(), vec![program]);
+        assert_eq!(launcher.launches(), 2);
+
+        manager.stop_all();
+    }
+
+    #[test]
+    fn reap_returns_exit_status_of_exited_processes() {
+        let (mut manager, _, _) = manager_with_test_builtin(TestBuiltin::Crashing);
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+
+        // Poll without querying the program itself.
+        let mut reaped = Vec::new();
+        for _ in 0..200 {
+            reaped = manager.reap();
+            if !reaped.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(reaped.len(), 1);
+        assert_eq!(reaped[0].0, program);
+        assert!(!reaped[0].1.success());
+        assert!(manager.restart_delay(program).is_some());
+
+        // Exited processes are only reported once.
+        assert!(manager.reap().is_empty());
+    }
+
+    #[test]
+    fn stopped_process_can_be_started_again() {
+        let (mut manager, _, launcher) = manager_with_test_builtin(TestBuiltin::LongRunning);
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+        let first_pid = manager.status(program).pid;
+        manager.stop(program).unwrap();
+
+        // The stopped process is reaped rather than restarted.
+        assert!(manager.poll_restarts().is_empty());
+        assert_eq!(launcher.launches(), 1);
+
+        manager.start(program).unwrap();
+        assert!(manager.is_running(program));
+        assert_ne!(manager.status(program).pid, first_pid);
+        assert_eq!(launcher.launches(), 2);
+
+        manager.stop_all();
+        assert!(manager.running().is_empty());
+    }
+
+    #[test]
+    fn drop_is_bounded_by_stop_timeout() {
+        let launcher = Arc::new(TestLauncher::new(TestBuiltin::IgnoringStop));
+        let mut manager = ProcessManager::new().with_launcher(launcher);
+        let program = BuiltinProgram::Zebar;
+
+        // The grace period alone would outlast the stop timeout.
+        manager.set_stop_grace_period(Duration::from_secs(30));
+        manager.set_stop_timeout(program, Duration::from_millis(500));
+        manager.start(program).unwrap();
+
+        // Give the stand-in time to start ignoring requests to exit.
+        std::thread::sleep(Duration::from_millis(200));
+
+        let started_at = Instant::now();
+        drop(manager);
+
+        assert!(started_at.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn repeated_crashes_back_off_restarts() {
+        let (mut manager, clock, launcher) = manager_with_test_builtin(TestBuiltin::Crashing);
+        let program = BuiltinProgram::Zebar;
+
+        manager.start(program).unwrap();
+
+        for (launches, expected_secs) in [(1, 1), (2, 2), (3, 4)] {
+            wait_until_exited(&mut manager, program);
+            assert_eq!(launcher.launches(), launches);
+            assert_eq!(manager.restart_delay(program), Some(Duration::from_secs(expected_secs)));
+
+            clock.advance(Duration::from_secs(expected_secs));
+            assert_eq!(manager.poll_restarts(), vec![program]);
+        }
+
+        // Stopping cancels the restart scheduled after the last crash.
+        wait_until_exited(&mut manager, program);
+        manager.stop(program).unwrap();
+        assert_eq!(manager.restart_delay(program), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Resolution of process trees from a snapshot of running processes.
+//!
+//! Parent PIDs in a snapshot can be stale: a parent may have exited and
+//! its PID been recycled by an unrelated process. Creation times are used
+//! to tell real children apart from processes that merely point at a
+//! recycled PID.
+
+use std::collections::HashSet;
+
+/// A process in a snapshot of running processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProcessEntry {
+    pub pid: u32,
+    pub parent_pid: u32,
+    /// Creation time of the process, if it could be queried.
+    pub created_at: Option<u64>,
+}
+
+impl ProcessEntry {
+    /// Whether this process can be a child of `parent`. A child can't
+    /// have been created before its parent, so an older process that
+    /// points at the parent's PID is left over from a previous owner of
+    /// that PID.
+    fn is_child_of(&self, parent: &ProcessEntry) -> bool {
+        self.parent_pid == parent.pid
+            && self.pid != parent.pid
+            && match (self.created_at, parent.created_at) {
+                (Some(created_at), Some(parent_created_at)) => created_at >= parent_created_at,
+                _ => true,
+            }
+    }
+}
+
+/// Get the PIDs of a process and its ancestors, which must never be
+/// terminated as part of a process tree.
+pub(crate) fn protected_pids(own_pid: u32, entries: &[ProcessEntry]) -> HashSet<u32> {
+    let mut protected = HashSet::from([own_pid]);
+    let mut current = entries.iter().find(|entry| entry.pid == own_pid);
+
+    while let Some(entry) = current {
+        // Stop on cycles in the parent relationship.
+        if !protected.insert(entry.parent_pid) {
+            break;
+        }
+
+        current = entries
+            .iter()
+            .find(|parent| parent.pid == entry.parent_pid)
+            .filter(|parent| entry.is_child_of(parent));
+    }
+
+    protected
+}
+
+/// Get the processes in the tree rooted at `root`, with children ordered
+/// before their parents such that they can be terminated in order.
+///
+/// Each process is visited at most once, and protected processes are
+/// skipped along with their descendants.
+pub(crate) fn process_tree(root: ProcessEntry, entries: &[ProcessEntry], protected: &HashSet<u32>) -> Vec<ProcessEntry> {
+    let mut visited = HashSet::new();
+    let mut tree = Vec::new();
+    collect_tree(root, entries, protected, &mut visited, &mut tree);
+    tree
+}
+
+fn collect_tree(
+    process: ProcessEntry,
+    entries: &[ProcessEntry],
+    protected: &HashSet<u32>,
+    visited: &mut HashSet<u32>,
+    tree: &mut Vec<ProcessEntry>,
+) {
+    if protected.contains(&process.pid) || !visited.insert(process.pid) {
+        return;
+    }
+
+    for child in entries.iter().filter(|entry| entry.is_child_of(&process)) {
+        collect_tree(*child, entries, protected, visited, tree);
+    }
+
+    tree.push(process);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent_pid: u32, created_at: u64) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent_pid,
+            created_at: Some(created_at),
+        }
+    }
+
+    fn pids(tree: &[ProcessEntry]) -> Vec<u32> {
+        tree.iter().map(|entry| entry.pid).collect()
+    }
+
+    #[test]
+    fn children_are_ordered_before_parents() {
+        let entries = [entry(10, 1, 100), entry(11, 10, 110), entry(12, 11, 120), entry(13, 10, 130)];
+
+        let tree = process_tree(entries[0], &entries, &HashSet::new());
+        assert_eq!(pids(&tree), vec![12, 11, 13, 10]);
+    }
+
+    #[test]
+    fn skips_processes_older_than_their_parent() {
+        // PID 10 was recycled after the original parent of PID 11 exited.
+        let entries = [entry(11, 10, 50), entry(10, 1, 100), entry(12, 10, 120)];
+
+        let tree = process_tree(entries[1], &entries, &HashSet::new());
+        assert_eq!(pids(&tree), vec![12, 10]);
+    }
+
+    #[test]
+    fn cyclic_parents_terminate() {
+        let entries = [
+            ProcessEntry { pid: 10, parent_pid: 11, created_at: None },
+            ProcessEntry { pid: 11, parent_pid: 10, created_at: None },
+        ];
+
+        let tree = process_tree(entries[0], &entries, &HashSet::new());
+        assert_eq!(pids(&tree), vec![11, 10]);
+    }
+
+    #[test]
+    fn never_includes_own_process_or_ancestors() {
+        // The WM (PID 20) is itself a descendant of PID 10.
+        let entries = [entry(10, 1, 100), entry(20, 10, 200), entry(21, 10, 210), entry(30, 20, 300)];
+        let protected = protected_pids(20, &entries);
+
+        assert!(protected.contains(&20));
+        assert!(protected.contains(&10));
+
+        let tree = process_tree(entries[0], &entries, &protected);
+        assert!(tree.is_empty());
+
+        let tree = process_tree(entries[2], &entries, &protected);
+        assert_eq!(pids(&tree), vec![21]);
+    }
+}
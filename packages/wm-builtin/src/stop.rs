@@ -0,0 +1,307 @@
+//! Graceful shutdown of builtin processes.
+//!
+//! A process is first asked to exit (`WM_CLOSE` on Windows, `SIGTERM` on
+//! unix) and is forcibly terminated if it's still running once the grace
+//! period has passed. Waiting for a terminated process to be reaped is
+//! bounded as well, so that a process stuck in the kernel can't hang the
+//! WM.
+
+use std::io;
+use std::process::{Child, ExitStatus};
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::Clock;
+
+/// How often to check whether a process has exited during the grace
+/// period.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How a process ended up exiting when stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// The process exited on its own within the grace period.
+    Exited,
+    /// The process had to be forcibly terminated.
+    Terminated,
+    /// The process was forcibly terminated, but still hadn't exited once
+    /// the reap timeout had passed.
+    Unreaped,
+}
+
+/// Stop a child process, giving it `grace_period` to exit gracefully.
+///
+/// If the process has to be terminated, it's waited on for at most
+/// `reap_timeout` before giving up on it.
+pub(crate) fn stop_child(child: &mut Child, grace_period: Duration, reap_timeout: Duration, clock: &dyn Clock) -> StopOutcome {
+    if let Err(e) = request_exit(child) {
+        warn!("Failed to request exit of PID {}: {}", child.id(), e);
+    }
+
+    if wait_for_exit(|| child.try_wait(), grace_period, clock) {
+        return StopOutcome::Exited;
+    }
+
+    warn!("PID {} did not exit within {:?}, terminating", child.id(), grace_period);
+
+    if let Err(e) = force_exit(child) {
+        warn!("Failed to terminate PID {}: {}", child.id(), e);
+    }
+
+    // Reap the process so it doesn't linger as a zombie.
+    if !wait_for_exit(|| child.try_wait(), reap_timeout, clock) {
+        warn!("PID {} was not reaped within {:?} of terminating it", child.id(), reap_timeout);
+        return StopOutcome::Unreaped;
+    }
+
+    StopOutcome::Terminated
+}
+
+/// Wait up to `timeout` for a process to exit, polling it with
+/// `try_wait`. Returns whether it exited.
+fn wait_for_exit(mut try_wait: impl FnMut() -> io::Result<Option<ExitStatus>>, timeout: Duration, clock: &dyn Clock) -> bool {
+    let deadline = clock.deadline(timeout);
+
+    loop {
+        match try_wait() {
+            Ok(Some(_)) => return true,
+            Ok(None) if !clock.is_due(deadline) => clock.sleep(EXIT_POLL_INTERVAL),
+            // Treat errors as the process still running, so that it gets
+            // forcibly terminated.
+            _ => return false,
+        }
+    }
+}
+
+/// Ask the process to exit by closing its top-level windows.
+#[cfg(windows)]
+fn request_exit(child: &Child) -> Result<()> {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM, TRUE, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE,
+    };
+
+    unsafe extern "system" fn close_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let mut window_pid = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut window_pid));
+
+        if window_pid as isize == lparam.0 {
+            let _ = PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+
+        TRUE
+    }
+
+    unsafe { EnumWindows(Some(close_window), LPARAM(child.id() as isize))? };
+    Ok(())
+}
+
+/// Ask the process to exit by sending `SIGTERM`.
+#[cfg(unix)]
+fn request_exit(child: &Child) -> Result<()> {
+    let pid = libc::pid_t::try_from(child.id())?;
+
+    if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+/// Forcibly terminate the process and all of its children.
+#[cfg(windows)]
+fn force_exit(child: &mut Child) -> Result<()> {
+    if let Err(e) = terminate_process_tree(child.id()) {
+        warn!("Failed to terminate process tree: {}", e);
+        // Fall back to kill
+        child.kill()?;
+    }
+
+    Ok(())
+}
+
+/// Forcibly terminate the process by sending `SIGKILL`.
+#[cfg(unix)]
+fn force_exit(child: &mut Child) -> Result<()> {
+    child.kill()?;
+    Ok(())
+}
+
+/// Terminate a process and all its children on Windows.
+///
+/// The process tree is resolved from a snapshot, so PIDs in it may have
+/// been recycled by the time they're terminated. Each process is only
+/// terminated if its creation time still matches the snapshot, and the
+/// WM's own process and its ancestors are never terminated.
+#[cfg(windows)]
+fn terminate_process_tree(pid: u32) -> Result<()> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32First, Process32Next,
+        PROCESSENTRY32, TH32CS_SNAPPROCESS,
+    };
+
+    use crate::process_tree::{process_tree, protected_pids, ProcessEntry};
+
+    let mut entries = Vec::new();
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
+
+        let mut entry = PROCESSENTRY32 {
+            dwSize: std::mem::size_of::<PROCESSENTRY32>() as u32,
+            ..Default::default()
+        };
+
+        if Process32First(snapshot, &mut entry).is_ok() {
+            loop {
+                entries.push(ProcessEntry {
+                    pid: entry.th32ProcessID,
+                    parent_pid: entry.th32ParentProcessID,
+                    created_at: process_creation_time(entry.th32ProcessID),
+                });
+
+                if Process32Next(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+
+    let root = entries.iter().find(|entry| entry.pid == pid).copied().unwrap_or(ProcessEntry {
+        pid,
+        parent_pid: 0,
+        created_at: None,
+    });
+
+    let protected = protected_pids(std::process::id(), &entries);
+
+    if protected.contains(&pid) {
+        anyhow::bail!("Refusing to terminate PID {} since it's the WM or one of its ancestors", pid);
+    }
+
+    for process in process_tree(root, &entries, &protected) {
+        terminate_if_unchanged(&process);
+    }
+
+    Ok(())
+}
+
+/// Get the creation time of a process as a `FILETIME` value.
+#[cfg(windows)]
+fn process_creation_time(pid: u32) -> Option<u64> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let created_at = handle_creation_time(handle);
+        let _ = CloseHandle(handle);
+        created_at
+    }
+}
+
+#[cfg(windows)]
+unsafe fn handle_creation_time(handle: windows::Win32::Foundation::HANDLE) -> Option<u64> {
+    use windows::Win32::Foundation::FILETIME;
+    use windows::Win32::System::Threading::GetProcessTimes;
+
+    let mut created_at = FILETIME::default();
+    let mut exited_at = FILETIME::default();
+    let mut kernel_time = FILETIME::default();
+    let mut user_time = FILETIME::default();
+
+    GetProcessTimes(handle, &mut created_at, &mut exited_at, &mut kernel_time, &mut user_time).ok()?;
+
+    Some((u64::from(created_at.dwHighDateTime) << 32) | u64::from(created_at.dwLowDateTime))
+}
+
+/// Terminate a process from a snapshot, unless its PID has since been
+/// recycled by another process.
+#[cfg(windows)]
+fn terminate_if_unchanged(process: &crate::process_tree::ProcessEntry) {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    };
+
+    unsafe {
+        let Ok(handle) = OpenProcess(PROCESS_TERMINATE | PROCESS_QUERY_LIMITED_INFORMATION, false, process.pid) else {
+            return;
+        };
+
+        // The open handle keeps the PID from being recycled again, so the
+        // process can't change between this check and terminating it.
+        let created_at = handle_creation_time(handle);
+
+        if process.created_at.is_none() || created_at == process.created_at {
+            let _ = TerminateProcess(handle, 0);
+        } else {
+            warn!("Not terminating PID {} since it was recycled by another process", process.pid);
+        }
+
+        let _ = CloseHandle(handle);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+    use crate::{FakeClock, SystemClock};
+
+    /// Spawn a shell that runs `on_term` when it receives `SIGTERM`.
+    fn spawn_trapping_child(on_term: &str) -> Child {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(format!("trap '{}' TERM; echo ready; while :; do sleep 0.05; done", on_term))
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // Wait until the trap is installed.
+        let mut ready = [0; 6];
+        std::io::Read::read_exact(child.stdout.as_mut().unwrap(), &mut ready).unwrap();
+
+        child
+    }
+
+    #[test]
+    fn sigterm_is_sent_before_kill() {
+        let marker = std::env::temp_dir().join(format!("wm-builtin-stop-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        let mut child = spawn_trapping_child(&format!("touch {}; exit 0", marker.display()));
+        let outcome = stop_child(&mut child, Duration::from_secs(5), Duration::from_secs(5), &SystemClock);
+
+        assert_eq!(outcome, StopOutcome::Exited);
+        assert!(marker.exists());
+        let _ = std::fs::remove_file(&marker);
+    }
+
+    #[test]
+    fn kills_child_that_ignores_sigterm() {
+        let mut child = spawn_trapping_child("");
+        let outcome = stop_child(&mut child, Duration::from_millis(200), Duration::from_secs(5), &SystemClock);
+
+        assert_eq!(outcome, StopOutcome::Terminated);
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[test]
+    fn gives_up_on_process_that_is_never_reaped() {
+        let clock = FakeClock::new();
+        let start = clock.now();
+
+        // Simulates a process stuck in an uninterruptible state.
+        let exited = wait_for_exit(|| Ok(None), Duration::from_secs(5), &clock);
+
+        assert!(!exited);
+        assert!(clock.now() - start >= Duration::from_secs(5));
+    }
+}
@@ -1,12 +1,14 @@
-use std::{iter, path::PathBuf};
+use std::{iter, path::PathBuf, str::FromStr};
 
-use clap::{error::KindFormatter, Args, Parser, ValueEnum};
+use anyhow::Context;
+use clap::{error::KindFormatter, ArgGroup, Args, Parser, ValueEnum};
 use serde::{Deserialize, Deserializer, Serialize};
 use tracing::Level;
 use uuid::Uuid;
 
 use crate::{
-  Delta, Direction, LengthValue, OpacityValue, TilingDirection,
+  Delta, Direction, LengthValue, MatchType, OpacityValue, SplitLayout,
+  TilingDirection, WindowMatchConfig,
 };
 
 const VERSION: &str = env!("VERSION_NUMBER");
@@ -22,6 +24,13 @@ pub enum AppCommand {
     #[clap(short = 'c', long = "config", value_hint = clap::ValueHint::FilePath)]
     config_path: Option<PathBuf>,
 
+    /// Replaces an already running instance of the window manager.
+    ///
+    /// The running instance is told to exit, and the window manager
+    /// starts once it has.
+    #[clap(long)]
+    replace: bool,
+
     #[clap(flatten)]
     verbosity: Verbosity,
   },
@@ -47,6 +56,71 @@ pub enum AppCommand {
     command: InvokeCommand,
   },
 
+  /// Starts a builtin embedded program (e.g. `zebar`), or all available
+  /// builtin programs if no name is given.
+  ///
+  /// Requires an already running instance of the window manager.
+  BuiltinStart {
+    /// Name of the builtin program.
+    name: Option<String>,
+  },
+
+  /// Stops a builtin embedded program.
+  ///
+  /// Requires an already running instance of the window manager.
+  BuiltinStop {
+    /// Name of the builtin program.
+    #[clap(required = true)]
+    name: String,
+  },
+
+  /// Restarts a builtin embedded program, or starts it if it isn't
+  /// running.
+  ///
+  /// Requires an already running instance of the window manager.
+  BuiltinRestart {
+    /// Name of the builtin program.
+    #[clap(required = true)]
+    name: String,
+  },
+
+  /// Stops a builtin embedded program if it's running, and re-extracts
+  /// its executable from scratch. Useful for recovering from a corrupted
+  /// extraction.
+  ///
+  /// Requires an already running instance of the window manager.
+  BuiltinReextract {
+    /// Name of the builtin program.
+    #[clap(required = true)]
+    name: String,
+
+    /// Starts the program again once re-extracted.
+    #[clap(long)]
+    restart: bool,
+  },
+
+  /// Outputs the state of a builtin embedded program, or of all builtin
+  /// programs if no name is given.
+  ///
+  /// Requires an already running instance of the window manager.
+  BuiltinStatus {
+    /// Name of the builtin program.
+    name: Option<String>,
+  },
+
+  /// Moves multiple workspaces between monitors at once, and outputs the
+  /// resulting workspaces of each monitor.
+  ///
+  /// All moves are validated before any are applied, so either every
+  /// move succeeds or none do.
+  ///
+  /// Requires an already running instance of the window manager.
+  MoveWorkspaces {
+    /// Moves in the form `<WORKSPACE_ID>=<MONITOR_ID>`.
+    #[clap(long = "move", required = true, num_args = 1..)]
+    moves: Vec<WorkspaceMove>,
+  },
+
   /// Subscribes to one or more WM events (e.g. `window_close`), and
   /// continuously outputs the incoming events.
   ///
@@ -55,6 +129,10 @@ pub enum AppCommand {
     /// WM event(s) to subscribe to.
     #[clap(short = 'e', long, value_enum, num_args = 1..)]
     events: Vec<SubscribableEvent>,
+
+    /// Number of recently emitted events to replay before new events.
+    #[clap(long)]
+    replay: Option<usize>,
   },
 
   /// Unsubscribes from a prior event subscription.
@@ -76,6 +154,7 @@ impl AppCommand {
     if args.len() == 1 {
       AppCommand::Start {
         config_path: None,
+        replace: false,
         verbosity: Verbosity {
           verbose: false,
           quiet: false,
@@ -123,12 +202,34 @@ pub enum QueryCommand {
   Focused,
   /// Outputs the tiling direction of the focused container.
   TilingDirection,
+  /// Outputs how often each command has run and how long it took.
+  ///
+  /// Requires `general.metrics` to be enabled in the user config.
+  Metrics,
+  /// Outputs the monitor at a point, or the monitor with the largest
+  /// intersection with a rect if a size is given.
+  MonitorAt {
+    #[clap(long, allow_hyphen_values = true)]
+    x: i32,
+
+    #[clap(long, allow_hyphen_values = true)]
+    y: i32,
+
+    #[clap(long, requires = "height")]
+    width: Option<i32>,
+
+    #[clap(long, requires = "width")]
+    height: Option<i32>,
+  },
   /// Outputs all monitors.
   Monitors,
   /// Outputs all windows.
   Windows,
   /// Outputs all active workspaces.
   Workspaces,
+  /// Outputs the entire container tree, starting from the root
+  /// container.
+  Tree,
   /// Outputs whether the window manager is paused.
   Paused,
 }
@@ -139,6 +240,7 @@ pub enum SubscribableEvent {
   All,
   ApplicationExiting,
   BindingModesChanged,
+  BuiltinExited,
   FocusChanged,
   FocusedContainerMoved,
   MonitorAdded,
@@ -146,8 +248,13 @@ pub enum SubscribableEvent {
   MonitorRemoved,
   TilingDirectionChanged,
   UserConfigChanged,
+  WindowAlwaysOnTopChanged,
+  WindowCloseRequested,
+  WindowLayoutChanged,
   WindowManaged,
+  WindowMaximizedChanged,
   WindowUnmanaged,
+  WindowUrgent,
   WorkspaceActivated,
   WorkspaceDeactivated,
   WorkspaceUpdated,
@@ -157,12 +264,103 @@ pub enum SubscribableEvent {
 #[derive(Clone, Debug, Parser, PartialEq, Serialize)]
 pub enum InvokeCommand {
   AdjustBorders(InvokeAdjustBordersCommand),
-  Close,
+  AdjustMasterRatio {
+    /// Amount to grow the master area of the subject container's
+    /// workspace by (e.g. `5%` or `-50px`).
+    #[clap(required = true, allow_hyphen_values = true)]
+    amount: LengthValue,
+  },
+  BalanceWindows {
+    /// Whether to balance every container in the workspace, rather than
+    /// only the siblings of the subject container.
+    #[clap(long, action)]
+    recursive: bool,
+  },
+  /// Close the subject window by asking it to close. Invoking this again
+  /// with `--force` once `window_behavior.force_close.timeout_ms` has
+  /// passed terminates the window's process instead.
+  Close {
+    /// Terminate the window's process if it was already asked to close,
+    /// and hasn't closed once the timeout has passed.
+    #[clap(long, action)]
+    force: bool,
+
+    /// Terminate the window's process automatically if it hasn't closed
+    /// once the timeout has passed.
+    #[clap(long, action)]
+    kill: bool,
+
+    /// Allow terminating a process that owns other managed windows.
+    #[clap(long, action)]
+    force_all: bool,
+  },
+  /// Move the subject workspace to the next monitor, wrapping around to
+  /// the first monitor after the last.
+  CycleWorkspaceMonitor,
+  /// Float every managed window that matches the given criteria, or tile
+  /// them with `--tiling`.
+  FloatMatching(InvokeFloatMatchingCommand),
   Focus(InvokeFocusCommand),
+  /// Focus the previously focused window, regardless of its workspace
+  /// or monitor. Running it again switches back.
+  FocusLast,
+  /// Focus the next window of the focused workspace, wrapping around
+  /// after the last.
+  FocusNextWindow {
+    /// Include minimized windows, restoring the focused one.
+    #[clap(long, action)]
+    include_minimized: bool,
+  },
+  /// Focus the `n`th most recently focused window, where 1 is the
+  /// previously focused window.
+  FocusNthLast {
+    #[clap(required = true)]
+    n: usize,
+  },
+  /// Focus the previous window of the focused workspace, wrapping around
+  /// before the first.
+  FocusPrevWindow {
+    /// Include minimized windows, restoring the focused one.
+    #[clap(long, action)]
+    include_minimized: bool,
+  },
+  /// Focus the window that most recently requested attention.
+  FocusUrgent,
+  /// Move every managed window onto the focused workspace.
+  GatherAllWindows,
   Ignore,
+  /// Stop managing the subject window, and restore its position and
+  /// style from before it was managed.
+  IgnoreWindow {
+    /// Keep ignoring windows with the same class and executable after
+    /// restarts.
+    #[clap(long, action)]
+    persist: bool,
+  },
+  /// Set where the next tiling window on the workspace gets inserted,
+  /// relative to the subject container.
+  Insert(InvokeInsertCommand),
+  Layout {
+    /// Layout to use for the parent split container of the subject
+    /// container.
+    #[clap(required = true)]
+    layout: SplitLayout,
+  },
+  /// Start managing a window that was ignored, e.g. via the
+  /// `ignore-window` command. Targets the window under the cursor unless
+  /// a window handle is given.
+  ManageWindow {
+    #[clap(long)]
+    window_handle: Option<isize>,
+  },
   Move(InvokeMoveCommand),
+  /// Move the subject window to the next slot in its workspace, i.e. the
+  /// slot set via `insert`, or otherwise after the next tiling window.
+  MoveToNextSlot,
   MoveWorkspace(InvokeMoveWorkspaceCommand),
+  MoveWindowToMonitor(InvokeMoveWindowToMonitorCommand),
   Position(InvokePositionCommand),
+  PromoteToMaster,
   Resize(InvokeResizeCommand),
   SetFloating {
     #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
@@ -201,14 +399,24 @@ pub enum InvokeCommand {
     #[clap(long, action)]
     hide_window: bool,
 
+    /// Workspace to move the launched program's first window to.
+    #[clap(long)]
+    workspace: Option<String>,
+
     #[clap(required = true, trailing_var_arg = true)]
     command: Vec<String>,
   },
   // Reuse `InvokeResizeCommand` struct.
   Size(InvokeResizeCommand),
-  /// Start a builtin embedded program (e.g., zebar).
+  /// Start a builtin embedded program (e.g., zebar), or all available
+  /// builtin programs if no name is given.
   StartBuiltin {
     /// Name of the builtin program to start (e.g., "zebar").
+    name: Option<String>,
+  },
+  /// Restart a builtin embedded program.
+  RestartBuiltin {
+    /// Name of the builtin program to restart (e.g., "zebar").
     #[clap(required = true)]
     name: String,
   },
@@ -218,6 +426,16 @@ pub enum InvokeCommand {
     #[clap(required = true)]
     name: String,
   },
+  SwapAllMonitorWorkspaces {
+    /// Indices of the two monitors to swap workspaces between (0-based).
+    #[clap(long, required = true, num_args = 2, value_names = ["MONITOR_A", "MONITOR_B"])]
+    monitors: Vec<usize>,
+  },
+  SwapWindow {
+    /// Direction of the tiling window to swap with.
+    #[clap(long, required = true)]
+    direction: Direction,
+  },
   ToggleFloating {
     #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
     shown_on_top: Option<bool>,
@@ -232,7 +450,16 @@ pub enum InvokeCommand {
     #[clap(long, default_missing_value = "true", require_equals = true, num_args = 0..=1)]
     maximized: Option<bool>,
   },
+  ToggleAlwaysOnTop,
   ToggleMinimized,
+  ToggleMonocle,
+  /// Toggle standby for a monitor, which hides its workspaces without
+  /// moving them to another monitor.
+  ToggleMonitorStandby {
+    /// Index of the monitor (0-based).
+    #[clap(long, required = true)]
+    monitor: usize,
+  },
   ToggleTiling,
   ToggleTilingDirection,
   SetTilingDirection {
@@ -263,9 +490,83 @@ pub enum InvokeCommand {
   WmExit,
   WmRedraw,
   WmReloadConfig,
+  WmToggleFocusFollowsCursor,
+  WmToggleFullscreenCoversReservedSpace,
+  WmToggleOpacityEffect,
   WmTogglePause,
 }
 
+impl InvokeCommand {
+  /// Gets the name of the command as it's written in the CLI and user
+  /// config (e.g. `toggle-floating`).
+  #[must_use]
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::AdjustBorders(_) => "adjust-borders",
+      Self::AdjustMasterRatio { .. } => "adjust-master-ratio",
+      Self::BalanceWindows { .. } => "balance-windows",
+      Self::Close { .. } => "close",
+      Self::CycleWorkspaceMonitor => "cycle-workspace-monitor",
+      Self::FloatMatching(_) => "float-matching",
+      Self::Focus(_) => "focus",
+      Self::FocusLast => "focus-last",
+      Self::FocusNextWindow { .. } => "focus-next-window",
+      Self::FocusNthLast { .. } => "focus-nth-last",
+      Self::FocusPrevWindow { .. } => "focus-prev-window",
+      Self::FocusUrgent => "focus-urgent",
+      Self::GatherAllWindows => "gather-all-windows",
+      Self::Ignore => "ignore",
+      Self::IgnoreWindow { .. } => "ignore-window",
+      Self::Insert(_) => "insert",
+      Self::Layout { .. } => "layout",
+      Self::ManageWindow { .. } => "manage-window",
+      Self::Move(_) => "move",
+      Self::MoveToNextSlot => "move-to-next-slot",
+      Self::MoveWorkspace(_) => "move-workspace",
+      Self::MoveWindowToMonitor(_) => "move-window-to-monitor",
+      Self::Position(_) => "position",
+      Self::PromoteToMaster => "promote-to-master",
+      Self::Resize(_) => "resize",
+      Self::SetFloating { .. } => "set-floating",
+      Self::SetFullscreen { .. } => "set-fullscreen",
+      Self::SetMinimized => "set-minimized",
+      Self::SetTiling => "set-tiling",
+      Self::SetTitleBarVisibility { .. } => "set-title-bar-visibility",
+      Self::SetTransparency(_) => "set-transparency",
+      Self::ShellExec { .. } => "shell-exec",
+      Self::Size(_) => "size",
+      Self::StartBuiltin { .. } => "start-builtin",
+      Self::RestartBuiltin { .. } => "restart-builtin",
+      Self::StopBuiltin { .. } => "stop-builtin",
+      Self::SwapAllMonitorWorkspaces { .. } => {
+        "swap-all-monitor-workspaces"
+      }
+      Self::SwapWindow { .. } => "swap-window",
+      Self::ToggleFloating { .. } => "toggle-floating",
+      Self::ToggleFullscreen { .. } => "toggle-fullscreen",
+      Self::ToggleAlwaysOnTop => "toggle-always-on-top",
+      Self::ToggleMinimized => "toggle-minimized",
+      Self::ToggleMonocle => "toggle-monocle",
+      Self::ToggleMonitorStandby { .. } => "toggle-monitor-standby",
+      Self::ToggleTiling => "toggle-tiling",
+      Self::ToggleTilingDirection => "toggle-tiling-direction",
+      Self::SetTilingDirection { .. } => "set-tiling-direction",
+      Self::WmCycleFocus { .. } => "wm-cycle-focus",
+      Self::WmDisableBindingMode { .. } => "wm-disable-binding-mode",
+      Self::WmEnableBindingMode { .. } => "wm-enable-binding-mode",
+      Self::WmExit => "wm-exit",
+      Self::WmRedraw => "wm-redraw",
+      Self::WmReloadConfig => "wm-reload-config",
+      Self::WmToggleFocusFollowsCursor => "wm-toggle-focus-follows-cursor",
+      Self::WmToggleFullscreenCoversReservedSpace => {
+        "wm-toggle-fullscreen-covers-reserved-space"
+      }
+      Self::WmToggleOpacityEffect => "wm-toggle-opacity-effect",
+      Self::WmTogglePause => "wm-toggle-pause",
+    }
+  }
+}
+
 impl<'de> Deserialize<'de> for InvokeCommand {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -309,6 +610,46 @@ pub struct InvokeAdjustBordersCommand {
   pub left: Option<LengthValue>,
 }
 
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+pub struct InvokeFloatMatchingCommand {
+  /// Process name of the windows to match (e.g. `chrome`).
+  #[clap(long, required_unless_present_any = ["class", "title"])]
+  pub process: Option<String>,
+
+  /// Class name of the windows to match.
+  #[clap(long)]
+  pub class: Option<String>,
+
+  /// Text that the title of the windows to match includes.
+  #[clap(long)]
+  pub title: Option<String>,
+
+  /// Tile the matching windows instead of floating them.
+  #[clap(long)]
+  pub tiling: bool,
+}
+
+impl InvokeFloatMatchingCommand {
+  /// Gets the window matcher for the given criteria, in the same form
+  /// as the `match` of a window rule.
+  #[must_use]
+  pub fn match_config(&self) -> WindowMatchConfig {
+    let equals = |value: &Option<String>| {
+      value.clone().map(|equals| MatchType::Equals { equals })
+    };
+
+    WindowMatchConfig {
+      window_process: equals(&self.process),
+      window_class: equals(&self.class),
+      window_title: self
+        .title
+        .clone()
+        .map(|includes| MatchType::Includes { includes }),
+      ..WindowMatchConfig::default()
+    }
+  }
+}
+
 #[derive(Args, Clone, Debug, PartialEq, Serialize)]
 #[group(required = true, multiple = false)]
 #[allow(clippy::struct_excessive_bools)]
@@ -348,10 +689,36 @@ pub struct InvokeFocusCommand {
 
   #[clap(long)]
   pub recent_workspace: bool,
+
+  /// Focus the next child of the nearest stacking or tabbed container.
+  #[clap(long)]
+  pub next_in_stack: bool,
+
+  /// Focus the previous child of the nearest stacking or tabbed
+  /// container.
+  #[clap(long)]
+  pub prev_in_stack: bool,
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Serialize)]
-#[group(required = true, multiple = false)]
+#[group(skip)]
+#[command(group(
+  ArgGroup::new("move_target")
+    .required(true)
+    .multiple(false)
+    .args([
+      "direction",
+      "workspace_in_direction",
+      "workspace",
+      "next_active_workspace",
+      "prev_active_workspace",
+      "next_workspace",
+      "prev_workspace",
+      "next_active_workspace_on_monitor",
+      "prev_active_workspace_on_monitor",
+      "recent_workspace",
+    ])
+))]
 #[allow(clippy::struct_excessive_bools)]
 pub struct InvokeMoveCommand {
   /// Direction to move the window.
@@ -386,6 +753,18 @@ pub struct InvokeMoveCommand {
 
   #[clap(long)]
   pub recent_workspace: bool,
+
+  /// Whether focus follows the window to its new workspace or monitor.
+  /// Defaults to `general.move_follows_window`, or if that isn't set, to
+  /// only following moves via `--direction`.
+  #[clap(
+    long,
+    requires = "move_target",
+    default_missing_value = "true",
+    require_equals = true,
+    num_args = 0..=1
+  )]
+  pub follow: Option<bool>,
 }
 
 #[derive(Args, Clone, Debug, PartialEq, Serialize)]
@@ -421,6 +800,53 @@ pub struct InvokePositionCommand {
   pub y_pos: Option<i32>,
 }
 
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+pub struct InvokeMoveWindowToMonitorCommand {
+  /// Direction of the target monitor.
+  #[clap(
+    long,
+    required_unless_present = "monitor",
+    conflicts_with = "monitor"
+  )]
+  pub direction: Option<Direction>,
+
+  /// Index of the target monitor (0-based).
+  #[clap(long)]
+  pub monitor: Option<usize>,
+
+  /// Whether focus follows the window to the target monitor. Defaults to
+  /// `general.move_follows_window`, or if that isn't set, to not
+  /// following.
+  #[clap(
+    long,
+    default_missing_value = "true",
+    require_equals = true,
+    num_args = 0..=1
+  )]
+  pub follow: Option<bool>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq, Serialize)]
+#[group(required = true, multiple = false)]
+pub struct InvokeInsertCommand {
+  /// Side of the subject container to insert at. The container gets
+  /// split if this is perpendicular to its parent's tiling direction.
+  #[clap(long)]
+  pub direction: Option<Direction>,
+
+  /// Insert as a sibling before the subject container.
+  #[clap(long)]
+  pub before: bool,
+
+  /// Insert as a sibling after the subject container.
+  #[clap(long)]
+  pub after: bool,
+
+  /// Clear the pending insertion hint of the workspace.
+  #[clap(long)]
+  pub cancel: bool,
+}
+
 #[derive(Args, Clone, Debug, PartialEq, Serialize)]
 #[group(required = true, multiple = false)]
 pub struct InvokeMoveWorkspaceCommand {
@@ -432,3 +858,33 @@ pub struct InvokeMoveWorkspaceCommand {
   #[clap(long)]
   pub monitor: Option<usize>,
 }
+
+/// A workspace to move to a monitor, identified by their container IDs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMove {
+  pub workspace_id: Uuid,
+  pub monitor_id: Uuid,
+}
+
+impl FromStr for WorkspaceMove {
+  type Err = anyhow::Error;
+
+  /// Parses a move in the form `<WORKSPACE_ID>=<MONITOR_ID>`.
+  fn from_str(unparsed: &str) -> anyhow::Result<Self> {
+    let (workspace_id, monitor_id) =
+      unparsed.split_once('=').with_context(|| {
+        format!(
+          "Not a valid workspace move '{unparsed}'. Must be of format \
+          '<WORKSPACE_ID>=<MONITOR_ID>'."
+        )
+      })?;
+
+    Ok(Self {
+      workspace_id: Uuid::parse_str(workspace_id.trim())
+        .context("Invalid workspace ID.")?,
+      monitor_id: Uuid::parse_str(monitor_id.trim())
+        .context("Invalid monitor ID.")?,
+    })
+  }
+}
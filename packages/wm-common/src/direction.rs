@@ -1,9 +1,9 @@
 use std::str::FromStr;
 
 use anyhow::bail;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Direction {
   Left,
@@ -17,7 +17,7 @@ impl Direction {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::Direction;
+  /// # use wm_common::Direction;
   /// let dir = Direction::Left.inverse();
   /// assert_eq!(dir, Direction::Right);
   /// ```
@@ -39,7 +39,7 @@ impl FromStr for Direction {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::Direction;
+  /// # use wm_common::Direction;
   /// # use std::str::FromStr;
   /// let dir = Direction::from_str("left");
   /// assert_eq!(dir.unwrap(), Direction::Left);
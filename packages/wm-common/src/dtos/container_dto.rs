@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::{
   MonitorDto, RootContainerDto, SplitContainerDto, WindowDto, WorkspaceDto,
@@ -16,3 +17,17 @@ pub enum ContainerDto {
   Split(SplitContainerDto),
   Window(WindowDto),
 }
+
+impl ContainerDto {
+  /// ID of the container.
+  #[must_use]
+  pub fn id(&self) -> Uuid {
+    match self {
+      Self::Root(root) => root.id,
+      Self::Monitor(monitor) => monitor.id,
+      Self::Workspace(workspace) => workspace.id,
+      Self::Split(split) => split.id,
+      Self::Window(window) => window.id,
+    }
+  }
+}
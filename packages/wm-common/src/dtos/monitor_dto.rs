@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::ContainerDto;
+use super::{ContainerDto, WorkspaceDto};
 use crate::Rect;
 
 /// User-friendly representation of a monitor.
@@ -26,4 +26,109 @@ pub struct MonitorDto {
   pub device_path: Option<String>,
   pub hardware_id: Option<String>,
   pub working_rect: Rect,
+  pub is_primary: bool,
+  #[serde(default)]
+  pub is_standby: bool,
+}
+
+impl MonitorDto {
+  /// Gets the monitor's workspaces in tree order.
+  pub fn workspaces(&self) -> impl Iterator<Item = &WorkspaceDto> {
+    self.children.iter().filter_map(|child| match child {
+      ContainerDto::Workspace(workspace) => Some(workspace),
+      _ => None,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{TilingDirection, WorkspaceLayout};
+
+  fn monitor_with_workspace() -> MonitorDto {
+    let monitor_id = Uuid::new_v4();
+    let workspace_id = Uuid::new_v4();
+
+    MonitorDto {
+      id: monitor_id,
+      parent_id: None,
+      children: vec![ContainerDto::Workspace(WorkspaceDto {
+        id: workspace_id,
+        name: "1".to_string(),
+        display_name: None,
+        parent_id: Some(monitor_id),
+        children: Vec::new(),
+        child_focus_order: Vec::new(),
+        has_focus: true,
+        is_displayed: true,
+        width: 1920,
+        height: 1040,
+        x: 0,
+        y: 0,
+        tiling_direction: TilingDirection::Horizontal,
+        monocle_window_id: None,
+        layout: WorkspaceLayout::Manual,
+        master_ratio: 0.55,
+        master_count: 1,
+        has_urgent_window: false,
+        insertion_hint: None,
+      })],
+      child_focus_order: vec![workspace_id],
+      has_focus: true,
+      width: 1920,
+      height: 1080,
+      x: 0,
+      y: 0,
+      dpi: 96,
+      scale_factor: 1.0,
+      handle: 0,
+      device_name: "\\\\.\\DISPLAY1".to_string(),
+      device_path: None,
+      hardware_id: None,
+      working_rect: Rect::from_xy(0, 0, 1920, 1040),
+      is_primary: true,
+      is_standby: false,
+    }
+  }
+
+  #[test]
+  fn workspace_without_master_fields_uses_defaults() {
+    let mut json = serde_json::to_value(ContainerDto::Monitor(
+      monitor_with_workspace(),
+    ))
+    .unwrap();
+
+    let workspace = json["children"][0].as_object_mut().unwrap();
+    workspace.remove("masterRatio").unwrap();
+    workspace.remove("masterCount").unwrap();
+
+    let ContainerDto::Monitor(monitor) =
+      serde_json::from_value(json).unwrap()
+    else {
+      panic!("Expected a monitor DTO.");
+    };
+
+    let workspace = monitor.workspaces().next().unwrap();
+    assert!((workspace.master_ratio - 0.55).abs() < f32::EPSILON);
+    assert_eq!(workspace.master_count, 1);
+  }
+
+  #[test]
+  fn monitor_without_standby_field_is_active() {
+    let mut json = serde_json::to_value(ContainerDto::Monitor(
+      monitor_with_workspace(),
+    ))
+    .unwrap();
+
+    json.as_object_mut().unwrap().remove("isStandby").unwrap();
+
+    let ContainerDto::Monitor(monitor) =
+      serde_json::from_value(json).unwrap()
+    else {
+      panic!("Expected a monitor DTO.");
+    };
+
+    assert!(!monitor.is_standby);
+  }
 }
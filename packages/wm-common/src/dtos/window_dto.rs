@@ -22,9 +22,20 @@ pub struct WindowDto {
   pub display_state: DisplayState,
   pub border_delta: RectDelta,
   pub floating_placement: Rect,
+  /// Placement that the window last had while floating, if it has floated
+  /// before.
+  #[serde(default)]
+  pub last_floating_placement: Option<Rect>,
   pub handle: isize,
   pub title: String,
   pub class_name: String,
   pub process_name: String,
   pub active_drag: Option<ActiveDrag>,
+  /// Whether the window is requesting attention.
+  #[serde(default)]
+  pub is_urgent: bool,
+  /// Whether the window is maximized by the OS (e.g. via its maximize
+  /// button). Fullscreen windows can be maximized as well.
+  #[serde(default)]
+  pub is_maximized: bool,
 }
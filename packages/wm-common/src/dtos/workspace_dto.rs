@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::ContainerDto;
-use crate::TilingDirection;
+use crate::{InsertionHint, TilingDirection, WorkspaceLayout};
 
 /// User-friendly representation of a workspace.
 ///
@@ -23,4 +23,21 @@ pub struct WorkspaceDto {
   pub x: i32,
   pub y: i32,
   pub tiling_direction: TilingDirection,
+  /// Tiling window that fills the workspace while its tiling siblings
+  /// are hidden, if monocle is on.
+  #[serde(default)]
+  pub monocle_window_id: Option<Uuid>,
+  #[serde(default)]
+  pub layout: WorkspaceLayout,
+  #[serde(default = "crate::parsed_config::default_master_ratio")]
+  pub master_ratio: f32,
+  #[serde(default = "crate::parsed_config::default_master_count")]
+  pub master_count: usize,
+  /// Whether any window in the workspace is requesting attention.
+  #[serde(default)]
+  pub has_urgent_window: bool,
+  /// Where the next tiling window gets inserted, if set via the `insert`
+  /// command.
+  #[serde(default)]
+  pub insertion_hint: Option<InsertionHint>,
 }
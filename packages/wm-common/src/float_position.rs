@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{LengthValue, Rect};
+
+/// Where a floating window is placed within its workspace.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FloatPosition {
+  /// Aligned to an edge, a corner, or the center of the workspace.
+  Anchor(FloatAnchor),
+
+  /// Offset of the window's top-left corner from the workspace's
+  /// top-left corner, as `[x, y]`.
+  Offset([LengthValue; 2]),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatAnchor {
+  TopLeft,
+  Top,
+  TopRight,
+  Left,
+  Center,
+  Right,
+  BottomLeft,
+  Bottom,
+  BottomRight,
+}
+
+impl FloatPosition {
+  /// Moves `rect` to this position within `bounds`.
+  ///
+  /// Pixel offsets are scaled by `scale_factor`, whereas percentages are
+  /// of the width and height of `bounds`.
+  #[must_use]
+  pub fn apply(
+    &self,
+    rect: &Rect,
+    bounds: &Rect,
+    scale_factor: f32,
+  ) -> Rect {
+    let free_x = bounds.width() - rect.width();
+    let free_y = bounds.height() - rect.height();
+
+    let (x, y) = match self {
+      Self::Offset([x, y]) => (
+        x.to_px(bounds.width(), Some(scale_factor)),
+        y.to_px(bounds.height(), Some(scale_factor)),
+      ),
+      Self::Anchor(anchor) => {
+        let x = match anchor {
+          FloatAnchor::TopLeft
+          | FloatAnchor::Left
+          | FloatAnchor::BottomLeft => 0,
+          FloatAnchor::Top | FloatAnchor::Center | FloatAnchor::Bottom => {
+            free_x / 2
+          }
+          _ => free_x,
+        };
+
+        let y = match anchor {
+          FloatAnchor::TopLeft
+          | FloatAnchor::Top
+          | FloatAnchor::TopRight => 0,
+          FloatAnchor::Left | FloatAnchor::Center | FloatAnchor::Right => {
+            free_y / 2
+          }
+          _ => free_y,
+        };
+
+        (x, y)
+      }
+    };
+
+    rect.translate_to_coordinates(bounds.x() + x, bounds.y() + y)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn anchors_within_bounds() {
+    let rect = Rect::from_xy(0, 0, 400, 600);
+    let bounds = Rect::from_xy(100, 50, 1920, 1000);
+
+    assert_eq!(
+      FloatPosition::Anchor(FloatAnchor::TopRight)
+        .apply(&rect, &bounds, 1.0),
+      Rect::from_xy(1620, 50, 400, 600)
+    );
+    assert_eq!(
+      FloatPosition::Anchor(FloatAnchor::Center)
+        .apply(&rect, &bounds, 1.0),
+      Rect::from_xy(860, 250, 400, 600)
+    );
+  }
+
+  #[test]
+  fn offsets_by_pixels_and_percentages() {
+    let rect = Rect::from_xy(0, 0, 400, 600);
+    let bounds = Rect::from_xy(0, 40, 2000, 1000);
+
+    let position =
+      serde_json::from_str::<FloatPosition>(r#"[100, "25%"]"#).unwrap();
+
+    assert_eq!(
+      position.apply(&rect, &bounds, 1.5),
+      Rect::from_xy(150, 290, 400, 600)
+    );
+  }
+
+  #[test]
+  fn parses_anchor() {
+    assert_eq!(
+      serde_json::from_str::<FloatPosition>(r#""top_right""#).unwrap(),
+      FloatPosition::Anchor(FloatAnchor::TopRight)
+    );
+  }
+}
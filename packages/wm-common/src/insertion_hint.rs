@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Direction, TilingDirection};
+
+/// Where the next tiling window that is managed in a workspace gets
+/// inserted, as set via the `insert` command.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertionHint {
+  /// Tiling container to insert the window next to.
+  pub container_id: Uuid,
+
+  /// Side of the container to insert the window at.
+  pub direction: Direction,
+}
+
+impl InsertionHint {
+  /// Whether the window gets inserted after the container (i.e. to its
+  /// right or below it).
+  #[must_use]
+  pub fn is_after(&self) -> bool {
+    matches!(self.direction, Direction::Right | Direction::Down)
+  }
+
+  /// Whether the container needs to be split to insert the window, which
+  /// is the case when the direction is perpendicular to the given tiling
+  /// direction of its parent.
+  #[must_use]
+  pub fn should_split(&self, parent_direction: &TilingDirection) -> bool {
+    TilingDirection::from_direction(&self.direction) != *parent_direction
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn splits_perpendicular_to_parent() {
+    let hint = InsertionHint {
+      container_id: Uuid::new_v4(),
+      direction: Direction::Down,
+    };
+
+    assert!(hint.is_after());
+    assert!(hint.should_split(&TilingDirection::Horizontal));
+    assert!(!hint.should_split(&TilingDirection::Vertical));
+  }
+}
@@ -1,9 +1,149 @@
+use std::{fmt, fs, io, net::SocketAddr, path::PathBuf, str::FromStr};
+
+use anyhow::{bail, Context};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{BindingModeConfig, ContainerDto, TilingDirection, WmEvent};
+use crate::{
+  BindingModeConfig, ConfigSection, ContainerDto, Rect, TilingDirection,
+  WmEvent,
+};
+
+pub const DEFAULT_IPC_PORT: u16 = 6123;
+
+/// Environment variable that IPC clients read the server endpoint from.
+///
+/// Takes precedence over the endpoint advertised by the running WM in
+/// its discovery file.
+pub const IPC_ENDPOINT_ENV: &str = "GLAZEWM_IPC_ENDPOINT";
+
+/// Address of the IPC server.
+///
+/// Messages are framed as WebSocket messages regardless of the transport.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IpcEndpoint {
+  Tcp(SocketAddr),
+  NamedPipe(String),
+  UnixSocket(PathBuf),
+}
+
+impl IpcEndpoint {
+  /// Gets the endpoint that clients should connect to.
+  ///
+  /// This is read from the `GLAZEWM_IPC_ENDPOINT` environment variable if
+  /// set, otherwise from the discovery file written by the running WM.
+  /// Falls back to the default endpoint if neither is available.
+  pub fn discover() -> anyhow::Result<Self> {
+    if let Ok(value) = std::env::var(IPC_ENDPOINT_ENV) {
+      return value.parse().with_context(|| {
+        format!("Invalid value for {IPC_ENDPOINT_ENV}: '{value}'.")
+      });
+    }
+
+    let Ok(value) = Self::discovery_path().and_then(|path| {
+      fs::read_to_string(path).context("Unable to read discovery file.")
+    }) else {
+      return Ok(Self::default());
+    };
+
+    value.trim().parse().with_context(|| {
+      format!("Invalid endpoint in IPC discovery file: '{value}'.")
+    })
+  }
 
-pub const DEFAULT_IPC_PORT: u32 = 6123;
+  /// Path to the file that the WM advertises its endpoint in, which is
+  /// `~/.glzr/glazewm/ipc-endpoint`.
+  pub fn discovery_path() -> anyhow::Result<PathBuf> {
+    let home_dir =
+      home::home_dir().context("Unable to get home directory.")?;
+
+    Ok(home_dir.join(".glzr/glazewm/ipc-endpoint"))
+  }
+
+  /// Advertises the endpoint to clients that aren't started by the WM,
+  /// such that they can connect without it being passed explicitly.
+  pub fn write_discovery_file(&self) -> anyhow::Result<()> {
+    let path = Self::discovery_path()?;
+
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, self.to_string())
+      .with_context(|| format!("Unable to write {}.", path.display()))
+  }
+
+  /// Removes the discovery file written by `write_discovery_file`.
+  pub fn remove_discovery_file() -> anyhow::Result<()> {
+    let path = Self::discovery_path()?;
+
+    match fs::remove_file(&path) {
+      Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err)
+        .with_context(|| format!("Unable to remove {}.", path.display())),
+      _ => Ok(()),
+    }
+  }
+
+  #[must_use]
+  pub fn default_named_pipe() -> Self {
+    Self::NamedPipe(r"\\.\pipe\glazewm".to_string())
+  }
+
+  #[must_use]
+  pub fn default_unix_socket() -> Self {
+    Self::UnixSocket(std::env::temp_dir().join("glazewm.sock"))
+  }
+}
+
+impl Default for IpcEndpoint {
+  fn default() -> Self {
+    Self::default_named_pipe()
+  }
+}
+
+impl fmt::Display for IpcEndpoint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Tcp(addr) => write!(f, "tcp://{addr}"),
+      Self::NamedPipe(name) => write!(f, "pipe://{name}"),
+      Self::UnixSocket(path) => write!(f, "unix://{}", path.display()),
+    }
+  }
+}
+
+impl FromStr for IpcEndpoint {
+  type Err = anyhow::Error;
+
+  /// Parses an endpoint in the form `tcp://<addr>:<port>`,
+  /// `pipe://<name>`, or `unix://<path>`.
+  ///
+  /// Example:
+  /// ```
+  /// # use wm_common::IpcEndpoint;
+  /// # use std::str::FromStr;
+  /// let endpoint = IpcEndpoint::from_str(r"pipe://\\.\pipe\glazewm");
+  /// assert_eq!(endpoint.unwrap(), IpcEndpoint::default());
+  /// ```
+  fn from_str(unparsed: &str) -> anyhow::Result<Self> {
+    let (scheme, address) = unparsed
+      .split_once("://")
+      .context("Endpoint is missing a scheme.")?;
+
+    if address.is_empty() {
+      bail!("Endpoint is missing an address.");
+    }
+
+    match scheme {
+      "tcp" => address
+        .parse()
+        .map(Self::Tcp)
+        .context("Invalid TCP socket address."),
+      "pipe" => Ok(Self::NamedPipe(address.to_string())),
+      "unix" => Ok(Self::UnixSocket(address.into())),
+      _ => bail!("Unknown IPC transport '{scheme}'."),
+    }
+  }
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "messageType", rename_all = "snake_case")]
@@ -18,6 +158,10 @@ pub struct ClientResponseMessage {
   pub client_message: String,
   pub data: Option<ClientResponseData>,
   pub error: Option<String>,
+  /// Machine-readable code of the error, if it's one that clients can
+  /// match on.
+  #[serde(default)]
+  pub error_code: Option<String>,
   pub success: bool,
 }
 
@@ -26,13 +170,18 @@ pub struct ClientResponseMessage {
 pub enum ClientResponseData {
   AppMetadata(AppMetadataData),
   BindingModes(BindingModesData),
+  Builtins(BuiltinsData),
   Command(CommandData),
   EventSubscribe(EventSubscribeData),
   EventUnsubscribe,
   Focused(FocusedData),
+  Metrics(MetricsData),
+  Monitor(MonitorData),
   Monitors(MonitorsData),
   TilingDirection(TilingDirectionData),
+  Tree(TreeData),
   Windows(WindowsData),
+  WorkspaceLayout(WorkspaceLayoutData),
   Workspaces(WorkspacesData),
   Paused(bool),
 }
@@ -49,10 +198,50 @@ pub struct BindingModesData {
   pub binding_modes: Vec<BindingModeConfig>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltinsData {
+  pub builtins: Vec<BuiltinStatusData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltinStatusData {
+  pub name: String,
+  /// Whether the program was embedded in this build.
+  pub available: bool,
+  pub running: bool,
+  /// PID of the running process, if any.
+  #[serde(default)]
+  pub pid: Option<u32>,
+  /// Path of the executable the running process was started from.
+  #[serde(default)]
+  pub exe_path: Option<String>,
+  /// Milliseconds until a scheduled restart, if the program exited
+  /// unexpectedly.
+  pub restart_in_ms: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandData {
   pub subject_container_id: Uuid,
+  /// Rect of the subject container after the command has run.
+  pub subject_container_rect: Option<Rect>,
+  /// Outcome of a `wm-reload-config` command.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub config_reload: Option<ConfigReloadData>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadData {
+  /// Config sections that changed and were applied.
+  pub applied: Vec<ConfigSection>,
+  /// Config sections that changed, but only take effect after a restart.
+  pub requires_restart: Vec<ConfigSection>,
+  /// Names of workspaces that were moved to their newly bound monitor.
+  pub moved_workspaces: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -67,6 +256,30 @@ pub struct FocusedData {
   pub focused: ContainerDto,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsData {
+  pub commands: Vec<CommandMetricsData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetricsData {
+  pub name: String,
+  /// Number of times the command has run.
+  pub count: u64,
+  /// Median duration in milliseconds of the most recent runs.
+  pub p50_ms: f64,
+  /// 99th percentile duration in milliseconds of the most recent runs.
+  pub p99_ms: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorData {
+  pub monitor: ContainerDto,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitorsData {
@@ -80,12 +293,33 @@ pub struct TilingDirectionData {
   pub direction_container: ContainerDto,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeData {
+  /// Root container, with all descendants nested under `children`.
+  pub tree: ContainerDto,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowsData {
   pub windows: Vec<ContainerDto>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLayoutData {
+  pub layout: Vec<MonitorWorkspacesData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorWorkspacesData {
+  pub monitor_id: Uuid,
+  /// Names of the monitor's workspaces in display order.
+  pub workspaces: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspacesData {
@@ -97,6 +331,10 @@ pub struct WorkspacesData {
 pub struct EventSubscriptionMessage {
   pub data: Option<WmEvent>,
   pub error: Option<String>,
+  /// Whether the event was emitted before the subscription was made, and
+  /// is being replayed on request.
+  #[serde(default)]
+  pub is_replay: bool,
   pub subscription_id: Uuid,
   pub success: bool,
 }
@@ -56,7 +56,7 @@ impl FromStr for LengthValue {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::{LengthValue, LengthUnit};
+  /// # use wm_common::{LengthValue, LengthUnit};
   /// # use std::str::FromStr;
   /// let check = LengthValue {
   ///   amount: 100.0,
@@ -96,7 +96,8 @@ impl FromStr for LengthValue {
   }
 }
 
-/// Deserialize a `LengthValue` from either a string or a struct.
+/// Deserialize a `LengthValue` from either a string, a struct, or a
+/// number of pixels.
 impl<'de> Deserialize<'de> for LengthValue {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -107,6 +108,7 @@ impl<'de> Deserialize<'de> for LengthValue {
     enum LengthValueDe {
       Struct { amount: f32, unit: LengthUnit },
       String(String),
+      Number(f32),
     }
 
     match LengthValueDe::deserialize(deserializer)? {
@@ -114,6 +116,10 @@ impl<'de> Deserialize<'de> for LengthValue {
       LengthValueDe::String(str) => {
         Self::from_str(&str).map_err(serde::de::Error::custom)
       }
+      LengthValueDe::Number(amount) => Ok(Self {
+        amount,
+        unit: LengthUnit::Pixel,
+      }),
     }
   }
 }
@@ -8,17 +8,23 @@ mod delta;
 mod direction;
 mod display_state;
 mod dtos;
+mod float_position;
+mod insertion_hint;
 mod ipc;
 mod length_value;
+mod monitor_field;
 mod opacity_value;
 mod parsed_config;
 mod point;
 mod rect;
 mod rect_delta;
+mod size_constraints;
+mod split_layout;
 mod tiling_direction;
 mod utils;
 mod window_state;
 mod wm_event;
+mod workspace_layout;
 
 pub use active_drag::*;
 pub use app_command::*;
@@ -27,14 +33,20 @@ pub use delta::*;
 pub use direction::*;
 pub use display_state::*;
 pub use dtos::*;
+pub use float_position::*;
+pub use insertion_hint::*;
 pub use ipc::*;
 pub use length_value::*;
+pub use monitor_field::*;
 pub use opacity_value::*;
 pub use parsed_config::*;
 pub use point::*;
 pub use rect::*;
 pub use rect_delta::*;
+pub use size_constraints::*;
+pub use split_layout::*;
 pub use tiling_direction::*;
 pub use utils::*;
 pub use window_state::*;
 pub use wm_event::*;
+pub use workspace_layout::*;
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Rect;
+
+/// A monitor property that can change while the monitor stays connected.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorField {
+  Rect,
+  WorkingArea,
+  ScaleFactor,
+  Primary,
+  Name,
+  Index,
+  Standby,
+}
+
+/// Point-in-time copy of the monitor properties tracked by
+/// `WmEvent::MonitorUpdated`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorSnapshot {
+  pub rect: Rect,
+  pub working_rect: Rect,
+  pub scale_factor: f32,
+  pub is_primary: bool,
+  pub device_name: String,
+  pub index: usize,
+  pub is_standby: bool,
+}
+
+impl MonitorSnapshot {
+  /// Gets the fields that differ between this snapshot and a later one.
+  ///
+  /// Fields are returned in declaration order of `MonitorField`.
+  #[must_use]
+  pub fn changed_fields(
+    &self,
+    other: &MonitorSnapshot,
+  ) -> Vec<MonitorField> {
+    #[allow(clippy::float_cmp)]
+    let candidates = [
+      (MonitorField::Rect, self.rect != other.rect),
+      (
+        MonitorField::WorkingArea,
+        self.working_rect != other.working_rect,
+      ),
+      (
+        MonitorField::ScaleFactor,
+        self.scale_factor != other.scale_factor,
+      ),
+      (MonitorField::Primary, self.is_primary != other.is_primary),
+      (MonitorField::Name, self.device_name != other.device_name),
+      (MonitorField::Index, self.index != other.index),
+      (MonitorField::Standby, self.is_standby != other.is_standby),
+    ];
+
+    candidates
+      .into_iter()
+      .filter_map(|(field, has_changed)| has_changed.then_some(field))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn snapshot() -> MonitorSnapshot {
+    MonitorSnapshot {
+      rect: Rect::from_xy(0, 0, 1920, 1080),
+      working_rect: Rect::from_xy(0, 0, 1920, 1040),
+      scale_factor: 1.0,
+      is_primary: true,
+      device_name: "\\\\.\\DISPLAY1".to_string(),
+      index: 0,
+      is_standby: false,
+    }
+  }
+
+  #[test]
+  fn unchanged_snapshot_has_no_changes() {
+    assert!(snapshot().changed_fields(&snapshot()).is_empty());
+  }
+
+  #[test]
+  fn detects_resolution_change() {
+    let after = MonitorSnapshot {
+      rect: Rect::from_xy(0, 0, 2560, 1440),
+      working_rect: Rect::from_xy(0, 0, 2560, 1400),
+      ..snapshot()
+    };
+
+    assert_eq!(
+      snapshot().changed_fields(&after),
+      vec![MonitorField::Rect, MonitorField::WorkingArea]
+    );
+  }
+
+  #[test]
+  fn detects_primary_and_index_change() {
+    let after = MonitorSnapshot {
+      is_primary: false,
+      index: 1,
+      ..snapshot()
+    };
+
+    assert_eq!(
+      snapshot().changed_fields(&after),
+      vec![MonitorField::Primary, MonitorField::Index]
+    );
+  }
+
+  #[test]
+  fn detects_scale_and_name_change() {
+    let after = MonitorSnapshot {
+      scale_factor: 1.5,
+      device_name: "\\\\.\\DISPLAY2".to_string(),
+      ..snapshot()
+    };
+
+    assert_eq!(
+      snapshot().changed_fields(&after),
+      vec![MonitorField::ScaleFactor, MonitorField::Name]
+    );
+  }
+
+  #[test]
+  fn detects_standby_change() {
+    let after = MonitorSnapshot {
+      is_standby: true,
+      ..snapshot()
+    };
+
+    assert_eq!(
+      snapshot().changed_fields(&after),
+      vec![MonitorField::Standby]
+    );
+  }
+}
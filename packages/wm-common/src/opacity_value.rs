@@ -34,11 +34,10 @@ impl FromStr for OpacityValue {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::{OpacityValue};
+  /// # use wm_common::{OpacityValue};
   /// # use std::str::FromStr;
-  /// let check = OpacityValue(0.75);
   /// let parsed = OpacityValue::from_str("75%");
-  /// assert_eq!(parsed.unwrap(), check);
+  /// assert_eq!(parsed.unwrap().to_alpha(), 191);
   /// ```
   fn from_str(unparsed: &str) -> anyhow::Result<Self> {
     let unparsed = unparsed.trim();
@@ -1,22 +1,65 @@
-use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-  app_command::InvokeCommand, Color, LengthValue, OpacityValue, RectDelta,
+  app_command::InvokeCommand, Color, FloatPosition, IpcEndpoint,
+  LengthValue, OpacityValue, RectDelta, TilingDirection, WorkspaceLayout,
+  DEFAULT_IPC_PORT,
 };
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all(serialize = "camelCase"))]
 pub struct ParsedConfig {
   pub binding_modes: Vec<BindingModeConfig>,
+  pub builtins: Vec<BuiltinConfig>,
   pub gaps: GapsConfig,
   pub general: GeneralConfig,
+  pub ipc: IpcConfig,
   pub keybindings: Vec<KeybindingConfig>,
+  pub monitors: Vec<MonitorConfig>,
+  pub mouse_bindings: MouseBindingsConfig,
   pub window_behavior: WindowBehaviorConfig,
   pub window_effects: WindowEffectsConfig,
   pub window_rules: Vec<WindowRuleConfig>,
   pub workspaces: Vec<WorkspaceConfig>,
 }
 
+/// Top-level section of the user config.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSection {
+  BindingModes,
+  Builtins,
+  Gaps,
+  General,
+  Ipc,
+  Keybindings,
+  Monitors,
+  MouseBindings,
+  WindowBehavior,
+  WindowEffects,
+  WindowRules,
+  Workspaces,
+}
+
+impl ConfigSection {
+  pub const ALL: [Self; 12] = [
+    Self::BindingModes,
+    Self::Builtins,
+    Self::Gaps,
+    Self::General,
+    Self::Ipc,
+    Self::Keybindings,
+    Self::Monitors,
+    Self::MouseBindings,
+    Self::WindowBehavior,
+    Self::WindowEffects,
+    Self::WindowRules,
+    Self::Workspaces,
+  ];
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct BindingModeConfig {
@@ -47,6 +90,15 @@ pub struct GapsConfig {
   /// Gap between window and the screen edge if there is only one window
   /// in the workspace
   pub single_window_outer_gap: Option<RectDelta>,
+
+  /// Whether gaps are removed while a workspace shows a single tiling
+  /// window (including a window in monocle), such that it fills the
+  /// working area.
+  pub smart_gaps: bool,
+
+  /// Whether the border of that single window is hidden as well. Only
+  /// applies if `smart_gaps` is enabled.
+  pub smart_borders: bool,
 }
 
 impl Default for GapsConfig {
@@ -61,10 +113,13 @@ impl Default for GapsConfig {
         LengthValue::from_px(0),
       ),
       single_window_outer_gap: None,
+      smart_gaps: false,
+      smart_borders: false,
     }
   }
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default, rename_all(serialize = "camelCase"))]
 pub struct GeneralConfig {
@@ -72,8 +127,20 @@ pub struct GeneralConfig {
   pub cursor_jump: CursorJumpConfig,
 
   /// Whether to automatically focus windows underneath the cursor.
+  #[serde(alias = "focus_follows_mouse")]
   pub focus_follows_cursor: bool,
 
+  /// Time (in milliseconds) that the cursor has to stay over a window
+  /// before it gets focused by `focus_follows_cursor`. A delay avoids
+  /// focus changing rapidly while the cursor crosses over windows.
+  ///
+  /// Defaults to 0, such that windows are focused right away.
+  pub focus_follows_cursor_delay_ms: u32,
+
+  /// Whether windows focused by `focus_follows_cursor` are brought to the
+  /// front. Otherwise, their z-order is left as is.
+  pub raise_on_focus: bool,
+
   /// Whether to switch back and forth between the previously focused
   /// workspace when focusing the current workspace.
   pub toggle_workspace_on_refocus: bool,
@@ -93,6 +160,87 @@ pub struct GeneralConfig {
 
   /// Affects which windows get shown in the native Windows taskbar.
   pub show_all_in_taskbar: bool,
+
+  /// Max time (in milliseconds) to spend redrawing windows in a single
+  /// sync. Remaining redraws are deferred to the next sync. A value of 0
+  /// disables the budget.
+  pub redraw_budget_ms: u32,
+
+  /// Whether to move the focused workspace on config reload when its
+  /// `bind_to_monitor` has changed. Otherwise, it's left in place.
+  pub force_rebind_on_reload: bool,
+
+  /// Whether to reload the config when the config file or any of the
+  /// files it includes are modified.
+  pub reload_on_config_change: bool,
+
+  /// Whether to record how often commands run and how long they take.
+  /// Retrievable via the `query metrics` IPC command.
+  pub metrics: bool,
+
+  /// Tiling direction of newly activated workspaces. Can be overridden
+  /// per workspace. If unset, it's based on the monitor's orientation.
+  pub default_tiling_direction: Option<TilingDirection>,
+
+  /// Whether focusing another tiling window in a workspace that is in
+  /// monocle moves monocle to that window. Otherwise, monocle is exited.
+  pub monocle_follows_focus: bool,
+
+  /// Whether fullscreen windows cover the entire monitor, including space
+  /// reserved by the OS and app bars (e.g. the taskbar or zebar).
+  /// Otherwise, they're sized to the monitor's working area, unless they
+  /// match a window rule with `exclusive_fullscreen` enabled.
+  pub fullscreen_covers_reserved_space: bool,
+
+  /// Whether focusing in a direction continues into the displayed
+  /// workspace of the adjacent monitor once there's nothing further in
+  /// that direction on the current workspace.
+  pub focus_crosses_monitors: bool,
+
+  /// Whether focusing in a direction wraps around to the opposite side of
+  /// the current workspace once there's nothing further in that
+  /// direction (and focus doesn't cross to another monitor).
+  pub focus_wraps: bool,
+
+  /// Whether focus (and the cursor) follows windows that are moved to
+  /// another workspace or monitor. Can be overridden per command via
+  /// `--follow` or `--follow=false`.
+  ///
+  /// If not set, focus follows windows moved via `move --direction`, but
+  /// not windows moved to a workspace or monitor.
+  pub move_follows_window: Option<bool>,
+
+  /// Order that `focus-next-window` and `focus-prev-window` cycle
+  /// through the windows of the focused workspace in.
+  pub window_cycle_order: WindowCycleOrder,
+
+  /// Whether the arrangement of windows is saved periodically and on
+  /// exit, and restored on the next startup for windows that are still
+  /// open.
+  pub restore_session: bool,
+
+  /// Whether disconnected monitors are put in standby rather than
+  /// removed, such that their workspaces are restored as they were once
+  /// the monitor is connected again.
+  pub standby_disconnected_monitors: bool,
+}
+
+impl GeneralConfig {
+  /// Gets whether focus follows a moved window, given the `--follow`
+  /// argument of the move command.
+  ///
+  /// Falls back to `move_follows_window`, and then to
+  /// `follows_by_default` if neither is set.
+  #[must_use]
+  pub fn follows_moved_window(
+    &self,
+    follow: Option<bool>,
+    follows_by_default: bool,
+  ) -> bool {
+    follow
+      .or(self.move_follows_window)
+      .unwrap_or(follows_by_default)
+  }
 }
 
 impl Default for GeneralConfig {
@@ -100,17 +248,101 @@ impl Default for GeneralConfig {
     GeneralConfig {
       cursor_jump: CursorJumpConfig::default(),
       focus_follows_cursor: false,
+      focus_follows_cursor_delay_ms: 0,
+      raise_on_focus: false,
       toggle_workspace_on_refocus: true,
       startup_commands: vec![],
       shutdown_commands: vec![],
       config_reload_commands: vec![],
       hide_method: HideMethod::Cloak,
       show_all_in_taskbar: false,
+      redraw_budget_ms: 8,
+      force_rebind_on_reload: false,
+      reload_on_config_change: false,
+      metrics: false,
+      default_tiling_direction: None,
+      monocle_follows_focus: true,
+      fullscreen_covers_reserved_space: true,
+      focus_crosses_monitors: true,
+      focus_wraps: false,
+      move_follows_window: None,
+      window_cycle_order: WindowCycleOrder::Tree,
+      restore_session: false,
+      standby_disconnected_monitors: false,
     }
   }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct IpcConfig {
+  /// Transport that the IPC server listens on.
+  pub transport: IpcTransport,
+
+  /// Address to bind to when using the TCP transport.
+  pub bind_address: IpAddr,
+
+  /// Port to bind to when using the TCP transport.
+  pub port: u16,
+
+  /// Pipe name or socket path when using the named pipe or Unix socket
+  /// transports. Uses a platform default if not set.
+  pub path: Option<String>,
+
+  /// Number of recent events to retain for replaying to new subscribers.
+  /// Capped at `MAX_EVENT_REPLAY_SIZE`.
+  pub event_replay_size: usize,
+}
+
+impl IpcConfig {
+  /// Upper bound on `event_replay_size`.
+  pub const MAX_EVENT_REPLAY_SIZE: usize = 1000;
+
+  /// Gets the endpoint that the IPC server should listen on.
+  #[must_use]
+  pub fn endpoint(&self) -> IpcEndpoint {
+    match self.transport {
+      IpcTransport::Tcp => {
+        IpcEndpoint::Tcp(SocketAddr::new(self.bind_address, self.port))
+      }
+      IpcTransport::NamedPipe => self
+        .path
+        .as_ref()
+        .map_or_else(IpcEndpoint::default_named_pipe, |path| {
+          IpcEndpoint::NamedPipe(path.clone())
+        }),
+      IpcTransport::UnixSocket => self
+        .path
+        .as_ref()
+        .map_or_else(IpcEndpoint::default_unix_socket, |path| {
+          IpcEndpoint::UnixSocket(path.into())
+        }),
+    }
+  }
+}
+
+impl Default for IpcConfig {
+  fn default() -> Self {
+    IpcConfig {
+      transport: IpcTransport::NamedPipe,
+      bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+      port: DEFAULT_IPC_PORT,
+      path: None,
+      event_replay_size: 100,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcTransport {
+  Tcp,
+  #[default]
+  NamedPipe,
+  UnixSocket,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default, rename_all(serialize = "camelCase"))]
 pub struct CursorJumpConfig {
   /// Whether to automatically move the cursor on the specified trigger.
@@ -118,6 +350,39 @@ pub struct CursorJumpConfig {
 
   /// Trigger for cursor jump.
   pub trigger: CursorJumpTrigger,
+
+  /// Where within the jump target to move the cursor to.
+  pub target: CursorJumpTarget,
+
+  /// Horizontal offset from the left edge of the jump target when using
+  /// `CursorJumpTarget::TopLeftOffset`.
+  pub offset_x: LengthValue,
+
+  /// Vertical offset from the top edge of the jump target when using
+  /// `CursorJumpTarget::TopLeftOffset`.
+  pub offset_y: LengthValue,
+
+  /// Names of commands that never cause the cursor to jump (e.g.
+  /// `move-workspace`).
+  pub suppressed_commands: Vec<String>,
+
+  /// Whether to skip the cursor jump when workspaces are moved to a
+  /// newly connected monitor.
+  pub suppress_on_monitor_change: bool,
+}
+
+impl Default for CursorJumpConfig {
+  fn default() -> Self {
+    CursorJumpConfig {
+      enabled: false,
+      trigger: CursorJumpTrigger::MonitorFocus,
+      target: CursorJumpTarget::Center,
+      offset_x: LengthValue::from_px(50),
+      offset_y: LengthValue::from_px(50),
+      suppressed_commands: vec![],
+      suppress_on_monitor_change: false,
+    }
+  }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -128,6 +393,36 @@ pub enum CursorJumpTrigger {
   WindowFocus,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorJumpTarget {
+  /// Center of the jump target.
+  #[default]
+  Center,
+
+  /// Fixed offset from the top-left corner of the jump target.
+  TopLeftOffset,
+
+  /// Where the cursor last was within the focused window when focus left
+  /// it. Falls back to the center of the jump target.
+  RememberLastPositionPerWindow,
+
+  /// Never move the cursor.
+  Disabled,
+}
+
+#[derive(
+  Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowCycleOrder {
+  /// Order of the windows in the container tree.
+  #[default]
+  Tree,
+  /// Order in which the windows were last focused.
+  Recent,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum HideMethod {
@@ -146,6 +441,98 @@ pub struct KeybindingConfig {
   pub commands: Vec<InvokeCommand>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct BuiltinConfig {
+  /// Name of the builtin program (e.g. `zebar`).
+  pub name: String,
+
+  /// File name that the program's executable is extracted to. Uses the
+  /// program's default file name if not set.
+  #[serde(default)]
+  pub file_name: Option<String>,
+
+  /// Whether to stamp the file name with a hash of the embedded binary,
+  /// such that a new build never overwrites an executable that is still
+  /// running.
+  #[serde(default = "default_bool::<false>")]
+  pub versioned_file_name: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct MonitorConfig {
+  /// Device name of the monitor to match (e.g. `\\.\DISPLAY1`).
+  pub device_name: Option<String>,
+
+  /// Index of the monitor to match (0-based).
+  pub index: Option<usize>,
+
+  /// Shell commands to run when a matching monitor is connected. Supports
+  /// the `{name}`, `{index}`, `{width}` and `{height}` placeholders.
+  pub on_connect: Vec<String>,
+
+  /// Shell commands to run when a matching monitor is disconnected.
+  /// Supports the same placeholders as `on_connect`.
+  pub on_disconnect: Vec<String>,
+
+  /// Time (in milliseconds) after which a command that is still running
+  /// gets terminated.
+  pub command_timeout_ms: u32,
+}
+
+impl MonitorConfig {
+  /// Whether the config applies to the monitor with the given device name
+  /// and index. A config without any criteria matches all monitors.
+  #[must_use]
+  pub fn matches(&self, device_name: &str, index: usize) -> bool {
+    self
+      .device_name
+      .as_ref()
+      .is_none_or(|name| name.eq_ignore_ascii_case(device_name))
+      && self.index.is_none_or(|config_index| config_index == index)
+  }
+}
+
+impl Default for MonitorConfig {
+  fn default() -> Self {
+    MonitorConfig {
+      device_name: None,
+      index: None,
+      on_connect: Vec::new(),
+      on_disconnect: Vec::new(),
+      command_timeout_ms: 30_000,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct MouseBindingsConfig {
+  /// Whether dragging a window with the modifier held moves it (left
+  /// button) or resizes it from the nearest corner (right button).
+  pub enabled: bool,
+
+  /// Key to hold while dragging (e.g. `alt` or `lwin+shift`). Clicks made
+  /// while it's held aren't passed on to other applications.
+  pub modifier: String,
+
+  /// Whether the gestures also apply to tiling windows. Moving a tiling
+  /// window then reorders it via the drop hint, and resizing it changes
+  /// its tiling size.
+  pub tiling_windows: bool,
+}
+
+impl Default for MouseBindingsConfig {
+  fn default() -> Self {
+    MouseBindingsConfig {
+      enabled: false,
+      modifier: "alt".to_string(),
+      tiling_windows: true,
+    }
+  }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all(serialize = "camelCase"))]
 pub struct WindowBehaviorConfig {
@@ -156,6 +543,171 @@ pub struct WindowBehaviorConfig {
   /// changes the defaults for when the state change commands, like
   /// `set_floating`, are used without any flags.
   pub state_defaults: WindowStateDefaultsConfig,
+
+  /// Whether windows that hide themselves (e.g. when minimizing to the
+  /// tray) are kept on their workspace as minimized, and restored to
+  /// their previous position once shown again. Otherwise, they're
+  /// unmanaged and managed anew on the current workspace.
+  pub keep_hidden_windows: bool,
+
+  /// Snapping of floating windows to nearby edges when moved.
+  pub snapping: SnappingConfig,
+
+  /// Resizing of tiling windows by dragging the edge between them.
+  pub edge_resize: EdgeResizeConfig,
+
+  /// Hint shown where a dragged tiling window gets inserted on drop, or
+  /// where the next window opens if set via the `insert` command.
+  pub drop_hint: DropHintConfig,
+
+  /// Terminating the processes of windows that don't close when asked.
+  pub force_close: ForceCloseConfig,
+
+  /// Moving windows of newly launched programs to a target workspace.
+  pub workspace_assignment: WorkspaceAssignmentConfig,
+
+  /// Respecting the minimum and maximum sizes of windows when tiling.
+  pub size_hints: SizeHintsConfig,
+
+  /// How windows are handled when maximized via their own maximize
+  /// button. Windows matching a rule with `keep_maximized` are always
+  /// made fullscreen instead.
+  pub on_maximize: MaximizeBehavior,
+
+  /// Whether the native title bar and resize frame are removed from
+  /// tiling windows. They're restored once the window floats or is no
+  /// longer managed. Can be overridden per window via the
+  /// `remove_title_bar` option of window rules.
+  pub remove_title_bars: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct SnappingConfig {
+  /// Whether floating windows snap to the edges of the monitor's working
+  /// area and of other floating windows on the same workspace.
+  pub enabled: bool,
+
+  /// Maximum distance between two edges for them to snap together.
+  pub threshold: LengthValue,
+
+  /// Key that disables snapping while held down during a drag.
+  pub bypass_key: String,
+}
+
+impl Default for SnappingConfig {
+  fn default() -> Self {
+    SnappingConfig {
+      enabled: false,
+      threshold: LengthValue::from_px(10),
+      bypass_key: "shift".to_string(),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct EdgeResizeConfig {
+  /// Whether dragging the edge shared by two adjacent tiling windows
+  /// resizes both of them.
+  pub enabled: bool,
+
+  /// Maximum distance from a shared edge for a drag to start resizing.
+  pub threshold: LengthValue,
+}
+
+impl Default for EdgeResizeConfig {
+  fn default() -> Self {
+    EdgeResizeConfig {
+      enabled: false,
+      threshold: LengthValue::from_px(4),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct ForceCloseConfig {
+  /// Time in milliseconds that a window is given to close before its
+  /// process can be terminated via the `close` command.
+  pub timeout_ms: u32,
+}
+
+impl Default for ForceCloseConfig {
+  fn default() -> Self {
+    ForceCloseConfig { timeout_ms: 5000 }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct WorkspaceAssignmentConfig {
+  /// Time in milliseconds after a program is launched (via
+  /// `shell-exec --workspace` or an `initial_workspace` window rule)
+  /// during which its new windows are moved to the target workspace.
+  pub timeout_ms: u32,
+}
+
+impl Default for WorkspaceAssignmentConfig {
+  fn default() -> Self {
+    WorkspaceAssignmentConfig { timeout_ms: 10_000 }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct SizeHintsConfig {
+  /// Whether tiling windows are kept within the minimum and maximum
+  /// sizes reported by their application. Space that a window can't
+  /// take up is redistributed to its siblings.
+  pub enabled: bool,
+
+  /// Whether windows whose minimum size doesn't fit in their workspace
+  /// are floated. Otherwise, they overflow their tile.
+  pub float_if_too_large: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct DropHintConfig {
+  /// Whether to show a translucent overlay over the area that a dragged
+  /// tiling window would be inserted into.
+  pub enabled: bool,
+
+  /// Color of the overlay. The alpha channel sets its transparency.
+  pub color: Color,
+}
+
+impl Default for DropHintConfig {
+  fn default() -> Self {
+    DropHintConfig {
+      enabled: true,
+      color: Color {
+        r: 141,
+        g: 188,
+        b: 255,
+        a: 80,
+      },
+    }
+  }
+}
+
+#[derive(
+  Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MaximizeBehavior {
+  /// Window is made fullscreen (maximized), covering gaps and other
+  /// windows.
+  #[default]
+  Fullscreen,
+
+  /// Window is restored and put in monocle, filling its workspace while
+  /// keeping gaps. Non-tiling windows are restored instead.
+  Monocle,
+
+  /// Window is restored back into its tile or floating placement.
+  Restore,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -219,6 +771,14 @@ pub struct WindowEffectsConfig {
 
   /// Visual effects to apply to non-focused windows.
   pub other_windows: WindowEffectConfig,
+
+  /// Opacity to apply to windows based on their state. Takes precedence
+  /// over the `transparency` effects when enabled.
+  pub opacity: OpacityEffectConfig,
+
+  /// Borders to apply to windows based on their state. Takes precedence
+  /// over the `border` effects when enabled.
+  pub borders: StateBorderEffectConfig,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -298,9 +858,70 @@ pub struct TransparencyEffectConfig {
   pub opacity: OpacityValue,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct OpacityEffectConfig {
+  /// Whether to enable the effect.
+  pub enabled: bool,
+
+  /// Opacity of the focused window.
+  pub focused: Option<OpacityValue>,
+
+  /// Opacity of non-focused windows.
+  pub unfocused: Option<OpacityValue>,
+
+  /// Opacity of floating windows. Overrides `focused` and `unfocused`.
+  pub floating: Option<OpacityValue>,
+
+  /// Opacity of fullscreen windows. Overrides `focused` and `unfocused`.
+  pub fullscreen: Option<OpacityValue>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct StateBorderEffectConfig {
+  /// Whether to enable the effect.
+  pub enabled: bool,
+
+  /// Border of the focused window.
+  pub focused: Option<BorderStyleConfig>,
+
+  /// Border of non-focused windows.
+  pub unfocused: Option<BorderStyleConfig>,
+
+  /// Border of windows requesting attention (i.e. flashing in the
+  /// taskbar). Overrides all other borders.
+  pub urgent: Option<BorderStyleConfig>,
+
+  /// Border of floating windows. Overrides `focused` and `unfocused`.
+  pub floating: Option<BorderStyleConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct BorderStyleConfig {
+  /// Color of the border.
+  pub color: Color,
+
+  /// Thickness of the border. Borders thicker than 1px are drawn as an
+  /// overlay, since DWM only supports 1px borders.
+  pub width: LengthValue,
+}
+
+impl Default for BorderStyleConfig {
+  fn default() -> Self {
+    BorderStyleConfig {
+      color: BorderEffectConfig::default().color,
+      width: LengthValue::from_px(1),
+    }
+  }
+}
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct WindowRuleConfig {
+  #[serde(default)]
   pub commands: Vec<InvokeCommand>,
 
   #[serde(rename = "match")]
@@ -311,6 +932,62 @@ pub struct WindowRuleConfig {
 
   #[serde(default = "default_bool::<true>")]
   pub run_once: bool,
+
+  /// Whether matching windows (e.g. terminals) get swallowed by GUI
+  /// windows launched from them. The swallowed window is hidden and
+  /// takes back its tile once the launched window closes.
+  #[serde(default = "default_bool::<false>")]
+  pub swallow: bool,
+
+  /// Whether matching windows are excluded from window effects. Some
+  /// apps render incorrectly once made layered for transparency.
+  #[serde(default = "default_bool::<false>")]
+  pub ignore_effects: bool,
+
+  /// Whether matching windows cover the entire monitor when fullscreen,
+  /// regardless of `fullscreen_covers_reserved_space` (e.g. for games
+  /// and video players).
+  #[serde(default = "default_bool::<false>")]
+  pub exclusive_fullscreen: bool,
+
+  /// Whether matching windows stay maximized when maximized via their
+  /// own maximize button, regardless of `window_behavior.on_maximize`.
+  #[serde(default = "default_bool::<false>")]
+  pub keep_maximized: bool,
+
+  /// Whether the native title bar and resize frame are removed from
+  /// matching windows while tiling. Takes precedence over
+  /// `window_behavior.remove_title_bars`, such that apps that misbehave
+  /// without a title bar can be excluded.
+  #[serde(default)]
+  pub remove_title_bar: Option<bool>,
+
+  /// Size that matching windows get when floated, as `[width, height]`.
+  /// Pixel values are scaled with the monitor's DPI, and percentages are
+  /// of the workspace's size.
+  #[serde(default)]
+  pub float_size: Option<[LengthValue; 2]>,
+
+  /// Position within the workspace that matching windows get when
+  /// floated.
+  #[serde(default)]
+  pub float_position: Option<FloatPosition>,
+
+  /// Whether the rule is re-evaluated when the window's title changes.
+  /// If disabled, the rule only runs on its other `on` events.
+  #[serde(default = "default_bool::<true>")]
+  pub on_title_change: bool,
+
+  /// Workspace that matching windows are moved to when their process
+  /// was recently launched.
+  #[serde(default)]
+  pub initial_workspace: Option<String>,
+
+  /// Number of windows of a launched process that are moved to
+  /// `initial_workspace`. Later windows (e.g. dialogs) stay on the
+  /// displayed workspace.
+  #[serde(default = "default_initial_workspace_windows")]
+  pub initial_workspace_windows: usize,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -319,6 +996,16 @@ pub struct WindowMatchConfig {
   pub window_process: Option<MatchType>,
   pub window_class: Option<MatchType>,
   pub window_title: Option<MatchType>,
+
+  /// Matched against the full path to the executable of the window's
+  /// process.
+  #[serde(alias = "match_process_path")]
+  pub window_process_path: Option<MatchType>,
+
+  /// Matched against the command line that the window's process was
+  /// launched with.
+  #[serde(alias = "match_command_line")]
+  pub window_command_line: Option<MatchType>,
 }
 
 /// Due to limitations in `serde_yaml`, we need to use an untagged enum
@@ -341,13 +1028,13 @@ impl MatchType {
     match self {
       MatchType::Equals { equals } => value == equals,
       MatchType::Includes { includes } => value.contains(includes),
-      MatchType::Regex { regex } => regex::Regex::new(regex)
-        .map(|re| re.is_match(value))
-        .unwrap_or(false),
+      MatchType::Regex { regex } => {
+        regex::Regex::new(regex).is_ok_and(|re| re.is_match(value))
+      }
       MatchType::NotEquals { not_equals } => value != not_equals,
-      MatchType::NotRegex { not_regex } => regex::Regex::new(not_regex)
-        .map(|re| !re.is_match(value))
-        .unwrap_or(false),
+      MatchType::NotRegex { not_regex } => {
+        regex::Regex::new(not_regex).is_ok_and(|re| !re.is_match(value))
+      }
     }
   }
 }
@@ -373,11 +1060,84 @@ pub struct WorkspaceConfig {
   #[serde(default)]
   pub display_name: Option<String>,
 
+  /// Monitors that the workspace is bound to, in order of preference.
+  /// The workspace is kept on the first of them that's connected.
   #[serde(default)]
-  pub bind_to_monitor: Option<u32>,
+  pub bind_to_monitor: Option<MonitorBinding>,
+
+  /// Position of the workspace among the workspaces of its bound monitor.
+  /// Workspaces with a position come first, followed by the rest in
+  /// config order. Only applies if `bind_to_monitor` is set.
+  #[serde(default)]
+  pub monitor_position: Option<u32>,
 
   #[serde(default = "default_bool::<false>")]
   pub keep_alive: bool,
+
+  #[serde(default)]
+  pub default_tiling_direction: Option<TilingDirection>,
+
+  #[serde(default)]
+  pub layout: WorkspaceLayout,
+
+  /// Share of the workspace taken up by the master area when using the
+  /// master-stack layout.
+  #[serde(default = "default_master_ratio")]
+  pub master_ratio: f32,
+
+  /// Number of windows in the master area when using the master-stack
+  /// layout.
+  #[serde(default = "default_master_count")]
+  pub master_count: usize,
+}
+
+/// Monitors that a workspace is bound to, in order of preference.
+///
+/// Parsed from either a single monitor or a list of monitors, and
+/// serialized the same way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorBinding(pub Vec<MonitorSelector>);
+
+/// A monitor given by its index or its name.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MonitorSelector {
+  /// Index of the monitor, where 0 is the leftmost monitor.
+  Index(u32),
+
+  /// Device name (e.g. `\\.\DISPLAY1`) or hardware ID of the monitor.
+  Name(String),
+}
+
+impl<'de> Deserialize<'de> for MonitorBinding {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MonitorBindingDe {
+      Single(MonitorSelector),
+      List(Vec<MonitorSelector>),
+    }
+
+    match MonitorBindingDe::deserialize(deserializer)? {
+      MonitorBindingDe::Single(selector) => Ok(Self(vec![selector])),
+      MonitorBindingDe::List(selectors) => Ok(Self(selectors)),
+    }
+  }
+}
+
+impl Serialize for MonitorBinding {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self.0.as_slice() {
+      [selector] => selector.serialize(serializer),
+      selectors => selectors.serialize(serializer),
+    }
+  }
 }
 
 /// Helper function for setting a default value for a boolean field.
@@ -385,7 +1145,68 @@ const fn default_bool<const V: bool>() -> bool {
   V
 }
 
+/// Helper function for setting a default value for the master ratio.
+pub(crate) const fn default_master_ratio() -> f32 {
+  0.55
+}
+
+/// Helper function for setting a default value for the master count.
+pub(crate) const fn default_master_count() -> usize {
+  1
+}
+
+/// Helper function for setting a default value for the number of windows
+/// moved to a rule's initial workspace.
+const fn default_initial_workspace_windows() -> usize {
+  1
+}
+
 /// Helper function for setting a default value for window rule events.
 fn default_window_rule_on() -> Vec<WindowRuleEvent> {
   vec![WindowRuleEvent::Manage, WindowRuleEvent::TitleChange]
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn general_config(move_follows_window: Option<bool>) -> GeneralConfig {
+    GeneralConfig {
+      move_follows_window,
+      ..GeneralConfig::default()
+    }
+  }
+
+  #[test]
+  fn moved_window_follows_command_default_when_unset() {
+    let config = general_config(None);
+
+    assert!(config.follows_moved_window(None, true));
+    assert!(!config.follows_moved_window(None, false));
+  }
+
+  #[test]
+  fn moved_window_follows_config_then_command() {
+    let config = general_config(Some(true));
+
+    assert!(config.follows_moved_window(None, false));
+    assert!(!config.follows_moved_window(Some(false), true));
+  }
+
+  #[test]
+  fn parses_unset_move_follows_window() {
+    let config: GeneralConfig =
+      serde_json::from_str(r#"{ "focus_wraps": true }"#)
+        .expect("Failed to parse general config.");
+
+    assert_eq!(config.move_follows_window, None);
+  }
+
+  #[test]
+  fn ipc_defaults_to_named_pipe() {
+    let config: IpcConfig = serde_json::from_str(r#"{ "port": 6124 }"#)
+      .expect("Failed to parse IPC config.");
+
+    assert_eq!(config.endpoint(), IpcEndpoint::default_named_pipe());
+  }
+}
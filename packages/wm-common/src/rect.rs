@@ -109,6 +109,22 @@ impl Rect {
     )
   }
 
+  /// Returns a new `Rect` that is moved to be fully within the bounds of
+  /// the given outer rectangle. The rectangle is shrunk if it's larger
+  /// than the outer rectangle.
+  #[must_use]
+  pub fn translate_within(&self, outer_rect: &Rect) -> Self {
+    let width = self.width().min(outer_rect.width());
+    let height = self.height().min(outer_rect.height());
+
+    Self::from_xy(
+      self.left.clamp(outer_rect.left, outer_rect.right - width),
+      self.top.clamp(outer_rect.top, outer_rect.bottom - height),
+      width,
+      height,
+    )
+  }
+
   #[must_use]
   pub fn clamp_size(&self, width: i32, height: i32) -> Self {
     Self::from_xy(
@@ -202,4 +218,96 @@ impl Rect {
     #[allow(clippy::cast_precision_loss)]
     ((dx * dx + dy * dy) as f32).sqrt()
   }
+
+  /// Gets the area of the intersection with another rect, or 0 if they
+  /// don't intersect.
+  #[must_use]
+  pub fn intersection_area(&self, other: &Rect) -> i64 {
+    let width = self.right.min(other.right) - self.left.max(other.left);
+    let height = self.bottom.min(other.bottom) - self.top.max(other.top);
+
+    if width <= 0 || height <= 0 {
+      return 0;
+    }
+
+    i64::from(width) * i64::from(height)
+  }
+
+  /// Gets the squared distance from the given point to the nearest point
+  /// of the rect. Points inside the rect have a distance of 0.
+  #[must_use]
+  pub fn squared_distance_to_point(&self, point: &Point) -> i64 {
+    let dx =
+      i64::from((self.left - point.x).max(point.x - self.right).max(0));
+    let dy =
+      i64::from((self.top - point.y).max(point.y - self.bottom).max(0));
+
+    dx * dx + dy * dy
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn intersection_area_of_overlapping_rects() {
+    let rect = Rect::from_xy(0, 0, 100, 100);
+
+    assert_eq!(
+      rect.intersection_area(&Rect::from_xy(50, 50, 100, 100)),
+      2500
+    );
+    assert_eq!(
+      rect.intersection_area(&Rect::from_xy(10, 10, 20, 20)),
+      400
+    );
+  }
+
+  #[test]
+  fn intersection_area_of_adjacent_rects_is_zero() {
+    let rect = Rect::from_xy(0, 0, 100, 100);
+
+    assert_eq!(
+      rect.intersection_area(&Rect::from_xy(100, 0, 100, 100)),
+      0
+    );
+    assert_eq!(
+      rect.intersection_area(&Rect::from_xy(300, 300, 10, 10)),
+      0
+    );
+  }
+
+  #[test]
+  fn translate_within_moves_and_shrinks_rect() {
+    let outer_rect = Rect::from_xy(0, 0, 1000, 800);
+
+    // Rects that are already within are left as is.
+    let rect = Rect::from_xy(100, 100, 400, 300);
+    assert_eq!(rect.translate_within(&outer_rect), rect);
+
+    assert_eq!(
+      Rect::from_xy(900, -50, 400, 300).translate_within(&outer_rect),
+      Rect::from_xy(600, 0, 400, 300)
+    );
+    assert_eq!(
+      Rect::from_xy(-200, 100, 1200, 300).translate_within(&outer_rect),
+      Rect::from_xy(0, 100, 1000, 300)
+    );
+  }
+
+  #[test]
+  fn squared_distance_to_point() {
+    let rect = Rect::from_xy(0, 0, 100, 100);
+
+    assert_eq!(rect.squared_distance_to_point(&Point { x: 50, y: 50 }), 0);
+    assert_eq!(
+      rect.squared_distance_to_point(&Point { x: 103, y: 104 }),
+      25
+    );
+    assert_eq!(
+      rect.squared_distance_to_point(&Point { x: -10, y: 50 }),
+      100
+    );
+  }
 }
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// Minimum and maximum dimensions that a window accepts, as reported by
+/// the application (e.g. via `WM_GETMINMAXINFO`).
+///
+/// A `None` value means that the dimension is unconstrained.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeConstraints {
+  pub min_width: Option<i32>,
+  pub min_height: Option<i32>,
+  pub max_width: Option<i32>,
+  pub max_height: Option<i32>,
+}
+
+impl SizeConstraints {
+  /// Whether none of the dimensions are constrained.
+  #[must_use]
+  pub fn is_unconstrained(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Gets the minimum width or height in pixels.
+  #[must_use]
+  pub fn min_length(&self, is_width: bool) -> Option<i32> {
+    if is_width {
+      self.min_width
+    } else {
+      self.min_height
+    }
+  }
+
+  /// Gets the maximum width or height in pixels.
+  ///
+  /// Maximums smaller than the corresponding minimum are ignored.
+  #[must_use]
+  pub fn max_length(&self, is_width: bool) -> Option<i32> {
+    let max_length = if is_width {
+      self.max_width
+    } else {
+      self.max_height
+    };
+
+    max_length.filter(|&max_length| {
+      self
+        .min_length(is_width)
+        .is_none_or(|min_length| max_length >= min_length)
+    })
+  }
+
+  /// Clamps a width or height in pixels to the constraints.
+  #[must_use]
+  pub fn clamp_length(&self, length: i32, is_width: bool) -> i32 {
+    let length = match self.max_length(is_width) {
+      Some(max_length) => length.min(max_length),
+      None => length,
+    };
+
+    match self.min_length(is_width) {
+      Some(min_length) => length.max(min_length),
+      None => length,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn clamps_to_min_and_max() {
+    let constraints = SizeConstraints {
+      min_width: Some(400),
+      min_height: None,
+      max_width: Some(800),
+      max_height: Some(600),
+    };
+
+    assert_eq!(constraints.clamp_length(300, true), 400);
+    assert_eq!(constraints.clamp_length(500, true), 500);
+    assert_eq!(constraints.clamp_length(900, true), 800);
+    assert_eq!(constraints.clamp_length(100, false), 100);
+    assert_eq!(constraints.clamp_length(700, false), 600);
+  }
+
+  #[test]
+  fn ignores_max_below_min() {
+    let constraints = SizeConstraints {
+      min_width: Some(400),
+      max_width: Some(300),
+      ..SizeConstraints::default()
+    };
+
+    assert_eq!(constraints.max_length(true), None);
+    assert_eq!(constraints.clamp_length(1000, true), 1000);
+    assert!(!constraints.is_unconstrained());
+    assert!(SizeConstraints::default().is_unconstrained());
+  }
+}
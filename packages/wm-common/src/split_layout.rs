@@ -0,0 +1,61 @@
+use std::str::FromStr;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// How a split container arranges its children.
+#[derive(
+  Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitLayout {
+  /// Children are tiled side-by-side in the container's tiling direction.
+  #[default]
+  Split,
+  /// Children fill the container with only one shown at a time. Titles
+  /// are meant to be listed above each other.
+  Stacking,
+  /// Children fill the container with only one shown at a time. Titles
+  /// are meant to be shown as tabs.
+  Tabbed,
+}
+
+impl SplitLayout {
+  /// Whether only one child is shown at a time.
+  ///
+  /// Example:
+  /// ```
+  /// # use wm_common::SplitLayout;
+  /// assert!(SplitLayout::Tabbed.is_stacked());
+  /// assert!(!SplitLayout::Split.is_stacked());
+  /// ```
+  #[must_use]
+  pub fn is_stacked(&self) -> bool {
+    matches!(self, Self::Stacking | Self::Tabbed)
+  }
+}
+
+impl FromStr for SplitLayout {
+  type Err = anyhow::Error;
+
+  /// Parses a string into a split layout.
+  ///
+  /// Example:
+  /// ```
+  /// # use wm_common::SplitLayout;
+  /// # use std::str::FromStr;
+  /// let layout = SplitLayout::from_str("stacking");
+  /// assert_eq!(layout.unwrap(), SplitLayout::Stacking);
+  ///
+  /// let layout = SplitLayout::from_str("tabbed");
+  /// assert_eq!(layout.unwrap(), SplitLayout::Tabbed);
+  /// ```
+  fn from_str(unparsed: &str) -> anyhow::Result<Self> {
+    match unparsed {
+      "split" => Ok(Self::Split),
+      "stacking" => Ok(Self::Stacking),
+      "tabbed" => Ok(Self::Tabbed),
+      _ => bail!("Not a valid split layout: {}", unparsed),
+    }
+  }
+}
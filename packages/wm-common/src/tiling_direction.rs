@@ -17,7 +17,7 @@ impl TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::TilingDirection;
+  /// # use wm_common::TilingDirection;
   /// let dir = TilingDirection::Horizontal.inverse();
   /// assert_eq!(dir, TilingDirection::Vertical);
   /// ```
@@ -34,7 +34,7 @@ impl TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::{Direction, TilingDirection};
+  /// # use wm_common::{Direction, TilingDirection};
   /// let dir = TilingDirection::from_direction(&Direction::Left);
   /// assert_eq!(dir, TilingDirection::Horizontal);
   /// ```
@@ -54,7 +54,7 @@ impl FromStr for TilingDirection {
   ///
   /// Example:
   /// ```
-  /// # use wm::common::TilingDirection;
+  /// # use wm_common::TilingDirection;
   /// # use std::str::FromStr;
   /// let dir = TilingDirection::from_str("horizontal");
   /// assert_eq!(dir.unwrap(), TilingDirection::Horizontal);
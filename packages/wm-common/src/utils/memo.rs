@@ -31,6 +31,14 @@ where
     Self::default()
   }
 
+  /// Creates a new `Memo` instance with the given value already cached.
+  #[must_use]
+  pub fn with_value(value: T) -> Self {
+    Self {
+      value: Arc::new(Mutex::new(Some(value))),
+    }
+  }
+
   /// Retrieves the cached value if it exists, otherwise initializes it
   /// using the provided closure.
   ///
@@ -4,7 +4,7 @@ use uuid::Uuid;
 use crate::{
   dtos::ContainerDto,
   parsed_config::{BindingModeConfig, ParsedConfig},
-  TilingDirection,
+  MonitorField, Rect, TilingDirection,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -18,6 +18,13 @@ pub enum WmEvent {
   BindingModesChanged {
     new_binding_modes: Vec<BindingModeConfig>,
   },
+  /// Emitted when a builtin program (e.g. Zebar) exits without being
+  /// stopped by the WM.
+  BuiltinExited {
+    name: String,
+    /// Exit code of the process, if it exited normally.
+    exit_code: Option<i32>,
+  },
   FocusChanged {
     focused_container: ContainerDto,
   },
@@ -33,6 +40,7 @@ pub enum WmEvent {
   },
   MonitorUpdated {
     updated_monitor: ContainerDto,
+    changed: Vec<MonitorField>,
   },
   TilingDirectionChanged {
     direction_container: ContainerDto,
@@ -41,18 +49,59 @@ pub enum WmEvent {
   UserConfigChanged {
     config_path: String,
     config_string: String,
-    parsed_config: ParsedConfig,
+    parsed_config: Box<ParsedConfig>,
+  },
+  /// Emitted when a floating window is toggled to be shown above all
+  /// other windows, or back.
+  WindowAlwaysOnTopChanged {
+    updated_window: ContainerDto,
+    is_always_on_top: bool,
+  },
+  /// Emitted when a window is asked to close, or when its process is
+  /// terminated because it didn't close when asked to.
+  WindowCloseRequested {
+    closing_window: ContainerDto,
+    is_forced: bool,
+  },
+  /// Emitted when a window is repositioned during a redraw.
+  WindowLayoutChanged {
+    window_id: Uuid,
+    /// Rect computed by the layout, in physical pixels.
+    rect: Rect,
+    /// DPI of the window's monitor.
+    dpi: u32,
+    /// Scale factor of the window's monitor, for converting `rect` to
+    /// logical pixels.
+    scale_factor: f32,
   },
   WindowManaged {
     managed_window: ContainerDto,
   },
+  /// Emitted when a window is maximized or restored via its own
+  /// maximize button (or the OS).
+  WindowMaximizedChanged {
+    updated_window: ContainerDto,
+    is_maximized: bool,
+  },
   WindowUnmanaged {
     unmanaged_id: Uuid,
     unmanaged_handle: isize,
   },
+  /// Emitted when a window starts requesting attention (i.e. flashing in
+  /// the taskbar).
+  WindowUrgent {
+    urgent_window: ContainerDto,
+  },
+  /// Emitted when a workspace is created on a monitor, including when
+  /// one is activated to fill a monitor that was left without any (e.g.
+  /// after `move-workspace` or when a monitor is added). The monitor is
+  /// the workspace's `parent_id`.
   WorkspaceActivated {
     activated_workspace: ContainerDto,
   },
+  /// Emitted when a workspace is destroyed, as opposed to
+  /// `WorkspaceUpdated` which is only emitted for changes to a
+  /// workspace that stays active.
   WorkspaceDeactivated {
     deactivated_id: Uuid,
     deactivated_name: String,
@@ -0,0 +1,57 @@
+use std::str::FromStr;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// How a workspace arranges its tiling children.
+#[derive(
+  Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceLayout {
+  /// Children are tiled according to the splits made by the user.
+  #[default]
+  Manual,
+  /// Children are split between a master area and a stack, dwm-style.
+  /// Their tiling sizes are ignored.
+  MasterStack,
+}
+
+impl WorkspaceLayout {
+  /// Whether children are arranged into a master area and a stack.
+  ///
+  /// Example:
+  /// ```
+  /// # use wm_common::WorkspaceLayout;
+  /// assert!(WorkspaceLayout::MasterStack.is_master_stack());
+  /// assert!(!WorkspaceLayout::Manual.is_master_stack());
+  /// ```
+  #[must_use]
+  pub fn is_master_stack(&self) -> bool {
+    matches!(self, Self::MasterStack)
+  }
+}
+
+impl FromStr for WorkspaceLayout {
+  type Err = anyhow::Error;
+
+  /// Parses a string into a workspace layout.
+  ///
+  /// Example:
+  /// ```
+  /// # use wm_common::WorkspaceLayout;
+  /// # use std::str::FromStr;
+  /// let layout = WorkspaceLayout::from_str("master_stack");
+  /// assert_eq!(layout.unwrap(), WorkspaceLayout::MasterStack);
+  ///
+  /// let layout = WorkspaceLayout::from_str("manual");
+  /// assert_eq!(layout.unwrap(), WorkspaceLayout::Manual);
+  /// ```
+  fn from_str(unparsed: &str) -> anyhow::Result<Self> {
+    match unparsed {
+      "manual" => Ok(Self::Manual),
+      "master_stack" => Ok(Self::MasterStack),
+      _ => bail!("Not a valid workspace layout: {}", unparsed),
+    }
+  }
+}
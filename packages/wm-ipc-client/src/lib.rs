@@ -1,26 +1,67 @@
 #![allow(clippy::missing_errors_doc)]
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use futures_util::{SinkExt, StreamExt};
-use tokio::net::TcpStream;
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  net::TcpStream,
+};
 use tokio_tungstenite::{
-  connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+  client_async, tungstenite::Message, WebSocketStream,
 };
 use uuid::Uuid;
 use wm_common::{
-  ClientResponseMessage, EventSubscriptionMessage, ServerMessage,
-  DEFAULT_IPC_PORT,
+  ClientResponseMessage, EventSubscriptionMessage, IpcEndpoint,
+  ServerMessage,
 };
 
+/// Byte stream for any of the supported IPC transports.
+trait IpcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T> IpcStream for T where T: AsyncRead + AsyncWrite + Unpin + Send {}
+
 pub struct IpcClient {
-  stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+  stream: WebSocketStream<Box<dyn IpcStream>>,
 }
 
 impl IpcClient {
+  /// Connects to the IPC server at the endpoint given by
+  /// `IpcEndpoint::discover`.
   pub async fn connect() -> anyhow::Result<Self> {
-    let server_addr = format!("ws://127.0.0.1:{DEFAULT_IPC_PORT}");
+    Self::connect_to(&IpcEndpoint::discover()?).await
+  }
+
+  /// Connects to the IPC server at the given endpoint.
+  pub async fn connect_to(endpoint: &IpcEndpoint) -> anyhow::Result<Self> {
+    let stream: Box<dyn IpcStream> = match endpoint {
+      IpcEndpoint::Tcp(addr) => Box::new(
+        TcpStream::connect(addr)
+          .await
+          .context("Failed to connect to IPC server.")?,
+      ),
+      #[cfg(windows)]
+      IpcEndpoint::NamedPipe(name) => Box::new(
+        tokio::net::windows::named_pipe::ClientOptions::new()
+          .open(name)
+          .context("Failed to connect to IPC server.")?,
+      ),
+      #[cfg(unix)]
+      IpcEndpoint::UnixSocket(path) => Box::new(
+        tokio::net::UnixStream::connect(path)
+          .await
+          .context("Failed to connect to IPC server.")?,
+      ),
+      #[allow(unreachable_patterns)]
+      _ => bail!("IPC endpoint '{endpoint}' is not supported on this OS."),
+    };
+
+    // The host in the URL is only used for the WebSocket handshake.
+    let url = match endpoint {
+      IpcEndpoint::Tcp(addr) => format!("ws://{addr}"),
+      _ => "ws://localhost".to_string(),
+    };
 
-    let (stream, _) = connect_async(server_addr)
+    let (stream, _) = client_async(url, stream)
       .await
       .context("Failed to connect to IPC server.")?;
 
@@ -0,0 +1,264 @@
+use std::{sync::mpsc, thread};
+
+use anyhow::{bail, Context};
+use windows::{
+  core::w,
+  Win32::{
+    Foundation::{COLORREF, HWND, LPARAM, LRESULT, WPARAM},
+    Graphics::Gdi::{
+      BeginPaint, CombineRgn, CreateRectRgn, CreateSolidBrush,
+      DeleteObject, EndPaint, FillRect, InvalidateRect, SetWindowRgn,
+      HRGN, PAINTSTRUCT, RGN_DIFF,
+    },
+    UI::WindowsAndMessaging::{
+      CreateWindowExW, DefWindowProcW, GetWindow, GetWindowLongPtrW,
+      PostMessageW, PostQuitMessage, RegisterClassW,
+      SetLayeredWindowAttributes, SetWindowLongPtrW, SetWindowPos,
+      ShowWindowAsync, GWLP_USERDATA, GW_HWNDPREV, HWND_TOP, HWND_TOPMOST,
+      LWA_ALPHA, SWP_NOACTIVATE, SWP_NOZORDER, SWP_SHOWWINDOW, SW_HIDE,
+      WM_CLOSE, WM_DESTROY, WM_PAINT, WNDCLASSW, WS_EX_LAYERED,
+      WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TRANSPARENT, WS_POPUP,
+    },
+  },
+};
+use wm_common::{Color, Rect};
+
+use super::{NativeWindow, Platform};
+
+/// A click-through window that draws a colored frame over the edges of
+/// another window.
+///
+/// Used as a fallback for borders that DWM can't draw, either because
+/// colored borders are unsupported on the Windows build or because the
+/// border is thicker than DWM's 1px border. Can also be shown as a solid
+/// rect, which is used for drop hints while dragging tiling windows.
+///
+/// Each overlay runs its own
+/// message loop on a separate thread, and is destroyed on drop.
+#[derive(Debug)]
+pub struct BorderOverlay {
+  handle: isize,
+}
+
+impl BorderOverlay {
+  /// Creates a hidden border overlay.
+  pub fn new() -> anyhow::Result<Self> {
+    let (handle_tx, handle_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+      let handle = Self::create_window();
+      let is_created = handle.is_ok();
+      _ = handle_tx.send(handle);
+
+      if is_created {
+        Platform::run_message_loop();
+      }
+    });
+
+    let handle = handle_rx
+      .recv()
+      .context("Border overlay thread exited unexpectedly.")??;
+
+    Ok(Self { handle })
+  }
+
+  fn create_window() -> anyhow::Result<isize> {
+    let wnd_class = WNDCLASSW {
+      lpszClassName: w!("BorderOverlay"),
+      lpfnWndProc: Some(border_overlay_proc),
+      ..Default::default()
+    };
+
+    unsafe { RegisterClassW(&raw const wnd_class) };
+
+    // Layered + transparent windows let mouse input pass through, and
+    // tool windows are never managed by the WM.
+    let handle = unsafe {
+      CreateWindowExW(
+        WS_EX_LAYERED
+          | WS_EX_TRANSPARENT
+          | WS_EX_TOOLWINDOW
+          | WS_EX_NOACTIVATE,
+        w!("BorderOverlay"),
+        w!("BorderOverlay"),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        None,
+        None,
+        wnd_class.hInstance,
+        None,
+      )
+    };
+
+    if handle.0 == 0 {
+      bail!("Creation of border overlay window failed.");
+    }
+
+    Ok(handle.0)
+  }
+
+  /// Shows the overlay as a frame of the given width along the inside
+  /// edges of `rect`, directly above `window` in the z-order.
+  pub fn show(
+    &self,
+    rect: &Rect,
+    width: i32,
+    color: &Color,
+    window: &NativeWindow,
+  ) -> anyhow::Result<()> {
+    let handle = HWND(self.handle);
+    let width = width.min(rect.width() / 2).min(rect.height() / 2).max(1);
+
+    unsafe {
+      // The fill color is read from the window data when painting.
+      SetWindowLongPtrW(
+        handle,
+        GWLP_USERDATA,
+        isize::try_from(color.to_bgr()?)?,
+      );
+
+      SetLayeredWindowAttributes(handle, None, color.a, LWA_ALPHA)?;
+
+      // Cut out the inside of the frame. The system takes ownership of
+      // the resulting region.
+      let region = CreateRectRgn(0, 0, rect.width(), rect.height());
+      let inner_region = CreateRectRgn(
+        width,
+        width,
+        rect.width() - width,
+        rect.height() - width,
+      );
+
+      CombineRgn(region, region, inner_region, RGN_DIFF);
+      DeleteObject(inner_region);
+      SetWindowRgn(handle, region, true);
+
+      // Place the overlay right above the window by inserting it after
+      // the window that precedes it in the z-order.
+      let prev_window = GetWindow(HWND(window.handle), GW_HWNDPREV);
+
+      let insert_after = match prev_window {
+        prev_window if prev_window.0 == 0 => HWND_TOP,
+        prev_window => prev_window,
+      };
+
+      let mut swp_flags = SWP_NOACTIVATE | SWP_SHOWWINDOW;
+
+      // Skip the z-order change if the overlay is already right above.
+      if prev_window == handle {
+        swp_flags |= SWP_NOZORDER;
+      }
+
+      SetWindowPos(
+        handle,
+        insert_after,
+        rect.x(),
+        rect.y(),
+        rect.width(),
+        rect.height(),
+        swp_flags,
+      )?;
+
+      InvalidateRect(handle, None, true);
+    }
+
+    Ok(())
+  }
+
+  /// Shows the overlay as a solid rect above all other windows.
+  pub fn show_filled(
+    &self,
+    rect: &Rect,
+    color: &Color,
+  ) -> anyhow::Result<()> {
+    let handle = HWND(self.handle);
+
+    unsafe {
+      SetWindowLongPtrW(
+        handle,
+        GWLP_USERDATA,
+        isize::try_from(color.to_bgr()?)?,
+      );
+
+      SetLayeredWindowAttributes(handle, None, color.a, LWA_ALPHA)?;
+
+      // Clear any region from being shown as a frame.
+      SetWindowRgn(handle, HRGN::default(), true);
+
+      SetWindowPos(
+        handle,
+        HWND_TOPMOST,
+        rect.x(),
+        rect.y(),
+        rect.width(),
+        rect.height(),
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+      )?;
+
+      InvalidateRect(handle, None, true);
+    }
+
+    Ok(())
+  }
+
+  /// Hides the overlay.
+  pub fn hide(&self) {
+    unsafe { ShowWindowAsync(HWND(self.handle), SW_HIDE) };
+  }
+}
+
+impl Drop for BorderOverlay {
+  fn drop(&mut self) {
+    // Closing the window destroys it, which in turn exits the message
+    // loop of its thread.
+    _ = unsafe {
+      PostMessageW(
+        HWND(self.handle),
+        WM_CLOSE,
+        WPARAM::default(),
+        LPARAM::default(),
+      )
+    };
+  }
+}
+
+/// Window procedure for border overlays.
+///
+/// Fills the window with the color stored in its window data. Only the
+/// frame is visible due to the window's region.
+extern "system" fn border_overlay_proc(
+  handle: HWND,
+  message: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  match message {
+    WM_PAINT => {
+      let mut paint = PAINTSTRUCT::default();
+
+      unsafe {
+        let hdc = BeginPaint(handle, &raw mut paint);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let brush = CreateSolidBrush(COLORREF(GetWindowLongPtrW(
+          handle,
+          GWLP_USERDATA,
+        ) as u32));
+
+        FillRect(hdc, &raw const paint.rcPaint, brush);
+        DeleteObject(brush);
+        EndPaint(handle, &raw const paint);
+      }
+
+      LRESULT(0)
+    }
+    WM_DESTROY => {
+      unsafe { PostQuitMessage(0) };
+      LRESULT(0)
+    }
+    _ => unsafe { DefWindowProcW(handle, message, wparam, lparam) },
+  }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use tokio::sync::mpsc::{self, UnboundedReceiver};
 use wm_common::{
   BindingModeConfig, InvokeCommand, KeybindingConfig, ParsedConfig, Point,
@@ -9,8 +11,10 @@ use super::{EventWindow, NativeWindow};
 pub enum PlatformEvent {
   DisplaySettingsChanged,
   KeybindingTriggered(KeybindingConfig),
+  MouseDrag(MouseDragEvent),
   MouseMove(MouseMoveEvent),
   WindowDestroyed(NativeWindow),
+  WindowFlashed(NativeWindow),
   WindowFocused(NativeWindow),
   WindowHidden(NativeWindow),
   WindowLocationChanged(NativeWindow),
@@ -32,6 +36,35 @@ pub struct MouseMoveEvent {
   pub is_mouse_down: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct MouseDragEvent {
+  /// Whether the window under the cursor is being moved or resized.
+  pub action: MouseDragAction,
+
+  /// Whether the drag started, continued, or ended.
+  pub phase: MouseDragPhase,
+
+  /// Location of mouse with 0,0 being the top-left corner of the primary
+  /// monitor.
+  pub point: Point,
+}
+
+/// What a drag with the mouse binding modifier held does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseDragAction {
+  /// Left-click drag.
+  Move,
+  /// Right-click drag.
+  Resize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseDragPhase {
+  Start,
+  Update,
+  End,
+}
+
 pub struct EventListener {
   pub event_rx: UnboundedReceiver<PlatformEvent>,
   event_window: EventWindow,
@@ -47,7 +80,8 @@ impl EventListener {
     let event_window = EventWindow::new(
       &event_tx,
       &config.keybindings,
-      config.general.focus_follows_cursor,
+      Self::needs_mouse_events(config),
+      &config.mouse_bindings,
     )?;
 
     Ok(Self {
@@ -81,8 +115,25 @@ impl EventListener {
       }
     };
 
-    self
-      .event_window
-      .update(keybindings, config.general.focus_follows_cursor && !paused);
+    // Mouse bindings are released while paused, such that clicks with the
+    // modifier held reach other applications again.
+    self.event_window.update(
+      keybindings,
+      Self::needs_mouse_events(config) && !paused,
+      &config.mouse_bindings,
+      !paused,
+    );
+  }
+
+  /// Updates the windows that can be dragged via mouse bindings. Clicks
+  /// with the modifier held down are only blocked over these windows.
+  pub fn set_draggable_windows(&self, handles: HashSet<isize>) {
+    self.event_window.set_draggable_windows(handles);
+  }
+
+  /// Whether mouse events are needed by any of the enabled features.
+  fn needs_mouse_events(config: &ParsedConfig) -> bool {
+    config.general.focus_follows_cursor
+      || config.window_behavior.edge_resize.enabled
   }
 }
@@ -1,6 +1,7 @@
 use std::{
+  collections::HashSet,
   sync::{
-    atomic::{AtomicBool, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicIsize, AtomicU32, AtomicU64, Ordering},
     Arc, OnceLock,
   },
   thread::{self, JoinHandle},
@@ -9,32 +10,38 @@ use std::{
 
 use tokio::sync::mpsc;
 use tracing::{info, warn};
-use windows::Win32::{
-  Devices::HumanInterfaceDevice::{
-    HID_USAGE_GENERIC_MOUSE, HID_USAGE_PAGE_GENERIC,
-  },
-  Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
-  UI::{
-    Input::{
-      GetRawInputData, RegisterRawInputDevices, HRAWINPUT, RAWINPUT,
-      RAWINPUTDEVICE, RAWINPUTHEADER, RIDEV_INPUTSINK, RID_INPUT,
-      RIM_TYPEMOUSE,
+use windows::{
+  core::w,
+  Win32::{
+    Devices::HumanInterfaceDevice::{
+      HID_USAGE_GENERIC_MOUSE, HID_USAGE_PAGE_GENERIC,
     },
-    WindowsAndMessaging::{
-      DefWindowProcW, DestroyWindow, GetCursorPos, DBT_DEVNODES_CHANGED,
-      PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
-      RI_MOUSE_LEFT_BUTTON_DOWN, RI_MOUSE_LEFT_BUTTON_UP,
-      RI_MOUSE_RIGHT_BUTTON_DOWN, RI_MOUSE_RIGHT_BUTTON_UP,
-      SPI_ICONVERTICALSPACING, SPI_SETWORKAREA, WM_DEVICECHANGE,
-      WM_DISPLAYCHANGE, WM_INPUT, WM_POWERBROADCAST, WM_SETTINGCHANGE,
+    Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+    UI::{
+      Input::{
+        GetRawInputData, RegisterRawInputDevices, HRAWINPUT, RAWINPUT,
+        RAWINPUTDEVICE, RAWINPUTHEADER, RIDEV_INPUTSINK, RID_INPUT,
+        RIM_TYPEMOUSE,
+      },
+      WindowsAndMessaging::{
+        DefWindowProcW, DeregisterShellHookWindow, DestroyWindow,
+        GetCursorPos, PostMessageW, RegisterShellHookWindow,
+        RegisterWindowMessageW, DBT_DEVNODES_CHANGED, HSHELL_HIGHBIT,
+        HSHELL_REDRAW, PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND,
+        PBT_APMSUSPEND, RI_MOUSE_LEFT_BUTTON_DOWN,
+        RI_MOUSE_LEFT_BUTTON_UP, RI_MOUSE_RIGHT_BUTTON_DOWN,
+        RI_MOUSE_RIGHT_BUTTON_UP, SPI_ICONVERTICALSPACING,
+        SPI_SETWORKAREA, WM_APP, WM_DEVICECHANGE, WM_DISPLAYCHANGE,
+        WM_INPUT, WM_POWERBROADCAST, WM_SETTINGCHANGE,
+      },
     },
   },
 };
-use wm_common::{KeybindingConfig, Point};
+use wm_common::{KeybindingConfig, MouseBindingsConfig, Point};
 
 use super::{
-  KeyboardHook, MouseMoveEvent, Platform, PlatformEvent, WindowEventHook,
-  FOREGROUND_INPUT_IDENTIFIER,
+  KeyboardHook, MouseHook, MouseMoveEvent, NativeWindow, Platform,
+  PlatformEvent, WindowEventHook, FOREGROUND_INPUT_IDENTIFIER,
 };
 
 /// Global instance of sender for platform events.
@@ -43,6 +50,18 @@ use super::{
 static PLATFORM_EVENT_TX: OnceLock<mpsc::UnboundedSender<PlatformEvent>> =
   OnceLock::new();
 
+/// Global instance of the mouse hook.
+///
+/// For use with window procedure.
+static MOUSE_HOOK: OnceLock<Arc<MouseHook>> = OnceLock::new();
+
+/// Handle of the event window. Is 0 until the window is created.
+static EVENT_WINDOW_HANDLE: AtomicIsize = AtomicIsize::new(0);
+
+/// Message sent to the event window to start or stop the mouse hook,
+/// which has to be done on the thread that runs the message loop.
+const WM_SYNC_MOUSE_HOOK: u32 = WM_APP + 1;
+
 /// Whether mouse hook is currently enabled.
 ///
 /// For use with window procedure.
@@ -63,6 +82,12 @@ static IS_L_MOUSE_DOWN: AtomicBool = AtomicBool::new(false);
 /// For use with window procedure.
 static IS_R_MOUSE_DOWN: AtomicBool = AtomicBool::new(false);
 
+/// ID of the registered message for shell hook notifications (e.g. a
+/// window flashing in the taskbar).
+///
+/// For use with window procedure.
+static SHELL_HOOK_MESSAGE: AtomicU32 = AtomicU32::new(0);
+
 /// Timestamp of the last mouse event emission.
 ///
 /// For use with window procedure.
@@ -71,6 +96,7 @@ static LAST_MOUSE_EVENT_TIME: AtomicU64 = AtomicU64::new(0);
 #[derive(Debug)]
 pub struct EventWindow {
   keyboard_hook: Arc<KeyboardHook>,
+  mouse_hook: Arc<MouseHook>,
   window_thread: Option<JoinHandle<anyhow::Result<()>>>,
 }
 
@@ -84,16 +110,24 @@ impl EventWindow {
     event_tx: &mpsc::UnboundedSender<PlatformEvent>,
     keybindings: &Vec<KeybindingConfig>,
     enable_mouse_events: bool,
+    mouse_bindings: &MouseBindingsConfig,
   ) -> anyhow::Result<Self> {
     let keyboard_hook = KeyboardHook::new(keybindings, event_tx.clone())?;
+    let mouse_hook =
+      MouseHook::new(mouse_bindings, true, event_tx.clone())?;
     let window_event_hook = WindowEventHook::new(event_tx.clone())?;
     let keyboard_hook_clone = keyboard_hook.clone();
+    let mouse_hook_clone = mouse_hook.clone();
 
     // Add the sender for platform events to global state.
     PLATFORM_EVENT_TX.set(event_tx.clone()).map_err(|_| {
       anyhow::anyhow!("Platform event sender already set.")
     })?;
 
+    MOUSE_HOOK
+      .set(mouse_hook.clone())
+      .map_err(|_| anyhow::anyhow!("Mouse hook already set."))?;
+
     ENABLE_MOUSE_EVENTS.store(enable_mouse_events, Ordering::Relaxed);
 
     let window_thread = thread::spawn(move || {
@@ -105,6 +139,11 @@ impl EventWindow {
       let handle =
         Platform::create_message_window(Some(event_window_proc))?;
 
+      // The mouse hook is started after storing the window handle, such
+      // that mouse bindings that get toggled in between aren't missed.
+      EVENT_WINDOW_HANDLE.store(handle, Ordering::Relaxed);
+      mouse_hook_clone.start()?;
+
       let rid = RAWINPUTDEVICE {
         usUsagePage: HID_USAGE_PAGE_GENERIC,
         usUsage: HID_USAGE_GENERIC_MOUSE,
@@ -121,11 +160,24 @@ impl EventWindow {
         )
       }?;
 
+      // Register our window to receive shell hook notifications, which
+      // is how windows requesting attention get detected.
+      if unsafe { RegisterShellHookWindow(HWND(handle)) }.as_bool() {
+        SHELL_HOOK_MESSAGE.store(
+          unsafe { RegisterWindowMessageW(w!("SHELLHOOK")) },
+          Ordering::Relaxed,
+        );
+      } else {
+        warn!("Failed to register shell hook window.");
+      }
+
       Platform::run_message_loop();
 
       // Clean-up on message loop exit.
+      unsafe { DeregisterShellHookWindow(HWND(handle)) };
       unsafe { DestroyWindow(HWND(handle)) }?;
       keyboard_hook_clone.stop()?;
+      mouse_hook_clone.stop()?;
       window_event_hook.stop()?;
 
       Ok(())
@@ -133,6 +185,7 @@ impl EventWindow {
 
     Ok(Self {
       keyboard_hook,
+      mouse_hook,
       window_thread: Some(window_thread),
     })
   }
@@ -141,9 +194,34 @@ impl EventWindow {
     &mut self,
     keybindings: &Vec<KeybindingConfig>,
     enable_mouse_events: bool,
+    mouse_bindings: &MouseBindingsConfig,
+    enable_mouse_bindings: bool,
   ) {
     self.keyboard_hook.update(keybindings);
+    self
+      .mouse_hook
+      .update(mouse_bindings, enable_mouse_bindings);
     ENABLE_MOUSE_EVENTS.store(enable_mouse_events, Ordering::Relaxed);
+
+    let handle = EVENT_WINDOW_HANDLE.load(Ordering::Relaxed);
+
+    if handle != 0 {
+      if let Err(err) = unsafe {
+        PostMessageW(
+          HWND(handle),
+          WM_SYNC_MOUSE_HOOK,
+          WPARAM::default(),
+          LPARAM::default(),
+        )
+      } {
+        warn!("Failed to update mouse hook: {}", err);
+      }
+    }
+  }
+
+  /// Updates the windows that can be dragged via mouse bindings.
+  pub fn set_draggable_windows(&self, handles: HashSet<isize>) {
+    self.mouse_hook.set_draggable_windows(handles);
   }
 
   /// Destroys the event window and stops the message loop.
@@ -212,6 +290,15 @@ pub extern "system" fn event_window_proc(
 
         LRESULT(0)
       }
+      WM_SYNC_MOUSE_HOOK => {
+        if let Some(mouse_hook) = MOUSE_HOOK.get() {
+          if let Err(err) = mouse_hook.start() {
+            warn!("Failed to update mouse hook: {}", err);
+          }
+        }
+
+        LRESULT(0)
+      }
       WM_INPUT if ENABLE_MOUSE_EVENTS.load(Ordering::Relaxed) => {
         if let Err(err) = handle_input_msg(wparam, lparam, event_tx) {
           warn!("Failed to handle input message: {}", err);
@@ -219,6 +306,21 @@ pub extern "system" fn event_window_proc(
 
         LRESULT(0)
       }
+      message
+        if message != 0
+          && message == SHELL_HOOK_MESSAGE.load(Ordering::Relaxed) =>
+      {
+        // A window flashing in the taskbar is notified as a redraw
+        // with the high bit set.
+        #[allow(clippy::cast_possible_truncation)]
+        if wparam.0 as u32 == HSHELL_REDRAW | HSHELL_HIGHBIT {
+          _ = event_tx.send(PlatformEvent::WindowFlashed(
+            NativeWindow::new(lparam.0),
+          ));
+        }
+
+        LRESULT(0)
+      }
       _ => unsafe { DefWindowProcW(handle, message, wparam, lparam) },
     };
   }
@@ -171,7 +171,7 @@ impl KeyboardHook {
   }
 
   #[allow(clippy::too_many_lines)]
-  fn key_to_vk_code(key: &str) -> Option<u16> {
+  pub(crate) fn key_to_vk_code(key: &str) -> Option<u16> {
     match key.to_lowercase().as_str() {
       "a" => Some(VK_A.0),
       "b" => Some(VK_B.0),
@@ -3,20 +3,24 @@
 #![feature(iterator_try_collect)]
 #![feature(once_cell_try)]
 
+mod border_overlay;
 mod com;
 mod event_listener;
 mod event_window;
 mod keyboard_hook;
+mod mouse_hook;
 mod native_monitor;
 mod native_window;
 mod platform;
 mod single_instance;
 mod window_event_hook;
 
+pub use border_overlay::*;
 pub use com::*;
 pub use event_listener::*;
 pub use event_window::*;
 pub use keyboard_hook::*;
+pub use mouse_hook::*;
 pub use native_monitor::*;
 pub use native_window::*;
 pub use platform::*;
@@ -2,6 +2,7 @@ mod com;
 mod event_listener;
 mod event_window;
 mod keyboard_hook;
+mod mouse_hook;
 mod native_monitor;
 mod native_window;
 mod platform;
@@ -12,6 +13,7 @@ pub use com::*;
 pub use event_listener::*;
 pub use event_window::*;
 pub use keyboard_hook::*;
+pub use mouse_hook::*;
 pub use native_monitor::*;
 pub use native_window::*;
 pub use platform::*;
@@ -0,0 +1,367 @@
+use std::{
+  collections::HashSet,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex, OnceLock,
+  },
+  time::SystemTime,
+};
+
+use tokio::sync::mpsc;
+use tracing::warn;
+use windows::Win32::{
+  Foundation::{LPARAM, LRESULT, WPARAM},
+  UI::{
+    Input::KeyboardAndMouse::{
+      SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT,
+      KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, VIRTUAL_KEY,
+    },
+    WindowsAndMessaging::{
+      CallNextHookEx, SetWindowsHookExW, UnhookWindowsHookEx, HHOOK,
+      MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_LBUTTONDOWN, WM_LBUTTONUP,
+      WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP,
+    },
+  },
+};
+use wm_common::{MouseBindingsConfig, Point};
+
+use super::{
+  KeyboardHook, MouseDragAction, MouseDragEvent, MouseDragPhase, Platform,
+  PlatformEvent, FOREGROUND_INPUT_IDENTIFIER,
+};
+
+/// Global instance of `MouseHook`.
+///
+/// For use with hook procedure.
+static MOUSE_HOOK: OnceLock<Arc<MouseHook>> = OnceLock::new();
+
+/// Minimum time in milliseconds between drag updates.
+const DRAG_UPDATE_INTERVAL_MS: u64 = 10;
+
+/// Unassigned virtual-key code that gets tapped when a drag starts.
+const MASK_KEY: VIRTUAL_KEY = VIRTUAL_KEY(0xE8);
+
+#[derive(Debug)]
+pub struct MouseHook {
+  /// Sender to emit platform events.
+  event_tx: mpsc::UnboundedSender<PlatformEvent>,
+
+  /// Handle to the mouse hook. Is only installed while mouse bindings
+  /// are enabled.
+  hook: Arc<Mutex<HHOOK>>,
+
+  /// Keys that need to be held down for a click to start a drag. `None`
+  /// if mouse bindings are disabled.
+  modifier_keys: Arc<Mutex<Option<Vec<String>>>>,
+
+  /// Handles of windows that can be dragged. Clicks on other windows
+  /// are passed through, even if the modifier is held down.
+  draggable_windows: Arc<Mutex<HashSet<isize>>>,
+
+  /// Drag that is currently in progress.
+  active_drag: Arc<Mutex<Option<MouseDragAction>>>,
+
+  /// Timestamp of the last drag update emission.
+  last_update_time: AtomicU64,
+}
+
+impl MouseHook {
+  /// Creates an instance of `MouseHook`.
+  pub fn new(
+    config: &MouseBindingsConfig,
+    enabled: bool,
+    event_tx: mpsc::UnboundedSender<PlatformEvent>,
+  ) -> anyhow::Result<Arc<Self>> {
+    let mouse_hook = Arc::new(Self {
+      event_tx,
+      hook: Arc::new(Mutex::new(HHOOK::default())),
+      modifier_keys: Arc::new(Mutex::new(Self::modifier_keys(
+        config, enabled,
+      ))),
+      draggable_windows: Arc::new(Mutex::new(HashSet::new())),
+      active_drag: Arc::new(Mutex::new(None)),
+      last_update_time: AtomicU64::new(0),
+    });
+
+    MOUSE_HOOK
+      .set(mouse_hook.clone())
+      .map_err(|_| anyhow::anyhow!("Mouse hook already running."))?;
+
+    Ok(mouse_hook)
+  }
+
+  /// Starts a low-level mouse hook on the current thread if mouse
+  /// bindings are enabled, or stops it if they're disabled.
+  ///
+  /// Assumes that a message loop is currently running. Needs to be called
+  /// again from the same thread after `update`.
+  ///
+  /// # Panics
+  ///
+  /// If the internal mutex is poisoned.
+  pub fn start(&self) -> anyhow::Result<()> {
+    let is_enabled = self.modifier_keys.lock().unwrap().is_some();
+    let mut hook = self.hook.lock().unwrap();
+
+    if is_enabled && hook.is_invalid() {
+      *hook = unsafe {
+        SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), None, 0)
+      }?;
+    } else if !is_enabled && !hook.is_invalid() {
+      unsafe { UnhookWindowsHookEx(*hook) }?;
+      *hook = HHOOK::default();
+    }
+
+    Ok(())
+  }
+
+  /// Updates the mouse bindings for the mouse hook. A drag in progress
+  /// is ended if mouse bindings get disabled.
+  ///
+  /// The hook itself is only started or stopped on the next call to
+  /// `start`.
+  ///
+  /// # Panics
+  ///
+  /// If the internal mutex is poisoned.
+  pub fn update(&self, config: &MouseBindingsConfig, enabled: bool) {
+    let modifier_keys = Self::modifier_keys(config, enabled);
+
+    if modifier_keys.is_none() {
+      self.end_drag();
+    }
+
+    *self.modifier_keys.lock().unwrap() = modifier_keys;
+  }
+
+  /// Updates the windows that can be dragged.
+  ///
+  /// # Panics
+  ///
+  /// If the internal mutex is poisoned.
+  pub fn set_draggable_windows(&self, handles: HashSet<isize>) {
+    *self.draggable_windows.lock().unwrap() = handles;
+  }
+
+  /// Stops the low-level mouse hook, ending any drag in progress.
+  ///
+  /// # Panics
+  ///
+  /// If the internal mutex is poisoned.
+  pub fn stop(&self) -> anyhow::Result<()> {
+    *self.modifier_keys.lock().unwrap() = None;
+    self.end_drag();
+    self.start()
+  }
+
+  /// Gets the keys of the modifier to hold down for a drag.
+  ///
+  /// Returns `None` if mouse bindings are disabled or the modifier
+  /// contains an unrecognized key, since every click would otherwise be
+  /// treated as a drag.
+  fn modifier_keys(
+    config: &MouseBindingsConfig,
+    enabled: bool,
+  ) -> Option<Vec<String>> {
+    if !enabled || !config.enabled {
+      return None;
+    }
+
+    let keys = config
+      .modifier
+      .split('+')
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+
+    for key in &keys {
+      if KeyboardHook::key_to_vk_code(key).is_none() {
+        warn!(
+          "Unrecognized mouse binding modifier '{}'. Mouse bindings are disabled.",
+          key
+        );
+
+        return None;
+      }
+    }
+
+    Some(keys)
+  }
+
+  /// Ends the drag in progress, if any.
+  fn end_drag(&self) {
+    if let Some(action) = self.active_drag.lock().unwrap().take() {
+      if let Ok(point) = Platform::mouse_position() {
+        self.emit(action, MouseDragPhase::End, point);
+      }
+    }
+  }
+
+  /// Emits a platform event if the mouse event starts, continues, or ends
+  /// a drag.
+  ///
+  /// Returns `true` if the event should be blocked and not sent to other
+  /// applications.
+  fn handle_mouse_event(&self, message: u32, point: Point) -> bool {
+    let mut active_drag = self.active_drag.lock().unwrap();
+
+    match (message, *active_drag) {
+      (WM_LBUTTONDOWN | WM_RBUTTONDOWN, None) => {
+        // Clicks are only blocked if they start a drag, such that e.g.
+        // clicks on the desktop or on unmanaged windows go through.
+        if !self.is_modifier_down() || !self.is_draggable_at(&point) {
+          return false;
+        }
+
+        let action = if message == WM_LBUTTONDOWN {
+          MouseDragAction::Move
+        } else {
+          MouseDragAction::Resize
+        };
+
+        *active_drag = Some(action);
+        Self::mask_modifier_release();
+        self.emit(action, MouseDragPhase::Start, point);
+        true
+      }
+      (WM_MOUSEMOVE, Some(action)) => {
+        #[allow(clippy::cast_possible_truncation)]
+        let event_time = SystemTime::now()
+          .duration_since(SystemTime::UNIX_EPOCH)
+          .map_or(0, |dur| dur.as_millis() as u64);
+
+        let last_update_time =
+          self.last_update_time.load(Ordering::Relaxed);
+
+        // Throttle updates, since each one moves or resizes the window.
+        if event_time.saturating_sub(last_update_time)
+          >= DRAG_UPDATE_INTERVAL_MS
+        {
+          self.last_update_time.store(event_time, Ordering::Relaxed);
+          self.emit(action, MouseDragPhase::Update, point);
+        }
+
+        // The cursor should still move along with the drag.
+        false
+      }
+      (WM_LBUTTONUP, Some(MouseDragAction::Move))
+      | (WM_RBUTTONUP, Some(MouseDragAction::Resize)) => {
+        if let Some(action) = active_drag.take() {
+          self.emit(action, MouseDragPhase::End, point);
+        }
+
+        true
+      }
+      // Block the other button while dragging, such that it doesn't
+      // click through to the window being dragged.
+      (
+        WM_LBUTTONDOWN | WM_LBUTTONUP | WM_RBUTTONDOWN | WM_RBUTTONUP,
+        Some(_),
+      ) => true,
+      _ => false,
+    }
+  }
+
+  fn emit(
+    &self,
+    action: MouseDragAction,
+    phase: MouseDragPhase,
+    point: Point,
+  ) {
+    let _ = self.event_tx.send(PlatformEvent::MouseDrag(MouseDragEvent {
+      action,
+      phase,
+      point,
+    }));
+  }
+
+  /// Gets whether all keys of the modifier are currently down.
+  fn is_modifier_down(&self) -> bool {
+    self
+      .modifier_keys
+      .lock()
+      .unwrap()
+      .as_ref()
+      .is_some_and(|keys| {
+        keys
+          .iter()
+          .all(|key| Platform::is_key_down(key).unwrap_or(false))
+      })
+  }
+
+  /// Gets whether the window under the given point can be dragged.
+  fn is_draggable_at(&self, point: &Point) -> bool {
+    let Ok(window) = Platform::window_from_point(point)
+      .and_then(|window| Platform::root_ancestor(&window))
+    else {
+      return false;
+    };
+
+    self
+      .draggable_windows
+      .lock()
+      .unwrap()
+      .contains(&window.handle)
+  }
+
+  /// Taps an unassigned key, such that releasing the modifier after the
+  /// drag doesn't open the start menu (for `lwin`) or focus the menu bar
+  /// of the focused window (for `alt`).
+  fn mask_modifier_release() {
+    let input = |flags| INPUT {
+      r#type: INPUT_KEYBOARD,
+      Anonymous: INPUT_0 {
+        ki: KEYBDINPUT {
+          wVk: MASK_KEY,
+          dwFlags: flags,
+          dwExtraInfo: FOREGROUND_INPUT_IDENTIFIER as usize,
+          ..Default::default()
+        },
+      },
+    };
+
+    let inputs =
+      [input(KEYBD_EVENT_FLAGS::default()), input(KEYEVENTF_KEYUP)];
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    unsafe {
+      SendInput(&inputs, std::mem::size_of::<INPUT>() as i32)
+    };
+  }
+}
+
+extern "system" fn mouse_hook_proc(
+  code: i32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  // If the code is less than zero, the hook procedure must pass the hook
+  // notification directly to other applications.
+  if code != 0 {
+    return unsafe { CallNextHookEx(None, code, wparam, lparam) };
+  }
+
+  // Get struct with mouse input event.
+  let input = unsafe { *(lparam.0 as *const MSLLHOOKSTRUCT) };
+
+  // Ignore inputs simulated by the WM (e.g. by
+  // `NativeWindow::set_foreground`).
+  if input.dwExtraInfo == FOREGROUND_INPUT_IDENTIFIER as usize {
+    return unsafe { CallNextHookEx(None, code, wparam, lparam) };
+  }
+
+  if let Some(hook) = MOUSE_HOOK.get() {
+    let point = Point {
+      x: input.pt.x,
+      y: input.pt.y,
+    };
+
+    #[allow(clippy::cast_possible_truncation)]
+    let should_block = hook.handle_mouse_event(wparam.0 as u32, point);
+
+    if should_block {
+      return LRESULT(1);
+    }
+  }
+
+  unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
@@ -11,7 +11,9 @@ use windows::{
     },
     UI::{
       HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
-      WindowsAndMessaging::EDD_GET_DEVICE_INTERFACE_NAME,
+      WindowsAndMessaging::{
+        EDD_GET_DEVICE_INTERFACE_NAME, MONITORINFOF_PRIMARY,
+      },
     },
   },
 };
@@ -32,6 +34,7 @@ struct MonitorInfo {
   working_rect: Rect,
   dpi: u32,
   scale_factor: f32,
+  is_primary: bool,
 }
 
 impl NativeMonitor {
@@ -43,6 +46,40 @@ impl NativeMonitor {
     }
   }
 
+  /// Creates a `NativeMonitor` instance with the given rect and a DPI of
+  /// 96, such that its details aren't read from the OS.
+  #[cfg(feature = "test_utils")]
+  #[must_use]
+  pub fn with_rect(handle: isize, rect: Rect) -> Self {
+    Self::with_rects(handle, rect.clone(), rect)
+  }
+
+  /// Creates a `NativeMonitor` instance like [`NativeMonitor::with_rect`],
+  /// but with space reserved outside of the given working area.
+  #[cfg(feature = "test_utils")]
+  #[must_use]
+  pub fn with_rects(
+    handle: isize,
+    rect: Rect,
+    working_rect: Rect,
+  ) -> Self {
+    let info = MonitorInfo {
+      device_name: format!("\\\\.\\DISPLAY{handle}"),
+      device_path: None,
+      hardware_id: None,
+      is_primary: rect.x() == 0 && rect.y() == 0,
+      working_rect,
+      rect,
+      dpi: 96,
+      scale_factor: 1.0,
+    };
+
+    Self {
+      handle,
+      info: OnceCell::from(info),
+    }
+  }
+
   pub fn device_name(&self) -> anyhow::Result<&String> {
     self.monitor_info().map(|info| &info.device_name)
   }
@@ -71,6 +108,12 @@ impl NativeMonitor {
     self.monitor_info().map(|info| info.scale_factor)
   }
 
+  /// Whether this is the primary monitor. The primary monitor is the one
+  /// positioned at 0,0 and can be changed at runtime via display settings.
+  pub fn is_primary(&self) -> anyhow::Result<bool> {
+    self.monitor_info().map(|info| info.is_primary)
+  }
+
   fn monitor_info(&self) -> anyhow::Result<&MonitorInfo> {
     self.info.get_or_try_init(|| {
       let mut monitor_info = MONITORINFOEXW {
@@ -153,6 +196,9 @@ impl NativeMonitor {
         rc_work.bottom,
       );
 
+      let is_primary =
+        monitor_info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0;
+
       Ok(MonitorInfo {
         device_name,
         device_path,
@@ -161,6 +207,7 @@ impl NativeMonitor {
         working_rect,
         dpi,
         scale_factor,
+        is_primary,
       })
     })
   }
@@ -1,12 +1,20 @@
-use std::time::Duration;
+use std::{
+  sync::{Arc, Mutex},
+  time::Duration,
+};
 
 use anyhow::{bail, Context};
 use tokio::task;
 use tracing::warn;
 use windows::{
   core::PWSTR,
+  Wdk::System::Threading::{
+    NtQueryInformationProcess, ProcessCommandLineInformation,
+  },
   Win32::{
-    Foundation::{CloseHandle, BOOL, HWND, LPARAM, RECT},
+    Foundation::{
+      CloseHandle, BOOL, HWND, LPARAM, RECT, UNICODE_STRING, WPARAM,
+    },
     Graphics::Dwm::{
       DwmGetWindowAttribute, DwmSetWindowAttribute, DWMWA_BORDER_COLOR,
       DWMWA_CLOAKED, DWMWA_COLOR_NONE, DWMWA_EXTENDED_FRAME_BOUNDS,
@@ -14,36 +22,41 @@ use windows::{
       DWMWCP_ROUND, DWMWCP_ROUNDSMALL,
     },
     System::Threading::{
-      OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
-      PROCESS_QUERY_LIMITED_INFORMATION,
+      OpenProcess, QueryFullProcessImageNameW, TerminateProcess,
+      PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+      PROCESS_TERMINATE,
     },
     UI::{
       Input::KeyboardAndMouse::{
         SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEINPUT,
       },
       WindowsAndMessaging::{
-        EnumWindows, GetClassNameW, GetLayeredWindowAttributes, GetWindow,
+        EnumWindows, GetClassNameW, GetGUIThreadInfo,
+        GetLayeredWindowAttributes, GetSystemMetrics, GetWindow,
         GetWindowLongPtrW, GetWindowRect, GetWindowTextW,
         GetWindowThreadProcessId, IsIconic, IsWindowVisible, IsZoomed,
-        SendNotifyMessageW, SetForegroundWindow,
+        SendMessageTimeoutW, SendNotifyMessageW, SetForegroundWindow,
         SetLayeredWindowAttributes, SetWindowLongPtrW, SetWindowPlacement,
-        SetWindowPos, ShowWindowAsync, GWL_EXSTYLE, GWL_STYLE, GW_OWNER,
-        HWND_NOTOPMOST, HWND_TOP, HWND_TOPMOST,
+        SetWindowPos, ShowWindowAsync, GUITHREADINFO, GUI_INMENUMODE,
+        GUI_POPUPMENUMODE, GUI_SYSTEMMENUMODE, GWL_EXSTYLE, GWL_STYLE,
+        GW_HWNDPREV, GW_OWNER, HWND_NOTOPMOST, HWND_TOP, HWND_TOPMOST,
         LAYERED_WINDOW_ATTRIBUTES_FLAGS, LWA_ALPHA, LWA_COLORKEY,
-        SWP_ASYNCWINDOWPOS, SWP_FRAMECHANGED, SWP_NOACTIVATE,
-        SWP_NOCOPYBITS, SWP_NOMOVE, SWP_NOOWNERZORDER, SWP_NOSENDCHANGING,
-        SWP_NOSIZE, SWP_NOZORDER, SWP_SHOWWINDOW, SW_HIDE, SW_MAXIMIZE,
-        SW_MINIMIZE, SW_RESTORE, SW_SHOWNA, WINDOWPLACEMENT,
-        WINDOW_EX_STYLE, WINDOW_STYLE, WM_CLOSE, WPF_ASYNCWINDOWPLACEMENT,
-        WS_CAPTION, WS_CHILD, WS_DLGFRAME, WS_EX_LAYERED,
-        WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_MAXIMIZEBOX, WS_THICKFRAME,
+        MINMAXINFO, SMTO_ABORTIFHUNG, SM_CXMAXTRACK, SM_CXMINTRACK,
+        SM_CYMAXTRACK, SM_CYMINTRACK, SWP_ASYNCWINDOWPOS,
+        SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOCOPYBITS, SWP_NOMOVE,
+        SWP_NOOWNERZORDER, SWP_NOSENDCHANGING, SWP_NOSIZE, SWP_NOZORDER,
+        SWP_SHOWWINDOW, SW_HIDE, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE,
+        SW_SHOWNA, WINDOWPLACEMENT, WINDOW_EX_STYLE, WINDOW_STYLE,
+        WM_CLOSE, WM_GETMINMAXINFO, WPF_ASYNCWINDOWPLACEMENT, WS_CAPTION,
+        WS_CHILD, WS_DLGFRAME, WS_EX_LAYERED, WS_EX_NOACTIVATE,
+        WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_MAXIMIZEBOX, WS_THICKFRAME,
       },
     },
   },
 };
 use wm_common::{
   Color, CornerStyle, Delta, HideMethod, LengthValue, Memo, OpacityValue,
-  Rect, RectDelta, WindowState,
+  Rect, RectDelta, SizeConstraints, WindowState,
 };
 
 use super::COM_INIT;
@@ -65,11 +78,17 @@ pub struct NativeWindow {
   pub handle: isize,
   title: Memo<String>,
   process_name: Memo<String>,
+  process_path: Memo<String>,
+  command_line: Memo<String>,
   class_name: Memo<String>,
   frame_position: Memo<Rect>,
   border_position: Memo<Rect>,
   is_minimized: Memo<bool>,
   is_maximized: Memo<bool>,
+
+  /// Frame styles (`WS_CAPTION` and `WS_THICKFRAME`) that were removed
+  /// from the window, and that get added back once it's restored.
+  removed_frame_style: Arc<Mutex<Option<isize>>>,
 }
 
 impl NativeWindow {
@@ -80,11 +99,33 @@ impl NativeWindow {
       handle,
       title: Memo::new(),
       process_name: Memo::new(),
+      process_path: Memo::new(),
+      command_line: Memo::new(),
       class_name: Memo::new(),
       frame_position: Memo::new(),
       border_position: Memo::new(),
       is_minimized: Memo::new(),
       is_maximized: Memo::new(),
+      removed_frame_style: Arc::new(Mutex::new(None)),
+    }
+  }
+
+  /// Creates a `NativeWindow` instance with its title, class name, and
+  /// process name already cached, such that they aren't read from the
+  /// window.
+  #[cfg(feature = "test_utils")]
+  #[must_use]
+  pub fn with_details(
+    handle: isize,
+    title: &str,
+    class_name: &str,
+    process_name: &str,
+  ) -> Self {
+    Self {
+      title: Memo::with_value(title.to_string()),
+      class_name: Memo::with_value(class_name.to_string()),
+      process_name: Memo::with_value(process_name.to_string()),
+      ..Self::new(handle)
     }
   }
 
@@ -121,8 +162,28 @@ impl NativeWindow {
       .get_or_init(Self::updated_process_name, self)
   }
 
-  /// Gets the process name associated with the window.
-  fn updated_process_name(&self) -> anyhow::Result<String> {
+  /// Gets the full path to the executable of the window's process.
+  ///
+  /// This value is lazily retrieved and cached after first retrieval.
+  pub fn process_path(&self) -> anyhow::Result<String> {
+    self
+      .process_path
+      .get_or_init(Self::updated_process_path, self)
+  }
+
+  /// Gets the command line that the window's process was launched with.
+  ///
+  /// This value is lazily retrieved and cached after first retrieval.
+  pub fn command_line(&self) -> anyhow::Result<String> {
+    self
+      .command_line
+      .get_or_init(Self::updated_command_line, self)
+  }
+
+  /// Gets the ID of the process that created the window. Returns 0 if
+  /// the window is invalid.
+  #[must_use]
+  pub fn process_id(&self) -> u32 {
     let mut process_id = 0u32;
     unsafe {
       GetWindowThreadProcessId(
@@ -131,8 +192,74 @@ impl NativeWindow {
       );
     }
 
+    process_id
+  }
+
+  /// Gets the window's owner (e.g. the main window of a dialog), if any.
+  #[must_use]
+  pub fn owner(&self) -> Option<NativeWindow> {
+    let handle = unsafe { GetWindow(HWND(self.handle), GW_OWNER) };
+    (handle.0 != 0).then(|| NativeWindow::new(handle.0))
+  }
+
+  /// Gets the window directly above this window in the z-order.
+  ///
+  /// Returns `None` if the window is already the topmost non-topmost
+  /// window (i.e. only "always on top" windows are above it).
+  #[must_use]
+  pub fn window_above(&self) -> Option<NativeWindow> {
+    let handle = unsafe { GetWindow(HWND(self.handle), GW_HWNDPREV) };
+
+    (handle.0 != 0)
+      .then(|| NativeWindow::new(handle.0))
+      .filter(|window| !window.has_window_style_ex(WS_EX_TOPMOST))
+  }
+
+  /// Whether the thread that created the window is currently showing a
+  /// menu (e.g. a context menu or the title bar menu).
+  #[must_use]
+  pub fn is_menu_open(&self) -> bool {
+    let thread_id =
+      unsafe { GetWindowThreadProcessId(HWND(self.handle), None) };
+
+    let mut thread_info = GUITHREADINFO {
+      #[allow(clippy::cast_possible_truncation)]
+      cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+      ..Default::default()
+    };
+
+    if unsafe { GetGUIThreadInfo(thread_id, &raw mut thread_info) }
+      .is_err()
+    {
+      return false;
+    }
+
+    let menu_flags =
+      GUI_INMENUMODE | GUI_POPUPMENUMODE | GUI_SYSTEMMENUMODE;
+    (thread_info.flags & menu_flags).0 != 0
+  }
+
+  /// Gets the process name associated with the window.
+  fn updated_process_name(&self) -> anyhow::Result<String> {
+    let exe_path = self.process_path()?;
+
+    exe_path
+      .split('\\')
+      .next_back()
+      .map(|file_name| {
+        file_name.split('.').next().unwrap_or(file_name).to_string()
+      })
+      .context("Failed to parse process name.")
+  }
+
+  /// Gets the full path to the executable of the window's process.
+  fn updated_process_path(&self) -> anyhow::Result<String> {
     let process_handle = unsafe {
-      OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id)
+      OpenProcess(
+        PROCESS_QUERY_LIMITED_INFORMATION,
+        false,
+        self.process_id(),
+      )
     }?;
 
     let mut buffer = [0u16; 256];
@@ -148,15 +275,62 @@ impl NativeWindow {
       CloseHandle(process_handle)?;
     };
 
-    let exe_path = String::from_utf16_lossy(&buffer[..length as usize]);
+    Ok(String::from_utf16_lossy(&buffer[..length as usize]))
+  }
 
-    exe_path
-      .split('\\')
-      .next_back()
-      .map(|file_name| {
-        file_name.split('.').next().unwrap_or(file_name).to_string()
-      })
-      .context("Failed to parse process name.")
+  /// Gets the command line that the window's process was launched with.
+  fn updated_command_line(&self) -> anyhow::Result<String> {
+    let process_handle = unsafe {
+      OpenProcess(
+        PROCESS_QUERY_LIMITED_INFORMATION,
+        false,
+        self.process_id(),
+      )
+    }?;
+
+    // Query the required buffer size first. The buffer gets filled with a
+    // `UNICODE_STRING` that points to the command line right after it.
+    let mut length = 0u32;
+    let _ = unsafe {
+      NtQueryInformationProcess(
+        process_handle,
+        ProcessCommandLineInformation,
+        std::ptr::null_mut(),
+        0,
+        &raw mut length,
+      )
+    };
+
+    // Use a `u64` buffer to satisfy the alignment of `UNICODE_STRING`.
+    let mut buffer = vec![0u64; (length as usize).div_ceil(8)];
+    let status = unsafe {
+      NtQueryInformationProcess(
+        process_handle,
+        ProcessCommandLineInformation,
+        buffer.as_mut_ptr().cast(),
+        u32::try_from(buffer.len() * 8)?,
+        &raw mut length,
+      )
+    };
+
+    unsafe { CloseHandle(process_handle) }?;
+    status.ok()?;
+
+    let command_line =
+      unsafe { &*buffer.as_ptr().cast::<UNICODE_STRING>() };
+
+    if command_line.Length == 0 || command_line.Buffer.is_null() {
+      return Ok(String::new());
+    }
+
+    let chars = unsafe {
+      std::slice::from_raw_parts(
+        command_line.Buffer.0,
+        usize::from(command_line.Length / 2),
+      )
+    };
+
+    Ok(String::from_utf16_lossy(chars))
   }
 
   /// Gets the class name of the window.
@@ -243,8 +417,7 @@ impl NativeWindow {
     // bar menu in Keepass. Although not foolproof, these can typically be
     // identified by having an owner window and no title bar.
     let is_menu_window =
-      unsafe { GetWindow(HWND(self.handle), GW_OWNER) }.0 != 0
-        && !self.has_window_style(WS_CAPTION);
+      self.owner().is_some() && !self.has_window_style(WS_CAPTION);
 
     Ok(!is_menu_window)
   }
@@ -295,6 +468,58 @@ impl NativeWindow {
     self.has_window_style(WS_THICKFRAME)
   }
 
+  /// Gets the minimum and maximum size that the window can be resized
+  /// to, as reported by its application via `WM_GETMINMAXINFO`.
+  ///
+  /// Sizes that match the system defaults for the window's styles are
+  /// treated as unconstrained. Windows without resize handles have no
+  /// size constraints.
+  pub fn size_constraints(&self) -> anyhow::Result<SizeConstraints> {
+    if !self.is_resizable() {
+      return Ok(SizeConstraints::default());
+    }
+
+    // Fields that the application doesn't set are left zeroed, and are
+    // treated as unconstrained.
+    let mut info = MINMAXINFO::default();
+
+    let result = unsafe {
+      SendMessageTimeoutW(
+        HWND(self.handle),
+        WM_GETMINMAXINFO,
+        WPARAM(0),
+        LPARAM(std::ptr::from_mut(&mut info) as _),
+        SMTO_ABORTIFHUNG,
+        200,
+        None,
+      )
+    };
+
+    if result.0 == 0 {
+      bail!("Window did not respond to WM_GETMINMAXINFO.");
+    }
+
+    let (min_width, min_height, max_width, max_height) = unsafe {
+      (
+        GetSystemMetrics(SM_CXMINTRACK),
+        GetSystemMetrics(SM_CYMINTRACK),
+        GetSystemMetrics(SM_CXMAXTRACK),
+        GetSystemMetrics(SM_CYMAXTRACK),
+      )
+    };
+
+    Ok(SizeConstraints {
+      min_width: Some(info.ptMinTrackSize.x)
+        .filter(|&width| width > min_width),
+      min_height: Some(info.ptMinTrackSize.y)
+        .filter(|&height| height > min_height),
+      max_width: Some(info.ptMaxTrackSize.x)
+        .filter(|&width| width > 0 && width < max_width),
+      max_height: Some(info.ptMaxTrackSize.y)
+        .filter(|&height| height > 0 && height < max_height),
+    })
+  }
+
   /// Whether the window is fullscreen.
   ///
   /// Returns `false` if the window is maximized.
@@ -426,6 +651,65 @@ impl NativeWindow {
     Ok(())
   }
 
+  /// Removes or restores the window's title bar and resize frame.
+  ///
+  /// Only the frame styles that the window originally had are restored.
+  /// Unlike `set_title_bar_visibility`, the frame change is applied
+  /// synchronously, such that the window's position can be refreshed
+  /// right after. Returns whether the frame was changed.
+  ///
+  /// # Panics
+  ///
+  /// If the internal mutex is poisoned.
+  pub fn set_frame_visibility(
+    &self,
+    visible: bool,
+  ) -> anyhow::Result<bool> {
+    let mut removed_style = self.removed_frame_style.lock().unwrap();
+
+    if visible == removed_style.is_none() {
+      return Ok(false);
+    }
+
+    let style = unsafe { GetWindowLongPtrW(HWND(self.handle), GWL_STYLE) };
+
+    #[allow(clippy::cast_possible_wrap)]
+    let frame_style = (WS_CAPTION.0 | WS_THICKFRAME.0) as isize;
+
+    let new_style = if let Some(removed) = removed_style.take() {
+      style | removed
+    } else {
+      let removed = style & frame_style;
+
+      if removed == 0 {
+        return Ok(false);
+      }
+
+      *removed_style = Some(removed);
+      style & !frame_style
+    };
+
+    unsafe {
+      SetWindowLongPtrW(HWND(self.handle), GWL_STYLE, new_style);
+      SetWindowPos(
+        HWND(self.handle),
+        HWND_NOTOPMOST,
+        0,
+        0,
+        0,
+        0,
+        SWP_FRAMECHANGED
+          | SWP_NOMOVE
+          | SWP_NOSIZE
+          | SWP_NOZORDER
+          | SWP_NOOWNERZORDER
+          | SWP_NOACTIVATE,
+      )?;
+    }
+
+    Ok(true)
+  }
+
   fn add_window_style_ex(&self, style: WINDOW_EX_STYLE) {
     let current_style =
       unsafe { GetWindowLongPtrW(HWND(self.handle), GWL_EXSTYLE) };
@@ -646,6 +930,25 @@ impl NativeWindow {
     Ok(())
   }
 
+  /// Forcibly terminates the process that created the window. This
+  /// closes all other windows of the process as well.
+  pub fn terminate_process(&self) -> anyhow::Result<()> {
+    let process_id = self.process_id();
+
+    if process_id == 0 || process_id == std::process::id() {
+      bail!("Refusing to terminate process with ID {process_id}.");
+    }
+
+    let process_handle =
+      unsafe { OpenProcess(PROCESS_TERMINATE, false, process_id) }?;
+
+    let res = unsafe { TerminateProcess(process_handle, 1) };
+    unsafe { CloseHandle(process_handle) }?;
+    res?;
+
+    Ok(())
+  }
+
   pub fn set_visible(
     &self,
     visible: bool,
@@ -824,6 +1127,30 @@ impl NativeWindow {
     Ok(())
   }
 
+  /// Moves and resizes the window to the given position, including the
+  /// window's shadow borders.
+  ///
+  /// Unlike `set_position`, the window's state, z-order, and visibility
+  /// are left as is.
+  pub fn set_border_position(&self, rect: &Rect) -> anyhow::Result<()> {
+    unsafe {
+      SetWindowPos(
+        HWND(self.handle),
+        HWND(0),
+        rect.x(),
+        rect.y(),
+        rect.width(),
+        rect.height(),
+        SWP_NOACTIVATE
+          | SWP_NOZORDER
+          | SWP_NOOWNERZORDER
+          | SWP_ASYNCWINDOWPOS,
+      )
+    }?;
+
+    Ok(())
+  }
+
   /// Marks the window as fullscreen.
   ///
   /// Causes the native Windows taskbar to be moved to the bottom of the
@@ -898,6 +1225,7 @@ impl NativeWindow {
     }
 
     _ = self.set_taskbar_visibility(true);
+    _ = self.set_frame_visibility(true);
     _ = self.set_border_color(None);
     _ = self.set_transparency(&OpacityValue::from_alpha(u8::MAX));
   }
@@ -1,18 +1,30 @@
 use std::{
+  collections::HashMap,
   os::windows::io::AsRawHandle,
   path::{Path, PathBuf},
   thread::JoinHandle,
+  time::Duration,
 };
 
 use anyhow::{bail, Context};
 use windows::{
   core::{w, PCWSTR},
   Win32::{
-    Foundation::{HANDLE, HWND, LPARAM, POINT, WPARAM},
+    Foundation::{
+      CloseHandle, HANDLE, HWND, LPARAM, POINT, WAIT_TIMEOUT, WPARAM,
+    },
     System::{
-      Environment::ExpandEnvironmentStringsW, Threading::GetThreadId,
+      Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32First, Process32Next,
+        PROCESSENTRY32, TH32CS_SNAPPROCESS,
+      },
+      Environment::ExpandEnvironmentStringsW,
+      Threading::{
+        GetProcessId, GetThreadId, TerminateProcess, WaitForSingleObject,
+      },
     },
     UI::{
+      Input::KeyboardAndMouse::GetAsyncKeyState,
       Shell::{
         ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS,
         SHELLEXECUTEINFOW,
@@ -35,8 +47,8 @@ use windows::{
 use wm_common::{ParsedConfig, Point};
 
 use super::{
-  native_monitor, native_window, EventListener, NativeMonitor,
-  NativeWindow, SingleInstance,
+  native_monitor, native_window, EventListener, KeyboardHook,
+  NativeMonitor, NativeWindow, SingleInstance,
 };
 
 pub type WindowProcedure = WNDPROC;
@@ -140,6 +152,38 @@ impl Platform {
     Ok(NativeWindow::new(handle.0))
   }
 
+  /// Gets the parent process ID of every running process, keyed by
+  /// process ID.
+  ///
+  /// Parent IDs are not validated, and may point at a process that has
+  /// since exited or at an unrelated process that reused its ID.
+  pub fn parent_process_ids() -> anyhow::Result<HashMap<u32, u32>> {
+    let mut parent_ids = HashMap::new();
+
+    let snapshot =
+      unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }?;
+
+    let mut entry = PROCESSENTRY32 {
+      #[allow(clippy::cast_possible_truncation)]
+      dwSize: std::mem::size_of::<PROCESSENTRY32>() as u32,
+      ..Default::default()
+    };
+
+    if unsafe { Process32First(snapshot, &raw mut entry) }.is_ok() {
+      loop {
+        parent_ids.insert(entry.th32ProcessID, entry.th32ParentProcessID);
+
+        if unsafe { Process32Next(snapshot, &raw mut entry) }.is_err() {
+          break;
+        }
+      }
+    }
+
+    unsafe { CloseHandle(snapshot) }?;
+
+    Ok(parent_ids)
+  }
+
   /// Sets the cursor position to the specified coordinates.
   pub fn set_cursor_pos(x: i32, y: i32) -> anyhow::Result<()> {
     unsafe {
@@ -171,6 +215,20 @@ impl Platform {
     })
   }
 
+  /// Gets whether the key with the given name (e.g. `shift`) is
+  /// currently held down.
+  pub fn is_key_down(key: &str) -> anyhow::Result<bool> {
+    let vk_code = KeyboardHook::key_to_vk_code(key)
+      .with_context(|| format!("Unrecognized key '{key}'."))?;
+
+    // The async key state is used, since the WM's own thread doesn't
+    // receive keyboard input.
+    #[allow(clippy::cast_sign_loss)]
+    let key_state = unsafe { GetAsyncKeyState(vk_code.into()) } as u16;
+
+    Ok(key_state & 0x8000 == 0x8000)
+  }
+
   /// Creates a hidden message window.
   ///
   /// Returns a handle to the created window.
@@ -429,11 +487,69 @@ impl Platform {
   }
 
   /// Runs the specified program with the given arguments.
+  ///
+  /// Returns the ID of the launched process. This is `None` if the
+  /// command was handed off to an already running process (e.g. opening
+  /// a URL).
   pub fn run_command(
     program: &str,
     args: &str,
     hide_window: bool,
+  ) -> anyhow::Result<Option<u32>> {
+    let process = Self::shell_execute(program, args, hide_window)?;
+
+    if process.is_invalid() {
+      return Ok(None);
+    }
+
+    let process_id = unsafe { GetProcessId(process) };
+    unsafe { CloseHandle(process) }?;
+
+    Ok((process_id != 0).then_some(process_id))
+  }
+
+  /// Runs the specified program like `run_command`, but blocks until it
+  /// exits. The process is terminated if it's still running after the
+  /// timeout.
+  pub fn run_command_with_timeout(
+    program: &str,
+    args: &str,
+    hide_window: bool,
+    timeout: Duration,
   ) -> anyhow::Result<()> {
+    let process = Self::shell_execute(program, args, hide_window)?;
+
+    // No process handle is returned if the command was handed off to an
+    // already running process (e.g. opening a URL).
+    if process.is_invalid() {
+      return Ok(());
+    }
+
+    let timeout_ms =
+      u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+    let has_timed_out =
+      unsafe { WaitForSingleObject(process, timeout_ms) } == WAIT_TIMEOUT;
+
+    if has_timed_out {
+      let _ = unsafe { TerminateProcess(process, 1) };
+    }
+
+    unsafe { CloseHandle(process) }?;
+
+    if has_timed_out {
+      bail!("Command timed out after {timeout:?} and was terminated.");
+    }
+
+    Ok(())
+  }
+
+  /// Launches a program via `ShellExecuteExW` and returns a handle to the
+  /// created process, if any.
+  fn shell_execute(
+    program: &str,
+    args: &str,
+    hide_window: bool,
+  ) -> anyhow::Result<HANDLE> {
     let home_dir = home::home_dir()
       .context("Unable to get home directory.")?
       .to_str()
@@ -464,7 +580,7 @@ impl Platform {
     };
 
     unsafe { ShellExecuteExW(&raw mut exec_info) }?;
-    Ok(())
+    Ok(exec_info.hProcess)
   }
 
   pub fn show_error_dialog(title: &str, message: &str) {
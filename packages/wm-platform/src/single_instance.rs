@@ -7,8 +7,7 @@ use windows::{
       ERROR_FILE_NOT_FOUND, HANDLE,
     },
     System::Threading::{
-      CreateMutexW, OpenMutexW, ReleaseMutex,
-      SYNCHRONIZATION_ACCESS_RIGHTS,
+      CreateMutexW, OpenMutexW, ReleaseMutex, SYNCHRONIZATION_SYNCHRONIZE,
     },
   },
 };
@@ -30,6 +29,9 @@ impl SingleInstance {
 
     if let Err(err) = unsafe { GetLastError() } {
       if err == ERROR_ALREADY_EXISTS.into() {
+        // Close the handle to the existing mutex, such that it's freed
+        // once the running instance exits.
+        unsafe { CloseHandle(handle) }?;
         bail!("Another instance of the application is already running.");
       }
     }
@@ -40,15 +42,18 @@ impl SingleInstance {
   /// Gets whether there is an active instance of the application.
   #[must_use]
   pub fn is_running() -> bool {
-    let res = unsafe {
-      OpenMutexW(SYNCHRONIZATION_ACCESS_RIGHTS::default(), false, APP_GUID)
-    };
+    let res =
+      unsafe { OpenMutexW(SYNCHRONIZATION_SYNCHRONIZE, false, APP_GUID) };
 
-    // Check whether the mutex exists. If it doesn't, then this is the
-    // only instance.
+    // Check whether the mutex exists. If it doesn't, then there's no
+    // other instance. Access can be denied to a mutex that exists (e.g.
+    // when it's owned by an elevated instance).
     match res {
-      Ok(_) => false,
-      Err(err) => err == ERROR_FILE_NOT_FOUND.into(),
+      Ok(handle) => {
+        unsafe { _ = CloseHandle(handle) };
+        true
+      }
+      Err(err) => err != ERROR_FILE_NOT_FOUND.into(),
     }
   }
 }
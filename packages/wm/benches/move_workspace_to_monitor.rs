@@ -0,0 +1,55 @@
+//! Benchmarks moving a workspace with many windows between monitors.
+//!
+//! Run with `cargo bench -p wm --features test_utils`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use wm::{
+  commands::workspace::{
+    move_workspace_to_monitor_impl, MoveWorkspaceOptions,
+  },
+  test_utils,
+};
+
+fn move_workspace(c: &mut Criterion) {
+  let mut group = c.benchmark_group("move_workspace_to_monitor");
+
+  for window_count in [250, 1000, 4000] {
+    let (mut state, mut event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let origin_monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&origin_monitor, "origin");
+    let target_monitor = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&target_monitor, "target");
+
+    let workspace = test_utils::workspace_with_windows(
+      &origin_monitor,
+      "moved",
+      window_count,
+    );
+
+    group.bench_function(BenchmarkId::from_parameter(window_count), |b| {
+      // Moves the workspace there and back, such that every iteration
+      // starts from the same tree.
+      b.iter(|| {
+        for monitor in [&target_monitor, &origin_monitor] {
+          move_workspace_to_monitor_impl(
+            &workspace,
+            monitor,
+            &mut state,
+            &config,
+            MoveWorkspaceOptions::SINGLE,
+          )
+          .unwrap();
+        }
+
+        test_utils::emitted_events(&mut event_rx);
+      });
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, move_workspace);
+criterion_main!(benches);
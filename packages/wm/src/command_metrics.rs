@@ -0,0 +1,124 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  time::Duration,
+};
+
+use wm_common::CommandMetricsData;
+
+/// Max number of durations to keep per command for calculating latency
+/// percentiles. Older samples are discarded first.
+const MAX_SAMPLES: usize = 1000;
+
+/// Timings of commands run via `WindowManager::run_command`.
+///
+/// Only recorded if `general.metrics` is enabled in the user config.
+#[derive(Default)]
+pub struct CommandMetrics {
+  commands: HashMap<&'static str, CommandSamples>,
+}
+
+#[derive(Default)]
+struct CommandSamples {
+  /// Total number of times the command has run.
+  count: u64,
+
+  /// Most recent durations of the command.
+  durations: VecDeque<Duration>,
+}
+
+impl CommandMetrics {
+  pub fn record(
+    &mut self,
+    command_name: &'static str,
+    duration: Duration,
+  ) {
+    let samples = self.commands.entry(command_name).or_default();
+    samples.count += 1;
+
+    if samples.durations.len() == MAX_SAMPLES {
+      samples.durations.pop_front();
+    }
+
+    samples.durations.push_back(duration);
+  }
+
+  /// Gets metrics for all recorded commands, sorted by command name.
+  pub fn to_data(&self) -> Vec<CommandMetricsData> {
+    let mut data = self
+      .commands
+      .iter()
+      .map(|(name, samples)| {
+        let mut durations =
+          samples.durations.iter().copied().collect::<Vec<_>>();
+        durations.sort_unstable();
+
+        CommandMetricsData {
+          name: (*name).to_string(),
+          count: samples.count,
+          p50_ms: percentile(&durations, 50),
+          p99_ms: percentile(&durations, 99),
+        }
+      })
+      .collect::<Vec<_>>();
+
+    data.sort_by(|a, b| a.name.cmp(&b.name));
+    data
+  }
+}
+
+/// Gets the nearest-rank percentile of sorted durations in milliseconds.
+fn percentile(sorted_durations: &[Duration], percentile: usize) -> f64 {
+  if sorted_durations.is_empty() {
+    return 0.0;
+  }
+
+  let rank = (percentile * sorted_durations.len()).div_ceil(100);
+  let index = rank.clamp(1, sorted_durations.len()) - 1;
+
+  sorted_durations[index].as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn calculates_percentiles() {
+    let mut metrics = CommandMetrics::default();
+
+    for ms in (1..=100).rev() {
+      metrics.record("focus", Duration::from_millis(ms));
+    }
+
+    metrics.record("wm-redraw", Duration::from_millis(5));
+
+    let data = metrics.to_data();
+    assert_eq!(data.len(), 2);
+
+    assert_eq!(data[0].name, "focus");
+    assert_eq!(data[0].count, 100);
+    assert!((data[0].p50_ms - 50.0).abs() < 1e-9);
+    assert!((data[0].p99_ms - 99.0).abs() < 1e-9);
+
+    assert_eq!(data[1].name, "wm-redraw");
+    assert!((data[1].p50_ms - 5.0).abs() < 1e-9);
+    assert!((data[1].p99_ms - 5.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn discards_oldest_samples() {
+    let mut metrics = CommandMetrics::default();
+
+    for _ in 0..MAX_SAMPLES {
+      metrics.record("close", Duration::from_millis(100));
+    }
+
+    for _ in 0..MAX_SAMPLES {
+      metrics.record("close", Duration::from_millis(1));
+    }
+
+    let data = metrics.to_data();
+    assert_eq!(data[0].count, 2 * MAX_SAMPLES as u64);
+    assert!((data[0].p99_ms - 1.0).abs() < 1e-9);
+  }
+}
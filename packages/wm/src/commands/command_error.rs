@@ -0,0 +1,57 @@
+use std::fmt;
+
+/// Errors from commands that callers (e.g. IPC clients) may want to
+/// distinguish from other failures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+  /// No monitor exists at the requested index.
+  MonitorIndexOutOfRange { requested: usize, available: usize },
+
+  /// The monitor at the requested index is in standby.
+  MonitorInStandby { index: usize },
+}
+
+impl CommandError {
+  /// Stable identifier of the error, included in IPC responses.
+  #[must_use]
+  pub fn code(&self) -> &'static str {
+    match self {
+      Self::MonitorIndexOutOfRange { .. } => "monitor_index_out_of_range",
+      Self::MonitorInStandby { .. } => "monitor_in_standby",
+    }
+  }
+}
+
+impl fmt::Display for CommandError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::MonitorIndexOutOfRange { requested, .. } => {
+        write!(f, "Monitor at index {requested} was not found.")
+      }
+      Self::MonitorInStandby { index } => {
+        write!(f, "Monitor at index {index} is in standby.")
+      }
+    }
+  }
+}
+
+impl std::error::Error for CommandError {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_message_when_wrapped_in_anyhow() {
+    let err = anyhow::Error::from(CommandError::MonitorIndexOutOfRange {
+      requested: 3,
+      available: 2,
+    });
+
+    assert_eq!(err.to_string(), "Monitor at index 3 was not found.");
+    assert_eq!(
+      err.downcast_ref::<CommandError>().map(CommandError::code),
+      Some("monitor_index_out_of_range")
+    );
+  }
+}
@@ -0,0 +1,136 @@
+use anyhow::Context;
+use wm_common::WmEvent;
+
+use crate::{
+  models::{Container, DirectionContainer},
+  traits::{CommonGetters, TilingSizeGetters},
+  wm_state::WmState,
+};
+
+/// Resets the tiling sizes of a container and its siblings to equal
+/// shares.
+///
+/// If `recursive` is set, every container in the workspace is balanced
+/// instead. Non-tiling windows are left untouched, and balancing from one
+/// does nothing.
+pub fn balance_windows(
+  container: &Container,
+  recursive: bool,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let workspace = container.workspace().context("No workspace.")?;
+
+  let direction_containers = match container {
+    Container::TilingWindow(_)
+    | Container::Split(_)
+    | Container::Workspace(_)
+      if recursive =>
+    {
+      workspace
+        .self_and_descendants()
+        .filter_map(|descendant| descendant.as_direction_container().ok())
+        .collect::<Vec<_>>()
+    }
+    Container::TilingWindow(_) | Container::Split(_) => {
+      vec![container.direction_container().context("No parent.")?]
+    }
+    Container::Workspace(workspace) => {
+      vec![workspace.clone().into()]
+    }
+    // Non-tiling windows have no tiling size to balance.
+    _ => return Ok(()),
+  };
+
+  for direction_container in &direction_containers {
+    balance_children(direction_container);
+  }
+
+  let redraw_target = match (recursive, direction_containers.first()) {
+    (false, Some(parent)) => parent.clone().into(),
+    _ => Container::from(workspace.clone()),
+  };
+
+  state.pending_sync.queue_container_to_redraw(redraw_target);
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
+
+fn balance_children(parent: &DirectionContainer) {
+  let tiling_children = parent.tiling_children().collect::<Vec<_>>();
+
+  for (child, share) in tiling_children
+    .iter()
+    .zip(equal_shares(tiling_children.len()))
+  {
+    child.set_tiling_size(share);
+  }
+}
+
+/// Splits 1.0 into `count` equal shares. The last share absorbs any
+/// rounding error, such that the shares sum to exactly 1.0.
+#[allow(clippy::cast_precision_loss)]
+fn equal_shares(count: usize) -> Vec<f32> {
+  if count == 0 {
+    return Vec::new();
+  }
+
+  let share = 1.0 / count as f32;
+  let mut shares = vec![share; count - 1];
+
+  let remainder = 1.0 - shares.iter().sum::<f32>();
+  shares.push(remainder);
+
+  shares
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{FloatingStateConfig, WindowState};
+
+  use super::*;
+  use crate::test_utils;
+
+  #[test]
+  fn shares_sum_to_exactly_one() {
+    for count in 1..=16 {
+      let shares = equal_shares(count);
+
+      assert_eq!(shares.len(), count);
+      assert!((shares.iter().sum::<f32>() - 1.0).abs() <= f32::EPSILON);
+      assert!(shares.iter().all(|share| (share - shares[0]).abs() < 1e-6));
+    }
+  }
+
+  #[test]
+  fn no_shares_for_no_children() {
+    assert!(equal_shares(0).is_empty());
+  }
+
+  #[test]
+  fn recursive_ignores_non_tiling_subject() {
+    let (mut state, mut event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+    let floating = test_utils::non_tiling_window(
+      &workspace,
+      3,
+      WindowState::Floating(FloatingStateConfig::default()),
+    );
+
+    first.set_tiling_size(0.7);
+    second.set_tiling_size(0.3);
+
+    balance_windows(&floating.into(), true, &mut state).unwrap();
+
+    assert!((first.tiling_size() - 0.7).abs() < f32::EPSILON);
+    assert!((second.tiling_size() - 0.3).abs() < f32::EPSILON);
+    assert!(test_utils::emitted_events(&mut event_rx).is_empty());
+  }
+}
@@ -13,9 +13,19 @@ use crate::{
 /// H[1 H[2, 3]] -> H[1, 2, 3]
 /// H[V[1]] -> V[1]
 /// ```
+///
+/// Stacking and tabbed containers are never flattened, and neither are
+/// the children of one, since they make up its stack.
 pub fn flatten_child_split_containers(
   parent: &Container,
 ) -> anyhow::Result<()> {
+  if parent
+    .as_split()
+    .is_some_and(|parent| parent.layout().is_stacked())
+  {
+    return Ok(());
+  }
+
   if let Ok(parent) = parent.as_direction_container() {
     // Get children that are either tiling windows or split containers.
     let tiling_children = parent
@@ -27,7 +37,10 @@ pub fn flatten_child_split_containers(
     if tiling_children.len() == 1 {
       // Handle case where the parent is a split container and has a
       // single split container child.
-      if let Some(split_child) = tiling_children[0].as_split() {
+      if let Some(split_child) = tiling_children[0]
+        .as_split()
+        .filter(|split_child| !split_child.layout().is_stacked())
+      {
         flatten_split_container(split_child.clone())?;
         parent.set_tiling_direction(parent.tiling_direction().inverse());
       }
@@ -39,12 +52,15 @@ pub fn flatten_child_split_containers(
 
       for split_child in split_children.iter().filter(|split_child| {
         split_child.tiling_direction() == parent.tiling_direction()
+          && !split_child.layout().is_stacked()
       }) {
         // Additionally flatten redundant top-level split containers in
         // the child.
         if split_child.child_count() == 1 {
           if let Some(split_grandchild) =
-            split_child.children()[0].as_split()
+            split_child.children()[0].as_split().filter(
+              |split_grandchild| !split_grandchild.layout().is_stacked(),
+            )
           {
             flatten_split_container(split_grandchild.clone())?;
           }
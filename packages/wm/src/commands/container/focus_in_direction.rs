@@ -1,10 +1,15 @@
+use std::cmp::Reverse;
+
 use anyhow::Context;
-use wm_common::{Direction, TilingDirection, WindowState};
+use wm_common::{Direction, Rect, TilingDirection, WindowState};
 
 use super::set_focused_descendant;
 use crate::{
-  models::{Container, TilingContainer},
-  traits::{CommonGetters, TilingDirectionGetters, WindowGetters},
+  models::{Container, TilingContainer, Workspace},
+  traits::{
+    CommonGetters, PositionGetters, TilingDirectionGetters, WindowGetters,
+  },
+  user_config::UserConfig,
   wm_state::WmState,
 };
 
@@ -12,28 +17,47 @@ pub fn focus_in_direction(
   origin_container: &Container,
   direction: &Direction,
   state: &mut WmState,
+  config: &UserConfig,
 ) -> anyhow::Result<()> {
+  let crosses_monitors = config.value.general.focus_crosses_monitors;
+
   let focus_target = match origin_container {
     Container::TilingWindow(_) => {
       // If a suitable focus target isn't found in the current workspace,
-      // attempt to find a workspace in the given direction.
-      tiling_focus_target(origin_container, direction)?.map_or_else(
-        || workspace_focus_target(origin_container, direction, state),
-        |container| Ok(Some(container)),
-      )?
+      // attempt to find a workspace in the given direction. Otherwise,
+      // optionally wrap around within the current workspace.
+      if let Some(container) =
+        tiling_focus_target(origin_container, direction)?
+      {
+        Some(container)
+      } else {
+        let workspace_target = if crosses_monitors {
+          workspace_focus_target(origin_container, direction, state)?
+        } else {
+          None
+        };
+
+        match workspace_target {
+          Some(container) => Some(container),
+          None if config.value.general.focus_wraps => {
+            wrapped_focus_target(origin_container, direction)?
+          }
+          None => None,
+        }
+      }
     }
     Container::NonTilingWindow(ref non_tiling_window) => {
       match non_tiling_window.state() {
         WindowState::Floating(_) => {
           floating_focus_target(origin_container, direction)
         }
-        WindowState::Fullscreen(_) => {
+        WindowState::Fullscreen(_) if crosses_monitors => {
           workspace_focus_target(origin_container, direction, state)?
         }
         _ => None,
       }
     }
-    Container::Workspace(_) => {
+    Container::Workspace(_) if crosses_monitors => {
       workspace_focus_target(origin_container, direction, state)?
     }
     _ => None,
@@ -78,7 +102,7 @@ fn floating_focus_target(
 
 /// Gets a focus target within the current workspace. Traverse upwards from
 /// the origin container to find an adjacent container that can be focused.
-fn tiling_focus_target(
+pub fn tiling_focus_target(
   origin_container: &Container,
   direction: &Direction,
 ) -> anyhow::Result<Option<Container>> {
@@ -130,8 +154,9 @@ fn tiling_focus_target(
 /// direction.
 ///
 /// This will descend into the workspace in the given direction, and will
-/// always return a tiling container. This makes it different from the
-/// `focus_workspace` command with `FocusWorkspaceTarget::Direction`.
+/// always return a tiling container (or the workspace itself if it has
+/// none). This makes it different from the `focus_workspace` command with
+/// `FocusWorkspaceTarget::Direction`.
 fn workspace_focus_target(
   origin_container: &Container,
   direction: &Direction,
@@ -153,15 +178,189 @@ fn workspace_focus_target(
       _ => false,
     });
 
-  let focus_target = focused_fullscreen
-    .or_else(|| {
-      target_workspace.as_ref().and_then(|workspace| {
-        workspace
-          .descendant_in_direction(&direction.inverse())
-          .map(Into::into)
-      })
-    })
-    .or(target_workspace.map(Into::into));
+  let focus_target = match (focused_fullscreen, target_workspace) {
+    (Some(fullscreen), _) => Some(fullscreen),
+    (None, Some(workspace)) => Some(
+      aligned_focus_target(
+        &workspace,
+        origin_container,
+        direction,
+        false,
+      )?
+      .unwrap_or_else(|| workspace.into()),
+    ),
+    (None, None) => None,
+  };
 
   Ok(focus_target)
 }
+
+/// Gets a focus target on the opposite side of the current workspace,
+/// for when there's nothing further in the given direction.
+fn wrapped_focus_target(
+  origin_container: &Container,
+  direction: &Direction,
+) -> anyhow::Result<Option<Container>> {
+  let workspace = origin_container.workspace().context("No workspace.")?;
+
+  aligned_focus_target(&workspace, origin_container, direction, true)
+}
+
+/// Gets the visible tiling window of a workspace that would be entered
+/// first when moving into it in the given direction, and that best lines
+/// up with the origin container.
+///
+/// Among equally good candidates, the most recently focused one is
+/// picked. The origin container is never returned. When wrapping within
+/// the origin's workspace, only windows that lie beyond the origin in the
+/// opposite direction are candidates.
+fn aligned_focus_target(
+  workspace: &Workspace,
+  origin_container: &Container,
+  direction: &Direction,
+  is_wrapping: bool,
+) -> anyhow::Result<Option<Container>> {
+  let origin_rect = origin_container.to_rect()?;
+
+  let candidates = workspace
+    .descendant_focus_order()
+    .filter(|descendant| {
+      descendant.is_tiling_window()
+        && descendant.id() != origin_container.id()
+        && !descendant.is_hidden_in_stack()
+        && !descendant.is_hidden_by_monocle()
+    })
+    .map(|descendant| Ok((descendant.to_rect()?, descendant)))
+    .collect::<anyhow::Result<Vec<_>>>()?
+    .into_iter()
+    .filter(|(rect, _)| {
+      !is_wrapping
+        || entry_distance(rect, direction)
+          < entry_distance(&origin_rect, direction)
+    })
+    .collect::<Vec<_>>();
+
+  let rects = candidates
+    .iter()
+    .map(|(rect, _)| rect.clone())
+    .collect::<Vec<_>>();
+
+  Ok(
+    best_aligned_index(&origin_rect, direction, &rects)
+      .map(|index| candidates[index].1.clone()),
+  )
+}
+
+/// Gets the index of the rect that is nearest to the edge entered from
+/// when moving in the given direction, and that overlaps the most with
+/// the origin rect along the other axis.
+///
+/// Rects are expected in focus order, such that ties are broken in favor
+/// of the most recently focused one.
+fn best_aligned_index(
+  origin_rect: &Rect,
+  direction: &Direction,
+  rects: &[Rect],
+) -> Option<usize> {
+  // Overlap with the origin rect along the other axis, and distance
+  // between their centers as a fallback when there's no overlap.
+  let alignment = |rect: &Rect| match direction {
+    Direction::Left | Direction::Right => (
+      rect.bottom.min(origin_rect.bottom) - rect.top.max(origin_rect.top),
+      (rect.center_point().y - origin_rect.center_point().y).abs(),
+    ),
+    Direction::Up | Direction::Down => (
+      rect.right.min(origin_rect.right) - rect.left.max(origin_rect.left),
+      (rect.center_point().x - origin_rect.center_point().x).abs(),
+    ),
+  };
+
+  let nearest_entry = rects
+    .iter()
+    .map(|rect| entry_distance(rect, direction))
+    .min()?;
+
+  rects
+    .iter()
+    .enumerate()
+    .filter(|(_, rect)| entry_distance(rect, direction) == nearest_entry)
+    .min_by_key(|(index, rect)| {
+      let (overlap, center_distance) = alignment(rect);
+      (Reverse(overlap.max(0)), center_distance, *index)
+    })
+    .map(|(index, _)| index)
+}
+
+/// Position of the edge through which a rect is entered when moving in
+/// the given direction. Lower values are entered first.
+fn entry_distance(rect: &Rect, direction: &Direction) -> i32 {
+  match direction {
+    Direction::Right => rect.left,
+    Direction::Left => -rect.right,
+    Direction::Down => rect.top,
+    Direction::Up => -rect.bottom,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn prefers_rect_overlapping_origin() {
+    let origin = Rect::from_xy(0, 600, 800, 400);
+    let rects = [
+      Rect::from_xy(1000, 0, 800, 500),
+      Rect::from_xy(1000, 500, 800, 500),
+      Rect::from_xy(1800, 500, 800, 500),
+    ];
+
+    assert_eq!(
+      best_aligned_index(&origin, &Direction::Right, &rects),
+      Some(1)
+    );
+  }
+
+  #[test]
+  fn only_considers_rects_on_entered_edge() {
+    let origin = Rect::from_xy(0, 0, 800, 1000);
+    let rects = [
+      Rect::from_xy(1000, 0, 400, 100),
+      Rect::from_xy(1400, 0, 400, 1000),
+    ];
+
+    assert_eq!(
+      best_aligned_index(&origin, &Direction::Right, &rects),
+      Some(0)
+    );
+
+    // Moving left enters from the right edge instead.
+    assert_eq!(
+      best_aligned_index(&origin, &Direction::Left, &rects),
+      Some(1)
+    );
+  }
+
+  #[test]
+  fn ties_prefer_most_recently_focused() {
+    let origin = Rect::from_xy(0, 0, 1500, 500);
+    let rects = [
+      Rect::from_xy(1000, 600, 500, 400),
+      Rect::from_xy(0, 600, 500, 400),
+    ];
+
+    // Both overlap and are centered equally far from the origin, so the
+    // first (most recently focused) rect wins.
+
+    assert_eq!(
+      best_aligned_index(&origin, &Direction::Down, &rects),
+      Some(0)
+    );
+  }
+
+  #[test]
+  fn no_rects() {
+    let origin = Rect::from_xy(0, 0, 100, 100);
+    assert_eq!(best_aligned_index(&origin, &Direction::Up, &[]), None);
+  }
+}
@@ -0,0 +1,70 @@
+use super::set_focused_descendant;
+use crate::{models::Container, traits::CommonGetters, wm_state::WmState};
+
+/// Focuses the next child of the nearest stacking or tabbed container
+/// that holds the origin container, or the previous child if `reverse` is
+/// set. Wraps around at either end of the stack.
+///
+/// Does nothing if the origin container isn't within such a container.
+pub fn focus_in_stack(
+  origin: &Container,
+  reverse: bool,
+  state: &mut WmState,
+) {
+  let stack_and_child =
+    origin.self_and_ancestors().find_map(|container| {
+      container
+        .parent()
+        .and_then(|parent| parent.as_split().cloned())
+        .filter(|parent| parent.layout().is_stacked())
+        .map(|parent| (parent, container))
+    });
+
+  let Some((stack, current_child)) = stack_and_child else {
+    return;
+  };
+
+  let children = stack.tiling_children().collect::<Vec<_>>();
+
+  let Some(index) = children
+    .iter()
+    .position(|child| child.id() == current_child.id())
+  else {
+    return;
+  };
+
+  let target = &children[cycled_index(index, children.len(), reverse)];
+
+  // Restore focus to where it last was within the target child.
+  let focus_target = target
+    .descendant_focus_order()
+    .next()
+    .unwrap_or_else(|| target.clone().into());
+
+  set_focused_descendant(&focus_target, None);
+  state.pending_sync.queue_focus_change().queue_cursor_jump();
+}
+
+/// Gets the index after `index` in a list of `count` items, or the index
+/// before it if `reverse` is set. Wraps around at either end.
+fn cycled_index(index: usize, count: usize, reverse: bool) -> usize {
+  if reverse {
+    (index + count - 1) % count
+  } else {
+    (index + 1) % count
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cycles_through_stack_in_both_directions() {
+    assert_eq!(cycled_index(0, 3, false), 1);
+    assert_eq!(cycled_index(2, 3, false), 0);
+    assert_eq!(cycled_index(0, 3, true), 2);
+    assert_eq!(cycled_index(1, 3, true), 0);
+    assert_eq!(cycled_index(0, 1, false), 0);
+  }
+}
@@ -1,25 +1,33 @@
 mod attach_container;
+mod balance_windows;
 mod detach_container;
 mod flatten_child_split_containers;
 mod flatten_split_container;
 mod focus_container_by_id;
 mod focus_in_direction;
+mod focus_in_stack;
 mod move_container_within_tree;
 mod replace_container;
 mod resize_tiling_container;
 mod set_focused_descendant;
+mod set_insertion_hint;
+mod set_split_layout;
 mod toggle_tiling_direction;
 mod wrap_in_split_container;
 
 pub use attach_container::*;
+pub use balance_windows::*;
 pub use detach_container::*;
 pub use flatten_child_split_containers::*;
 pub use flatten_split_container::*;
 pub use focus_container_by_id::*;
 pub use focus_in_direction::*;
+pub use focus_in_stack::*;
 pub use move_container_within_tree::*;
 pub use replace_container::*;
 pub use resize_tiling_container::*;
 pub use set_focused_descendant::*;
+pub use set_insertion_hint::*;
+pub use set_split_layout::*;
 pub use toggle_tiling_direction::*;
 pub use wrap_in_split_container::*;
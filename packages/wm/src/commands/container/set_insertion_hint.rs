@@ -0,0 +1,265 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::{
+  Direction, InsertionHint, Rect, TilingDirection, WmEvent,
+};
+
+use super::wrap_in_split_container;
+use crate::{
+  models::{Container, DropPosition, SplitContainer, Workspace},
+  traits::{CommonGetters, PositionGetters, TilingDirectionGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Sets where the next tiling window in the container's workspace gets
+/// inserted, as a sibling on the given side of the container. The
+/// container gets split if the direction is perpendicular to its parent's
+/// tiling direction.
+///
+/// Does nothing if the container isn't a tiling container.
+pub fn set_insertion_hint(
+  container: &Container,
+  direction: &Direction,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if container.as_tiling_container().is_err() {
+    return Ok(());
+  }
+
+  let workspace = container.workspace().context("No workspace.")?;
+
+  info!("Setting insertion hint {direction:?} in workspace: {workspace}");
+
+  workspace.set_insertion_hint(Some(InsertionHint {
+    container_id: container.id(),
+    direction: direction.clone(),
+  }));
+
+  // Mark where the window gets inserted.
+  state.pending_sync.queue_insertion_hint_update();
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
+
+/// Sets where the next tiling window gets inserted, as a sibling before
+/// or after the container in its parent's tiling direction.
+pub fn set_sibling_insertion_hint(
+  container: &Container,
+  is_after: bool,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let parent = container
+    .parent()
+    .and_then(|parent| parent.as_direction_container().ok())
+    .context("No parent.")?;
+
+  let direction = match (parent.tiling_direction(), is_after) {
+    (TilingDirection::Horizontal, false) => Direction::Left,
+    (TilingDirection::Horizontal, true) => Direction::Right,
+    (TilingDirection::Vertical, false) => Direction::Up,
+    (TilingDirection::Vertical, true) => Direction::Down,
+  };
+
+  set_insertion_hint(container, &direction, state)
+}
+
+/// Clears the insertion hint of the workspace, if one is set.
+pub fn clear_insertion_hint(
+  workspace: &Workspace,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if workspace.insertion_hint().is_none() {
+    return Ok(());
+  }
+
+  workspace.set_insertion_hint(None);
+  state.pending_sync.queue_insertion_hint_update();
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
+
+/// Clears the insertion hint of the workspace, and gets where to insert
+/// a tiling window based on it. Splits the hinted container if needed.
+///
+/// Returns tuple of (parent container, insertion index), or `None` if no
+/// hint is set or the hinted container no longer exists.
+pub fn take_insertion_hint(
+  workspace: &Workspace,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<Option<(Container, usize)>> {
+  let Some(hint) = workspace.insertion_hint() else {
+    return Ok(None);
+  };
+
+  clear_insertion_hint(workspace, state)?;
+
+  let Some(target) = workspace
+    .descendants()
+    .find(|descendant| descendant.id() == hint.container_id)
+    .and_then(|descendant| descendant.as_tiling_container().ok())
+  else {
+    return Ok(None);
+  };
+
+  let parent = target
+    .parent()
+    .and_then(|parent| parent.as_direction_container().ok())
+    .context("No parent.")?;
+
+  let target_index = target.index() + usize::from(hint.is_after());
+
+  if !hint.should_split(&parent.tiling_direction()) {
+    return Ok(Some((parent.into(), target_index)));
+  }
+
+  let tiling_direction = TilingDirection::from_direction(&hint.direction);
+  let child_index = usize::from(hint.is_after());
+
+  // Insert directly into the target if it's already split in the hinted
+  // direction.
+  let target_container: Container = target.clone().into();
+
+  if let Some(split) = target_container
+    .as_split()
+    .filter(|split| split.tiling_direction() == tiling_direction)
+  {
+    let split_index = child_index * split.child_count();
+    return Ok(Some((split.clone().into(), split_index)));
+  }
+
+  let split_container =
+    SplitContainer::new(tiling_direction, config.value.gaps.clone());
+
+  wrap_in_split_container(&split_container, &parent.into(), &[target])?;
+
+  Ok(Some((split_container.into(), child_index)))
+}
+
+/// Gets the area marking where the next tiling window in the workspace
+/// gets inserted, based on the current position of the hinted container.
+///
+/// Returns `None` if no hint is set or the hinted container no longer
+/// exists.
+pub fn insertion_hint_rect(workspace: &Workspace) -> Option<Rect> {
+  let hint = workspace.insertion_hint()?;
+
+  let target = workspace
+    .descendants()
+    .find(|descendant| descendant.id() == hint.container_id)?;
+
+  Some(drop_position(&hint.direction).half_of(&target.to_rect().ok()?))
+}
+
+/// Gets the side of a container that the window gets inserted at.
+fn drop_position(direction: &Direction) -> DropPosition {
+  match direction {
+    Direction::Left => DropPosition::Left,
+    Direction::Right => DropPosition::Right,
+    Direction::Up => DropPosition::Top,
+    Direction::Down => DropPosition::Bottom,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+  use wm_common::ContainerDto;
+
+  use super::*;
+  use crate::test_utils;
+
+  fn set_hint(
+    workspace: &Workspace,
+    container_id: Uuid,
+    direction: Direction,
+  ) {
+    workspace.set_insertion_hint(Some(InsertionHint {
+      container_id,
+      direction,
+    }));
+  }
+
+  #[test]
+  fn takes_hint_as_sibling_in_parent_direction() {
+    let (mut state, mut event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    set_hint(&workspace, first.id(), Direction::Right);
+
+    let (parent, index) =
+      take_insertion_hint(&workspace, &mut state, &config)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(parent.id(), workspace.id());
+    assert_eq!(index, 1);
+
+    // The hint is only used once, and its overlay gets hidden.
+    assert_eq!(workspace.insertion_hint(), None);
+    assert!(state.pending_sync.needs_insertion_hint_update());
+
+    let events = test_utils::emitted_events(&mut event_rx);
+    assert!(matches!(
+      events.as_slice(),
+      [WmEvent::WorkspaceUpdated {
+        updated_workspace: ContainerDto::Workspace(dto),
+      }] if dto.insertion_hint.is_none()
+    ));
+  }
+
+  #[test]
+  fn takes_hint_by_splitting_perpendicular_container() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    set_hint(&workspace, first.id(), Direction::Up);
+
+    let (parent, index) =
+      take_insertion_hint(&workspace, &mut state, &config)
+        .unwrap()
+        .unwrap();
+
+    let split = parent.as_split().unwrap().clone();
+    assert_eq!(split.tiling_direction(), TilingDirection::Vertical);
+    assert_eq!(split.index(), 0);
+    assert_eq!(index, 0);
+    assert_eq!(first.parent().map(|parent| parent.id()), Some(split.id()));
+  }
+
+  #[test]
+  fn clears_hint_of_removed_container() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+
+    set_hint(&workspace, Uuid::new_v4(), Direction::Right);
+
+    assert!(take_insertion_hint(&workspace, &mut state, &config)
+      .unwrap()
+      .is_none());
+    assert_eq!(workspace.insertion_hint(), None);
+  }
+}
@@ -0,0 +1,67 @@
+use anyhow::Context;
+use wm_common::{SplitLayout, WmEvent};
+
+use super::wrap_in_split_container;
+use crate::{
+  models::{Container, DirectionContainer, SplitContainer},
+  traits::{CommonGetters, TilingDirectionGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Sets the layout of the split container that holds the given tiling
+/// window. If given a split container, its own layout is set instead.
+///
+/// Workspaces always tile their children, so the tiling children of a
+/// workspace are first wrapped in a new split container.
+pub fn set_split_layout(
+  container: &Container,
+  layout: SplitLayout,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  if !matches!(container, Container::TilingWindow(_) | Container::Split(_))
+  {
+    return Ok(());
+  }
+
+  let split_container =
+    match container.direction_container().context("No parent.")? {
+      DirectionContainer::Split(split_container) => split_container,
+      DirectionContainer::Workspace(workspace) => {
+        if layout == SplitLayout::Split {
+          return Ok(());
+        }
+
+        let split_container = SplitContainer::new(
+          workspace.tiling_direction(),
+          config.value.gaps.clone(),
+        );
+
+        wrap_in_split_container(
+          &split_container,
+          &workspace.clone().into(),
+          &workspace.tiling_children().collect::<Vec<_>>(),
+        )?;
+
+        split_container
+      }
+    };
+
+  if split_container.layout() == layout {
+    return Ok(());
+  }
+
+  split_container.set_layout(layout);
+
+  let workspace = split_container.workspace().context("No workspace.")?;
+  state
+    .pending_sync
+    .queue_container_to_redraw(split_container);
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
@@ -3,7 +3,11 @@ mod disable_binding_mode;
 mod enable_binding_mode;
 mod platform_sync;
 mod reload_config;
+mod restore_session;
 mod shell_exec;
+mod toggle_focus_follows_cursor;
+mod toggle_fullscreen_covers_reserved_space;
+mod toggle_opacity_effect;
 mod toggle_pause;
 
 pub use cycle_focus::*;
@@ -11,5 +15,9 @@ pub use disable_binding_mode::*;
 pub use enable_binding_mode::*;
 pub use platform_sync::*;
 pub use reload_config::*;
+pub use restore_session::*;
 pub use shell_exec::*;
+pub use toggle_focus_follows_cursor::*;
+pub use toggle_fullscreen_covers_reserved_space::*;
+pub use toggle_opacity_effect::*;
 pub use toggle_pause::*;
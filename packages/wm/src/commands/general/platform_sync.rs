@@ -1,16 +1,28 @@
-use std::time::Duration;
+use std::{
+  collections::{hash_map::Entry, HashMap, HashSet},
+  time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use tokio::task;
 use tracing::{info, warn};
 use wm_common::{
-  CornerStyle, CursorJumpTrigger, DisplayState, HideMethod, OpacityValue,
-  UniqueExt, WindowEffectConfig, WindowState, WmEvent,
+  BorderStyleConfig, Color, CornerStyle, CursorJumpTarget,
+  CursorJumpTrigger, DisplayState, HideMethod, OpacityEffectConfig,
+  OpacityValue, Point, Rect, StateBorderEffectConfig, UniqueExt,
+  WindowEffectConfig, WindowState, WmEvent,
 };
-use wm_platform::{Platform, ZOrder};
+use wm_platform::{BorderOverlay, Platform, ZOrder};
 
 use crate::{
-  models::{Container, WindowContainer},
+  commands::{
+    container::insertion_hint_rect,
+    window::{
+      refresh_size_constraints, sync_size_constraints,
+      sync_swallowed_windows,
+    },
+  },
+  models::{Container, WindowContainer, Workspace},
   traits::{CommonGetters, PositionGetters, WindowGetters},
   user_config::UserConfig,
   wm_state::WmState,
@@ -23,20 +35,74 @@ pub fn platform_sync(
   let focused_container =
     state.focused_container().context("No focused container.")?;
 
+  // Windows stop requesting attention once they get focused.
+  if let Ok(window) = focused_container.as_window_container() {
+    if window.is_urgent() {
+      window.set_urgent_since(None);
+
+      state.emit_event(WmEvent::WorkspaceUpdated {
+        updated_workspace: window
+          .workspace()
+          .context("No workspace.")?
+          .to_dto()?,
+      });
+    }
+  }
+
+  // Record the focused window for `focus-last`.
+  if let Ok(window) = focused_container.as_window_container() {
+    state.focus_history.push(window.id());
+  }
+
+  // Focus changes from focus follows cursor leave the z-order as is,
+  // unless `raise_on_focus` is enabled.
+  let is_focus_raised = !state.pending_sync.is_cursor_focus()
+    || config.value.general.raise_on_focus;
+
   if state.pending_sync.needs_focus_update() {
-    sync_focus(&focused_container, state)?;
+    if config.value.general.cursor_jump.target
+      == CursorJumpTarget::RememberLastPositionPerWindow
+    {
+      remember_cursor_position(&focused_container, state)?;
+    }
+
+    sync_focus(&focused_container, is_focus_raised, state)?;
   }
 
+  sync_swallowed_windows(state)?;
+  sync_size_constraints(state, config)?;
+  sync_monocle(&focused_container, state, config)?;
+  let smart_gaps_workspaces = sync_smart_gaps(state);
+  queue_stacks_to_redraw(&focused_container, state);
+  queue_master_stacks_to_redraw(state);
+
+  // Insertion hints follow their container, so they're updated whenever
+  // containers are redrawn.
+  let needs_insertion_hint_update =
+    state.pending_sync.needs_insertion_hint_update()
+      || !state.pending_sync.containers_to_redraw().is_empty();
+
+  let mut deferred_windows = Vec::new();
+
   if !state.pending_sync.containers_to_redraw().is_empty()
     || !state.pending_sync.workspaces_to_reorder().is_empty()
   {
-    redraw_containers(&focused_container, state, config)?;
+    deferred_windows = redraw_containers(
+      &focused_container,
+      is_focus_raised,
+      state,
+      config,
+    )?;
   }
 
+  // The cursor never jumps when focus has followed it.
   if state.pending_sync.needs_cursor_jump()
+    && !state.pending_sync.is_cursor_focus()
     && config.value.general.cursor_jump.enabled
+    && config.value.general.cursor_jump.target
+      != CursorJumpTarget::Disabled
   {
-    jump_cursor(focused_container.clone(), state, config)?;
+    jump_cursor(&focused_container, state, config)?;
   }
 
   if state.pending_sync.needs_focused_effect_update()
@@ -47,7 +113,7 @@ pub fn platform_sync(
     let prev_effects_window = state.prev_effects_window.clone();
 
     if let Ok(window) = focused_container.as_window_container() {
-      apply_window_effects(&window, true, config);
+      apply_window_effects(&window, true, state, config);
       state.prev_effects_window = Some(window.clone());
     } else {
       state.prev_effects_window = None;
@@ -67,17 +133,44 @@ pub fn platform_sync(
       .filter(|window| window.id() != focused_container.id());
 
     for window in unfocused_windows {
-      apply_window_effects(&window, false, config);
+      apply_window_effects(&window, false, state, config);
     }
   }
 
-  state.pending_sync.clear();
+  // Borders are shown or hidden once smart gaps start or stop applying.
+  if !state.pending_sync.needs_all_effects_update() {
+    let windows = smart_gaps_workspaces
+      .iter()
+      .filter(|workspace| workspace.has_smart_borders_enabled())
+      .flat_map(CommonGetters::descendants)
+      .filter_map(|descendant| descendant.as_window_container().ok())
+      .collect::<Vec<_>>();
+
+    for window in windows {
+      let is_focused = window.id() == focused_container.id();
+      apply_window_effects(&window, is_focused, state, config);
+    }
+  }
+
+  if state.pending_sync.needs_drop_hint_update() {
+    sync_drop_hint(state, config);
+  }
+
+  if needs_insertion_hint_update {
+    sync_insertion_hints(state, config);
+  }
+
+  state
+    .pending_sync
+    .clear()
+    .defer_containers_to_redraw(deferred_windows);
 
   Ok(())
 }
 
 fn sync_focus(
   focused_container: &Container,
+  is_focus_raised: bool,
   state: &mut WmState,
 ) -> anyhow::Result<()> {
   let native_window = match focused_container.as_window_container() {
@@ -94,11 +187,27 @@ fn sync_focus(
       info!("Setting focus to the desktop window.");
     }
 
+    // Setting the foreground window also brings it to the front, so get
+    // the window above it to restore its z-order afterwards.
+    let window_above = (!is_focus_raised)
+      .then(|| native_window.window_above())
+      .flatten();
+
     if let Err(err) = native_window.set_foreground() {
       warn!("Failed to set foreground window: {}", err);
     }
+
+    if let Some(window_above) = window_above {
+      if let Err(err) = native_window
+        .set_z_order(&ZOrder::AfterWindow(window_above.handle))
+      {
+        warn!("Failed to restore window z-order: {}", err);
+      }
+    }
   }
 
+  state.is_focus_from_cursor = state.pending_sync.is_cursor_focus();
+
   state.emit_event(WmEvent::FocusChanged {
     focused_container: focused_container.to_dto()?,
   });
@@ -106,16 +215,162 @@ fn sync_focus(
   Ok(())
 }
 
+/// Updates which window is in monocle on workspaces that have pending
+/// changes.
+///
+/// Once another tiling window in the workspace has been focused, or the
+/// window in monocle is no longer tiling within the workspace, monocle
+/// moves to the most recently focused tiling window if
+/// `monocle_follows_focus` is enabled, and is exited otherwise.
+fn sync_monocle(
+  focused_container: &Container,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let focused_workspace = state
+    .pending_sync
+    .needs_focus_update()
+    .then(|| focused_container.workspace())
+    .flatten();
+
+  let workspaces = state
+    .pending_sync
+    .containers_to_redraw()
+    .values()
+    .filter_map(CommonGetters::workspace)
+    .chain(focused_workspace)
+    .unique_by(CommonGetters::id)
+    .collect::<Vec<_>>();
+
+  for workspace in workspaces {
+    let Some(monocle_id) = workspace.monocle_window_id() else {
+      continue;
+    };
+
+    let recent_tiling_window = workspace
+      .descendant_focus_order()
+      .find(Container::is_tiling_window);
+
+    if recent_tiling_window
+      .as_ref()
+      .is_some_and(|window| window.id() == monocle_id)
+    {
+      continue;
+    }
+
+    let new_monocle_id = recent_tiling_window
+      .filter(|_| config.value.general.monocle_follows_focus)
+      .map(|window| window.id());
+
+    workspace.set_monocle_window_id(new_monocle_id);
+
+    state
+      .pending_sync
+      .queue_container_to_redraw(workspace.clone());
+
+    state.emit_event(WmEvent::WorkspaceUpdated {
+      updated_workspace: workspace.to_dto()?,
+    });
+  }
+
+  Ok(())
+}
+
+/// Queues workspaces for redraw where `gaps.smart_gaps` starts or stops
+/// applying, i.e. where the number of shown tiling windows crosses
+/// between one and two.
+///
+/// Returns the workspaces that were queued.
+fn sync_smart_gaps(state: &mut WmState) -> Vec<Workspace> {
+  let workspaces = state.workspaces();
+
+  let smart_gaps_workspaces = workspaces
+    .iter()
+    .filter(|workspace| workspace.has_smart_gaps())
+    .map(CommonGetters::id)
+    .collect::<HashSet<_>>();
+
+  let changed_workspaces = workspaces
+    .into_iter()
+    .filter(|workspace| {
+      smart_gaps_workspaces.contains(&workspace.id())
+        != state.smart_gaps_workspaces.contains(&workspace.id())
+    })
+    .collect::<Vec<_>>();
+
+  state.smart_gaps_workspaces = smart_gaps_workspaces;
+
+  state
+    .pending_sync
+    .queue_containers_to_redraw(changed_workspaces.clone());
+
+  changed_workspaces
+}
+
+/// Queues stacking and tabbed containers for redraw on workspaces that
+/// have pending changes.
+///
+/// Which child of a stack is shown depends on focus and on the stack's
+/// children, so a stack is redrawn as a whole whenever its workspace
+/// changes or gains focus.
+fn queue_stacks_to_redraw(
+  focused_container: &Container,
+  state: &mut WmState,
+) {
+  let focused_workspace = state
+    .pending_sync
+    .needs_focus_update()
+    .then(|| focused_container.workspace())
+    .flatten();
+
+  let stacks = state
+    .pending_sync
+    .containers_to_redraw()
+    .values()
+    .filter_map(CommonGetters::workspace)
+    .chain(focused_workspace)
+    .unique_by(CommonGetters::id)
+    .flat_map(|workspace| workspace.descendants())
+    .filter(|descendant| {
+      descendant
+        .as_split()
+        .is_some_and(|split| split.layout().is_stacked())
+    })
+    .collect::<Vec<_>>();
+
+  state.pending_sync.queue_containers_to_redraw(stacks);
+}
+
+/// Queues master-stack workspaces to be redrawn as a whole.
+///
+/// The position of every tiling child of a master-stack workspace
+/// depends on its siblings, so adding, removing or resizing one child
+/// moves the others as well.
+fn queue_master_stacks_to_redraw(state: &mut WmState) {
+  let workspaces = state
+    .pending_sync
+    .containers_to_redraw()
+    .values()
+    .filter_map(CommonGetters::workspace)
+    .filter(|workspace| workspace.layout().is_master_stack())
+    .unique_by(CommonGetters::id)
+    .collect::<Vec<_>>();
+
+  state.pending_sync.queue_containers_to_redraw(workspaces);
+}
+
 /// Finds windows that should be brought to the top of their workspace's
 /// z-order.
 ///
 /// Windows are brought to front if they match the focused window's state
 /// (floating/tiling) and any of these conditions are met:
-///  * Focus has changed to a different window.
+///  * Focus has changed to a different window (unless `is_focus_raised` is
+///    false).
 ///  * Focused window's state has changed (e.g. tiling -> floating).
 ///  * Focused window has moved to a different workspace.
 fn windows_to_bring_to_front(
   focused_container: &Container,
+  is_focus_raised: bool,
   state: &WmState,
 ) -> anyhow::Result<Vec<WindowContainer>> {
   let focused_workspace =
@@ -127,9 +382,7 @@ fn windows_to_bring_to_front(
     .workspaces_to_reorder()
     .iter()
     .chain(
-      state
-        .pending_sync
-        .needs_focus_update()
+      (state.pending_sync.needs_focus_update() && is_focus_raised)
         .then_some(&focused_workspace),
     )
     .unique_by(|workspace| workspace.id());
@@ -165,15 +418,40 @@ fn windows_to_bring_to_front(
   Ok(windows_to_bring_to_front)
 }
 
+/// Emits the layout rect of a window that was just repositioned, along
+/// with its monitor's DPI so that consumers can convert to logical pixels.
+fn emit_layout_changed(
+  window: &WindowContainer,
+  rect: Rect,
+  state: &WmState,
+) -> anyhow::Result<()> {
+  let monitor = window.monitor().context("Window has no monitor.")?;
+
+  state.emit_event(WmEvent::WindowLayoutChanged {
+    window_id: window.id(),
+    rect,
+    dpi: monitor.native().dpi()?,
+    scale_factor: monitor.native().scale_factor()?,
+  });
+
+  Ok(())
+}
+
+/// Redraws windows that have a pending redraw or z-order change.
+///
+/// Returns the windows that were skipped due to exceeding the redraw
+/// budget, along with windows whose size constraints changed, such that
+/// they're redrawn in the next sync.
 #[allow(clippy::too_many_lines)]
 fn redraw_containers(
   focused_container: &Container,
+  is_focus_raised: bool,
   state: &mut WmState,
   config: &UserConfig,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<WindowContainer>> {
   let windows_to_redraw = state.windows_to_redraw();
   let windows_to_bring_to_front =
-    windows_to_bring_to_front(focused_container, state)?;
+    windows_to_bring_to_front(focused_container, is_focus_raised, state)?;
 
   let windows_to_update = {
     let mut windows = windows_to_redraw
@@ -195,40 +473,115 @@ fn redraw_containers(
         .position(|order| order.id() == window.id())
     });
 
+    // Windows get redrawn from least to most recently focused, such that
+    // the most recently focused window ends up highest in the z-order.
+    windows.reverse();
+
+    // Prioritize windows that were deferred in a previous sync, followed
+    // by windows on the focused workspace, and then other displayed
+    // workspaces. The sort is stable, so focus order is kept within each
+    // group.
+    let focused_workspace_id = focused_container
+      .workspace()
+      .map(|workspace| workspace.id());
+
+    windows.sort_by_key(|window| {
+      let workspace = window.workspace();
+
+      if state.pending_sync.is_redraw_deferred(&window.id()) {
+        0
+      } else if workspace.as_ref().map(CommonGetters::id)
+        == focused_workspace_id
+      {
+        1
+      } else if workspace.is_some_and(|workspace| workspace.is_displayed())
+      {
+        2
+      } else {
+        3
+      }
+    });
+
     windows
   };
 
-  for window in windows_to_update.iter().rev() {
+  let redraw_budget = match config.value.general.redraw_budget_ms {
+    0 => None,
+    budget_ms => Some(Duration::from_millis(u64::from(budget_ms))),
+  };
+
+  let redraw_start = Instant::now();
+
+  // Windows whose size constraints changed, which need to be refit and
+  // redrawn in the next sync.
+  let mut refreshed_windows = Vec::new();
+
+  for (index, window) in windows_to_update.iter().enumerate() {
+    // Defer the remaining windows to the next sync if the redraw budget
+    // is exceeded. At least one window is always redrawn per sync.
+    if index > 0
+      && redraw_budget
+        .is_some_and(|budget| redraw_start.elapsed() > budget)
+    {
+      let deferred_windows = windows_to_update[index..]
+        .iter()
+        .map(|&window| window.clone())
+        .collect::<Vec<_>>();
+
+      info!(
+        "Redraw budget exceeded. Deferring {} windows.",
+        deferred_windows.len()
+      );
+
+      return Ok(
+        deferred_windows
+          .into_iter()
+          .chain(refreshed_windows)
+          .collect(),
+      );
+    }
+
+    // Windows that hid themselves stay hidden until they're shown again.
+    if state.self_hidden_windows.contains(&window.id()) {
+      continue;
+    }
+
     let should_bring_to_front = windows_to_bring_to_front.contains(window);
 
     let workspace =
       window.workspace().context("Window has no workspace.")?;
 
+    let is_standby = workspace
+      .monitor()
+      .is_some_and(|monitor| monitor.is_standby());
+
+    // Windows on a monitor in standby are left untouched once hidden,
+    // until the monitor is resumed.
+    if is_standby && matches!(window.display_state(), DisplayState::Hidden)
+    {
+      continue;
+    }
+
     // Whether the window should be shown above all other windows.
-    let z_order = match window.state() {
-      WindowState::Floating(config) if config.shown_on_top => {
-        ZOrder::TopMost
-      }
-      WindowState::Fullscreen(config) if config.shown_on_top => {
-        ZOrder::TopMost
-      }
-      _ if should_bring_to_front => {
-        let focused_descendant = workspace
-          .descendant_focus_order()
-          .next()
-          .and_then(|container| container.as_window_container().ok());
-
-        if let Some(focused_descendant) = focused_descendant {
-          if window.id() == focused_descendant.id() {
-            ZOrder::Normal
-          } else {
-            ZOrder::AfterWindow(focused_descendant.native().handle)
-          }
-        } else {
+    let z_order = if window.is_shown_on_top() {
+      ZOrder::TopMost
+    } else if should_bring_to_front {
+      let focused_descendant = workspace
+        .descendant_focus_order()
+        .next()
+        .and_then(|container| container.as_window_container().ok());
+
+      if let Some(focused_descendant) = focused_descendant {
+        if window.id() == focused_descendant.id() {
           ZOrder::Normal
+        } else {
+          ZOrder::AfterWindow(focused_descendant.native().handle)
         }
+      } else {
+        ZOrder::Normal
       }
-      _ => ZOrder::Normal,
+    } else {
+      ZOrder::Normal
     };
 
     // Set the z-order of the window and skip updating it's position if the
@@ -245,21 +598,40 @@ fn redraw_containers(
 
     // Transition display state depending on whether window will be
     // shown or hidden.
-    window.set_display_state(
-      match (window.display_state(), workspace.is_displayed()) {
-        (DisplayState::Hidden | DisplayState::Hiding, true) => {
-          DisplayState::Showing
-        }
-        (DisplayState::Shown | DisplayState::Showing, false) => {
-          DisplayState::Hiding
-        }
-        _ => window.display_state(),
-      },
-    );
+    let is_shown = workspace.is_displayed()
+      && !is_standby
+      && !window.is_hidden_in_stack()
+      && !window.is_hidden_by_monocle();
+
+    window.set_display_state(match (window.display_state(), is_shown) {
+      (DisplayState::Hidden | DisplayState::Hiding, true) => {
+        DisplayState::Showing
+      }
+      (DisplayState::Shown | DisplayState::Showing, false) => {
+        DisplayState::Hiding
+      }
+      _ => window.display_state(),
+    });
+
+    // Update the window's frame before getting its border delta, since
+    // removing the frame changes the size of its shadow borders.
+    sync_window_frame(window, state, config);
+
+    // Fullscreen windows that leave the reserved space visible are sized
+    // to their monitor's working area. This is kept on the window, such
+    // that its rect is also used for e.g. borders and cursor jumps.
+    if let WindowContainer::NonTilingWindow(non_tiling_window) = window {
+      non_tiling_window.set_fullscreen_within_working_area(
+        config.is_fullscreen_within_working_area(window),
+      );
+    }
+
+    let layout_rect = window.to_rect()?;
+    let rect =
+      layout_rect.apply_delta(&window.total_border_delta()?, None);
 
-    let rect = window
-      .to_rect()?
-      .apply_delta(&window.total_border_delta()?, None);
+    // Take the pending adjustment so that it's only applied once.
+    let dpi_adjustment = window.take_pending_dpi_adjustment();
 
     let is_visible = matches!(
       window.display_state(),
@@ -274,11 +646,28 @@ fn redraw_containers(
       &z_order,
       is_visible,
       &config.value.general.hide_method,
-      window.has_pending_dpi_adjustment(),
+      dpi_adjustment.is_some(),
     ) {
       warn!("Failed to set window position: {}", err);
     }
 
+    // Size constraints change along with the window's DPI, which is only
+    // applied once the window has been moved.
+    if dpi_adjustment.is_some() && refresh_size_constraints(window, config)
+    {
+      refreshed_windows.push((*window).clone());
+    }
+
+    if let Err(err) = emit_layout_changed(window, layout_rect, state) {
+      warn!("Failed to emit layout change: {}", err);
+    }
+
+    // Move border overlays along with their window.
+    if state.border_overlays.contains_key(&window.id()) {
+      let is_focused = window.id() == focused_container.id();
+      apply_state_border_effect(window, is_focused, state, config);
+    }
+
     // Whether the window is either transitioning to or from fullscreen.
     // TODO: This check can be improved since `prev_state` can be
     // fullscreen without it needing to be marked as not fullscreen.
@@ -316,25 +705,25 @@ fn redraw_containers(
     }
   }
 
-  Ok(())
+  Ok(refreshed_windows)
 }
 
 fn jump_cursor(
-  focused_container: Container,
+  focused_container: &Container,
   state: &WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
   let cursor_jump = &config.value.general.cursor_jump;
 
   let jump_target = match cursor_jump.trigger {
-    CursorJumpTrigger::WindowFocus => Some(focused_container),
+    CursorJumpTrigger::WindowFocus => Some(focused_container.clone()),
     CursorJumpTrigger::MonitorFocus => {
       let target_monitor =
         focused_container.monitor().context("No monitor.")?;
 
       let cursor_monitor = Platform::mouse_position()
         .ok()
-        .and_then(|pos| state.monitor_at_point(&pos));
+        .and_then(|pos| state.monitor_at(&pos));
 
       // Jump to the target monitor if the cursor is not already on it.
       cursor_monitor
@@ -343,35 +732,155 @@ fn jump_cursor(
     }
   };
 
-  if let Some(jump_target) = jump_target {
-    let center = jump_target.to_rect()?.center_point();
+  let Some(jump_target) = jump_target else {
+    return Ok(());
+  };
 
-    if let Err(err) = Platform::set_cursor_pos(center.x, center.y) {
-      warn!("Failed to set cursor position: {}", err);
+  let target_rect = jump_target.to_rect()?;
+
+  let point = match cursor_jump.target {
+    CursorJumpTarget::Center => target_rect.center_point(),
+    CursorJumpTarget::TopLeftOffset => {
+      let scale_factor = jump_target
+        .monitor()
+        .and_then(|monitor| monitor.native().scale_factor().ok());
+
+      offset_point(
+        &target_rect,
+        cursor_jump
+          .offset_x
+          .to_px(target_rect.width(), scale_factor),
+        cursor_jump
+          .offset_y
+          .to_px(target_rect.height(), scale_factor),
+      )
     }
+    CursorJumpTarget::RememberLastPositionPerWindow => {
+      // The remembered position is relative to the focused window, even
+      // when jumping between monitors.
+      let remembered = focused_container
+        .as_window_container()
+        .ok()
+        .and_then(|window| {
+          let position = state.cursor_positions.get(&window.id())?;
+          Some(point_at_position(&window.to_rect().ok()?, *position))
+        });
+
+      remembered.unwrap_or_else(|| target_rect.center_point())
+    }
+    CursorJumpTarget::Disabled => return Ok(()),
+  };
+
+  if let Err(err) = Platform::set_cursor_pos(point.x, point.y) {
+    warn!("Failed to set cursor position: {}", err);
   }
 
   Ok(())
 }
 
+/// Stores the cursor position within the OS focused window, if focus is
+/// about to move away from it and the cursor is inside of it.
+fn remember_cursor_position(
+  focused_container: &Container,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let Some(window) =
+    state.window_from_native(&Platform::foreground_window())
+  else {
+    return Ok(());
+  };
+
+  if window.id() == focused_container.id() {
+    return Ok(());
+  }
+
+  let Ok(cursor_pos) = Platform::mouse_position() else {
+    return Ok(());
+  };
+
+  if let Some(position) =
+    relative_position(&window.to_rect()?, &cursor_pos)
+  {
+    state.cursor_positions.insert(window.id(), position);
+  }
+
+  Ok(())
+}
+
+/// Gets the position of a point relative to the size of a rect, or `None`
+/// if the point is outside of it.
+#[allow(clippy::cast_precision_loss)]
+fn relative_position(rect: &Rect, point: &Point) -> Option<(f32, f32)> {
+  if !rect.contains_point(point) || rect.width() <= 0 || rect.height() <= 0
+  {
+    return None;
+  }
+
+  Some((
+    (point.x - rect.left) as f32 / rect.width() as f32,
+    (point.y - rect.top) as f32 / rect.height() as f32,
+  ))
+}
+
+/// Inverse of `relative_position`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn point_at_position(rect: &Rect, (x, y): (f32, f32)) -> Point {
+  Point {
+    x: rect.left + (x * rect.width() as f32).round() as i32,
+    y: rect.top + (y * rect.height() as f32).round() as i32,
+  }
+}
+
+/// Gets the point at the given offset from the top-left corner of a rect,
+/// clamped to within the rect.
+fn offset_point(rect: &Rect, offset_x: i32, offset_y: i32) -> Point {
+  Point {
+    x: rect.left + offset_x.clamp(0, rect.width().max(0)),
+    y: rect.top + offset_y.clamp(0, rect.height().max(0)),
+  }
+}
+
 fn apply_window_effects(
   window: &WindowContainer,
   is_focused: bool,
+  state: &mut WmState,
   config: &UserConfig,
 ) {
   let window_effects = &config.value.window_effects;
 
+  // Skip windows that render incorrectly with effects applied.
+  if config.is_ignore_effects_window(window).unwrap_or(false) {
+    return;
+  }
+
   let effect_config = if is_focused {
     &window_effects.focused_window
   } else {
     &window_effects.other_windows
   };
 
+  // Smart borders hide the border of a workspace's single tiling window.
+  // Whether smart gaps apply is looked up from the last sync, rather than
+  // counting the workspace's windows again for every window.
+  let is_border_hidden = window.is_tiling_window()
+    && window.workspace().is_some_and(|workspace| {
+      workspace.has_smart_borders_enabled()
+        && state.smart_gaps_workspaces.contains(&workspace.id())
+    });
+
   // Skip if both focused + non-focused window effects are disabled.
-  if window_effects.focused_window.border.enabled
+  if window_effects.borders.enabled
+    || window_effects.focused_window.border.enabled
     || window_effects.other_windows.border.enabled
   {
-    apply_border_effect(window, effect_config);
+    if is_border_hidden {
+      state.border_overlays.remove(&window.id());
+      _ = set_border_color(window, None);
+    } else if window_effects.borders.enabled {
+      apply_state_border_effect(window, is_focused, state, config);
+    } else {
+      apply_border_effect(window, effect_config);
+    }
   }
 
   if window_effects.focused_window.hide_title_bar.enabled
@@ -386,7 +895,12 @@ fn apply_window_effects(
     apply_corner_effect(window, effect_config);
   }
 
-  if window_effects.focused_window.transparency.enabled
+  if window_effects.opacity.enabled {
+    let opacity =
+      window_opacity(&window_effects.opacity, &window.state(), is_focused);
+
+    _ = window.native().set_transparency(&opacity);
+  } else if window_effects.focused_window.transparency.enabled
     || window_effects.other_windows.transparency.enabled
   {
     apply_transparency_effect(window, effect_config);
@@ -403,7 +917,189 @@ fn apply_border_effect(
     None
   };
 
-  _ = window.native().set_border_color(border_color);
+  _ = set_border_color(window, border_color);
+}
+
+/// Applies the border for the window's state.
+///
+/// Borders are drawn via DWM where possible. Borders thicker than 1px, or
+/// any borders on Windows builds without support for colored DWM borders,
+/// are drawn via an overlay window instead.
+fn apply_state_border_effect(
+  window: &WindowContainer,
+  is_focused: bool,
+  state: &mut WmState,
+  config: &UserConfig,
+) {
+  let Some(border) = window_border(
+    &config.value.window_effects.borders,
+    &window.state(),
+    is_focused,
+    window.is_urgent(),
+  ) else {
+    state.border_overlays.remove(&window.id());
+    _ = set_border_color(window, None);
+    return;
+  };
+
+  let Ok(rect) = window.to_rect() else {
+    return;
+  };
+
+  let scale_factor = window
+    .monitor()
+    .and_then(|monitor| monitor.native().scale_factor().ok());
+
+  let width = border
+    .width
+    .to_px(rect.width().min(rect.height()), scale_factor);
+
+  if width <= 1 && set_border_color(window, Some(&border.color)).is_ok() {
+    state.border_overlays.remove(&window.id());
+    return;
+  }
+
+  _ = window.native().set_border_color(None);
+
+  let is_shown = window.state() != WindowState::Minimized
+    && matches!(
+      window.display_state(),
+      DisplayState::Showing | DisplayState::Shown
+    );
+
+  if !is_shown {
+    if let Some(overlay) = state.border_overlays.get(&window.id()) {
+      overlay.hide();
+    }
+
+    return;
+  }
+
+  let overlay = match state.border_overlays.entry(window.id()) {
+    Entry::Occupied(entry) => entry.into_mut(),
+    Entry::Vacant(entry) => match BorderOverlay::new() {
+      Ok(overlay) => entry.insert(overlay),
+      Err(err) => {
+        warn!("Failed to create border overlay: {}", err);
+        return;
+      }
+    },
+  };
+
+  if let Err(err) =
+    overlay.show(&rect, width, &border.color, &window.native())
+  {
+    warn!("Failed to show border overlay: {}", err);
+  }
+}
+
+/// Shows the drop hint overlay over the area where a dragged tiling
+/// window gets inserted, or hides it if there's no such drag.
+fn sync_drop_hint(state: &mut WmState, config: &UserConfig) {
+  let drop_hint_config = &config.value.window_behavior.drop_hint;
+
+  let Some(rect) =
+    state.drop_hint.clone().filter(|_| drop_hint_config.enabled)
+  else {
+    if let Some(overlay) = &state.drop_hint_overlay {
+      overlay.hide();
+    }
+
+    return;
+  };
+
+  if state.drop_hint_overlay.is_none() {
+    match BorderOverlay::new() {
+      Ok(overlay) => state.drop_hint_overlay = Some(overlay),
+      Err(err) => {
+        warn!("Failed to create drop hint overlay: {}", err);
+        return;
+      }
+    }
+  }
+
+  let Some(overlay) = &state.drop_hint_overlay else {
+    return;
+  };
+
+  if let Err(err) = overlay.show_filled(&rect, &drop_hint_config.color) {
+    warn!("Failed to show drop hint overlay: {}", err);
+  }
+}
+
+/// Shows an overlay over where the next tiling window gets inserted in
+/// each displayed workspace that has an insertion hint. Overlays of other
+/// workspaces are removed, such that they're hidden along with their
+/// workspace.
+fn sync_insertion_hints(state: &mut WmState, config: &UserConfig) {
+  let drop_hint_config = &config.value.window_behavior.drop_hint;
+
+  let hint_rects = state
+    .workspaces()
+    .into_iter()
+    .filter(|workspace| {
+      drop_hint_config.enabled && workspace.is_displayed()
+    })
+    .filter_map(|workspace| {
+      Some((workspace.id(), insertion_hint_rect(&workspace)?))
+    })
+    .collect::<HashMap<_, _>>();
+
+  state
+    .insertion_hint_overlays
+    .retain(|workspace_id, _| hint_rects.contains_key(workspace_id));
+
+  for (workspace_id, rect) in hint_rects {
+    let overlay = match state.insertion_hint_overlays.entry(workspace_id) {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => match BorderOverlay::new() {
+        Ok(overlay) => entry.insert(overlay),
+        Err(err) => {
+          warn!("Failed to create insertion hint overlay: {}", err);
+          continue;
+        }
+      },
+    };
+
+    if let Err(err) = overlay.show_filled(&rect, &drop_hint_config.color) {
+      warn!("Failed to show insertion hint overlay: {}", err);
+    }
+  }
+}
+
+/// Gets the border of a window based on its state.
+///
+/// Urgent borders take precedence over all others, followed by floating
+/// borders and then focused and unfocused ones.
+fn window_border<'a>(
+  borders_config: &'a StateBorderEffectConfig,
+  window_state: &WindowState,
+  is_focused: bool,
+  is_urgent: bool,
+) -> Option<&'a BorderStyleConfig> {
+  let urgent_border = borders_config.urgent.as_ref().filter(|_| is_urgent);
+
+  let floating_border = borders_config
+    .floating
+    .as_ref()
+    .filter(|_| matches!(window_state, WindowState::Floating(_)));
+
+  let focus_border = if is_focused {
+    borders_config.focused.as_ref()
+  } else {
+    borders_config.unfocused.as_ref()
+  };
+
+  urgent_border.or(floating_border).or(focus_border)
+}
+
+/// Sets the DWM border color of a window. Errors if colored borders are
+/// unsupported on the Windows build.
+fn set_border_color(
+  window: &WindowContainer,
+  border_color: Option<&Color>,
+) -> anyhow::Result<()> {
+  window.native().set_border_color(border_color)?;
 
   let native = window.native().clone();
   let border_color = border_color.cloned();
@@ -414,6 +1110,47 @@ fn apply_border_effect(
     tokio::time::sleep(Duration::from_millis(50)).await;
     _ = native.set_border_color(border_color.as_ref());
   });
+
+  Ok(())
+}
+
+/// Removes the native title bar and frame of the window if it's tiling
+/// and configured to have them removed. Otherwise, restores them.
+fn sync_window_frame(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) {
+  let is_removed = window.is_tiling_window()
+    && is_title_bar_removed(window, state, config);
+
+  let native = window.native().clone();
+
+  match native.set_frame_visibility(!is_removed) {
+    // Refresh the cached positions, which the shadow border delta is
+    // derived from. The window would otherwise get offset by the size of
+    // its previous frame.
+    Ok(true) => {
+      if let Err(err) = native.refresh_frame_position() {
+        warn!("Failed to refresh window position: {}", err);
+      }
+    }
+    Ok(false) => {}
+    Err(err) => warn!("Failed to update window frame: {}", err),
+  }
+}
+
+/// Gets whether the window's title bar is removed while tiling, which is
+/// only matched against the user config once per window.
+fn is_title_bar_removed(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> bool {
+  *state
+    .title_bar_removals
+    .entry(window.id())
+    .or_insert_with(|| config.is_title_bar_removed_window(window))
 }
 
 fn apply_hide_title_bar_effect(
@@ -451,3 +1188,203 @@ fn apply_transparency_effect(
 
   _ = window.native().set_transparency(transparency);
 }
+
+/// Gets the opacity of a window based on its state.
+///
+/// Floating and fullscreen opacities take precedence over focused and
+/// unfocused ones. Windows without a matching opacity are fully opaque.
+fn window_opacity(
+  opacity_config: &OpacityEffectConfig,
+  window_state: &WindowState,
+  is_focused: bool,
+) -> OpacityValue {
+  let state_opacity = match window_state {
+    WindowState::Floating(_) => opacity_config.floating.as_ref(),
+    WindowState::Fullscreen(_) => opacity_config.fullscreen.as_ref(),
+    _ => None,
+  };
+
+  let focus_opacity = if is_focused {
+    opacity_config.focused.as_ref()
+  } else {
+    opacity_config.unfocused.as_ref()
+  };
+
+  state_opacity
+    .or(focus_opacity)
+    .cloned()
+    .unwrap_or_else(|| OpacityValue::from_alpha(u8::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    FloatingStateConfig, FullscreenStateConfig, LengthValue, ParsedConfig,
+  };
+
+  use super::*;
+  use crate::test_utils;
+
+  #[test]
+  fn relative_position_round_trips() {
+    let rect = Rect::from_xy(100, 200, 800, 600);
+    let point = Point { x: 300, y: 650 };
+
+    let position = relative_position(&rect, &point).unwrap();
+    let restored = point_at_position(&rect, position);
+
+    assert_eq!((restored.x, restored.y), (point.x, point.y));
+  }
+
+  #[test]
+  fn relative_position_outside_rect_is_none() {
+    let rect = Rect::from_xy(0, 0, 100, 100);
+
+    assert!(relative_position(&rect, &Point { x: 150, y: 50 }).is_none());
+  }
+
+  #[test]
+  fn remembered_position_scales_with_rect() {
+    let rect = Rect::from_xy(-1000, 0, 500, 400);
+    let point = point_at_position(&rect, (0.5, 0.25));
+
+    assert_eq!((point.x, point.y), (-750, 100));
+  }
+
+  #[test]
+  fn offset_point_is_clamped_to_rect() {
+    let rect = Rect::from_xy(10, 20, 100, 50);
+
+    let point = offset_point(&rect, 40, 30);
+    assert_eq!((point.x, point.y), (50, 50));
+
+    let point = offset_point(&rect, 400, -30);
+    assert_eq!((point.x, point.y), (110, 20));
+  }
+
+  #[test]
+  fn window_state_opacity_overrides_focus() {
+    let opacity_config = OpacityEffectConfig {
+      enabled: true,
+      focused: Some(OpacityValue::from_alpha(255)),
+      unfocused: Some(OpacityValue::from_alpha(235)),
+      floating: None,
+      fullscreen: Some(OpacityValue::from_alpha(200)),
+    };
+
+    let opacity = |window_state, is_focused| {
+      window_opacity(&opacity_config, &window_state, is_focused).to_alpha()
+    };
+
+    assert_eq!(opacity(WindowState::Tiling, true), 255);
+    assert_eq!(opacity(WindowState::Tiling, false), 235);
+    assert_eq!(
+      opacity(
+        WindowState::Floating(FloatingStateConfig::default()),
+        false
+      ),
+      235
+    );
+    assert_eq!(
+      opacity(
+        WindowState::Fullscreen(FullscreenStateConfig::default()),
+        true
+      ),
+      200
+    );
+  }
+
+  #[test]
+  fn window_opacity_defaults_to_opaque() {
+    let opacity = window_opacity(
+      &OpacityEffectConfig::default(),
+      &WindowState::Tiling,
+      false,
+    );
+
+    assert_eq!(opacity.to_alpha(), u8::MAX);
+  }
+
+  #[test]
+  fn urgent_border_overrides_state_borders() {
+    let border = |px| BorderStyleConfig {
+      width: LengthValue::from_px(px),
+      ..BorderStyleConfig::default()
+    };
+
+    let borders_config = StateBorderEffectConfig {
+      enabled: true,
+      focused: Some(border(1)),
+      unfocused: None,
+      urgent: Some(border(4)),
+      floating: Some(border(2)),
+    };
+
+    let width = |window_state, is_focused, is_urgent| {
+      window_border(&borders_config, &window_state, is_focused, is_urgent)
+        .map(|border| border.width.to_px(0, None))
+    };
+
+    let floating =
+      || WindowState::Floating(FloatingStateConfig::default());
+
+    assert_eq!(width(WindowState::Tiling, true, false), Some(1));
+    assert_eq!(width(WindowState::Tiling, false, false), None);
+    assert_eq!(width(floating(), true, false), Some(2));
+    assert_eq!(width(floating(), false, true), Some(4));
+    assert_eq!(width(WindowState::Tiling, false, true), Some(4));
+  }
+
+  fn title_bar_config(remove_title_bars: bool) -> UserConfig {
+    let mut parsed_config = ParsedConfig::default();
+    parsed_config.window_behavior.remove_title_bars = remove_title_bars;
+    parsed_config.window_rules = serde_yaml::from_str(
+      "
+      - remove_title_bar: false
+        match:
+          - window_process: { equals: 'process-1' }
+      ",
+    )
+    .unwrap();
+
+    UserConfig::from_value(parsed_config)
+  }
+
+  #[test]
+  fn window_rule_overrides_title_bar_removal() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = title_bar_config(true);
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let matched = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let other = test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    assert!(!is_title_bar_removed(&matched.into(), &mut state, &config));
+    assert!(is_title_bar_removed(&other.into(), &mut state, &config));
+  }
+
+  #[test]
+  fn caches_title_bar_removal_per_window() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let window: WindowContainer =
+      test_utils::tiling_window(&workspace.clone().into(), 2).into();
+
+    assert!(is_title_bar_removed(
+      &window,
+      &mut state,
+      &title_bar_config(true)
+    ));
+
+    // The config is only matched against again once the cached decision
+    // is cleared (e.g. on config reload).
+    let config = title_bar_config(false);
+    assert!(is_title_bar_removed(&window, &mut state, &config));
+
+    state.title_bar_removals.clear();
+    assert!(!is_title_bar_removed(&window, &mut state, &config));
+  }
+}
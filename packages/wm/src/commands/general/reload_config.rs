@@ -1,45 +1,111 @@
 use anyhow::Context;
 use tracing::{info, warn};
-use wm_common::{HideMethod, ParsedConfig, WindowRuleEvent, WmEvent};
+use wm_common::{
+  ConfigReloadData, ConfigSection, HideMethod, ParsedConfig,
+  WindowRuleEvent, WmEvent,
+};
 
 use crate::{
-  commands::{window::run_window_rules, workspace::sort_workspaces},
+  commands::{
+    window::run_window_rules,
+    workspace::{
+      move_workspace_to_monitor_impl, sort_workspaces,
+      MoveWorkspaceOptions,
+    },
+  },
   traits::{CommonGetters, TilingSizeGetters, WindowGetters},
   user_config::UserConfig,
   wm::WindowManager,
   wm_state::WmState,
 };
 
+/// Reloads the user config, and applies only the sections that changed.
+///
+/// Containers are kept in place, such that windows are only redrawn if
+/// a change affects their layout. A summary of the applied changes (and
+/// of changes that need a restart) is stored in
+/// `WmState::last_config_reload`.
 pub fn reload_config(
   state: &mut WmState,
   config: &mut UserConfig,
 ) -> anyhow::Result<()> {
-  info!("Config reloaded.");
-
   // Keep reference to old config for comparison.
   let old_config = config.value.clone();
 
   // Re-evaluate user config file and set its values in state.
   config.reload()?;
 
+  apply_config_changes(&old_config, state, config)
+}
+
+/// Applies the config sections that differ from `old_config`.
+fn apply_config_changes(
+  old_config: &ParsedConfig,
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  let changed_sections =
+    UserConfig::changed_sections(old_config, &config.value)?;
+
+  let (requires_restart, applied): (Vec<_>, Vec<_>) = changed_sections
+    .iter()
+    .copied()
+    .partition(|&section| requires_restart(section, old_config, config));
+
+  info!(
+    "Config reloaded. Applied changes: {:?}. Changes requiring a \
+    restart: {:?}.",
+    applied, requires_restart
+  );
+
+  let has_changed =
+    |section: ConfigSection| changed_sections.contains(&section);
+
+  // Builtins pick up their new settings the next time they're started.
+  if has_changed(ConfigSection::Builtins) {
+    config.configure_builtins();
+  }
+
   // Re-run window rules on all active windows.
-  for window in state.windows() {
-    window.set_done_window_rules(Vec::new());
-    run_window_rules(window, &WindowRuleEvent::Manage, state, config)?;
+  if has_changed(ConfigSection::WindowRules) {
+    for window in state.windows() {
+      window.set_done_window_rules(Vec::new());
+      run_window_rules(window, &WindowRuleEvent::Manage, state, config)?;
+    }
   }
 
-  update_workspace_configs(state, config)?;
+  let mut moved_workspaces = Vec::new();
+
+  if has_changed(ConfigSection::Workspaces) {
+    update_workspace_configs(state, config)?;
+
+    moved_workspaces =
+      update_workspace_bindings(old_config, state, config)?;
 
-  update_container_gaps(state, config);
+    if !moved_workspaces.is_empty() {
+      info!(
+        "Moved workspaces to their bound monitors: {}.",
+        moved_workspaces.join(", ")
+      );
+    }
+  }
+
+  if has_changed(ConfigSection::Gaps) {
+    update_container_gaps(state, config);
+  }
 
-  update_window_effects(&old_config, state, config)?;
+  if has_changed(ConfigSection::WindowEffects) {
+    update_window_effects(old_config, state, config)?;
+  }
 
   // Ensure all windows are shown when hide method is changed.
   if old_config.general.hide_method != config.value.general.hide_method
     && config.value.general.hide_method == HideMethod::Cloak
   {
     for window in state.windows() {
-      let _ = window.native().show();
+      if !state.self_hidden_windows.contains(&window.id()) {
+        let _ = window.native().show();
+      }
     }
   }
 
@@ -54,25 +120,48 @@ pub fn reload_config(
     }
   }
 
-  // Clear active binding modes.
-  state.binding_modes = Vec::new();
+  // Title bars are removed per `window_behavior` and window rules, so
+  // they're decided anew for all windows.
+  if has_changed(ConfigSection::WindowBehavior)
+    || has_changed(ConfigSection::WindowRules)
+  {
+    state.title_bar_removals.clear();
+    state
+      .pending_sync
+      .queue_container_to_redraw(state.root_container.clone());
+  }
 
-  // Redraw full container tree.
-  state
-    .pending_sync
-    .queue_container_to_redraw(state.root_container.clone());
+  // Clear active binding modes, since their keybindings might've changed.
+  if has_changed(ConfigSection::BindingModes) {
+    state.binding_modes = Vec::new();
+  }
+
+  // Redraw the full container tree if the layout of windows might've
+  // changed.
+  if [
+    ConfigSection::Gaps,
+    ConfigSection::General,
+    ConfigSection::WindowBehavior,
+    ConfigSection::Workspaces,
+  ]
+  .into_iter()
+  .any(has_changed)
+  {
+    state
+      .pending_sync
+      .queue_container_to_redraw(state.root_container.clone());
+  }
 
-  // Emit the updated config.
-  state.emit_event(WmEvent::UserConfigChanged {
-    config_path: config
-      .path
-      .to_str()
-      .context("Invalid config path.")?
-      .to_string(),
-    config_string: config.value_str.clone(),
-    parsed_config: config.value.clone(),
+  state.last_config_reload = Some(ConfigReloadData {
+    applied,
+    requires_restart,
+    moved_workspaces,
   });
 
+  // Emit the updated config. Keyboard and mouse listeners are updated on
+  // this event, which re-registers changed keybindings.
+  state.emit_event(config.changed_event()?);
+
   // Run config reload commands.
   WindowManager::run_commands(
     &config.value.general.config_reload_commands.clone(),
@@ -84,6 +173,19 @@ pub fn reload_config(
   Ok(())
 }
 
+/// Whether a changed config section only takes effect after a restart.
+///
+/// The IPC server keeps listening on its original endpoint, whereas
+/// every other setting is applied on reload.
+fn requires_restart(
+  section: ConfigSection,
+  old_config: &ParsedConfig,
+  config: &UserConfig,
+) -> bool {
+  section == ConfigSection::Ipc
+    && old_config.ipc.endpoint() != config.value.ipc.endpoint()
+}
+
 /// Update configs of active workspaces.
 fn update_workspace_configs(
   state: &mut WmState,
@@ -104,7 +206,7 @@ fn update_workspace_configs(
         // workspace has been removed. So, we reassign the first suitable
         // workspace config to the workspace.
         config
-          .workspace_config_for_monitor(&monitor, &workspaces)
+          .workspace_config_for_monitor(&monitor, state)
           .or_else(|| config.next_inactive_workspace_config(&workspaces))
       });
 
@@ -116,9 +218,19 @@ fn update_workspace_configs(
       }
       Some(workspace_config) => {
         if *workspace_config != workspace.config() {
+          // Reset any resizing of the master area if the configured ratio
+          // has changed.
+          if (workspace_config.master_ratio
+            - workspace.config().master_ratio)
+            .abs()
+            > f32::EPSILON
+          {
+            workspace.set_master_ratio(workspace_config.master_ratio);
+          }
+
           workspace.set_config(workspace_config.clone());
 
-          sort_workspaces(&monitor, config)?;
+          sort_workspaces(&monitor, config);
 
           state.emit_event(WmEvent::WorkspaceUpdated {
             updated_workspace: workspace.to_dto()?,
@@ -131,6 +243,68 @@ fn update_workspace_configs(
   Ok(())
 }
 
+/// Moves active workspaces whose `bind_to_monitor` has changed to their
+/// newly bound monitor.
+///
+/// Returns the names of the moved workspaces.
+fn update_workspace_bindings(
+  old_config: &ParsedConfig,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<Vec<String>> {
+  let mut moved_workspaces = Vec::new();
+
+  for workspace in state.workspaces() {
+    let workspace_config = workspace.config();
+
+    let old_binding = old_config
+      .workspaces
+      .iter()
+      .find(|config| config.name == workspace_config.name)
+      .and_then(|config| config.bind_to_monitor.as_ref());
+
+    let Some(new_binding) = &workspace_config.bind_to_monitor else {
+      continue;
+    };
+
+    if old_binding == Some(new_binding) {
+      continue;
+    }
+
+    // The workspace gets moved once one of its monitors is added.
+    let Some(target_monitor) = state.bound_monitor(new_binding) else {
+      continue;
+    };
+
+    let monitor = workspace.monitor().context("No monitor.")?;
+    if monitor.id() == target_monitor.id() {
+      continue;
+    }
+
+    if workspace.has_focus(None)
+      && !config.value.general.force_rebind_on_reload
+    {
+      info!(
+        "Not moving focused workspace '{}' to its bound monitor.",
+        workspace_config.name
+      );
+      continue;
+    }
+
+    move_workspace_to_monitor_impl(
+      &workspace,
+      &target_monitor,
+      state,
+      config,
+      MoveWorkspaceOptions::SINGLE,
+    )?;
+
+    moved_workspaces.push(workspace_config.name);
+  }
+
+  Ok(moved_workspaces)
+}
+
 /// Updates outer gap of workspaces and inner gaps of tiling containers.
 fn update_container_gaps(state: &mut WmState, config: &UserConfig) {
   let tiling_containers = state
@@ -182,7 +356,71 @@ fn update_window_effects(
     }
   }
 
+  // Reset borders and remove any border overlays when transitioning from
+  // state borders to having them disabled.
+  if !window_effects.borders.enabled && old_window_effects.borders.enabled
+  {
+    for window in state.windows() {
+      _ = window.native().set_border_color(None);
+    }
+
+    state.border_overlays.clear();
+  }
+
   state.pending_sync.queue_all_effects_update();
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::WorkspaceConfig;
+
+  use super::*;
+  use crate::{commands::container::set_focused_descendant, test_utils};
+
+  fn workspace_configs(bound_workspace: Option<&str>) -> ParsedConfig {
+    let workspaces = ["1", "2", "3"]
+      .into_iter()
+      .map(|name| {
+        let yaml = if bound_workspace == Some(name) {
+          format!("{{ name: '{name}', bind_to_monitor: 1 }}")
+        } else {
+          format!("{{ name: '{name}' }}")
+        };
+
+        serde_yaml::from_str::<WorkspaceConfig>(&yaml).unwrap()
+      })
+      .collect();
+
+    ParsedConfig {
+      workspaces,
+      ..ParsedConfig::default()
+    }
+  }
+
+  #[test]
+  fn reports_moved_workspaces() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let other_monitor = test_utils::monitor(&state, 2, 1920);
+    let focused_workspace = test_utils::workspace(&monitor, "1");
+    let workspace = test_utils::workspace(&monitor, "2");
+    test_utils::workspace(&other_monitor, "3");
+    set_focused_descendant(&focused_workspace.into(), None);
+
+    let old_config = workspace_configs(None);
+    let mut config = UserConfig::from_value(workspace_configs(Some("2")));
+
+    apply_config_changes(&old_config, &mut state, &mut config).unwrap();
+
+    let summary = state.last_config_reload.take().unwrap();
+    assert_eq!(summary.applied, vec![ConfigSection::Workspaces]);
+    assert_eq!(summary.moved_workspaces, vec!["2".to_string()]);
+    assert_eq!(
+      workspace.monitor().map(|monitor| monitor.id()),
+      Some(other_monitor.id())
+    );
+  }
+}
@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use tracing::{info, warn};
+use uuid::Uuid;
+use wm_common::{ContainerDto, MonitorDto, WindowState, WorkspaceDto};
+
+use crate::{
+  commands::{
+    container::{move_container_within_tree, wrap_in_split_container},
+    window::update_window_state,
+    workspace::activate_workspace,
+  },
+  models::{
+    Container, Monitor, SplitContainer, TilingContainer, WindowContainer,
+  },
+  session::{match_windows, saved_windows, Session, WindowIdentity},
+  traits::{
+    CommonGetters, TilingDirectionGetters, TilingSizeGetters,
+    WindowGetters,
+  },
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves managed windows back to where they were in a saved session.
+///
+/// Running windows are matched with the saved ones via
+/// `session::match_windows`, and are moved to their saved workspace
+/// (activating it if needed), along with their saved state, split
+/// containers, and tiling sizes. Unmatched windows are left as is, and
+/// saved windows that are no longer running are dropped.
+pub fn restore_session(
+  session: &Session,
+  state: &mut WmState,
+  config: &UserConfig,
+) {
+  let saved_workspaces = session.workspaces();
+
+  let saved = saved_workspaces
+    .iter()
+    .flat_map(|(_, workspace)| saved_windows(&workspace.children))
+    .collect::<Vec<_>>();
+
+  let running = state
+    .windows()
+    .into_iter()
+    .filter_map(|window| {
+      let identity = WindowIdentity::from_native(&window.native()).ok()?;
+      Some((window, identity))
+    })
+    .collect::<Vec<_>>();
+
+  let saved_identities = saved
+    .iter()
+    .map(|&window| WindowIdentity::from(window))
+    .collect::<Vec<_>>();
+
+  let running_identities = running
+    .iter()
+    .map(|(_, identity)| identity.clone())
+    .collect::<Vec<_>>();
+
+  // Running windows by the ID of the saved window they were matched to.
+  let mut matched = match_windows(&saved_identities, &running_identities)
+    .into_iter()
+    .map(|(saved_index, running_index)| {
+      (saved[saved_index].id, running[running_index].0.clone())
+    })
+    .collect::<HashMap<_, _>>();
+
+  info!("Restoring session with {} matched windows.", matched.len());
+
+  for (saved_monitor, saved_workspace) in saved_workspaces {
+    let has_matches = saved_windows(&saved_workspace.children)
+      .iter()
+      .any(|window| matched.contains_key(&window.id));
+
+    if !has_matches {
+      continue;
+    }
+
+    if let Err(err) = restore_workspace(
+      saved_monitor,
+      saved_workspace,
+      &mut matched,
+      state,
+      config,
+    ) {
+      warn!(
+        "Failed to restore workspace '{}': {:?}",
+        saved_workspace.name, err
+      );
+    }
+  }
+
+  for workspace in state.workspaces() {
+    state.pending_sync.queue_container_to_redraw(workspace);
+  }
+}
+
+/// Moves the matched windows of a saved workspace onto the workspace
+/// with the same name, and rebuilds its split containers.
+fn restore_workspace(
+  saved_monitor: &MonitorDto,
+  saved_workspace: &WorkspaceDto,
+  matched: &mut HashMap<Uuid, WindowContainer>,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let workspace = if let Some(workspace) =
+    state.workspace_by_name(&saved_workspace.name)
+  {
+    workspace
+  } else {
+    let target_monitor = saved_monitor_match(saved_monitor, state);
+
+    activate_workspace(
+      Some(&saved_workspace.name),
+      target_monitor,
+      state,
+      config,
+    )?;
+
+    state
+      .workspace_by_name(&saved_workspace.name)
+      .context("Failed to activate workspace.")?
+  };
+
+  workspace.set_tiling_direction(saved_workspace.tiling_direction.clone());
+
+  // Move the windows onto the workspace in tree order, such that the
+  // windows of each split container end up next to each other.
+  for saved_window in saved_windows(&saved_workspace.children) {
+    let Some(window) = matched.get(&saved_window.id).cloned() else {
+      continue;
+    };
+
+    let target_state = match &saved_window.state {
+      WindowState::Floating(_) | WindowState::Tiling => {
+        saved_window.state.clone()
+      }
+      _ => window.state(),
+    };
+
+    window.set_last_floating_placement(
+      saved_window.last_floating_placement.clone(),
+    );
+
+    // Changing state creates a new container for the window.
+    let window = update_window_state(window, target_state, state, config)?;
+
+    if !window.is_tiling_window() {
+      window
+        .set_floating_placement(saved_window.floating_placement.clone());
+    }
+
+    move_container_within_tree(
+      &window.clone().into(),
+      &workspace.clone().into(),
+      workspace.child_count(),
+      state,
+    )?;
+
+    matched.insert(saved_window.id, window);
+  }
+
+  let restored = restore_split_containers(
+    &saved_workspace.children,
+    &workspace.clone().into(),
+    matched,
+    config,
+  )?;
+
+  // Sizes are only restored if no other windows share the workspace.
+  if restored.len() == workspace.tiling_children().count() {
+    restore_tiling_sizes(&restored);
+  }
+
+  Ok(())
+}
+
+/// Wraps the restored tiling windows of the given saved containers in
+/// split containers, from the innermost split container outwards.
+///
+/// The restored windows are expected to be direct children of `parent`.
+/// Returns the tiling containers that take the place of the saved
+/// containers, along with their saved tiling sizes.
+fn restore_split_containers(
+  saved_children: &[ContainerDto],
+  parent: &Container,
+  matched: &HashMap<Uuid, WindowContainer>,
+  config: &UserConfig,
+) -> anyhow::Result<Vec<(TilingContainer, f32)>> {
+  let mut restored = Vec::new();
+
+  for saved_child in saved_children {
+    match saved_child {
+      ContainerDto::Window(saved_window) => {
+        let window = matched
+          .get(&saved_window.id)
+          .and_then(|window| window.as_tiling_container().ok());
+
+        if let Some(window) = window {
+          restored.push((window, saved_window.tiling_size.unwrap_or(1.0)));
+        }
+      }
+      ContainerDto::Split(saved_split) => {
+        let split_children = restore_split_containers(
+          &saved_split.children,
+          parent,
+          matched,
+          config,
+        )?;
+
+        match split_children.as_slice() {
+          [] => {}
+          // A lone child takes the place of its split container.
+          [(child, _)] => {
+            restored.push((child.clone(), saved_split.tiling_size));
+          }
+          _ => {
+            let split_container = SplitContainer::new(
+              saved_split.tiling_direction.clone(),
+              config.value.gaps.clone(),
+            );
+
+            split_container.set_layout(saved_split.layout);
+
+            wrap_in_split_container(
+              &split_container,
+              parent,
+              &split_children
+                .iter()
+                .map(|(child, _)| child.clone())
+                .collect::<Vec<_>>(),
+            )?;
+
+            restore_tiling_sizes(&split_children);
+            restored
+              .push((split_container.into(), saved_split.tiling_size));
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  Ok(restored)
+}
+
+/// Sets the tiling sizes of sibling containers to their saved sizes,
+/// scaled such that they add up to 1.
+fn restore_tiling_sizes(siblings: &[(TilingContainer, f32)]) {
+  let total_size = siblings.iter().map(|(_, size)| size).sum::<f32>();
+
+  if total_size <= 0.0 {
+    return;
+  }
+
+  for (container, size) in siblings {
+    container.set_tiling_size(size / total_size);
+  }
+}
+
+/// Gets the monitor that a saved workspace was on, if it's still
+/// connected.
+fn saved_monitor_match(
+  saved_monitor: &MonitorDto,
+  state: &WmState,
+) -> Option<Monitor> {
+  state.monitors().into_iter().find(|monitor| {
+    let native = monitor.native();
+
+    match &saved_monitor.hardware_id {
+      Some(hardware_id) => {
+        native.hardware_id().ok().flatten() == Some(hardware_id)
+      }
+      None => native.device_name().is_ok_and(|device_name| {
+        *device_name == saved_monitor.device_name
+      }),
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::sync::mpsc;
+  use wm_common::{
+    DisplayState, GapsConfig, LengthValue, ParsedConfig, Rect, RectDelta,
+    SplitContainerDto, SplitLayout, TilingDirection, WindowDto,
+    WorkspaceLayout,
+  };
+  use wm_platform::{NativeMonitor, NativeWindow};
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container,
+    models::{TilingWindow, Workspace},
+  };
+
+  fn no_delta() -> RectDelta {
+    let no_delta = LengthValue::from_px(0);
+
+    RectDelta::new(
+      no_delta.clone(),
+      no_delta.clone(),
+      no_delta.clone(),
+      no_delta,
+    )
+  }
+
+  fn tiling_window(handle: isize) -> TilingWindow {
+    TilingWindow::new(
+      None,
+      NativeWindow::new(handle),
+      None,
+      no_delta(),
+      Rect::from_xy(0, 0, 400, 300),
+      false,
+      GapsConfig::default(),
+      Vec::new(),
+      None,
+    )
+  }
+
+  fn workspace(name: &str) -> Workspace {
+    Workspace::new(
+      serde_yaml::from_str(&format!("name: '{name}'")).unwrap(),
+      GapsConfig::default(),
+      TilingDirection::Horizontal,
+    )
+  }
+
+  fn saved_window(handle: isize, tiling_size: f32) -> WindowDto {
+    WindowDto {
+      id: Uuid::new_v4(),
+      parent_id: None,
+      has_focus: false,
+      tiling_size: Some(tiling_size),
+      width: 0,
+      height: 0,
+      x: 0,
+      y: 0,
+      state: WindowState::Tiling,
+      prev_state: None,
+      display_state: DisplayState::Shown,
+      border_delta: no_delta(),
+      floating_placement: Rect::from_xy(0, 0, 400, 300),
+      last_floating_placement: None,
+      handle,
+      title: String::new(),
+      class_name: String::new(),
+      process_name: String::new(),
+      active_drag: None,
+      is_urgent: false,
+      is_maximized: false,
+    }
+  }
+
+  fn saved_workspace(
+    name: &str,
+    children: Vec<ContainerDto>,
+  ) -> WorkspaceDto {
+    WorkspaceDto {
+      id: Uuid::new_v4(),
+      name: name.to_string(),
+      display_name: None,
+      parent_id: None,
+      children,
+      child_focus_order: Vec::new(),
+      has_focus: false,
+      is_displayed: true,
+      width: 1920,
+      height: 1080,
+      x: 0,
+      y: 0,
+      tiling_direction: TilingDirection::Vertical,
+      monocle_window_id: None,
+      layout: WorkspaceLayout::Manual,
+      master_ratio: 0.55,
+      master_count: 1,
+      has_urgent_window: false,
+      insertion_hint: None,
+    }
+  }
+
+  fn saved_monitor() -> MonitorDto {
+    MonitorDto {
+      id: Uuid::new_v4(),
+      parent_id: None,
+      children: Vec::new(),
+      child_focus_order: Vec::new(),
+      has_focus: false,
+      width: 1920,
+      height: 1080,
+      x: 0,
+      y: 0,
+      dpi: 96,
+      scale_factor: 1.0,
+      handle: 0,
+      device_name: String::new(),
+      device_path: None,
+      hardware_id: None,
+      working_rect: Rect::from_xy(0, 0, 1920, 1080),
+      is_primary: true,
+      is_standby: false,
+    }
+  }
+
+  #[test]
+  fn restores_order_splits_and_sizes() {
+    let mut state = WmState::new(
+      mpsc::unbounded_channel().0,
+      mpsc::unbounded_channel().0,
+    );
+    let config = UserConfig::from_value(ParsedConfig::default());
+
+    let monitor = Monitor::new(NativeMonitor::new(1));
+    attach_container(
+      &monitor.clone().into(),
+      &state.root_container.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    // The restored workspace is kept unfocused, since moving a focused
+    // window emits an event that reads from the native window.
+    let restored_workspace = workspace("1");
+    for workspace in [workspace("2"), restored_workspace.clone()] {
+      attach_container(&workspace.into(), &monitor.clone().into(), None)
+        .unwrap();
+    }
+
+    // Windows are running in a different order than they were saved in.
+    let windows = [tiling_window(3), tiling_window(1), tiling_window(2)];
+    for window in &windows {
+      attach_container(
+        &window.clone().into(),
+        &restored_workspace.clone().into(),
+        None,
+      )
+      .unwrap();
+    }
+
+    let saved_windows = [
+      saved_window(1, 0.25),
+      saved_window(2, 0.5),
+      saved_window(3, 0.5),
+    ];
+
+    let saved_split = SplitContainerDto {
+      id: Uuid::new_v4(),
+      parent_id: None,
+      children: vec![
+        ContainerDto::Window(saved_windows[1].clone()),
+        ContainerDto::Window(saved_windows[2].clone()),
+      ],
+      child_focus_order: Vec::new(),
+      has_focus: false,
+      tiling_size: 0.75,
+      width: 0,
+      height: 0,
+      x: 0,
+      y: 0,
+      tiling_direction: TilingDirection::Horizontal,
+      layout: SplitLayout::default(),
+    };
+
+    let saved_workspace = saved_workspace(
+      "1",
+      vec![
+        ContainerDto::Window(saved_windows[0].clone()),
+        ContainerDto::Split(saved_split),
+      ],
+    );
+
+    // Saved windows are matched by their handle.
+    let mut matched = saved_windows
+      .iter()
+      .map(|saved| {
+        let window = windows
+          .iter()
+          .find(|window| window.native().handle == saved.handle)
+          .unwrap();
+
+        (saved.id, window.clone().into())
+      })
+      .collect::<HashMap<_, _>>();
+
+    restore_workspace(
+      &saved_monitor(),
+      &saved_workspace,
+      &mut matched,
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    assert_eq!(
+      restored_workspace.tiling_direction(),
+      TilingDirection::Vertical
+    );
+
+    let children =
+      restored_workspace.tiling_children().collect::<Vec<_>>();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].id(), windows[1].id());
+    assert!((children[0].tiling_size() - 0.25).abs() < f32::EPSILON);
+
+    let split = children[1].as_split().unwrap();
+    assert_eq!(split.tiling_direction(), TilingDirection::Horizontal);
+    assert!((split.tiling_size() - 0.75).abs() < f32::EPSILON);
+
+    let split_ids = split
+      .tiling_children()
+      .map(|child| child.id())
+      .collect::<Vec<_>>();
+    assert_eq!(split_ids, [windows[2].id(), windows[0].id()]);
+  }
+}
@@ -1,7 +1,12 @@
 use tracing::info;
 use wm_platform::Platform;
 
-pub fn shell_exec(command: &str, hide_window: bool) -> anyhow::Result<()> {
+/// Launches the given command and returns the ID of the launched process,
+/// if any.
+pub fn shell_exec(
+  command: &str,
+  hide_window: bool,
+) -> anyhow::Result<Option<u32>> {
   let (program, args) = Platform::parse_command(command)?;
   info!("Parsed command program: '{}', args: '{}'.", program, args);
 
@@ -9,7 +14,5 @@ pub fn shell_exec(command: &str, hide_window: bool) -> anyhow::Result<()> {
     anyhow::anyhow!(format!(
       "Failed to execute '{command}'.\n\nError: {err}"
     ))
-  })?;
-
-  Ok(())
+  })
 }
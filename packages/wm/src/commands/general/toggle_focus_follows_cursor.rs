@@ -0,0 +1,26 @@
+use tracing::info;
+
+use crate::{user_config::UserConfig, wm_state::WmState};
+
+/// Enables or disables `focus_follows_cursor`. The config file is left
+/// untouched, such that the setting reverts on config reload.
+pub fn toggle_focus_follows_cursor(
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  let general = &mut config.value.general;
+  general.focus_follows_cursor = !general.focus_follows_cursor;
+
+  info!(
+    "Focus follows cursor toggled: {}",
+    general.focus_follows_cursor
+  );
+
+  state.cursor_focus_candidate = None;
+
+  // The updated config is emitted so that the mouse listener gets
+  // enabled or disabled accordingly.
+  state.emit_event(config.changed_event()?);
+
+  Ok(())
+}
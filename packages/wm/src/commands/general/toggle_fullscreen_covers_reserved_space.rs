@@ -0,0 +1,34 @@
+use tracing::info;
+use wm_common::WindowState;
+
+use crate::{
+  traits::WindowGetters, user_config::UserConfig, wm_state::WmState,
+};
+
+/// Toggles whether fullscreen windows cover the space reserved on their
+/// monitor. The config file is left untouched, such that the setting
+/// reverts on config reload.
+pub fn toggle_fullscreen_covers_reserved_space(
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  let general = &mut config.value.general;
+  general.fullscreen_covers_reserved_space =
+    !general.fullscreen_covers_reserved_space;
+
+  info!(
+    "Fullscreen covers reserved space toggled: {}",
+    general.fullscreen_covers_reserved_space
+  );
+
+  // Resize any fullscreen windows to match the new setting.
+  for window in state.windows() {
+    if matches!(window.state(), WindowState::Fullscreen(_)) {
+      state.pending_sync.queue_container_to_redraw(window);
+    }
+  }
+
+  state.emit_event(config.changed_event()?);
+
+  Ok(())
+}
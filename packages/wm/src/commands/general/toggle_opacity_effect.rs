@@ -0,0 +1,33 @@
+use tracing::info;
+use wm_common::OpacityValue;
+
+use crate::{
+  traits::WindowGetters, user_config::UserConfig, wm_state::WmState,
+};
+
+/// Enables or disables the opacity window effect. The config file is left
+/// untouched, such that the setting reverts on config reload.
+pub fn toggle_opacity_effect(
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  let opacity = &mut config.value.window_effects.opacity;
+  opacity.enabled = !opacity.enabled;
+
+  info!("Opacity effect toggled: {}", opacity.enabled);
+
+  // Make windows opaque again. Any `transparency` effects get reapplied
+  // on the effects update below.
+  if !opacity.enabled {
+    for window in state.windows() {
+      _ = window
+        .native()
+        .set_transparency(&OpacityValue::from_alpha(u8::MAX));
+    }
+  }
+
+  state.pending_sync.queue_all_effects_update();
+  state.emit_event(config.changed_event()?);
+
+  Ok(())
+}
@@ -1,5 +1,8 @@
+mod command_error;
 pub mod container;
 pub mod general;
 pub mod monitor;
 pub mod window;
 pub mod workspace;
+
+pub use command_error::*;
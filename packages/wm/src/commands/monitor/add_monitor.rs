@@ -2,10 +2,14 @@ use tracing::info;
 use wm_common::WmEvent;
 use wm_platform::NativeMonitor;
 
+use super::{run_monitor_commands, MonitorCommandTrigger};
 use crate::{
   commands::{
     container::attach_container,
-    workspace::{activate_workspace, move_workspace_to_monitor_impl},
+    workspace::{
+      activate_workspace, move_workspace_to_monitor_impl, sort_workspaces,
+      MoveWorkspaceOptions,
+    },
   },
   models::Monitor,
   traits::CommonGetters,
@@ -31,32 +35,48 @@ pub fn add_monitor(
 
   info!("Monitor added: {monitor}");
 
-  state.emit_event(WmEvent::MonitorAdded {
-    added_monitor: monitor.to_dto()?,
-  });
-
-  let bound_workspace_configs = config
+  // Get workspaces for which the new monitor is the most preferred of
+  // their bound monitors that are connected.
+  let mut bound_workspace_configs = config
     .value
     .workspaces
     .iter()
     .filter(|config| {
-      config.bind_to_monitor.is_some_and(|monitor_index| {
-        monitor.index() == monitor_index as usize
-      })
+      config
+        .bind_to_monitor
+        .as_ref()
+        .and_then(|binding| state.bound_monitor(binding))
+        .is_some_and(|bound_monitor| bound_monitor.id() == monitor.id())
     })
     .collect::<Vec<_>>();
 
+  // Populate the monitor in order of `monitor_position`. The sort is
+  // stable, so workspaces without a position keep their config order.
+  bound_workspace_configs.sort_by_key(|config| {
+    (config.monitor_position.is_none(), config.monitor_position)
+  });
+
   for workspace_config in bound_workspace_configs {
     let existing_workspace =
       state.workspace_by_name(&workspace_config.name);
 
     if let Some(existing_workspace) = existing_workspace {
       // Move workspaces that should be bound to the newly added monitor.
+      // This includes workspaces that fell back to a less preferred
+      // monitor while this one was disconnected.
       move_workspace_to_monitor_impl(
         &existing_workspace,
         &monitor,
         state,
         config,
+        MoveWorkspaceOptions {
+          sort: true,
+          jump_cursor: !config
+            .value
+            .general
+            .cursor_jump
+            .suppress_on_monitor_change,
+        },
       )?;
     } else if workspace_config.keep_alive {
       // Activate all `keep_alive` workspaces for this monitor.
@@ -73,8 +93,65 @@ pub fn add_monitor(
   // automatically prioritize bound workspace configs and fall back to the
   // first available one if needed.
   if monitor.child_count() == 0 {
-    activate_workspace(None, Some(monitor), state, config)?;
+    activate_workspace(None, Some(monitor.clone()), state, config)?;
   }
 
+  sort_workspaces(&monitor, config);
+
+  // Emit once the monitor has its initial workspaces, so that subscribers
+  // receive it fully populated.
+  state.emit_event(WmEvent::MonitorAdded {
+    added_monitor: monitor.to_dto()?,
+  });
+
+  run_monitor_commands(&monitor, MonitorCommandTrigger::Connect, config)?;
+
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{ContainerDto, ParsedConfig, Rect};
+
+  use super::*;
+  use crate::test_utils;
+
+  #[test]
+  fn emits_monitor_added_with_its_workspace() {
+    let (mut state, mut event_rx) = test_utils::state();
+
+    let config = UserConfig::from_value(ParsedConfig {
+      workspaces: serde_yaml::from_str("[{ name: '1' }, { name: '2' }]")
+        .unwrap(),
+      ..ParsedConfig::default()
+    });
+
+    add_monitor(
+      NativeMonitor::with_rect(1, Rect::from_xy(0, 0, 1920, 1080)),
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    let events = test_utils::emitted_events(&mut event_rx);
+
+    // The monitor is only announced once its workspace is activated.
+    let Some(WmEvent::MonitorAdded {
+      added_monitor: ContainerDto::Monitor(added_monitor),
+    }) = events.last()
+    else {
+      panic!("Expected `MonitorAdded` to be the last event.");
+    };
+
+    assert!(events
+      .iter()
+      .any(|event| matches!(event, WmEvent::WorkspaceActivated { .. })));
+    assert_eq!(
+      added_monitor
+        .workspaces()
+        .map(|workspace| workspace.name.as_str())
+        .collect::<Vec<_>>(),
+      ["1"]
+    );
+  }
+}
@@ -11,11 +11,7 @@ pub fn focus_monitor(
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
-  let monitors = state.monitors();
-
-  let target_monitor = monitors.get(monitor_index).with_context(|| {
-    format!("Monitor at index {monitor_index} was not found.")
-  })?;
+  let target_monitor = state.active_monitor_by_index(monitor_index)?;
 
   let workspace_name = target_monitor
     .displayed_workspace()
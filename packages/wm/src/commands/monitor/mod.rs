@@ -1,11 +1,15 @@
 mod add_monitor;
 mod focus_monitor;
 mod remove_monitor;
+mod run_monitor_commands;
 mod sort_monitors;
+mod standby_monitor;
 mod update_monitor;
 
 pub use add_monitor::*;
 pub use focus_monitor::*;
 pub use remove_monitor::*;
+pub use run_monitor_commands::*;
 pub use sort_monitors::*;
+pub use standby_monitor::*;
 pub use update_monitor::*;
@@ -2,6 +2,7 @@ use anyhow::Context;
 use tracing::info;
 use wm_common::WmEvent;
 
+use super::{run_monitor_commands, MonitorCommandTrigger};
 use crate::{
   commands::{
     container::{detach_container, move_container_within_tree},
@@ -21,10 +22,19 @@ pub fn remove_monitor(
 ) -> anyhow::Result<()> {
   info!("Removing monitor: {monitor}");
 
-  let target_monitor = state
-    .monitors()
-    .into_iter()
-    .find(|m| m.id() != monitor.id())
+  // Run before detaching, while the monitor's index is still known.
+  run_monitor_commands(
+    &monitor,
+    MonitorCommandTrigger::Disconnect,
+    config,
+  )?;
+
+  // Prefer moving workspaces to the primary monitor, otherwise fall back
+  // to whichever monitor remains.
+  let default_monitor = state
+    .primary_monitor()
+    .filter(|m| m.id() != monitor.id())
+    .or_else(|| state.monitors_iter().find(|m| m.id() != monitor.id()))
     .context("No target monitor to move workspaces.")?;
 
   // Avoid moving empty workspaces.
@@ -34,6 +44,19 @@ pub fn remove_monitor(
     });
 
   for workspace in workspaces_to_move {
+    // Bound workspaces are moved to the next of their bound monitors that
+    // remains connected, if any.
+    let target_monitor = workspace
+      .config()
+      .bind_to_monitor
+      .and_then(|binding| {
+        state
+          .bound_monitors(&binding)
+          .into_iter()
+          .find(|bound_monitor| bound_monitor.id() != monitor.id())
+      })
+      .unwrap_or_else(|| default_monitor.clone());
+
     // Move workspace to target monitor.
     move_container_within_tree(
       &workspace.clone().into(),
@@ -42,7 +65,7 @@ pub fn remove_monitor(
       state,
     )?;
 
-    sort_workspaces(&target_monitor, config)?;
+    sort_workspaces(&target_monitor, config);
 
     state.emit_event(WmEvent::WorkspaceUpdated {
       updated_workspace: workspace.to_dto()?,
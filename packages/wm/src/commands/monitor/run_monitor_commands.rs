@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use tokio::task;
+use tracing::{info, warn};
+use wm_platform::Platform;
+
+use crate::{
+  models::Monitor, traits::CommonGetters, user_config::UserConfig,
+};
+
+/// Event that triggers a monitor's configured commands.
+#[derive(Clone, Copy, Debug)]
+pub enum MonitorCommandTrigger {
+  Connect,
+  Disconnect,
+}
+
+/// Runs the `on_connect` or `on_disconnect` commands of all monitor
+/// configs that match the given monitor.
+///
+/// Commands run in the background, so a slow or hanging command doesn't
+/// block the WM. Commands that exceed their timeout are terminated.
+pub fn run_monitor_commands(
+  monitor: &Monitor,
+  trigger: MonitorCommandTrigger,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let device_name = monitor.native().device_name()?.clone();
+  let index = monitor.index();
+  let rect = monitor.native().rect()?.clone();
+
+  let monitor_configs =
+    config.value.monitors.iter().filter(|monitor_config| {
+      monitor_config.matches(&device_name, index)
+    });
+
+  for monitor_config in monitor_configs {
+    let commands = match trigger {
+      MonitorCommandTrigger::Connect => &monitor_config.on_connect,
+      MonitorCommandTrigger::Disconnect => &monitor_config.on_disconnect,
+    };
+
+    let timeout =
+      Duration::from_millis(u64::from(monitor_config.command_timeout_ms));
+
+    for command in commands {
+      let command = command
+        .replace("{name}", &device_name)
+        .replace("{index}", &index.to_string())
+        .replace("{width}", &rect.width().to_string())
+        .replace("{height}", &rect.height().to_string());
+
+      info!("Running monitor {:?} command: '{}'.", trigger, command);
+
+      task::spawn_blocking(move || {
+        let res =
+          Platform::parse_command(&command).and_then(|(program, args)| {
+            Platform::run_command_with_timeout(
+              &program, &args, true, timeout,
+            )
+          });
+
+        if let Err(err) = res {
+          warn!("Monitor command '{}' failed: {}", command, err);
+        }
+      });
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,231 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::{MonitorField, WmEvent};
+
+use crate::{
+  commands::{container::set_focused_descendant, CommandError},
+  models::Monitor,
+  traits::CommonGetters,
+  wm_state::WmState,
+};
+
+/// Hides a monitor's workspaces without moving them in the tree.
+///
+/// Unlike `remove_monitor`, the workspaces stay on the monitor, so
+/// resuming it via `resume_monitor` restores them as they were. Focus is
+/// moved to another monitor if it was on the one put in standby.
+pub fn standby_monitor(
+  monitor: &Monitor,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if monitor.is_standby() {
+    return Ok(());
+  }
+
+  let target_monitor = state
+    .monitors_iter()
+    .find(|other| other.id() != monitor.id() && !other.is_standby())
+    .context("Cannot put the only active monitor in standby.")?;
+
+  info!("Putting monitor in standby: {monitor}");
+
+  monitor.set_standby(true);
+
+  let has_focus = state
+    .focused_container()
+    .and_then(|focused| focused.monitor())
+    .is_some_and(|focused_monitor| focused_monitor.id() == monitor.id());
+
+  if has_focus {
+    let target_workspace = target_monitor
+      .displayed_workspace()
+      .context("No displayed workspace.")?;
+
+    let container_to_focus = target_workspace
+      .descendant_focus_order()
+      .next()
+      .unwrap_or_else(|| target_workspace.clone().into());
+
+    set_focused_descendant(&container_to_focus, None);
+    state.pending_sync.queue_focus_change();
+  }
+
+  state
+    .pending_sync
+    .queue_containers_to_redraw(monitor.workspaces());
+
+  emit_standby_changed(monitor, state)
+}
+
+/// Shows the workspaces of a monitor in standby again.
+pub fn resume_monitor(
+  monitor: &Monitor,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if !monitor.is_standby() {
+    return Ok(());
+  }
+
+  info!("Resuming monitor from standby: {monitor}");
+
+  monitor.set_standby(false);
+
+  state
+    .pending_sync
+    .queue_containers_to_redraw(monitor.workspaces());
+
+  emit_standby_changed(monitor, state)
+}
+
+/// Toggles standby for the monitor at the given index.
+pub fn toggle_monitor_standby(
+  monitor_index: usize,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let monitor =
+    state.monitor_by_index(monitor_index).ok_or_else(|| {
+      CommandError::MonitorIndexOutOfRange {
+        requested: monitor_index,
+        available: state.monitors().len(),
+      }
+    })?;
+
+  if monitor.is_standby() {
+    resume_monitor(&monitor, state)
+  } else {
+    standby_monitor(&monitor, state)
+  }
+}
+
+fn emit_standby_changed(
+  monitor: &Monitor,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  state.emit_event(WmEvent::MonitorUpdated {
+    updated_monitor: monitor.to_dto()?,
+    changed: vec![MonitorField::Standby],
+  });
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::Direction;
+
+  use super::*;
+  use crate::test_utils;
+
+  #[test]
+  fn standby_moves_focus_to_other_monitor() {
+    let (mut state, mut event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let window = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let other_monitor = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&other_monitor, "2");
+    set_focused_descendant(&window.clone().into(), None);
+
+    standby_monitor(&monitor, &mut state).unwrap();
+
+    assert!(monitor.is_standby());
+    assert_eq!(
+      state.focused_monitor().map(|monitor| monitor.id()),
+      Some(other_monitor.id())
+    );
+
+    // Workspaces stay on the monitor in standby.
+    assert_eq!(workspace.monitor().map(|m| m.id()), Some(monitor.id()));
+    assert!(test_utils::emitted_events(&mut event_rx).iter().any(
+      |event| matches!(
+        event,
+        WmEvent::MonitorUpdated { changed, .. }
+          if changed == &[MonitorField::Standby]
+      )
+    ));
+  }
+
+  #[test]
+  fn resume_shows_workspaces_as_they_were() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let window = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let other_monitor = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&other_monitor, "2");
+
+    standby_monitor(&monitor, &mut state).unwrap();
+    resume_monitor(&monitor, &mut state).unwrap();
+
+    assert!(!monitor.is_standby());
+    assert_eq!(
+      monitor.displayed_workspace().map(|w| w.id()),
+      Some(workspace.id())
+    );
+    assert_eq!(window.workspace().map(|w| w.id()), Some(workspace.id()));
+  }
+
+  #[test]
+  fn keeps_only_active_monitor_out_of_standby() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&monitor, "1");
+
+    assert!(standby_monitor(&monitor, &mut state).is_err());
+    assert!(!monitor.is_standby());
+  }
+
+  #[test]
+  fn commands_skip_monitor_in_standby() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&monitor, "1");
+    let standby = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&standby, "2");
+    let far_monitor = test_utils::monitor(&state, 3, 3840);
+    test_utils::workspace(&far_monitor, "3");
+
+    standby_monitor(&standby, &mut state).unwrap();
+
+    assert_eq!(
+      state.active_monitor_by_index(1).unwrap_err(),
+      CommandError::MonitorInStandby { index: 1 }
+    );
+    assert_eq!(
+      state
+        .monitor_in_direction(&monitor, &Direction::Right)
+        .unwrap()
+        .map(|monitor| monitor.id()),
+      Some(far_monitor.id())
+    );
+  }
+
+  #[test]
+  fn toggles_by_index() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&monitor, "1");
+    let other_monitor = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&other_monitor, "2");
+
+    toggle_monitor_standby(1, &mut state).unwrap();
+    assert!(other_monitor.is_standby());
+
+    toggle_monitor_standby(1, &mut state).unwrap();
+    assert!(!other_monitor.is_standby());
+
+    let err = toggle_monitor_standby(2, &mut state).unwrap_err();
+    assert_eq!(
+      err.downcast_ref::<CommandError>(),
+      Some(&CommandError::MonitorIndexOutOfRange {
+        requested: 2,
+        available: 2
+      })
+    );
+  }
+}
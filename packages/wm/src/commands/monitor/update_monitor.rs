@@ -1,22 +1,63 @@
 use tracing::info;
-use wm_common::WmEvent;
 use wm_platform::NativeMonitor;
 
-use crate::{models::Monitor, wm_state::WmState};
+use crate::models::Monitor;
 
-pub fn update_monitor(
-  monitor: &Monitor,
-  native_monitor: NativeMonitor,
-  state: &mut WmState,
-) -> anyhow::Result<()> {
+/// Updates the native monitor of a `Monitor` instance.
+///
+/// Doesn't emit `WmEvent::MonitorUpdated`, since the monitor's index can
+/// still change when monitors get re-sorted. Callers should diff against a
+/// `MonitorSnapshot` taken beforehand instead.
+pub fn update_monitor(monitor: &Monitor, native_monitor: NativeMonitor) {
+  let was_primary = monitor.is_primary();
   monitor.set_native(native_monitor);
 
   info!("Monitor updated: {monitor}");
 
-  // TODO: Check that a property on the monitor actually changed.
-  state.emit_event(WmEvent::MonitorUpdated {
-    updated_monitor: monitor.to_dto()?,
-  });
+  if was_primary != monitor.is_primary() {
+    info!(
+      "Primary monitor flag changed to {}: {monitor}",
+      monitor.is_primary()
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{MonitorField, Rect};
+
+  use super::*;
+  use crate::{commands::monitor::sort_monitors, test_utils};
+
+  #[test]
+  fn snapshot_diff_includes_index_after_resort() {
+    let (state, _event_rx) = test_utils::state();
+
+    let moved = test_utils::monitor(&state, 1, 0);
+    let other = test_utils::monitor(&state, 2, 1920);
+
+    let moved_snapshot = moved.snapshot().unwrap();
+    let other_snapshot = other.snapshot().unwrap();
+
+    // Move the first monitor to the right of the other one.
+    update_monitor(
+      &moved,
+      NativeMonitor::with_rect(1, Rect::from_xy(3840, 0, 1920, 1080)),
+    );
+    sort_monitors(&state.root_container).unwrap();
 
-  Ok(())
+    assert_eq!(
+      moved_snapshot.changed_fields(&moved.snapshot().unwrap()),
+      [
+        MonitorField::Rect,
+        MonitorField::WorkingArea,
+        MonitorField::Primary,
+        MonitorField::Index
+      ]
+    );
+    assert_eq!(
+      other_snapshot.changed_fields(&other.snapshot().unwrap()),
+      [MonitorField::Index]
+    );
+  }
 }
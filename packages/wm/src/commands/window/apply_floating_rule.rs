@@ -0,0 +1,61 @@
+use anyhow::Context;
+use wm_common::{Rect, WindowRuleConfig, WindowState};
+
+use crate::{
+  models::WindowContainer,
+  traits::{CommonGetters, PositionGetters, WindowGetters},
+  wm_state::WmState,
+};
+
+/// Sizes and positions a floating window based on the `float_size` and
+/// `float_position` of a window rule.
+///
+/// Both are relative to the workspace (i.e. excluding space reserved on
+/// the monitor), and pixel values are scaled with the monitor's DPI. If
+/// only a size is given, the window is resized around its center.
+///
+/// Does nothing if the window isn't floating, or if the rule has neither
+/// option set.
+pub fn apply_floating_rule(
+  window: &WindowContainer,
+  rule: &WindowRuleConfig,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if !matches!(window.state(), WindowState::Floating(_))
+    || (rule.float_size.is_none() && rule.float_position.is_none())
+  {
+    return Ok(());
+  }
+
+  let workspace = window.workspace().context("No workspace.")?;
+  let monitor = workspace.monitor().context("No monitor.")?;
+  let scale_factor = monitor.native().scale_factor()?;
+  let workspace_rect = workspace.to_rect()?;
+
+  let placement = window.floating_placement();
+
+  let placement = match &rule.float_size {
+    Some([width, height]) => Rect::from_xy(
+      0,
+      0,
+      width.to_px(workspace_rect.width(), Some(scale_factor)),
+      height.to_px(workspace_rect.height(), Some(scale_factor)),
+    )
+    .translate_to_center(&placement),
+    None => placement,
+  };
+
+  let placement = match &rule.float_position {
+    Some(position) => {
+      position.apply(&placement, &workspace_rect, scale_factor)
+    }
+    None => placement,
+  };
+
+  window.set_floating_placement(placement);
+  window.set_has_custom_floating_placement(true);
+
+  state.pending_sync.queue_container_to_redraw(window.clone());
+
+  Ok(())
+}
@@ -0,0 +1,181 @@
+use std::{
+  collections::{HashMap, HashSet},
+  time::Duration,
+};
+
+use tracing::info;
+use wm_common::{try_warn, WindowRuleEvent};
+use wm_platform::Platform;
+
+use crate::{
+  commands::window::{ancestor_pids, move_window_to_workspace},
+  models::{PendingWorkspaceAssignment, WindowContainer, WorkspaceTarget},
+  traits::WindowGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves a newly managed window to the workspace that its process has
+/// been assigned to.
+///
+/// Processes are assigned a workspace when launched via `shell-exec
+/// --workspace`, or when their first window matches a window rule with
+/// `initial_workspace`. Windows of child processes count towards the
+/// assignment of their launching process. Assignments expire after
+/// `window_behavior.workspace_assignment.timeout_ms`, but are kept while
+/// the process has managed windows, such that its later windows don't
+/// match the window rule again.
+pub fn assign_initial_workspace(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let timeout = Duration::from_millis(u64::from(
+    config.value.window_behavior.workspace_assignment.timeout_ms,
+  ));
+
+  let window_pids = state
+    .windows()
+    .iter()
+    .map(|window| window.native().process_id())
+    .collect::<HashSet<_>>();
+
+  retain_live_assignments(
+    &mut state.pending_workspace_assignments,
+    timeout,
+    &window_pids,
+  );
+
+  // Windows that are managed on startup are left where they are.
+  if !state.has_initialized() {
+    return Ok(());
+  }
+
+  let process_id = window.native().process_id();
+
+  let assigned_pid =
+    if let Some(assigned_pid) = assigned_process_id(process_id, state)? {
+      assigned_pid
+    } else {
+      let rule = config
+        .matching_window_rules(window, &WindowRuleEvent::Manage)?
+        .into_iter()
+        .find(|rule| rule.initial_workspace.is_some());
+
+      let Some(rule) = rule else {
+        return Ok(());
+      };
+
+      state.pending_workspace_assignments.insert(
+        process_id,
+        PendingWorkspaceAssignment::new(
+          rule.initial_workspace.unwrap_or_default(),
+          rule.initial_workspace_windows,
+        ),
+      );
+
+      process_id
+    };
+
+  let Some(workspace_name) = state
+    .pending_workspace_assignments
+    .get_mut(&assigned_pid)
+    .and_then(|assignment| assignment.take(timeout))
+  else {
+    return Ok(());
+  };
+
+  info!("Assigning window to workspace: '{workspace_name}'.");
+
+  try_warn!(move_window_to_workspace(
+    window,
+    &WorkspaceTarget::Name(workspace_name),
+    false,
+    state,
+    config,
+  ));
+
+  Ok(())
+}
+
+/// Removes assignments that have expired, unless their process still has
+/// managed windows.
+///
+/// An expired assignment stops moving windows, but is kept such that the
+/// process's later windows don't create a new assignment.
+fn retain_live_assignments(
+  assignments: &mut HashMap<u32, PendingWorkspaceAssignment>,
+  timeout: Duration,
+  window_pids: &HashSet<u32>,
+) {
+  assignments.retain(|pid, assignment| {
+    !assignment.is_expired(timeout) || window_pids.contains(pid)
+  });
+}
+
+/// Gets the ID of the process whose workspace assignment applies to the
+/// given process. This is either the process itself or its nearest
+/// ancestor with an assignment.
+fn assigned_process_id(
+  process_id: u32,
+  state: &WmState,
+) -> anyhow::Result<Option<u32>> {
+  let assignments = &state.pending_workspace_assignments;
+
+  if assignments.contains_key(&process_id) {
+    return Ok(Some(process_id));
+  }
+
+  // Avoid snapshotting processes if there's nothing to match.
+  if assignments.is_empty() {
+    return Ok(None);
+  }
+
+  let parent_pids = Platform::parent_process_ids()?;
+
+  Ok(
+    ancestor_pids(process_id, &parent_pids)
+      .into_iter()
+      .find(|pid| assignments.contains_key(pid)),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_expired_assignment_of_process_with_windows() {
+    let mut assignments = HashMap::from([
+      (1, PendingWorkspaceAssignment::new("1".to_string(), 2)),
+      (2, PendingWorkspaceAssignment::new("2".to_string(), 2)),
+    ]);
+
+    retain_live_assignments(
+      &mut assignments,
+      Duration::ZERO,
+      &HashSet::from([1]),
+    );
+
+    // The kept assignment no longer moves windows.
+    let assignment = assignments.get_mut(&1).unwrap();
+    assert_eq!(assignment.take(Duration::ZERO), None);
+    assert!(!assignments.contains_key(&2));
+  }
+
+  #[test]
+  fn keeps_assignment_within_timeout() {
+    let mut assignments = HashMap::from([(
+      1,
+      PendingWorkspaceAssignment::new("1".to_string(), 1),
+    )]);
+
+    retain_live_assignments(
+      &mut assignments,
+      Duration::from_secs(30),
+      &HashSet::new(),
+    );
+
+    assert!(assignments.contains_key(&1));
+  }
+}
@@ -0,0 +1,207 @@
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use tracing::{info, warn};
+use wm_common::WmEvent;
+
+use crate::{
+  models::{PendingClose, WindowContainer},
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Asks a window to close by sending it `WM_CLOSE`.
+///
+/// If the window was already asked to close and hasn't closed once
+/// `window_behavior.force_close` has timed out, closing it with `force`
+/// terminates its process instead. With `kill`, its process is terminated
+/// automatically once timed out.
+pub fn close_window(
+  window: &WindowContainer,
+  force: bool,
+  kill: bool,
+  force_all: bool,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let timeout = Duration::from_millis(u64::from(
+    config.value.window_behavior.force_close.timeout_ms,
+  ));
+
+  match close_action(
+    state.pending_closes.get(&window.id()),
+    force,
+    force_all,
+    timeout,
+  ) {
+    CloseAction::Wait => {
+      info!("Window is already closing: {window}");
+      return Ok(());
+    }
+    CloseAction::Terminate { force_all } => {
+      return force_close_window(window, force_all, state);
+    }
+    CloseAction::Request => {}
+  }
+
+  info!("Closing window: {window}");
+
+  // Window handle might no longer be valid here.
+  if let Err(err) = window.native().close() {
+    warn!("Failed to close window: {:?}", err);
+    return Ok(());
+  }
+
+  // The time of the first request is kept, such that asking the window
+  // to close again doesn't delay `--force` or `--kill`.
+  let pending_close = state
+    .pending_closes
+    .entry(window.id())
+    .or_insert_with(|| PendingClose {
+      requested_at: Instant::now(),
+      kill_on_timeout: false,
+      force_all: false,
+    });
+
+  pending_close.kill_on_timeout |= kill;
+  pending_close.force_all |= force_all;
+
+  state.emit_event(WmEvent::WindowCloseRequested {
+    closing_window: window.to_dto()?,
+    is_forced: false,
+  });
+
+  Ok(())
+}
+
+/// How a `close` command is handled for a window.
+#[derive(Debug, PartialEq)]
+enum CloseAction {
+  /// Ask the window to close.
+  Request,
+
+  /// Leave the window be, since it's still within the timeout of an
+  /// earlier request to close.
+  Wait,
+
+  /// Terminate the window's process.
+  Terminate { force_all: bool },
+}
+
+/// Gets how a `close` command is handled for a window, given any earlier
+/// request for it to close.
+///
+/// A window that is still open past the timeout might be waiting on the
+/// user (e.g. via a "save changes?" dialog), so its process is only
+/// terminated with `force`.
+fn close_action(
+  pending_close: Option<&PendingClose>,
+  force: bool,
+  force_all: bool,
+  timeout: Duration,
+) -> CloseAction {
+  match pending_close {
+    Some(pending_close) if !pending_close.has_timed_out(timeout) => {
+      CloseAction::Wait
+    }
+    Some(pending_close) if force => CloseAction::Terminate {
+      force_all: force_all || pending_close.force_all,
+    },
+    _ => CloseAction::Request,
+  }
+}
+
+/// Terminates the process of a window that didn't close when asked to.
+///
+/// Fails if the process owns other managed windows, unless `force_all`
+/// is set.
+pub fn force_close_window(
+  window: &WindowContainer,
+  force_all: bool,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let process_id = window.native().process_id();
+
+  let has_other_windows = state.windows().iter().any(|other| {
+    other.id() != window.id() && other.native().process_id() == process_id
+  });
+
+  if has_other_windows && !force_all {
+    bail!(
+      "Process of window {window} owns other managed windows. Use \
+       `close --force-all` to terminate it regardless."
+    );
+  }
+
+  info!("Terminating process of window: {window}");
+
+  // Terminating is only attempted once, since a process that can't be
+  // terminated won't be on retry either.
+  state.pending_closes.remove(&window.id());
+  window.native().terminate_process()?;
+
+  state.emit_event(WmEvent::WindowCloseRequested {
+    closing_window: window.to_dto()?,
+    is_forced: true,
+  });
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TIMEOUT: Duration = Duration::from_secs(5);
+
+  fn pending_close(age: Duration, force_all: bool) -> PendingClose {
+    PendingClose {
+      requested_at: Instant::now().checked_sub(age).unwrap(),
+      kill_on_timeout: false,
+      force_all,
+    }
+  }
+
+  #[test]
+  fn requests_close_first() {
+    assert_eq!(
+      close_action(None, true, false, TIMEOUT),
+      CloseAction::Request
+    );
+  }
+
+  #[test]
+  fn waits_within_timeout() {
+    let pending_close = pending_close(Duration::from_secs(1), false);
+
+    assert_eq!(
+      close_action(Some(&pending_close), true, false, TIMEOUT),
+      CloseAction::Wait
+    );
+  }
+
+  #[test]
+  fn only_escalates_with_force() {
+    let pending_close = pending_close(Duration::from_secs(10), false);
+
+    assert_eq!(
+      close_action(Some(&pending_close), false, false, TIMEOUT),
+      CloseAction::Request
+    );
+    assert_eq!(
+      close_action(Some(&pending_close), true, false, TIMEOUT),
+      CloseAction::Terminate { force_all: false }
+    );
+  }
+
+  #[test]
+  fn keeps_force_all_of_earlier_request() {
+    let pending_close = pending_close(Duration::from_secs(10), true);
+
+    assert_eq!(
+      close_action(Some(&pending_close), true, false, TIMEOUT),
+      CloseAction::Terminate { force_all: true }
+    );
+  }
+}
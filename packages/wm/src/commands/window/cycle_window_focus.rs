@@ -0,0 +1,151 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::{WindowCycleOrder, WindowState};
+
+use super::update_window_state;
+use crate::{
+  commands::container::set_focused_descendant,
+  models::WindowContainer,
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Cycles focus through the windows of the focused workspace, including
+/// floating windows, in the order set by `general.window_cycle_order`.
+/// Wraps around at either end.
+///
+/// Minimized windows are skipped unless `include_minimized` is set, in
+/// which case they're restored once focused. Windows that hid themselves
+/// are always skipped.
+pub fn cycle_window_focus(
+  forward: bool,
+  include_minimized: bool,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  let workspace = focused_container
+    .workspace()
+    .context("No focused workspace.")?;
+
+  let order = config.value.general.window_cycle_order;
+
+  let windows = match order {
+    WindowCycleOrder::Tree => workspace
+      .descendants()
+      .filter_map(|container| container.as_window_container().ok())
+      .collect::<Vec<_>>(),
+    WindowCycleOrder::Recent => workspace
+      .descendant_focus_order()
+      .filter_map(|container| container.as_window_container().ok())
+      .collect::<Vec<_>>(),
+  }
+  .into_iter()
+  .filter(|window| {
+    !state.self_hidden_windows.contains(&window.id())
+      && (include_minimized || window.state() != WindowState::Minimized)
+  })
+  .collect::<Vec<_>>();
+
+  let current_index = windows
+    .iter()
+    .position(|window| window.id() == focused_container.id());
+
+  let Some(window) =
+    cycle_index(windows.len(), current_index, forward, order)
+      .map(|index| windows[index].clone())
+  else {
+    return Ok(());
+  };
+
+  if current_index.is_some_and(|index| windows[index].id() == window.id())
+  {
+    return Ok(());
+  }
+
+  info!("Cycling focus to window: {window}");
+
+  let window = restore_if_minimized(window, state, config)?;
+
+  set_focused_descendant(&window.into(), None);
+  state.pending_sync.queue_focus_change().queue_cursor_jump();
+
+  Ok(())
+}
+
+/// Restores the window to its state from before it was minimized.
+fn restore_if_minimized(
+  window: WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<WindowContainer> {
+  if window.state() != WindowState::Minimized {
+    return Ok(window);
+  }
+
+  let target_state = window.toggled_state(WindowState::Minimized, config);
+  update_window_state(window, target_state, state, config)
+}
+
+/// Gets the index of the window to cycle to among `len` windows, where
+/// `current` is the index of the focused window, if it's among them.
+///
+/// In tree order, the adjacent window is picked. In recent order, where
+/// the focused window comes first, cycling forward picks the least
+/// recently focused window, such that repeated cycling visits every
+/// window in turn. Cycling backward picks the previously focused window.
+fn cycle_index(
+  len: usize,
+  current: Option<usize>,
+  forward: bool,
+  order: WindowCycleOrder,
+) -> Option<usize> {
+  if len == 0 {
+    return None;
+  }
+
+  let index = match (order, current) {
+    (WindowCycleOrder::Tree, Some(index)) if forward => (index + 1) % len,
+    (WindowCycleOrder::Tree, Some(index)) => (index + len - 1) % len,
+    (WindowCycleOrder::Tree, None) if forward => 0,
+    (WindowCycleOrder::Recent, Some(index)) if !forward => {
+      (index + 1) % len
+    }
+    (WindowCycleOrder::Recent, None) if !forward => 0,
+    _ => len - 1,
+  };
+
+  Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tree_order_wraps_at_ends() {
+    let order = WindowCycleOrder::Tree;
+
+    assert_eq!(cycle_index(3, Some(0), true, order), Some(1));
+    assert_eq!(cycle_index(3, Some(2), true, order), Some(0));
+    assert_eq!(cycle_index(3, Some(0), false, order), Some(2));
+
+    // Starts from either end if no window is focused.
+    assert_eq!(cycle_index(3, None, true, order), Some(0));
+    assert_eq!(cycle_index(3, None, false, order), Some(2));
+    assert_eq!(cycle_index(0, None, true, order), None);
+  }
+
+  #[test]
+  fn recent_order_visits_least_recent_window() {
+    let order = WindowCycleOrder::Recent;
+
+    assert_eq!(cycle_index(3, Some(0), true, order), Some(2));
+    assert_eq!(cycle_index(3, Some(0), false, order), Some(1));
+    assert_eq!(cycle_index(3, None, true, order), Some(2));
+    assert_eq!(cycle_index(3, None, false, order), Some(0));
+  }
+}
@@ -0,0 +1,126 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::{FloatingStateConfig, WindowMatchConfig, WindowState};
+
+use super::update_window_state;
+use crate::{
+  traits::{CommonGetters, PositionGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Floats every managed tiling window that matches the given match
+/// config, or tiles every matching floating window if `float` is false.
+///
+/// Newly floated windows are moved to where they last floated, or are
+/// otherwise centered on their workspace. Windows that are already in
+/// the target state (or are fullscreen or minimized) are left as is.
+/// Events of all state changes are emitted as a single coalesced batch.
+pub fn float_matching(
+  match_config: &WindowMatchConfig,
+  float: bool,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let windows = state
+    .windows()
+    .into_iter()
+    .filter(|window| match window.state() {
+      WindowState::Tiling => float,
+      WindowState::Floating(_) => !float,
+      _ => false,
+    })
+    .filter(|window| {
+      UserConfig::is_window_match(window, match_config).unwrap_or(false)
+    })
+    .collect::<Vec<_>>();
+
+  if windows.is_empty() {
+    return Ok(());
+  }
+
+  info!(
+    "{} {} matching windows.",
+    if float { "Floating" } else { "Tiling" },
+    windows.len()
+  );
+
+  let target_state = if float {
+    WindowState::Floating(FloatingStateConfig {
+      centered: true,
+      ..config.value.window_behavior.state_defaults.floating
+    })
+  } else {
+    WindowState::Tiling
+  };
+
+  state.with_event_batch(|state| {
+    for window in windows {
+      let window =
+        update_window_state(window, target_state.clone(), state, config)?;
+
+      // Windows without a remembered floating placement are centered.
+      if float && !window.has_custom_floating_placement() {
+        let workspace = window.workspace().context("No workspace.")?;
+
+        window.set_floating_placement(
+          window
+            .floating_placement()
+            .translate_to_center(&workspace.to_rect()?),
+        );
+      }
+    }
+
+    anyhow::Ok(())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{InvokeFloatMatchingCommand, Rect};
+
+  use super::*;
+  use crate::test_utils;
+
+  fn match_process(process: &str) -> WindowMatchConfig {
+    InvokeFloatMatchingCommand {
+      process: Some(process.to_string()),
+      class: None,
+      title: None,
+      tiling: false,
+    }
+    .match_config()
+  }
+
+  #[test]
+  fn floats_and_tiles_only_matching_windows() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let matching = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let other = test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    let match_config = match_process("process-1");
+    float_matching(&match_config, true, &mut state, &config).unwrap();
+
+    let floated = state.window_from_native(&matching.native()).unwrap();
+    assert!(matches!(floated.state(), WindowState::Floating(_)));
+
+    // Windows without a custom placement are centered on the workspace.
+    assert_eq!(
+      floated.floating_placement(),
+      Rect::from_xy(760, 390, 400, 300)
+    );
+
+    let unmatched = state.window_from_native(&other.native()).unwrap();
+    assert_eq!(unmatched.state(), WindowState::Tiling);
+
+    float_matching(&match_config, false, &mut state, &config).unwrap();
+
+    let tiled = state.window_from_native(&matching.native()).unwrap();
+    assert_eq!(tiled.state(), WindowState::Tiling);
+    assert_eq!(tiled.workspace().map(|w| w.id()), Some(workspace.id()));
+  }
+}
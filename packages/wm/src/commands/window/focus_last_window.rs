@@ -0,0 +1,89 @@
+use anyhow::Context;
+use tracing::info;
+
+use crate::{
+  commands::{
+    container::set_focused_descendant, workspace::focus_workspace,
+  },
+  models::WorkspaceTarget,
+  traits::CommonGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Focuses the `n`th most recently focused window, where 1 is the
+/// previously focused window. Repeatedly focusing the previous window
+/// therefore toggles between the two most recent windows.
+///
+/// The window's workspace is displayed if it isn't already. Does nothing
+/// if the focus history doesn't go back `n` windows.
+pub fn focus_last_window(
+  n: usize,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  let is_window = |id| {
+    state
+      .container_by_id(id)
+      .is_some_and(|container| container.as_window_container().is_ok())
+  };
+
+  let Some(window) = state
+    .focus_history
+    .nth_last(n, focused_container.id(), is_window)
+    .and_then(|id| state.container_by_id(id))
+    .and_then(|container| container.as_window_container().ok())
+  else {
+    return Ok(());
+  };
+
+  info!("Focusing last window: {window}");
+
+  let workspace = window.workspace().context("No workspace.")?;
+
+  if !workspace.is_displayed() {
+    focus_workspace(
+      WorkspaceTarget::Name(workspace.config().name),
+      state,
+      config,
+    )?;
+  }
+
+  set_focused_descendant(&window.into(), None);
+  state.pending_sync.queue_focus_change().queue_cursor_jump();
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{commands::window::ignore_window, test_utils};
+
+  #[test]
+  fn skips_ignored_window() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+    let third = test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    for window in [&first, &second, &third] {
+      set_focused_descendant(&window.clone().into(), None);
+      state.focus_history.push(window.id());
+    }
+
+    // The previously focused window is no longer managed, so the one
+    // focused before it is next in line.
+    ignore_window(second.into(), &mut state, &config).unwrap();
+    focus_last_window(1, &mut state, &config).unwrap();
+
+    assert_eq!(state.focused_container(), Some(first.into()));
+  }
+}
@@ -0,0 +1,106 @@
+use anyhow::Context;
+use tracing::info;
+
+use crate::{
+  commands::{
+    container::set_focused_descendant, workspace::focus_workspace,
+  },
+  models::{WindowContainer, WorkspaceTarget},
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Focuses the window that most recently started requesting attention.
+///
+/// The window's workspace is displayed if it isn't already. Does nothing
+/// if no window is urgent.
+pub fn focus_urgent_window(
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let Some(window) = most_recent_urgent_window(state.windows()) else {
+    return Ok(());
+  };
+
+  info!("Focusing urgent window: {window}");
+
+  let workspace = window.workspace().context("No workspace.")?;
+
+  if !workspace.is_displayed() {
+    focus_workspace(
+      WorkspaceTarget::Name(workspace.config().name),
+      state,
+      config,
+    )?;
+  }
+
+  set_focused_descendant(&window.into(), None);
+  state.pending_sync.queue_focus_change().queue_cursor_jump();
+
+  Ok(())
+}
+
+fn most_recent_urgent_window(
+  windows: Vec<WindowContainer>,
+) -> Option<WindowContainer> {
+  windows
+    .into_iter()
+    .filter(WindowGetters::is_urgent)
+    .max_by_key(WindowGetters::urgent_since)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, Instant};
+
+  use wm_common::{GapsConfig, LengthValue, Rect, RectDelta};
+  use wm_platform::NativeWindow;
+
+  use super::*;
+  use crate::models::TilingWindow;
+
+  fn window(handle: isize) -> WindowContainer {
+    let no_delta = LengthValue::from_px(0);
+
+    TilingWindow::new(
+      None,
+      NativeWindow::new(handle),
+      None,
+      RectDelta::new(
+        no_delta.clone(),
+        no_delta.clone(),
+        no_delta.clone(),
+        no_delta,
+      ),
+      Rect::from_xy(0, 0, 400, 300),
+      false,
+      GapsConfig::default(),
+      Vec::new(),
+      None,
+    )
+    .into()
+  }
+
+  #[test]
+  fn picks_most_recently_urgent_window() {
+    let now = Instant::now();
+    let windows = [window(1), window(2), window(3)];
+
+    windows[0].set_urgent_since(Some(now));
+    windows[1].set_urgent_since(Some(now + Duration::from_secs(1)));
+
+    let found = most_recent_urgent_window(windows.to_vec());
+    assert_eq!(found.map(|window| window.id()), Some(windows[1].id()));
+
+    windows[1].set_urgent_since(None);
+
+    let found = most_recent_urgent_window(windows.to_vec());
+    assert_eq!(found.map(|window| window.id()), Some(windows[0].id()));
+  }
+
+  #[test]
+  fn no_urgent_window() {
+    assert!(most_recent_urgent_window(vec![window(1)]).is_none());
+  }
+}
@@ -1,23 +1,94 @@
 use anyhow::Context;
-use wm_common::WindowState;
+use tracing::info;
+use wm_common::{CornerStyle, OpacityValue, WindowState};
+use wm_platform::ZOrder;
 
 use crate::{
   commands::container::{
     detach_container, flatten_child_split_containers,
   },
   models::WindowContainer,
+  runtime_rules::IgnoredWindow,
   traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
   wm_state::WmState,
 };
 
+/// Stops managing a window on request via the `ignore-window` command.
+///
+/// Unlike with the `ignore` command, the window is restored to its
+/// position and style from before it was managed, and isn't managed
+/// again when shown. If `persist` is set, windows with the same class and
+/// executable are also ignored after restarts.
+#[allow(clippy::needless_pass_by_value)]
+pub fn ignore_window_on_request(
+  window: WindowContainer,
+  persist: bool,
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  if persist {
+    config
+      .add_ignored_window(IgnoredWindow::from_native(&window.native())?)?;
+  }
+
+  info!("Ignoring window: {window}");
+
+  let native_window = window.native().clone();
+  let initial_position = state.initial_frame_positions.get(&window.id());
+
+  if let Some(initial_position) = initial_position {
+    _ = native_window.restore_to_position(initial_position);
+  }
+
+  ignore_window(window, state, config)?;
+  state.runtime_ignored_windows.push(native_window.clone());
+
+  // Undo any window effects that were applied while managed.
+  native_window.cleanup();
+
+  let effects = &config.value.window_effects;
+
+  if effects.focused_window.hide_title_bar.enabled
+    || effects.other_windows.hide_title_bar.enabled
+  {
+    _ = native_window.set_title_bar_visibility(true);
+  }
+
+  if effects.focused_window.corner_style.enabled
+    || effects.other_windows.corner_style.enabled
+  {
+    _ = native_window.set_corner_style(&CornerStyle::Default);
+  }
+
+  Ok(())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn ignore_window(
   window: WindowContainer,
   state: &mut WmState,
+  config: &UserConfig,
 ) -> anyhow::Result<()> {
+  // Don't leave the window translucent once it's no longer managed.
+  if config.value.window_effects.opacity.enabled {
+    _ = window
+      .native()
+      .set_transparency(&OpacityValue::from_alpha(u8::MAX));
+  }
+
+  // Don't leave the window above all other windows either.
+  if window.is_shown_on_top() {
+    _ = window.native().set_z_order(&ZOrder::Normal);
+  }
+
   // Create iterator of parent, grandparent, and great-grandparent.
   let ancestors = window.ancestors().take(3).collect::<Vec<_>>();
 
+  state.initial_frame_positions.remove(&window.id());
+  state.self_hidden_windows.remove(&window.id());
+  state.border_overlays.remove(&window.id());
+  state.focus_history.remove(window.id());
   state.ignored_windows.push(window.native().clone());
   detach_container(window.clone().into())?;
 
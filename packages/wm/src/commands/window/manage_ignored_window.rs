@@ -0,0 +1,58 @@
+use anyhow::bail;
+use tracing::info;
+use wm_platform::{NativeWindow, Platform};
+
+use super::manage_window;
+use crate::{
+  runtime_rules::IgnoredWindow, user_config::UserConfig, wm_state::WmState,
+};
+
+/// Starts managing a window that was previously ignored, e.g. via the
+/// `ignore-window` command.
+///
+/// Targets the window with the given handle, or otherwise the window
+/// under the cursor. Any saved rule that ignores windows with the same
+/// class and executable is removed.
+pub fn manage_ignored_window(
+  window_handle: Option<isize>,
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  let native_window = match window_handle {
+    Some(handle) => NativeWindow::new(handle),
+    None => Platform::root_ancestor(&Platform::window_from_point(
+      &Platform::mouse_position()?,
+    )?)?,
+  };
+
+  if state.window_from_native(&native_window).is_some() {
+    bail!("Window is already managed.");
+  }
+
+  if !native_window.is_manageable()? {
+    bail!("Window can't be managed.");
+  }
+
+  state
+    .ignored_windows
+    .retain(|ignored_window| *ignored_window != native_window);
+
+  state
+    .runtime_ignored_windows
+    .retain(|ignored_window| *ignored_window != native_window);
+
+  if let Ok(ignored_window) = IgnoredWindow::from_native(&native_window) {
+    if config.remove_ignored_window(&ignored_window)? {
+      info!("Removed saved rule ignoring window: {ignored_window:?}");
+    }
+  }
+
+  info!("Managing ignored window: {}", native_window.handle);
+  manage_window(native_window.clone(), None, state, config)?;
+
+  if state.window_from_native(&native_window).is_none() {
+    bail!("Window is ignored by a window rule in the config.");
+  }
+
+  Ok(())
+}
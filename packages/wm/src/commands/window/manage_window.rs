@@ -1,14 +1,17 @@
 use anyhow::Context;
-use tracing::info;
+use tracing::{info, warn};
 use wm_common::{
-  try_warn, LengthValue, RectDelta, WindowRuleEvent, WindowState, WmEvent,
+  try_warn, LengthValue, RectDelta, SizeConstraints, WindowRuleEvent,
+  WindowState, WmEvent,
 };
 use wm_platform::NativeWindow;
 
 use crate::{
   commands::{
-    container::{attach_container, set_focused_descendant},
-    window::run_window_rules,
+    container::{
+      attach_container, set_focused_descendant, take_insertion_hint,
+    },
+    window::{assign_initial_workspace, run_window_rules, swallow_window},
   },
   models::{
     Container, Monitor, NonTilingWindow, TilingWindow, WindowContainer,
@@ -29,10 +32,24 @@ pub fn manage_window(
   let window =
     try_warn!(create_window(native_window, target_parent, state, config));
 
+  // Remember where the window was, in case it's later ignored.
+  if let Ok(frame_position) = window.native().frame_position() {
+    state
+      .initial_frame_positions
+      .insert(window.id(), frame_position);
+  }
+
   // Set the newly added window as focus descendant. This means the window
   // rules will be run as if the window is focused.
   set_focused_descendant(&window.clone().into(), None);
 
+  // Move the window if its process was launched for another workspace.
+  // Focus is kept on the displayed workspace. The window is still managed
+  // if this fails.
+  if let Err(err) = assign_initial_workspace(&window, state, config) {
+    warn!("Failed to assign initial workspace: {:?}", err);
+  }
+
   // Window might be detached if `ignore` command has been invoked.
   let updated_window = run_window_rules(
     window.clone(),
@@ -44,6 +61,16 @@ pub fn manage_window(
   if let Some(window) = updated_window {
     info!("New window managed: {window}");
 
+    // Track the title change rules that already match, such that they
+    // only run on title change once the window starts matching them.
+    state.title_rule_matches.insert(
+      window.id(),
+      config
+        .matching_window_rules(&window, &WindowRuleEvent::TitleChange)?,
+    );
+
+    swallow_window(&window, state, config)?;
+
     state.emit_event(WmEvent::WindowManaged {
       managed_window: window.to_dto()?,
     });
@@ -80,8 +107,15 @@ fn create_window(
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<WindowContainer> {
-  let nearest_monitor = state
-    .nearest_monitor(&native_window)
+  // Windows that aren't on any monitor go to the primary monitor, rather
+  // than to whichever monitor the OS deems nearest.
+  let is_unassigned =
+    state.is_unassigned_rect(&native_window.frame_position()?);
+
+  let nearest_monitor = is_unassigned
+    .then(|| state.primary_monitor())
+    .flatten()
+    .or_else(|| state.window_monitor(&native_window))
     .context("No nearest monitor.")?;
 
   let nearest_workspace = nearest_monitor
@@ -96,7 +130,7 @@ fn create_window(
   // provided), otherwise, add as a sibling of the focused container.
   let (target_parent, target_index) = match target_parent {
     Some(parent) => (parent, 0),
-    None => insertion_target(&window_state, state)?,
+    None => insertion_target(&window_state, state, config)?,
   };
 
   let target_workspace =
@@ -111,16 +145,18 @@ fn create_window(
 
   // Calculate where window should be placed when floating is enabled. Use
   // the original width/height of the window and optionally position it in
-  // the center of the workspace.
+  // the center of the workspace. Windows that aren't on any monitor are
+  // always centered, since their position would keep them off-screen.
   let is_same_workspace = nearest_workspace.id() == target_workspace.id();
   let floating_placement = {
-    let placement = if !is_same_workspace || prefers_centered {
-      native_window
-        .frame_position()?
-        .translate_to_center(&target_workspace.to_rect()?)
-    } else {
-      native_window.frame_position()?
-    };
+    let placement =
+      if !is_same_workspace || prefers_centered || is_unassigned {
+        native_window
+          .frame_position()?
+          .translate_to_center(&target_workspace.to_rect()?)
+      } else {
+        native_window.frame_position()?
+      };
 
     // Clamp the window size to 90% of the workspace size.
     #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
@@ -130,6 +166,14 @@ fn create_window(
     )
   };
 
+  // Size constraints are only read if they're respected.
+  let size_constraints = if config.value.window_behavior.size_hints.enabled
+  {
+    native_window.size_constraints().unwrap_or_default()
+  } else {
+    SizeConstraints::default()
+  };
+
   // Window has no border delta unless it's later changed via the
   // `adjust_borders` command.
   let border_delta = RectDelta::new(
@@ -167,6 +211,8 @@ fn create_window(
     .into(),
   };
 
+  window_container.set_size_constraints(size_constraints);
+
   attach_container(
     &window_container.clone().into(),
     &target_parent,
@@ -175,11 +221,9 @@ fn create_window(
 
   // The OS might spawn the window on a different monitor to the target
   // parent, so adjustments might need to be made because of DPI.
-  if nearest_monitor
-    .has_dpi_difference(&window_container.clone().into())?
-  {
-    window_container.set_has_pending_dpi_adjustment(true);
-  }
+  window_container.queue_dpi_adjustment(
+    nearest_monitor.dpi_adjustment_to(&window_container.clone().into())?,
+  );
 
   Ok(window_container)
 }
@@ -204,7 +248,7 @@ fn window_state_to_create(
     .outer_gaps_for_workspace(&nearest_workspace)
     .is_significant()
   {
-    nearest_monitor.native().working_rect()?.clone()
+    nearest_monitor.working_rect()?
   } else {
     nearest_monitor.to_rect()?
   };
@@ -235,15 +279,18 @@ fn window_state_to_create(
 /// Rules:
 /// - For non-tiling windows: Always append to the workspace.
 /// - For tiling windows:
-///   1. Try to insert after the focused tiling window if one exists.
-///   2. If a non-tiling window is focused, try to insert after the first
+///   1. Insert where the workspace's insertion hint points to if one is
+///      set via the `insert` command. The hint is cleared afterwards.
+///   2. Try to insert after the focused tiling window if one exists.
+///   3. If a non-tiling window is focused, try to insert after the first
 ///      tiling window found.
-///   3. If no tiling windows exist, append to the workspace.
+///   4. If no tiling windows exist, append to the workspace.
 ///
 /// Returns tuple of (parent container, insertion index).
 fn insertion_target(
   window_state: &WindowState,
-  state: &WmState,
+  state: &mut WmState,
+  config: &UserConfig,
 ) -> anyhow::Result<(Container, usize)> {
   let focused_container =
     state.focused_container().context("No focused container.")?;
@@ -254,6 +301,12 @@ fn insertion_target(
   // For tiling windows, try to find a suitable tiling window to insert
   // next to.
   if *window_state == WindowState::Tiling {
+    if let Some(target) =
+      take_insertion_hint(&focused_workspace, state, config)?
+    {
+      return Ok(target);
+    }
+
     let sibling = match focused_container {
       Container::TilingWindow(_) => Some(focused_container),
       _ => focused_workspace
@@ -1,21 +1,57 @@
+mod apply_floating_rule;
+mod assign_initial_workspace;
+mod close_window;
+mod cycle_window_focus;
+mod float_matching;
+mod focus_last_window;
+mod focus_urgent_window;
 mod ignore_window;
+mod manage_ignored_window;
 mod manage_window;
 mod move_window_in_direction;
+mod move_window_to_monitor;
+mod move_window_to_next_slot;
 mod move_window_to_workspace;
+mod promote_to_master;
 mod resize_window;
 mod run_window_rules;
 mod set_window_position;
 mod set_window_size;
+mod snap_floating_placement;
+mod swallow_window;
+mod swap_window;
+mod sync_size_constraints;
+mod toggle_always_on_top;
+mod toggle_fullscreen;
+mod toggle_monocle;
 mod unmanage_window;
 mod update_window_state;
 
+pub use apply_floating_rule::*;
+pub use assign_initial_workspace::*;
+pub use close_window::*;
+pub use cycle_window_focus::*;
+pub use float_matching::*;
+pub use focus_last_window::*;
+pub use focus_urgent_window::*;
 pub use ignore_window::*;
+pub use manage_ignored_window::*;
 pub use manage_window::*;
 pub use move_window_in_direction::*;
+pub use move_window_to_monitor::*;
+pub use move_window_to_next_slot::*;
 pub use move_window_to_workspace::*;
+pub use promote_to_master::*;
 pub use resize_window::*;
 pub use run_window_rules::*;
 pub use set_window_position::*;
 pub use set_window_size::*;
+pub use snap_floating_placement::*;
+pub use swallow_window::*;
+pub use swap_window::*;
+pub use sync_size_constraints::*;
+pub use toggle_always_on_top::*;
+pub use toggle_fullscreen::*;
+pub use toggle_monocle::*;
 pub use unmanage_window::*;
 pub use update_window_state::*;
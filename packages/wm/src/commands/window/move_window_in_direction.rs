@@ -1,6 +1,7 @@
 use anyhow::Context;
 use wm_common::{Direction, Rect, TilingDirection, WindowState};
 
+use super::snap_floating_placement;
 use crate::{
   commands::container::{
     flatten_child_split_containers, flatten_split_container,
@@ -21,24 +22,32 @@ use crate::{
 /// The distance in pixels to snap the window to the monitor's edge.
 const SNAP_DISTANCE: i32 = 15;
 
+/// Moves a window in the given direction.
+///
+/// Tiling and fullscreen windows are moved to the adjacent monitor once
+/// there's nothing further in that direction. Focus then follows the
+/// window to that monitor if `follow` is set, and otherwise stays on the
+/// monitor that the window was moved from.
 pub fn move_window_in_direction(
   window: WindowContainer,
   direction: &Direction,
+  follow: bool,
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
   match window {
     WindowContainer::TilingWindow(window) => {
-      move_tiling_window(window, direction, state, config)
+      move_tiling_window(window, direction, follow, state, config)
     }
     WindowContainer::NonTilingWindow(non_tiling_window) => {
       match non_tiling_window.state() {
         WindowState::Floating(_) => {
-          move_floating_window(non_tiling_window, direction, state)
+          move_floating_window(non_tiling_window, direction, state, config)
         }
         WindowState::Fullscreen(_) => move_to_workspace_in_direction(
           &non_tiling_window.into(),
           direction,
+          follow,
           state,
         ),
         _ => Ok(()),
@@ -50,6 +59,7 @@ pub fn move_window_in_direction(
 fn move_tiling_window(
   window_to_move: TilingWindow,
   direction: &Direction,
+  follow: bool,
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
@@ -92,6 +102,7 @@ fn move_tiling_window(
     return move_to_workspace_in_direction(
       &window_to_move.into(),
       direction,
+      follow,
       state,
     );
   }
@@ -208,6 +219,7 @@ fn move_to_sibling_container(
 fn move_to_workspace_in_direction(
   window_to_move: &WindowContainer,
   direction: &Direction,
+  follow: bool,
   state: &mut WmState,
 ) -> anyhow::Result<()> {
   let parent = window_to_move.parent().context("No parent.")?;
@@ -221,9 +233,9 @@ fn move_to_workspace_in_direction(
   if let Some(target_workspace) = target_workspace {
     // Since the window is crossing monitors, adjustments might need to be
     // made because of DPI.
-    if monitor.has_dpi_difference(&target_workspace.clone().into())? {
-      window_to_move.set_has_pending_dpi_adjustment(true);
-    }
+    window_to_move.queue_dpi_adjustment(
+      monitor.dpi_adjustment_to(&target_workspace.clone().into())?,
+    );
 
     // Update floating placement since the window has to cross monitors.
     window_to_move.set_floating_placement(
@@ -257,10 +269,19 @@ fn move_to_workspace_in_direction(
     )?;
 
     if let Some(focus_target) = focus_target {
-      set_focused_descendant(
-        &focus_target,
-        Some(&workspace.clone().into()),
-      );
+      if follow {
+        // The moved window stays focused, so only reassign focus within
+        // the original workspace.
+        set_focused_descendant(
+          &focus_target,
+          Some(&workspace.clone().into()),
+        );
+
+        state.pending_sync.queue_cursor_jump();
+      } else {
+        set_focused_descendant(&focus_target, None);
+        state.pending_sync.queue_focus_change();
+      }
     }
 
     state
@@ -268,7 +289,6 @@ fn move_to_workspace_in_direction(
       .queue_container_to_redraw(window_to_move.clone())
       .queue_containers_to_redraw(target_workspace.tiling_children())
       .queue_containers_to_redraw(parent.tiling_children())
-      .queue_cursor_jump()
       .queue_workspace_to_reorder(target_workspace);
   }
 
@@ -381,6 +401,7 @@ fn move_floating_window(
   window_to_move: NonTilingWindow,
   direction: &Direction,
   state: &mut WmState,
+  config: &UserConfig,
 ) -> anyhow::Result<()> {
   let new_position =
     new_floating_position(&window_to_move, direction, state)?;
@@ -392,11 +413,22 @@ fn move_floating_window(
     // handler for `PlatformEvent::LocationChanged` will update the
     // window's workspace if it goes out of bounds of its current
     // workspace.
-    if monitor.id() != target_monitor.id()
-      && monitor.has_dpi_difference(&target_monitor.into())?
-    {
-      window_to_move.set_has_pending_dpi_adjustment(true);
-    }
+    let position_rect = if monitor.id() == target_monitor.id() {
+      // Snap to nearby edges further along the direction of the move.
+      snap_floating_placement(
+        &window_to_move.clone().into(),
+        &position_rect,
+        &window_to_move.workspace().context("No workspace.")?,
+        Some(direction),
+        config,
+      )?
+    } else {
+      window_to_move.queue_dpi_adjustment(
+        monitor.dpi_adjustment_to(&target_monitor.into())?,
+      );
+
+      position_rect
+    };
 
     window_to_move.set_floating_placement(position_rect);
     state.pending_sync.queue_container_to_redraw(window_to_move);
@@ -412,7 +444,7 @@ fn new_floating_position(
   state: &mut WmState,
 ) -> anyhow::Result<Option<(Rect, Monitor)>> {
   let monitor = window_to_move.monitor().context("No monitor.")?;
-  let monitor_rect = monitor.native().working_rect()?.clone();
+  let monitor_rect = monitor.working_rect()?;
   let window_pos = window_to_move.native().frame_position()?;
 
   let is_on_monitor_edge = match direction {
@@ -428,7 +460,7 @@ fn new_floating_position(
     let next_monitor = state.monitor_in_direction(&monitor, direction)?;
 
     if let Some(next_monitor) = next_monitor {
-      let monitor_rect = next_monitor.native().working_rect()?.clone();
+      let monitor_rect = next_monitor.working_rect()?;
 
       let position = snap_to_monitor_edge(
         &window_pos,
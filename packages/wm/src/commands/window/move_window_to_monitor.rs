@@ -0,0 +1,195 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::{Rect, WindowState};
+
+use crate::{
+  commands::container::{
+    move_container_within_tree, set_focused_descendant,
+  },
+  models::{Monitor, WindowContainer},
+  traits::{CommonGetters, PositionGetters, WindowGetters},
+  wm_state::WmState,
+};
+
+/// Moves a window to the displayed workspace of the target monitor.
+///
+/// The window keeps its state, and floating placements keep their
+/// relative position within the workspace. If the target workspace has a
+/// fullscreen window, the moved window is placed behind it.
+pub fn move_window_to_monitor(
+  window: WindowContainer,
+  target_monitor: &Monitor,
+  should_follow: bool,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let current_workspace = window.workspace().context("No workspace.")?;
+  let current_monitor =
+    current_workspace.monitor().context("No monitor.")?;
+
+  if current_monitor.id() == target_monitor.id() {
+    return Ok(());
+  }
+
+  let target_workspace = target_monitor
+    .displayed_workspace()
+    .context("No displayed workspace.")?;
+
+  info!(
+    "Moving window to monitor with workspace: '{}'.",
+    target_workspace.config().name
+  );
+
+  // Since the window is crossing monitors, adjustments might need to be
+  // made because of DPI.
+  window.queue_dpi_adjustment(
+    current_monitor.dpi_adjustment_to(&target_monitor.clone().into())?,
+  );
+
+  window.set_floating_placement(translate_proportionally(
+    &window.floating_placement(),
+    &current_workspace.to_rect()?,
+    &target_workspace.to_rect()?,
+  ));
+
+  if let WindowContainer::NonTilingWindow(window) = &window {
+    window.set_insertion_target(None);
+  }
+
+  let fullscreen_window = target_workspace
+    .descendant_focus_order()
+    .filter_map(|descendant| descendant.as_window_container().ok())
+    .find(|descendant| {
+      matches!(descendant.state(), WindowState::Fullscreen(_))
+    });
+
+  // Focus target is `None` if the window is not focused.
+  let focus_target = state.focus_target_after_removal(&window);
+
+  let insertion_sibling = target_workspace
+    .descendant_focus_order()
+    .filter_map(|descendant| descendant.as_window_container().ok())
+    .find(|descendant| descendant.state() == WindowState::Tiling);
+
+  // Insert tiling windows beside the most recently focused tiling window,
+  // and other windows at the end of the workspace.
+  match (&window, insertion_sibling) {
+    (WindowContainer::TilingWindow(_), Some(insertion_sibling)) => {
+      move_container_within_tree(
+        &window.clone().into(),
+        &insertion_sibling.parent().context("No parent.")?,
+        insertion_sibling.index() + 1,
+        state,
+      )?;
+    }
+    _ => {
+      move_container_within_tree(
+        &window.clone().into(),
+        &target_workspace.clone().into(),
+        target_workspace.child_count(),
+        state,
+      )?;
+    }
+  }
+
+  // Retain focus within the workspace from where the window was moved.
+  if let Some(focus_target) = &focus_target {
+    set_focused_descendant(
+      focus_target,
+      Some(&current_workspace.clone().into()),
+    );
+  }
+
+  // Windows are stacked by focus order, so keep the fullscreen window
+  // ahead of the moved window on the target workspace.
+  if let Some(fullscreen_window) = &fullscreen_window {
+    set_focused_descendant(
+      &fullscreen_window.clone().into(),
+      Some(&target_workspace.clone().into()),
+    );
+  }
+
+  if focus_target.is_some() {
+    if should_follow {
+      // Follow to the target monitor, but without raising the moved
+      // window above a fullscreen window.
+      let follow_target = fullscreen_window
+        .map_or_else(|| window.clone().into(), Into::into);
+
+      set_focused_descendant(&follow_target, None);
+      state.pending_sync.queue_cursor_jump();
+    }
+
+    state.pending_sync.queue_focus_change();
+  }
+
+  state
+    .pending_sync
+    .queue_container_to_redraw(window)
+    .queue_containers_to_redraw(current_workspace.tiling_children())
+    .queue_containers_to_redraw(target_workspace.tiling_children())
+    .queue_workspace_to_reorder(current_workspace)
+    .queue_workspace_to_reorder(target_workspace);
+
+  Ok(())
+}
+
+/// Translates a placement between two rects, such that its center keeps
+/// the same relative position. The result is kept within `to_rect` where
+/// possible.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn translate_proportionally(
+  placement: &Rect,
+  from_rect: &Rect,
+  to_rect: &Rect,
+) -> Rect {
+  let center = placement.center_point();
+
+  let ratio_x =
+    (center.x - from_rect.left) as f32 / from_rect.width().max(1) as f32;
+  let ratio_y =
+    (center.y - from_rect.top) as f32 / from_rect.height().max(1) as f32;
+
+  let width = placement.width().min(to_rect.width());
+  let height = placement.height().min(to_rect.height());
+
+  let center_x = to_rect.left + (ratio_x * to_rect.width() as f32) as i32;
+  let center_y = to_rect.top + (ratio_y * to_rect.height() as f32) as i32;
+
+  let x = (center_x - width / 2)
+    .min(to_rect.right - width)
+    .max(to_rect.left);
+  let y = (center_y - height / 2)
+    .min(to_rect.bottom - height)
+    .max(to_rect.top);
+
+  Rect::from_xy(x, y, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_relative_position() {
+    let placement = Rect::from_xy(200, 100, 400, 300);
+    let from_rect = Rect::from_xy(0, 0, 1600, 900);
+    let to_rect = Rect::from_xy(1600, 0, 3200, 1800);
+
+    assert_eq!(
+      translate_proportionally(&placement, &from_rect, &to_rect),
+      Rect::from_xy(2200, 350, 400, 300),
+    );
+  }
+
+  #[test]
+  fn keeps_placement_within_smaller_target() {
+    let placement = Rect::from_xy(1400, 700, 600, 400);
+    let from_rect = Rect::from_xy(0, 0, 2560, 1440);
+    let to_rect = Rect::from_xy(-800, 0, 800, 600);
+
+    assert_eq!(
+      translate_proportionally(&placement, &from_rect, &to_rect),
+      Rect::from_xy(-600, 175, 600, 400),
+    );
+  }
+}
@@ -0,0 +1,232 @@
+use anyhow::Context;
+use tracing::info;
+
+use crate::{
+  commands::container::{
+    clear_insertion_hint, move_container_within_tree, take_insertion_hint,
+  },
+  models::{Container, WindowContainer},
+  traits::CommonGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves a tiling window to the next slot in its workspace.
+///
+/// If an insertion hint is set on the workspace, the window is moved to
+/// the hinted slot, which splits the hinted container if needed.
+/// Otherwise, the window is moved to after the next tiling window in tree
+/// order, wrapping around to before the first tiling window.
+///
+/// Does nothing if the window isn't a tiling window.
+pub fn move_window_to_next_slot(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  if !window.is_tiling_window() {
+    return Ok(());
+  }
+
+  let workspace = window.workspace().context("No workspace.")?;
+  let container: Container = window.clone().into();
+
+  // A hint on the window itself can't be used as a slot for the window.
+  if workspace
+    .insertion_hint()
+    .is_some_and(|hint| hint.container_id == window.id())
+  {
+    clear_insertion_hint(&workspace, state)?;
+  }
+
+  let target = match take_insertion_hint(&workspace, state, config)? {
+    Some(target) => Some(target),
+    None => next_slot(&container, &workspace.clone().into())?,
+  };
+
+  let Some((target_parent, target_index)) = target else {
+    return Ok(());
+  };
+
+  info!("Moving window to next slot: {window}");
+
+  let original_parent = window.parent().context("No parent.")?;
+
+  move_container_within_tree(
+    &container,
+    &target_parent,
+    target_index,
+    state,
+  )?;
+
+  // The original parent might've been flattened after the move, in which
+  // case its former children are redrawn via the window's new parent.
+  state
+    .pending_sync
+    .queue_container_to_redraw(container.clone())
+    .queue_containers_to_redraw(original_parent.tiling_children())
+    .queue_containers_to_redraw(
+      container.parent().context("No parent.")?.tiling_children(),
+    );
+
+  Ok(())
+}
+
+/// Gets the slot after the next tiling window in tree order, or the slot
+/// before the first tiling window if the container is the last one.
+///
+/// Returns tuple of (parent container, insertion index), or `None` if
+/// there are no other tiling windows.
+fn next_slot(
+  container: &Container,
+  workspace: &Container,
+) -> anyhow::Result<Option<(Container, usize)>> {
+  let mut tiling_windows = Vec::new();
+  collect_tiling_windows(workspace, &mut tiling_windows);
+
+  let index = tiling_windows
+    .iter()
+    .position(|window| window == container)
+    .context("Window is not in workspace.")?;
+
+  let Some(next_window) = tiling_windows.get(index + 1) else {
+    // Wrap around to before the first tiling window.
+    return Ok(
+      tiling_windows
+        .first()
+        .filter(|first| *first != container)
+        .and_then(|first| Some((first.parent()?, first.index()))),
+    );
+  };
+
+  let parent = next_window.parent().context("No parent.")?;
+
+  // Siblings shift down by one when the container is removed from the
+  // same parent, so the next window's index is already the slot after it.
+  let target_index = if container.parent().as_ref() == Some(&parent) {
+    next_window.index()
+  } else {
+    next_window.index() + 1
+  };
+
+  Ok(Some((parent, target_index)))
+}
+
+/// Collects the tiling windows under the container in tree order (i.e.
+/// depth-first, from the first child to the last).
+fn collect_tiling_windows(
+  container: &Container,
+  windows: &mut Vec<Container>,
+) {
+  for child in container.children() {
+    if child.is_tiling_window() {
+      windows.push(child);
+    } else {
+      collect_tiling_windows(&child, windows);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{GapsConfig, TilingDirection};
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container,
+    models::{SplitContainer, Workspace},
+    test_utils,
+    traits::WindowGetters,
+  };
+
+  fn split(parent: &Container) -> SplitContainer {
+    let split = SplitContainer::new(
+      TilingDirection::Vertical,
+      GapsConfig::default(),
+    );
+
+    attach_container(&split.clone().into(), parent, None).unwrap();
+    split
+  }
+
+  /// Gets the handles of the workspace's tiling windows in tree order.
+  fn window_order(workspace: &Workspace) -> Vec<isize> {
+    let mut windows = Vec::new();
+    collect_tiling_windows(&workspace.clone().into(), &mut windows);
+
+    windows
+      .iter()
+      .map(|window| window.as_window_container().unwrap().native().handle)
+      .collect()
+  }
+
+  #[test]
+  fn moves_after_next_sibling() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    test_utils::tiling_window(&workspace.clone().into(), 2);
+    test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    move_window_to_next_slot(&first.clone().into(), &mut state, &config)
+      .unwrap();
+
+    assert_eq!(window_order(&workspace), [2, 1, 3]);
+    assert_eq!(first.parent(), Some(workspace.into()));
+  }
+
+  #[test]
+  fn moves_into_nested_split() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let split = split(&workspace.clone().into());
+    test_utils::tiling_window(&split.clone().into(), 2);
+    test_utils::tiling_window(&split.clone().into(), 3);
+    test_utils::tiling_window(&workspace.clone().into(), 4);
+
+    move_window_to_next_slot(&first.clone().into(), &mut state, &config)
+      .unwrap();
+
+    assert_eq!(window_order(&workspace), [2, 1, 3, 4]);
+    assert_eq!(first.parent(), Some(split.into()));
+  }
+
+  #[test]
+  fn wraps_around_to_first_slot() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+    test_utils::tiling_window(&workspace.clone().into(), 2);
+    let last = test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    move_window_to_next_slot(&last.into(), &mut state, &config).unwrap();
+
+    assert_eq!(window_order(&workspace), [3, 1, 2]);
+  }
+
+  #[test]
+  fn leaves_only_window_in_place() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let window = test_utils::tiling_window(&workspace.clone().into(), 1);
+
+    move_window_to_next_slot(&window.clone().into(), &mut state, &config)
+      .unwrap();
+
+    assert_eq!(window_order(&workspace), [1]);
+    assert!(!state.pending_sync.has_changes());
+  }
+}
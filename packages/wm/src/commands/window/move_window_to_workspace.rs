@@ -1,21 +1,27 @@
 use anyhow::Context;
 use tracing::info;
-use wm_common::WindowState;
+use wm_common::{WindowState, WmEvent};
 
 use crate::{
   commands::{
     container::{move_container_within_tree, set_focused_descendant},
-    workspace::activate_workspace,
+    workspace::{activate_workspace, focus_workspace},
   },
-  models::{WindowContainer, WorkspaceTarget},
+  models::{Container, WindowContainer, Workspace, WorkspaceTarget},
   traits::{CommonGetters, PositionGetters, WindowGetters},
   user_config::UserConfig,
   wm_state::WmState,
 };
 
+/// Moves a window to a workspace by a given target.
+///
+/// The workspace is activated if it isn't already active. Focus stays on
+/// the workspace that the window was moved from, unless `follow` is set,
+/// in which case the target workspace gets focused along with the window.
 pub fn move_window_to_workspace(
-  window: WindowContainer,
-  target: WorkspaceTarget,
+  window: &WindowContainer,
+  target: &WorkspaceTarget,
+  follow: bool,
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
@@ -23,8 +29,8 @@ pub fn move_window_to_workspace(
   let current_monitor =
     current_workspace.monitor().context("No monitor.")?;
 
-  let (target_workspace_name, target_workspace) =
-    state.workspace_by_target(&current_workspace, target, config)?;
+  let (target_workspace_name, target_workspace) = state
+    .workspace_by_target(&current_workspace, target.clone(), config)?;
 
   // Retrieve or activate the target workspace by its name.
   let target_workspace = match target_workspace {
@@ -54,14 +60,15 @@ pub fn move_window_to_workspace(
 
     // Since target workspace could be on a different monitor, adjustments
     // might need to be made because of DPI.
-    if current_monitor
-      .has_dpi_difference(&target_monitor.clone().into())?
-    {
-      window.set_has_pending_dpi_adjustment(true);
-    }
+    window.queue_dpi_adjustment(
+      current_monitor.dpi_adjustment_to(&target_monitor.clone().into())?,
+    );
 
-    // Update floating placement if the window has to cross monitors.
-    if target_monitor.id() != current_monitor.id() {
+    // Update floating placement if the window has to cross monitors, or
+    // if it isn't on any monitor and would otherwise stay off-screen.
+    if target_monitor.id() != current_monitor.id()
+      || state.is_unassigned_rect(&window.floating_placement())
+    {
       window.set_floating_placement(
         window
           .floating_placement()
@@ -69,12 +76,12 @@ pub fn move_window_to_workspace(
       );
     }
 
-    if let WindowContainer::NonTilingWindow(window) = &window {
+    if let WindowContainer::NonTilingWindow(window) = window {
       window.set_insertion_target(None);
     }
 
     // Focus target is `None` if the window is not focused.
-    let focus_target = state.focus_target_after_removal(&window);
+    let focus_target = state.focus_target_after_removal(window);
 
     let focus_reset_target = if target_workspace.is_displayed() {
       None
@@ -82,32 +89,16 @@ pub fn move_window_to_workspace(
       target_monitor.descendant_focus_order().next()
     };
 
-    let insertion_sibling = target_workspace
-      .descendant_focus_order()
-      .filter_map(|descendant| descendant.as_window_container().ok())
-      .find(|descendant| descendant.state() == WindowState::Tiling);
+    let (target_parent, target_index) =
+      workspace_insertion_target(window, &target_workspace)?;
 
     // Insert the window into the target workspace.
-    match (window.is_tiling_window(), insertion_sibling.is_some()) {
-      (true, true) => {
-        if let Some(insertion_sibling) = insertion_sibling {
-          move_container_within_tree(
-            &window.clone().into(),
-            &insertion_sibling.clone().parent().context("No parent.")?,
-            insertion_sibling.index() + 1,
-            state,
-          )?;
-        }
-      }
-      _ => {
-        move_container_within_tree(
-          &window.clone().into(),
-          &target_workspace.clone().into(),
-          target_workspace.child_count(),
-          state,
-        )?;
-      }
-    }
+    move_container_within_tree(
+      &window.clone().into(),
+      &target_parent,
+      target_index,
+      state,
+    )?;
 
     // When moving a focused window within the tree to another workspace,
     // the target workspace will get displayed. If moving the window e.g.
@@ -129,7 +120,7 @@ pub fn move_window_to_workspace(
 
     match window {
       WindowContainer::NonTilingWindow(_) => {
-        state.pending_sync.queue_container_to_redraw(window);
+        state.pending_sync.queue_container_to_redraw(window.clone());
       }
       WindowContainer::TilingWindow(_) => {
         state
@@ -141,8 +132,313 @@ pub fn move_window_to_workspace(
 
     state
       .pending_sync
-      .queue_workspace_to_reorder(target_workspace);
+      .queue_workspace_to_reorder(target_workspace.clone());
+
+    state.emit_event(WmEvent::WorkspaceUpdated {
+      updated_workspace: current_workspace.to_dto()?,
+    });
+
+    state.emit_event(WmEvent::WorkspaceUpdated {
+      updated_workspace: target_workspace.to_dto()?,
+    });
+
+    // Focus the window on the target workspace. This displays the target
+    // workspace if it's hidden.
+    if follow {
+      set_focused_descendant(
+        &window.clone().into(),
+        Some(&target_workspace.clone().into()),
+      );
+
+      focus_workspace(
+        WorkspaceTarget::Name(target_workspace.config().name),
+        state,
+        config,
+      )?;
+    }
   }
 
   Ok(())
 }
+
+/// Gets where to insert a window that is moved to the given workspace.
+///
+/// Tiling windows are inserted after the most recently focused tiling
+/// window in the workspace. Otherwise, the window is appended to the
+/// workspace.
+///
+/// Returns tuple of (parent container, insertion index).
+fn workspace_insertion_target(
+  window: &WindowContainer,
+  target_workspace: &Workspace,
+) -> anyhow::Result<(Container, usize)> {
+  if window.is_tiling_window() {
+    let insertion_sibling = target_workspace
+      .descendant_focus_order()
+      .filter_map(|descendant| descendant.as_window_container().ok())
+      .find(|descendant| descendant.state() == WindowState::Tiling);
+
+    if let Some(insertion_sibling) = insertion_sibling {
+      return Ok((
+        insertion_sibling.parent().context("No parent.")?,
+        insertion_sibling.index() + 1,
+      ));
+    }
+  }
+
+  Ok((
+    target_workspace.clone().into(),
+    target_workspace.child_count(),
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    FloatingStateConfig, GapsConfig, LengthValue, Rect, RectDelta,
+    TilingDirection,
+  };
+  use wm_platform::NativeWindow;
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container,
+    models::{NonTilingWindow, SplitContainer, TilingWindow},
+    test_utils,
+  };
+
+  fn workspace() -> Workspace {
+    Workspace::new(
+      serde_yaml::from_str("name: '3'").unwrap(),
+      GapsConfig::default(),
+      TilingDirection::Horizontal,
+    )
+  }
+
+  fn no_delta() -> RectDelta {
+    let no_delta = LengthValue::from_px(0);
+
+    RectDelta::new(
+      no_delta.clone(),
+      no_delta.clone(),
+      no_delta.clone(),
+      no_delta,
+    )
+  }
+
+  fn tiling_window(handle: isize) -> TilingWindow {
+    TilingWindow::new(
+      None,
+      NativeWindow::new(handle),
+      None,
+      no_delta(),
+      Rect::from_xy(0, 0, 400, 300),
+      false,
+      GapsConfig::default(),
+      Vec::new(),
+      None,
+    )
+  }
+
+  fn floating_window(handle: isize) -> NonTilingWindow {
+    NonTilingWindow::new(
+      None,
+      NativeWindow::new(handle),
+      WindowState::Floating(FloatingStateConfig::default()),
+      None,
+      no_delta(),
+      None,
+      Rect::from_xy(0, 0, 400, 300),
+      false,
+      Vec::new(),
+      None,
+    )
+  }
+
+  #[test]
+  fn tiling_window_goes_after_focused_tiling_window() {
+    let workspace = workspace();
+    let split = SplitContainer::new(
+      TilingDirection::Vertical,
+      GapsConfig::default(),
+    );
+
+    attach_container(
+      &split.clone().into(),
+      &workspace.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let first = tiling_window(1);
+    let second = tiling_window(2);
+
+    for window in [&first, &second] {
+      attach_container(
+        &window.clone().into(),
+        &split.clone().into(),
+        None,
+      )
+      .unwrap();
+    }
+
+    set_focused_descendant(&first.clone().into(), None);
+
+    let (parent, index) =
+      workspace_insertion_target(&tiling_window(3).into(), &workspace)
+        .unwrap();
+
+    assert_eq!(parent.id(), split.id());
+    assert_eq!(index, first.index() + 1);
+  }
+
+  #[test]
+  fn tiling_window_is_appended_to_workspace_without_tiling_windows() {
+    let workspace = workspace();
+
+    attach_container(
+      &floating_window(1).into(),
+      &workspace.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let (parent, index) =
+      workspace_insertion_target(&tiling_window(2).into(), &workspace)
+        .unwrap();
+
+    assert_eq!(parent.id(), workspace.id());
+    assert_eq!(index, 1);
+  }
+
+  #[test]
+  fn floating_window_is_appended_to_workspace() {
+    let workspace = workspace();
+
+    attach_container(
+      &tiling_window(1).into(),
+      &workspace.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let (parent, index) =
+      workspace_insertion_target(&floating_window(2).into(), &workspace)
+        .unwrap();
+
+    assert_eq!(parent.id(), workspace.id());
+    assert_eq!(index, 1);
+  }
+
+  /// Gets the IDs of the workspaces in emitted `WorkspaceUpdated` events.
+  fn updated_workspace_ids(events: &[WmEvent]) -> Vec<uuid::Uuid> {
+    events
+      .iter()
+      .filter_map(|event| match event {
+        WmEvent::WorkspaceUpdated { updated_workspace } => {
+          Some(updated_workspace.id())
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn focus_stays_on_origin_workspace_without_follow() {
+    let (mut state, mut event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let origin = test_utils::workspace(&monitor, "1");
+    let target = test_utils::workspace(&monitor, "2");
+
+    let moved = test_utils::tiling_window(&origin.clone().into(), 1);
+    let sibling = test_utils::tiling_window(&origin.clone().into(), 2);
+    set_focused_descendant(&moved.clone().into(), None);
+
+    move_window_to_workspace(
+      &moved.clone().into(),
+      &WorkspaceTarget::Name("2".to_string()),
+      false,
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    assert_eq!(moved.workspace().map(|w| w.id()), Some(target.id()));
+    assert_eq!(
+      state.focused_container().map(|c| c.id()),
+      Some(sibling.id())
+    );
+    assert!(origin.is_displayed());
+
+    let events = test_utils::emitted_events(&mut event_rx);
+    assert_eq!(updated_workspace_ids(&events), [origin.id(), target.id()]);
+  }
+
+  #[test]
+  fn focus_follows_window_to_target_workspace() {
+    let (mut state, mut event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let origin = test_utils::workspace(&monitor, "1");
+    let target = test_utils::workspace(&monitor, "2");
+
+    let moved = test_utils::tiling_window(&origin.clone().into(), 1);
+    test_utils::tiling_window(&origin.clone().into(), 2);
+    set_focused_descendant(&moved.clone().into(), None);
+
+    move_window_to_workspace(
+      &moved.clone().into(),
+      &WorkspaceTarget::Name("2".to_string()),
+      true,
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    assert_eq!(
+      state.focused_container().map(|c| c.id()),
+      Some(moved.id())
+    );
+    assert!(target.is_displayed());
+    assert_eq!(state.recent_workspace_name.as_deref(), Some("1"));
+
+    let events = test_utils::emitted_events(&mut event_rx);
+    assert_eq!(updated_workspace_ids(&events), [origin.id(), target.id()]);
+  }
+  #[test]
+  fn recenters_unassigned_floating_window() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let origin = test_utils::workspace(&monitor, "1");
+    let target = test_utils::workspace(&monitor, "2");
+
+    let window = test_utils::non_tiling_window(
+      &origin,
+      1,
+      WindowState::Floating(FloatingStateConfig::default()),
+    );
+
+    let off_screen = Rect::from_xy(-5000, 0, 400, 300);
+    window.set_floating_placement(off_screen.clone());
+
+    move_window_to_workspace(
+      &window.clone().into(),
+      &WorkspaceTarget::Name("2".to_string()),
+      false,
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    // The window stays on the same monitor, but is brought on-screen.
+    assert_eq!(
+      window.floating_placement(),
+      off_screen.translate_to_center(&target.to_rect().unwrap())
+    );
+  }
+}
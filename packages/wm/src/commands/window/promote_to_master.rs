@@ -0,0 +1,140 @@
+use anyhow::{bail, Context};
+use tracing::info;
+use wm_common::WmEvent;
+
+use crate::{
+  models::WindowContainer, traits::CommonGetters, wm_state::WmState,
+};
+
+/// Moves a tiling window to the front of the master area of its
+/// workspace.
+///
+/// The window is moved along with any split container it's in. If it's
+/// already at the front, it's swapped with the next tiling container
+/// instead, same as dwm's zoom.
+pub fn promote_to_master(
+  window: &WindowContainer,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if !window.is_tiling_window() {
+    return Ok(());
+  }
+
+  let workspace = window.workspace().context("No workspace.")?;
+
+  if !workspace.layout().is_master_stack() {
+    bail!("Workspace does not use the master-stack layout.");
+  }
+
+  // Get the tiling child of the workspace that contains the window.
+  let slot = window
+    .self_and_ancestors()
+    .find(|ancestor| {
+      ancestor
+        .parent()
+        .is_some_and(|parent| parent.id() == workspace.id())
+    })
+    .context("No tiling child of workspace.")?;
+
+  let mut tiling_children = workspace.tiling_children();
+  let first_child = tiling_children.next().context("No tiling child.")?;
+
+  let container_to_promote = if first_child.id() == slot.id() {
+    match tiling_children.next() {
+      Some(next_child) => next_child,
+      None => return Ok(()),
+    }
+  } else {
+    slot.as_tiling_container()?
+  };
+
+  info!("Promoting to master for window: {window}");
+
+  let index = container_to_promote.index();
+  let mut children = workspace.borrow_children_mut();
+  let container = children.remove(index).context("No container.")?;
+  children.push_front(container);
+  drop(children);
+
+  state
+    .pending_sync
+    .queue_container_to_redraw(workspace.clone());
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{models::Workspace, test_utils};
+
+  fn use_master_stack(workspace: &Workspace) {
+    workspace.set_config(
+      serde_yaml::from_str("{ name: '1', layout: master_stack }").unwrap(),
+    );
+  }
+
+  fn child_ids(workspace: &Workspace) -> Vec<uuid::Uuid> {
+    workspace.children().iter().map(CommonGetters::id).collect()
+  }
+
+  #[test]
+  fn moves_window_to_front() {
+    let (mut state, mut event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    use_master_stack(&workspace);
+
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+    let third = test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    promote_to_master(&third.clone().into(), &mut state).unwrap();
+
+    assert_eq!(
+      child_ids(&workspace),
+      [third.id(), first.id(), second.id()]
+    );
+    assert!(matches!(
+      test_utils::emitted_events(&mut event_rx).as_slice(),
+      [WmEvent::WorkspaceUpdated { .. }]
+    ));
+  }
+
+  #[test]
+  fn swaps_front_window_with_next() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    use_master_stack(&workspace);
+
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+    let third = test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    promote_to_master(&first.clone().into(), &mut state).unwrap();
+
+    assert_eq!(
+      child_ids(&workspace),
+      [second.id(), first.id(), third.id()]
+    );
+  }
+
+  #[test]
+  fn fails_without_master_stack_layout() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    assert!(promote_to_master(&second.into(), &mut state).is_err());
+  }
+}
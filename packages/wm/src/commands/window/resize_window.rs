@@ -7,6 +7,11 @@ use crate::{
   wm_state::WmState,
 };
 
+/// Resizes a window by the given width and height deltas.
+///
+/// Percentage deltas are relative to the parent container. For tiling
+/// windows, the delta is taken from or given to the siblings of the
+/// container being resized, in proportion to their current sizes.
 pub fn resize_window(
   window: &WindowContainer,
   width_delta: Option<LengthValue>,
@@ -20,12 +25,9 @@ pub fn resize_window(
       let parent_width = match window.as_tiling_container() {
         Ok(tiling_window) => tiling_window
           .container_to_resize(true)?
-          .and_then(|container| container.parent())
-          .and_then(|parent| {
-            parent.to_rect().ok().map(|rect| rect.width())
-          })
-          .and_then(|parent_width| {
-            let (horizontal_gap, _) = tiling_window.inner_gaps().ok()?;
+          .and_then(|container| {
+            let parent_width = container.parent()?.to_rect().ok()?.width();
+            let (horizontal_gap, _) = container.inner_gaps().ok()?;
 
             #[allow(
               clippy::cast_possible_wrap,
@@ -34,7 +36,7 @@ pub fn resize_window(
             Some(
               parent_width
                 - horizontal_gap
-                  * tiling_window.tiling_siblings().count() as i32,
+                  * container.tiling_siblings().count() as i32,
             )
           }),
         _ => window.parent().and_then(|parent| {
@@ -54,12 +56,10 @@ pub fn resize_window(
       let parent_height = match window.as_tiling_container() {
         Ok(tiling_window) => tiling_window
           .container_to_resize(false)?
-          .and_then(|container| container.parent())
-          .and_then(|parent| {
-            parent.to_rect().ok().map(|rect| rect.width())
-          })
-          .and_then(|parent_height| {
-            let (_, vertical_gap) = tiling_window.inner_gaps().ok()?;
+          .and_then(|container| {
+            let parent_height =
+              container.parent()?.to_rect().ok()?.height();
+            let (_, vertical_gap) = container.inner_gaps().ok()?;
 
             #[allow(
               clippy::cast_possible_wrap,
@@ -68,11 +68,11 @@ pub fn resize_window(
             Some(
               parent_height
                 - vertical_gap
-                  * tiling_window.tiling_siblings().count() as i32,
+                  * container.tiling_siblings().count() as i32,
             )
           }),
         _ => window.parent().and_then(|parent| {
-          parent.to_rect().ok().map(|rect| rect.width())
+          parent.to_rect().ok().map(|rect| rect.height())
         }),
       };
 
@@ -92,3 +92,82 @@ pub fn resize_window(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    FloatingStateConfig, GapsConfig, Rect, TilingDirection, WindowState,
+  };
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container, models::SplitContainer,
+    test_utils, traits::WindowGetters,
+  };
+
+  #[test]
+  fn converts_height_delta_using_parent_height() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+
+    let split = SplitContainer::new(
+      TilingDirection::Vertical,
+      GapsConfig::default(),
+    );
+    attach_container(
+      &split.clone().into(),
+      &workspace.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let window = test_utils::tiling_window(&split.clone().into(), 2);
+    test_utils::tiling_window(&split.into(), 3);
+
+    // The split is 960x1080, so 10% of its height is 108px.
+    resize_window(
+      &window.clone().into(),
+      None,
+      Some("10%".parse().unwrap()),
+      &mut state,
+    )
+    .unwrap();
+
+    assert!((window.tiling_size() - 0.6).abs() < 0.001);
+  }
+
+  #[test]
+  fn keeps_floating_window_within_working_area() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor_with_working_rect(
+      &state,
+      1,
+      Rect::from_xy(0, 0, 1920, 1080),
+      Rect::from_xy(0, 0, 1920, 1040),
+    );
+    let workspace = test_utils::workspace(&monitor, "1");
+    let window = test_utils::non_tiling_window(
+      &workspace,
+      1,
+      WindowState::Floating(FloatingStateConfig::default()),
+    );
+    window.set_floating_placement(Rect::from_xy(1700, 900, 400, 300));
+
+    resize_window(
+      &window.clone().into(),
+      None,
+      Some(LengthValue::from_px(200)),
+      &mut state,
+    )
+    .unwrap();
+
+    assert_eq!(
+      window.floating_placement(),
+      Rect::from_xy(1520, 540, 400, 500)
+    );
+  }
+}
@@ -1,6 +1,7 @@
 use tracing::info;
-use wm_common::WindowRuleEvent;
+use wm_common::{WindowRuleConfig, WindowRuleEvent};
 
+use super::apply_floating_rule;
 use crate::{
   models::WindowContainer,
   traits::{CommonGetters, WindowGetters},
@@ -20,6 +21,47 @@ pub fn run_window_rules(
   let pending_window_rules =
     config.pending_window_rules(&window, event_type)?;
 
+  apply_window_rules(window, pending_window_rules, state, config)
+}
+
+/// Runs the title change rules that the window has started to match
+/// since they were last evaluated for it.
+///
+/// Rules that keep matching across title changes aren't run again, even
+/// if `run_once` is disabled. Returns the window (if it's still attached)
+/// after running the window rules.
+pub fn run_title_change_rules(
+  window: WindowContainer,
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<Option<WindowContainer>> {
+  let matching_rules = config
+    .matching_window_rules(&window, &WindowRuleEvent::TitleChange)?;
+
+  let prev_matching_rules = state
+    .title_rule_matches
+    .insert(window.id(), matching_rules.clone())
+    .unwrap_or_default();
+
+  let done_window_rules = window.done_window_rules();
+
+  let pending_window_rules = matching_rules
+    .into_iter()
+    .filter(|rule| {
+      !prev_matching_rules.contains(rule)
+        && !done_window_rules.contains(rule)
+    })
+    .collect::<Vec<_>>();
+
+  apply_window_rules(window, pending_window_rules, state, config)
+}
+
+fn apply_window_rules(
+  window: WindowContainer,
+  pending_window_rules: Vec<WindowRuleConfig>,
+  state: &mut WmState,
+  config: &mut UserConfig,
+) -> anyhow::Result<Option<WindowContainer>> {
   let mut subject_window = window;
 
   for rule in pending_window_rules {
@@ -45,6 +87,13 @@ pub fn run_window_rules(
       }
     }
 
+    // Floating windows that haven't been placed yet (e.g. dialogs that
+    // are floating from the start) are placed based on the rule. Windows
+    // floated by the rule's commands are placed on the state change.
+    if !subject_window.has_custom_floating_placement() {
+      apply_floating_rule(&subject_window, &rule, state)?;
+    }
+
     // Add the window rule as done.
     if rule.run_once {
       let window_rules = subject_window
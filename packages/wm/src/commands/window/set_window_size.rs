@@ -1,11 +1,20 @@
 use anyhow::Context;
-use wm_common::{LengthValue, Rect, WindowState};
+use tracing::info;
+use wm_common::{LengthValue, Rect, TilingDirection, WindowState};
 
 use crate::{
-  commands::container::resize_tiling_container,
-  models::{NonTilingWindow, TilingWindow, WindowContainer},
+  commands::{
+    container::resize_tiling_container,
+    window::{tiling_max_length, tiling_min_length},
+    workspace::set_master_ratio,
+  },
+  models::{
+    NonTilingWindow, TilingContainer, TilingWindow, WindowContainer,
+    Workspace,
+  },
   traits::{
-    CommonGetters, PositionGetters, TilingSizeGetters, WindowGetters,
+    CommonGetters, PositionGetters, TilingDirectionGetters,
+    TilingSizeGetters, WindowGetters,
   },
   wm_state::WmState,
 };
@@ -69,20 +78,57 @@ fn set_tiling_window_length(
 
   if let Some(container_to_resize) = container_to_resize {
     let parent = container_to_resize.parent().context("No parent.")?;
+
+    // Resizing the tiling children of a master-stack workspace in its
+    // tiling direction moves the boundary between its master area and
+    // stack instead.
+    if let Some(workspace) = parent.as_workspace().filter(|workspace| {
+      workspace.layout().is_master_stack()
+        && is_width_resize
+          == (workspace.tiling_direction() == TilingDirection::Horizontal)
+    }) {
+      return set_master_area_length(
+        &container_to_resize,
+        workspace,
+        target_length,
+        is_width_resize,
+        state,
+      );
+    }
+
     let (horizontal_gap, vertical_gap) =
       container_to_resize.inner_gaps()?;
 
+    let sibling_count = container_to_resize.tiling_siblings().count();
+
     #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
     let parent_length = if is_width_resize {
-      parent.to_rect()?.width()
-        - horizontal_gap * window.tiling_siblings().count() as i32
+      parent.to_rect()?.width() - horizontal_gap * sibling_count as i32
     } else {
-      parent.to_rect()?.height()
-        - vertical_gap * window.tiling_siblings().count() as i32
+      parent.to_rect()?.height() - vertical_gap * sibling_count as i32
     };
 
+    // Keep the target length within the size constraints of the
+    // container's windows.
+    let target_px = target_length.to_px(parent_length, None);
+    let clamped_px = target_px
+      .min(
+        tiling_max_length(&container_to_resize, is_width_resize)
+          .unwrap_or(i32::MAX),
+      )
+      .max(tiling_min_length(&container_to_resize, is_width_resize)?);
+
     // Convert the target length to a tiling size.
-    let tiling_size = target_length.to_percentage(parent_length);
+    let tiling_size = if clamped_px == target_px {
+      target_length.to_percentage(parent_length)
+    } else {
+      info!(
+        "Clamped resize of {} from {target_px}px to {clamped_px}px to fit its size constraints.",
+        WindowContainer::from(window.clone())
+      );
+
+      LengthValue::from_px(clamped_px).to_percentage(parent_length)
+    };
 
     // Skip the resize if the window is already at the target size.
     if container_to_resize.tiling_size() - tiling_size != 0. {
@@ -97,6 +143,47 @@ fn set_tiling_window_length(
   Ok(())
 }
 
+/// Updates the master ratio of a master-stack workspace, such that the
+/// area containing the given tiling child gets the target length.
+fn set_master_area_length(
+  container: &TilingContainer,
+  workspace: &Workspace,
+  target_length: &LengthValue,
+  is_width_resize: bool,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let tiling_count = workspace.tiling_children().count();
+  let master_count = workspace.master_count().min(tiling_count);
+
+  // Skip if there's no boundary to move.
+  if master_count == 0 || master_count == tiling_count {
+    return Ok(());
+  }
+
+  let (horizontal_gap, vertical_gap) = container.inner_gaps()?;
+  let workspace_rect = workspace.to_rect()?;
+
+  let available_length = if is_width_resize {
+    workspace_rect.width() - horizontal_gap
+  } else {
+    workspace_rect.height() - vertical_gap
+  };
+
+  let area_ratio = target_length.to_percentage(available_length);
+
+  let master_ratio = if workspace.is_in_master_area(container.id()) {
+    area_ratio
+  } else {
+    1. - area_ratio
+  };
+
+  if (master_ratio - workspace.master_ratio()).abs() < f32::EPSILON {
+    return Ok(());
+  }
+
+  set_master_ratio(workspace, master_ratio, state)
+}
+
 fn set_floating_window_size(
   window: &NonTilingWindow,
   target_width: Option<LengthValue>,
@@ -139,14 +226,77 @@ fn set_floating_window_size(
     MIN_FLOATING_HEIGHT,
   );
 
-  window.set_floating_placement(Rect::from_xy(
-    window.floating_placement().x(),
-    window.floating_placement().y(),
-    new_width,
-    new_height,
+  // Keep the window within the size constraints of its application.
+  let constraints = window.size_constraints();
+  let clamped_width = constraints.clamp_length(new_width, true);
+  let clamped_height = constraints.clamp_length(new_height, false);
+
+  if clamped_width != new_width || clamped_height != new_height {
+    info!(
+      "Clamped resize of {} from {new_width}x{new_height} to {clamped_width}x{clamped_height} to fit its size constraints.",
+      WindowContainer::from(window.clone())
+    );
+  }
+
+  // Keep the resized window within the working area of its monitor.
+  window.set_floating_placement(fit_within(
+    &Rect::from_xy(
+      window.floating_placement().x(),
+      window.floating_placement().y(),
+      clamped_width,
+      clamped_height,
+    ),
+    &monitor.working_rect()?,
   ));
 
   state.pending_sync.queue_container_to_redraw(window.clone());
 
   Ok(())
 }
+
+/// Shrinks and shifts a rect such that it fits within the outer rect.
+fn fit_within(rect: &Rect, outer_rect: &Rect) -> Rect {
+  let width = rect.width().min(outer_rect.width());
+  let height = rect.height().min(outer_rect.height());
+
+  let x = rect.x().min(outer_rect.right - width).max(outer_rect.left);
+  let y = rect.y().min(outer_rect.bottom - height).max(outer_rect.top);
+
+  Rect::from_xy(x, y, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn shifts_rect_overflowing_outer_rect() {
+    let outer_rect = Rect::from_xy(0, 0, 1920, 1080);
+
+    assert_eq!(
+      fit_within(&Rect::from_xy(1600, 900, 500, 300), &outer_rect),
+      Rect::from_xy(1420, 780, 500, 300),
+    );
+    assert_eq!(
+      fit_within(&Rect::from_xy(-100, -50, 500, 300), &outer_rect),
+      Rect::from_xy(0, 0, 500, 300),
+    );
+  }
+
+  #[test]
+  fn shrinks_rect_larger_than_outer_rect() {
+    let outer_rect = Rect::from_xy(1920, 0, 1280, 720);
+
+    assert_eq!(
+      fit_within(&Rect::from_xy(2000, 100, 1500, 800), &outer_rect),
+      Rect::from_xy(1920, 0, 1280, 720),
+    );
+  }
+
+  #[test]
+  fn keeps_rect_within_outer_rect() {
+    let rect = Rect::from_xy(100, 100, 500, 300);
+
+    assert_eq!(fit_within(&rect, &Rect::from_xy(0, 0, 1920, 1080)), rect);
+  }
+}
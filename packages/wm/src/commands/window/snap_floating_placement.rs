@@ -0,0 +1,195 @@
+use anyhow::Context;
+use wm_common::{Direction, Rect, WindowState};
+
+use crate::{
+  models::{WindowContainer, Workspace},
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+};
+
+/// Snaps a floating window's placement to the edges of the monitor's
+/// working area and of other floating windows on `workspace`.
+///
+/// Edges are only snapped to when within the configured threshold. If
+/// `direction` is given, the placement is only moved further in that
+/// direction, such that snapping never undoes a move.
+pub fn snap_floating_placement(
+  window: &WindowContainer,
+  placement: &Rect,
+  workspace: &Workspace,
+  direction: Option<&Direction>,
+  config: &UserConfig,
+) -> anyhow::Result<Rect> {
+  let snapping_config = &config.value.window_behavior.snapping;
+
+  if !snapping_config.enabled {
+    return Ok(placement.clone());
+  }
+
+  let monitor = workspace.monitor().context("No monitor.")?;
+  let working_rect = monitor.working_rect()?;
+
+  let threshold = snapping_config
+    .threshold
+    .to_px(working_rect.width(), Some(monitor.native().scale_factor()?));
+
+  let window_rects = workspace
+    .descendants()
+    .filter_map(|descendant| descendant.as_non_tiling_window().cloned())
+    .filter(|other| {
+      other.id() != window.id()
+        && matches!(other.state(), WindowState::Floating(_))
+    })
+    .map(|other| other.floating_placement())
+    .collect::<Vec<_>>();
+
+  Ok(snap_rect(
+    placement,
+    &working_rect,
+    &window_rects,
+    threshold,
+    direction,
+  ))
+}
+
+/// Moves `rect` such that its nearest edges within `threshold` line up
+/// with the edges of `bounds` or of `others`.
+///
+/// Edges of other rects are only snapped to if the rects are next to
+/// each other along the opposite axis.
+fn snap_rect(
+  rect: &Rect,
+  bounds: &Rect,
+  others: &[Rect],
+  threshold: i32,
+  direction: Option<&Direction>,
+) -> Rect {
+  let delta_x =
+    if matches!(direction, Some(Direction::Up | Direction::Down)) {
+      0
+    } else {
+      let edges = others
+        .iter()
+        .filter(|other| {
+          rect.top < other.bottom + threshold
+            && rect.bottom > other.top - threshold
+        })
+        .flat_map(|other| [other.left, other.right])
+        .chain([bounds.left, bounds.right]);
+
+      snap_delta(rect.left, rect.right, edges, threshold, direction)
+    };
+
+  let delta_y =
+    if matches!(direction, Some(Direction::Left | Direction::Right)) {
+      0
+    } else {
+      let edges = others
+        .iter()
+        .filter(|other| {
+          rect.left < other.right + threshold
+            && rect.right > other.left - threshold
+        })
+        .flat_map(|other| [other.top, other.bottom])
+        .chain([bounds.top, bounds.bottom]);
+
+      snap_delta(rect.top, rect.bottom, edges, threshold, direction)
+    };
+
+  rect.translate_to_coordinates(rect.x() + delta_x, rect.y() + delta_y)
+}
+
+/// Gets the smallest offset that lines up either `start` or `end` with
+/// one of the given edges, or 0 if none are within `threshold`.
+///
+/// With a `direction`, only offsets that don't move against it are
+/// considered.
+fn snap_delta(
+  start: i32,
+  end: i32,
+  edges: impl Iterator<Item = i32>,
+  threshold: i32,
+  direction: Option<&Direction>,
+) -> i32 {
+  edges
+    .flat_map(|edge| [edge - start, edge - end])
+    .filter(|delta| delta.abs() <= threshold)
+    .filter(|delta| match direction {
+      Some(Direction::Left | Direction::Up) => *delta <= 0,
+      Some(Direction::Right | Direction::Down) => *delta >= 0,
+      None => true,
+    })
+    .min_by_key(|delta| delta.abs())
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn bounds() -> Rect {
+    Rect::from_xy(0, 0, 1000, 1000)
+  }
+
+  #[test]
+  fn snaps_to_nearby_bound_edges() {
+    let rect = Rect::from_xy(6, 795, 200, 200);
+
+    let snapped = snap_rect(&rect, &bounds(), &[], 10, None);
+    assert_eq!(snapped, Rect::from_xy(0, 800, 200, 200));
+  }
+
+  #[test]
+  fn leaves_rect_outside_threshold() {
+    let rect = Rect::from_xy(11, 200, 200, 200);
+
+    let snapped = snap_rect(&rect, &bounds(), &[], 10, None);
+    assert_eq!(snapped, rect);
+  }
+
+  #[test]
+  fn snaps_next_to_other_rects() {
+    let other = Rect::from_xy(100, 100, 300, 300);
+    let rect = Rect::from_xy(407, 250, 200, 200);
+
+    let snapped = snap_rect(&rect, &bounds(), &[other], 10, None);
+    assert_eq!(snapped, Rect::from_xy(400, 250, 200, 200));
+  }
+
+  #[test]
+  fn ignores_rects_far_away_on_opposite_axis() {
+    let other = Rect::from_xy(100, 100, 300, 300);
+    let rect = Rect::from_xy(407, 600, 200, 200);
+
+    let snapped = snap_rect(&rect, &bounds(), &[other], 10, None);
+    assert_eq!(snapped, rect);
+  }
+
+  #[test]
+  fn only_snaps_along_given_direction() {
+    let rect = Rect::from_xy(5, 5, 200, 200);
+
+    let snapped =
+      snap_rect(&rect, &bounds(), &[], 10, Some(&Direction::Left));
+
+    assert_eq!(snapped, Rect::from_xy(0, 5, 200, 200));
+  }
+
+  #[test]
+  fn never_snaps_against_given_direction() {
+    // The window was moved right, away from the left bound. Snapping it
+    // back would undo the move.
+    let rect = Rect::from_xy(5, 300, 200, 200);
+
+    let snapped =
+      snap_rect(&rect, &bounds(), &[], 10, Some(&Direction::Right));
+    assert_eq!(snapped, rect);
+
+    // Edges further along the direction are still snapped to.
+    let other = Rect::from_xy(210, 300, 200, 200);
+
+    let snapped =
+      snap_rect(&rect, &bounds(), &[other], 10, Some(&Direction::Right));
+    assert_eq!(snapped, Rect::from_xy(10, 300, 200, 200));
+  }
+}
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, hash::BuildHasher};
+
+use anyhow::Context;
+use tracing::info;
+use wm_common::{DisplayState, HideMethod};
+use wm_platform::Platform;
+
+use crate::{
+  commands::container::{
+    attach_container, detach_container, flatten_child_split_containers,
+    replace_container, set_focused_descendant,
+  },
+  models::{SwallowedWindow, TilingWindow, WindowContainer},
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Swallows the nearest ancestor process of a newly managed window that
+/// matches a window rule with `swallow` enabled.
+///
+/// The swallowed window must be tiling on the same workspace. It gets
+/// hidden, and the new window takes its place in the tree. Only applies
+/// to tiling windows managed after the WM has initialized.
+pub fn swallow_window(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let WindowContainer::TilingWindow(window) = window else {
+    return Ok(());
+  };
+
+  if !state.has_initialized() {
+    return Ok(());
+  }
+
+  let Some(swallowed) = swallow_target(window, config)? else {
+    return Ok(());
+  };
+
+  info!(
+    "Window swallowed: {}",
+    WindowContainer::from(swallowed.clone())
+  );
+
+  let workspace = window.workspace().context("No workspace.")?;
+
+  // Create iterator of parent, grandparent, and great-grandparent.
+  let ancestors = window.ancestors().take(3).collect::<Vec<_>>();
+
+  detach_container(window.clone().into())?;
+
+  for ancestor in ancestors.iter().rev() {
+    flatten_child_split_containers(ancestor)?;
+  }
+
+  // Get the swallowed window's position after flattening, since its
+  // parent might have changed.
+  let target_parent = swallowed.parent().context("No parent.")?;
+
+  replace_container(
+    &window.clone().into(),
+    &target_parent,
+    swallowed.index(),
+  )?;
+
+  set_focused_descendant(&window.clone().into(), None);
+
+  let hide_method = &config.value.general.hide_method;
+  swallowed.set_display_state(DisplayState::Hidden);
+  swallowed.native().set_visible(false, hide_method)?;
+
+  if *hide_method == HideMethod::Cloak
+    && !config.value.general.show_all_in_taskbar
+  {
+    swallowed.native().set_taskbar_visibility(false)?;
+  }
+
+  state.swallowed_windows.insert(
+    window.id(),
+    SwallowedWindow {
+      window: swallowed,
+      workspace: workspace.clone(),
+    },
+  );
+
+  state.pending_sync.queue_container_to_redraw(workspace);
+
+  Ok(())
+}
+
+/// Releases swallowed windows whose swallowing window has moved to
+/// another workspace or is no longer managed.
+///
+/// Released windows are appended to the workspace they were swallowed
+/// on, or to the swallowing window's workspace if the original has since
+/// been deactivated.
+pub fn sync_swallowed_windows(state: &mut WmState) -> anyhow::Result<()> {
+  if state.swallowed_windows.is_empty() {
+    return Ok(());
+  }
+
+  let to_release = state
+    .swallowed_windows
+    .iter()
+    .filter_map(|(id, swallowed)| {
+      let workspace = state
+        .container_by_id(*id)
+        .and_then(|container| container.workspace());
+
+      match workspace {
+        Some(workspace) if workspace.id() == swallowed.workspace.id() => {
+          None
+        }
+        workspace => Some((*id, workspace)),
+      }
+    })
+    .collect::<Vec<_>>();
+
+  for (id, current_workspace) in to_release {
+    let Some(swallowed) = state.swallowed_windows.remove(&id) else {
+      continue;
+    };
+
+    let target_workspace = Some(swallowed.workspace.clone())
+      .filter(|workspace| !workspace.is_detached())
+      .or(current_workspace)
+      .or_else(|| {
+        state
+          .focused_container()
+          .and_then(|focused| focused.workspace())
+      })
+      .context("No workspace to release swallowed window to.")?;
+
+    info!(
+      "Swallowed window released: {}",
+      WindowContainer::from(swallowed.window.clone())
+    );
+
+    attach_container(
+      &swallowed.window.clone().into(),
+      &target_workspace.clone().into(),
+      None,
+    )?;
+
+    state
+      .pending_sync
+      .queue_container_to_redraw(target_workspace);
+  }
+
+  Ok(())
+}
+
+/// Gets the window to be swallowed by the given window, if any.
+fn swallow_target(
+  window: &TilingWindow,
+  config: &UserConfig,
+) -> anyhow::Result<Option<TilingWindow>> {
+  let workspace = window.workspace().context("No workspace.")?;
+
+  let mut candidates = Vec::new();
+
+  for descendant in workspace.descendants() {
+    if let Some(candidate) = descendant.as_tiling_window() {
+      if candidate.id() != window.id()
+        && config.is_swallow_window(&candidate.clone().into())?
+      {
+        candidates.push(candidate.clone());
+      }
+    }
+  }
+
+  // Avoid snapshotting processes if there's nothing to swallow.
+  if candidates.is_empty() {
+    return Ok(None);
+  }
+
+  let parent_pids = Platform::parent_process_ids()?;
+
+  let target = ancestor_pids(window.native().process_id(), &parent_pids)
+    .into_iter()
+    .find_map(|pid| {
+      candidates
+        .iter()
+        .find(|candidate| candidate.native().process_id() == pid)
+        .cloned()
+    });
+
+  Ok(target)
+}
+
+/// Gets the ancestor process IDs of a process, nearest first.
+///
+/// Stops on cycles in the parent relationship, which can happen when a
+/// parent's PID has been recycled.
+pub fn ancestor_pids<S: BuildHasher>(
+  pid: u32,
+  parent_pids: &HashMap<u32, u32, S>,
+) -> Vec<u32> {
+  let mut ancestors = Vec::new();
+  let mut current = pid;
+
+  while let Some(&parent) = parent_pids.get(&current) {
+    if parent == 0 || parent == pid || ancestors.contains(&parent) {
+      break;
+    }
+
+    ancestors.push(parent);
+    current = parent;
+  }
+
+  ancestors
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ancestors_are_ordered_nearest_first() {
+    let parent_pids = HashMap::from([(30, 20), (20, 10), (10, 0)]);
+
+    assert_eq!(ancestor_pids(30, &parent_pids), vec![20, 10]);
+    assert!(ancestor_pids(10, &parent_pids).is_empty());
+    assert!(ancestor_pids(40, &parent_pids).is_empty());
+  }
+
+  #[test]
+  fn cyclic_parents_terminate() {
+    let parent_pids = HashMap::from([(30, 20), (20, 10), (10, 20)]);
+
+    assert_eq!(ancestor_pids(30, &parent_pids), vec![20, 10]);
+    assert_eq!(ancestor_pids(20, &parent_pids), vec![10]);
+  }
+}
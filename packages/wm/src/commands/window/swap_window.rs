@@ -0,0 +1,260 @@
+use anyhow::{bail, Context};
+use tracing::info;
+use wm_common::Direction;
+
+use crate::{
+  commands::container::{set_focused_descendant, tiling_focus_target},
+  models::{Container, TilingWindow, WindowContainer},
+  traits::{
+    CommonGetters, PositionGetters, TilingDirectionGetters,
+    TilingSizeGetters, WindowGetters,
+  },
+  wm_state::WmState,
+};
+
+/// Swaps a tiling window with the nearest tiling window in the given
+/// direction.
+///
+/// The neighbor is searched for within the window's workspace first, and
+/// on the monitor in the given direction otherwise. The windows exchange
+/// positions in the tree, and each position keeps its tiling size.
+pub fn swap_window(
+  window: WindowContainer,
+  direction: &Direction,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let WindowContainer::TilingWindow(window) = window else {
+    bail!("Cannot swap a non-tiling window.");
+  };
+
+  let Some(target) = swap_target(&window, direction, state)? else {
+    return Ok(());
+  };
+
+  info!("Swapping window with neighbor in direction: {direction:?}.");
+
+  let has_focus = state
+    .focused_container()
+    .is_some_and(|focused| focused.id() == window.id());
+
+  let monitor = window.monitor().context("No monitor.")?;
+  let target_monitor = target.monitor().context("No monitor.")?;
+
+  // Windows crossing monitors might need adjustments because of DPI.
+  if monitor.id() != target_monitor.id() {
+    window.queue_dpi_adjustment(
+      monitor.dpi_adjustment_to(&target_monitor.clone().into())?,
+    );
+    target.queue_dpi_adjustment(
+      target_monitor.dpi_adjustment_to(&monitor.clone().into())?,
+    );
+  }
+
+  let workspace = window.workspace().context("No workspace.")?;
+  let target_workspace = target.workspace().context("No workspace.")?;
+
+  if workspace.id() != target_workspace.id() {
+    window.set_floating_placement(
+      window
+        .floating_placement()
+        .translate_to_center(&target_workspace.to_rect()?),
+    );
+    target.set_floating_placement(
+      target
+        .floating_placement()
+        .translate_to_center(&workspace.to_rect()?),
+    );
+  }
+
+  let parent = window.parent().context("No parent.")?;
+  let target_parent = target.parent().context("No parent.")?;
+
+  swap_tiling_windows(&window, &target)?;
+
+  if has_focus {
+    set_focused_descendant(&window.clone().into(), None);
+    state.pending_sync.queue_focus_change().queue_cursor_jump();
+  }
+
+  state
+    .pending_sync
+    .queue_container_to_redraw(parent)
+    .queue_container_to_redraw(target_parent)
+    .queue_workspace_to_reorder(workspace)
+    .queue_workspace_to_reorder(target_workspace);
+
+  Ok(())
+}
+
+/// Gets the tiling window to swap with in the given direction.
+///
+/// Errors if the neighboring workspace's focused window is non-tiling.
+fn swap_target(
+  window: &TilingWindow,
+  direction: &Direction,
+  state: &WmState,
+) -> anyhow::Result<Option<TilingWindow>> {
+  if let Some(target) =
+    tiling_focus_target(&window.clone().into(), direction)?
+  {
+    return Ok(target.as_tiling_window().cloned());
+  }
+
+  let monitor = window.monitor().context("No monitor.")?;
+
+  let Some(target_workspace) = state
+    .monitor_in_direction(&monitor, direction)?
+    .and_then(|monitor| monitor.displayed_workspace())
+  else {
+    return Ok(None);
+  };
+
+  let focused_descendant =
+    target_workspace.descendant_focus_order().next();
+
+  if let Some(Container::NonTilingWindow(_)) = focused_descendant {
+    bail!("Cannot swap a tiling window with a non-tiling window.");
+  }
+
+  Ok(target_workspace.descendant_in_direction(&direction.inverse()))
+}
+
+/// Exchanges the positions of two tiling windows in the tree.
+///
+/// The windows are swapped in place rather than detached and reattached,
+/// such that no split containers get flattened in between.
+fn swap_tiling_windows(
+  window_a: &TilingWindow,
+  window_b: &TilingWindow,
+) -> anyhow::Result<()> {
+  let parent_a = window_a.parent().context("No parent.")?;
+  let parent_b = window_b.parent().context("No parent.")?;
+  let (index_a, index_b) = (window_a.index(), window_b.index());
+
+  if parent_a.id() == parent_b.id() {
+    parent_a.borrow_children_mut().swap(index_a, index_b);
+  } else {
+    parent_a.borrow_children_mut()[index_a] = window_b.clone().into();
+    parent_b.borrow_children_mut()[index_b] = window_a.clone().into();
+
+    // Each window takes the other's place in its new parent's focus
+    // order.
+    replace_in_focus_order(&parent_a, window_a, window_b);
+    replace_in_focus_order(&parent_b, window_b, window_a);
+
+    *window_a.borrow_parent_mut() = Some(parent_b);
+    *window_b.borrow_parent_mut() = Some(parent_a);
+  }
+
+  let (size_a, size_b) = (window_a.tiling_size(), window_b.tiling_size());
+  window_a.set_tiling_size(size_b);
+  window_b.set_tiling_size(size_a);
+
+  Ok(())
+}
+
+fn replace_in_focus_order(
+  parent: &Container,
+  replaced: &TilingWindow,
+  replacement: &TilingWindow,
+) {
+  for id in parent.borrow_child_focus_order_mut().iter_mut() {
+    if *id == replaced.id() {
+      *id = replacement.id();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    FloatingStateConfig, GapsConfig, TilingDirection, WindowState,
+  };
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container, models::SplitContainer,
+    test_utils,
+  };
+
+  #[test]
+  fn swaps_with_sibling() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    swap_window(first.clone().into(), &Direction::Right, &mut state)
+      .unwrap();
+
+    assert_eq!(first.index(), 1);
+    assert_eq!(second.index(), 0);
+  }
+
+  #[test]
+  fn swaps_into_split_without_flattening() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+
+    let split = SplitContainer::new(
+      TilingDirection::Vertical,
+      GapsConfig::default(),
+    );
+    attach_container(
+      &split.clone().into(),
+      &workspace.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let second = test_utils::tiling_window(&split.clone().into(), 2);
+    test_utils::tiling_window(&split.clone().into(), 3);
+    test_utils::tiling_window(&workspace.clone().into(), 4);
+
+    let (first_size, second_size) =
+      (first.tiling_size(), second.tiling_size());
+
+    swap_window(first.clone().into(), &Direction::Right, &mut state)
+      .unwrap();
+
+    // The split's last focused window is swapped, and the split keeps
+    // both of its windows.
+    assert_eq!(first.parent(), Some(split.clone().into()));
+    assert_eq!(first.index(), 0);
+    assert_eq!(second.parent(), Some(workspace.clone().into()));
+    assert_eq!(second.index(), 0);
+    assert_eq!(split.child_count(), 2);
+    assert_eq!(workspace.child_count(), 3);
+
+    // Each position keeps its tiling size.
+    assert!((first.tiling_size() - second_size).abs() < f32::EPSILON);
+    assert!((second.tiling_size() - first_size).abs() < f32::EPSILON);
+  }
+
+  #[test]
+  fn rejects_non_tiling_window() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+    let floating = test_utils::non_tiling_window(
+      &workspace,
+      2,
+      WindowState::Floating(FloatingStateConfig::default()),
+    );
+
+    assert!(swap_window(
+      floating.clone().into(),
+      &Direction::Left,
+      &mut state
+    )
+    .is_err());
+    assert_eq!(floating.parent(), Some(workspace.into()));
+  }
+}
@@ -0,0 +1,479 @@
+use tracing::{info, warn};
+use wm_common::{TilingDirection, UniqueExt, WindowState};
+
+use crate::{
+  commands::window::update_window_state,
+  models::{
+    DirectionContainer, TilingContainer, TilingWindow, WindowContainer,
+    Workspace,
+  },
+  traits::{
+    CommonGetters, PositionGetters, TilingDirectionGetters,
+    TilingSizeGetters, WindowGetters,
+  },
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Tolerance when comparing tiling sizes.
+const SIZE_TOLERANCE: f32 = 0.0001;
+
+/// Keeps tiling windows within the minimum and maximum sizes reported by
+/// their application.
+///
+/// Tiling sizes in workspaces that are pending a redraw are adjusted such
+/// that each window gets at least its minimum and at most its maximum
+/// size, with the difference taken from or given to its siblings. Windows
+/// whose minimum size can't fit are floated if
+/// `size_hints.float_if_too_large` is enabled.
+///
+/// Master-stack workspaces are skipped, since their tiling children are
+/// arranged by the master ratio rather than by their tiling sizes.
+pub fn sync_size_constraints(
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  if !config.value.window_behavior.size_hints.enabled {
+    return Ok(());
+  }
+
+  let workspaces = state
+    .pending_sync
+    .containers_to_redraw()
+    .values()
+    .filter_map(CommonGetters::workspace)
+    .filter(|workspace| !workspace.layout().is_master_stack())
+    .unique_by(CommonGetters::id)
+    .collect::<Vec<_>>();
+
+  for workspace in workspaces {
+    let has_constrained_windows = workspace
+      .descendants()
+      .filter_map(|descendant| descendant.as_tiling_window().cloned())
+      .any(|window| !window.size_constraints().is_unconstrained());
+
+    if has_constrained_windows {
+      fit_workspace(&workspace, state, config)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn fit_workspace(
+  workspace: &Workspace,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let float_if_too_large =
+    config.value.window_behavior.size_hints.float_if_too_large;
+
+  // Windows that are larger than the workspace itself can't be fit by
+  // resizing their siblings.
+  if float_if_too_large {
+    let workspace_rect = workspace.to_rect()?;
+
+    let oversized_windows = workspace
+      .descendants()
+      .filter_map(|descendant| descendant.as_tiling_window().cloned())
+      .filter(|window| {
+        let constraints = window.size_constraints();
+
+        constraints
+          .min_width
+          .is_some_and(|min_width| min_width > workspace_rect.width())
+          || constraints
+            .min_height
+            .is_some_and(|min_height| min_height > workspace_rect.height())
+      })
+      .collect::<Vec<_>>();
+
+    for window in oversized_windows {
+      float_window(window, state, config)?;
+    }
+  }
+
+  // Containers are fit from the outside in, since the length of a split
+  // container depends on its own tiling size.
+  let direction_containers = std::iter::once(workspace.clone().into())
+    .chain(
+      workspace
+        .descendants()
+        .filter_map(|descendant| descendant.as_direction_container().ok()),
+    )
+    .collect::<Vec<DirectionContainer>>();
+
+  for container in direction_containers {
+    let is_stacked = container
+      .as_split()
+      .is_some_and(|split| split.layout().is_stacked());
+
+    if !is_stacked {
+      fit_direction_container(&container, state, config)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Re-reads the size constraints of a window from its application.
+///
+/// Applications report different constraints once their DPI changes, so
+/// this is needed after a window is moved to another monitor, or after
+/// display settings change. Returns whether the constraints changed.
+pub fn refresh_size_constraints(
+  window: &WindowContainer,
+  config: &UserConfig,
+) -> bool {
+  if !config.value.window_behavior.size_hints.enabled {
+    return false;
+  }
+
+  match window.native().size_constraints() {
+    Ok(constraints) if constraints != window.size_constraints() => {
+      window.set_size_constraints(constraints);
+      true
+    }
+    Ok(_) => false,
+    Err(err) => {
+      warn!("Failed to get size constraints: {}", err);
+      false
+    }
+  }
+}
+
+/// Adjusts the tiling sizes of a container's tiling children to their
+/// size constraints.
+fn fit_direction_container(
+  container: &DirectionContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let is_width =
+    container.tiling_direction() == TilingDirection::Horizontal;
+
+  loop {
+    // Containers can get detached when a window is floated.
+    if container.is_detached() {
+      return Ok(());
+    }
+
+    let children = container.tiling_children().collect::<Vec<_>>();
+
+    let Some(first_child) = children.first() else {
+      return Ok(());
+    };
+
+    if children.len() < 2 {
+      return Ok(());
+    }
+
+    let (horizontal_gap, vertical_gap) = first_child.inner_gaps()?;
+    let container_rect = container.to_rect()?;
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    let available_length = if is_width {
+      container_rect.width() - horizontal_gap * (children.len() - 1) as i32
+    } else {
+      container_rect.height() - vertical_gap * (children.len() - 1) as i32
+    };
+
+    if available_length <= 0 {
+      return Ok(());
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let to_size = |length: i32| length as f32 / available_length as f32;
+
+    let mins = children
+      .iter()
+      .map(|child| Ok(to_size(tiling_min_length(child, is_width)?)))
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let maxes = children
+      .iter()
+      .map(|child| tiling_max_length(child, is_width).map(to_size))
+      .collect::<Vec<_>>();
+
+    if mins.iter().all(|&min| min <= 0.)
+      && maxes.iter().all(Option::is_none)
+    {
+      return Ok(());
+    }
+
+    let sizes = children
+      .iter()
+      .map(TilingSizeGetters::tiling_size)
+      .collect::<Vec<_>>();
+
+    if let Some(fitted_sizes) = fit_tiling_sizes(&sizes, &mins, &maxes) {
+      let has_changed =
+        sizes.iter().zip(&fitted_sizes).any(|(size, fitted_size)| {
+          (size - fitted_size).abs() > SIZE_TOLERANCE
+        });
+
+      if has_changed {
+        for (child, fitted_size) in children.iter().zip(fitted_sizes) {
+          child.set_tiling_size(fitted_size);
+        }
+
+        state
+          .pending_sync
+          .queue_containers_to_redraw(container.tiling_children());
+      }
+
+      return Ok(());
+    }
+
+    // The minimum sizes don't fit, so float the window with the largest
+    // minimum size and try again. Otherwise, windows are left to overflow.
+    let window_to_float = config
+      .value
+      .window_behavior
+      .size_hints
+      .float_if_too_large
+      .then(|| {
+        children
+          .iter()
+          .zip(&mins)
+          .filter_map(|(child, min)| {
+            child
+              .as_tiling_window()
+              .map(|window| (window.clone(), *min))
+          })
+          .filter(|(_, min)| *min > 0.)
+          .max_by(|(_, min_a), (_, min_b)| min_a.total_cmp(min_b))
+          .map(|(window, _)| window)
+      })
+      .flatten();
+
+    let Some(window) = window_to_float else {
+      return Ok(());
+    };
+
+    float_window(window, state, config)?;
+  }
+}
+
+fn float_window(
+  window: TilingWindow,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  info!(
+    "Floating window that doesn't fit its minimum size: {}",
+    WindowContainer::from(window.clone())
+  );
+
+  update_window_state(
+    window.into(),
+    WindowState::Floating(
+      config.value.window_behavior.state_defaults.floating.clone(),
+    ),
+    state,
+    config,
+  )?;
+
+  Ok(())
+}
+
+/// Gets the minimum width or height of a tiling container in pixels.
+///
+/// For split containers, this is the space needed to fit the minimum
+/// sizes of all its descendants.
+pub fn tiling_min_length(
+  container: &TilingContainer,
+  is_width: bool,
+) -> anyhow::Result<i32> {
+  match container {
+    TilingContainer::TilingWindow(window) => {
+      Ok(window.size_constraints().min_length(is_width).unwrap_or(0))
+    }
+    TilingContainer::Split(split) => {
+      let child_mins = split
+        .tiling_children()
+        .map(|child| tiling_min_length(&child, is_width))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+      let is_along_axis = !split.layout().is_stacked()
+        && is_width
+          == (split.tiling_direction() == TilingDirection::Horizontal);
+
+      if !is_along_axis {
+        return Ok(child_mins.into_iter().max().unwrap_or(0));
+      }
+
+      let (horizontal_gap, vertical_gap) = split.inner_gaps()?;
+      let gap = if is_width {
+        horizontal_gap
+      } else {
+        vertical_gap
+      };
+
+      #[allow(
+        clippy::cast_possible_wrap,
+        clippy::cast_possible_truncation
+      )]
+      let total_gaps = gap * child_mins.len().saturating_sub(1) as i32;
+
+      Ok(child_mins.into_iter().sum::<i32>() + total_gaps)
+    }
+  }
+}
+
+/// Gets the maximum width or height of a tiling container in pixels.
+///
+/// Split containers have no maximum size.
+pub fn tiling_max_length(
+  container: &TilingContainer,
+  is_width: bool,
+) -> Option<i32> {
+  match container {
+    TilingContainer::TilingWindow(window) => {
+      window.size_constraints().max_length(is_width)
+    }
+    TilingContainer::Split(_) => None,
+  }
+}
+
+/// Fits tiling sizes (which sum up to 1) within the given minimum and
+/// maximum sizes.
+///
+/// Containers that are too small get their minimum size and containers
+/// that are too large get their maximum size, with the rest of the space
+/// distributed in proportion to the current sizes. Maximum sizes are
+/// ignored if they can't fill up the space.
+///
+/// Returns `None` if the minimum sizes don't fit.
+fn fit_tiling_sizes(
+  sizes: &[f32],
+  mins: &[f32],
+  maxes: &[Option<f32>],
+) -> Option<Vec<f32>> {
+  if mins.iter().sum::<f32>() > 1. + SIZE_TOLERANCE {
+    return None;
+  }
+
+  distribute_tiling_sizes(sizes, mins, maxes).or_else(|| {
+    distribute_tiling_sizes(sizes, mins, &vec![None; sizes.len()])
+  })
+}
+
+fn distribute_tiling_sizes(
+  sizes: &[f32],
+  mins: &[f32],
+  maxes: &[Option<f32>],
+) -> Option<Vec<f32>> {
+  // Sizes that have been fixed to their minimum or maximum.
+  let mut fixed_sizes = vec![None::<f32>; sizes.len()];
+
+  loop {
+    let remaining_size = 1. - fixed_sizes.iter().flatten().sum::<f32>();
+
+    let free_size = sizes
+      .iter()
+      .zip(&fixed_sizes)
+      .filter(|(_, fixed_size)| fixed_size.is_none())
+      .map(|(size, _)| size)
+      .sum::<f32>();
+
+    if fixed_sizes.iter().all(Option::is_some) {
+      return (remaining_size.abs() <= SIZE_TOLERANCE)
+        .then(|| fixed_sizes.into_iter().flatten().collect());
+    }
+
+    let candidate_sizes = sizes
+      .iter()
+      .zip(&fixed_sizes)
+      .map(|(size, fixed_size)| {
+        fixed_size
+          .unwrap_or(size / free_size.max(f32::EPSILON) * remaining_size)
+      })
+      .collect::<Vec<_>>();
+
+    let is_free = |index: usize| fixed_sizes[index].is_none();
+
+    // Minimum sizes take precedence, so they're fixed first.
+    let below_min = (0..sizes.len())
+      .filter(|&index| {
+        is_free(index) && candidate_sizes[index] < mins[index]
+      })
+      .collect::<Vec<_>>();
+
+    let above_max = (0..sizes.len())
+      .filter(|&index| {
+        is_free(index)
+          && maxes[index].is_some_and(|max| candidate_sizes[index] > max)
+      })
+      .collect::<Vec<_>>();
+
+    if !below_min.is_empty() {
+      for index in below_min {
+        fixed_sizes[index] = Some(mins[index]);
+      }
+    } else if !above_max.is_empty() {
+      for index in above_max {
+        fixed_sizes[index] = maxes[index].map(|max| max.max(mins[index]));
+      }
+    } else {
+      return Some(candidate_sizes);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_sizes_eq(actual: Option<Vec<f32>>, expected: &[f32]) {
+    let actual = actual.expect("Sizes should fit.");
+
+    assert_eq!(actual.len(), expected.len());
+    for (actual, expected) in actual.iter().zip(expected) {
+      assert!((actual - expected).abs() < 0.001, "{actual} != {expected}");
+    }
+  }
+
+  #[test]
+  fn keeps_sizes_within_constraints() {
+    assert_sizes_eq(
+      fit_tiling_sizes(&[0.5, 0.5], &[0., 0.], &[None, None]),
+      &[0.5, 0.5],
+    );
+  }
+
+  #[test]
+  fn takes_shortfall_from_siblings() {
+    assert_sizes_eq(
+      fit_tiling_sizes(&[0.2, 0.4, 0.4], &[0.4, 0., 0.], &[None; 3]),
+      &[0.4, 0.3, 0.3],
+    );
+  }
+
+  #[test]
+  fn gives_excess_to_siblings() {
+    assert_sizes_eq(
+      fit_tiling_sizes(
+        &[0.5, 0.25, 0.25],
+        &[0., 0., 0.],
+        &[Some(0.2), None, None],
+      ),
+      &[0.2, 0.4, 0.4],
+    );
+  }
+
+  #[test]
+  fn ignores_maxes_that_cannot_fill_space() {
+    assert_sizes_eq(
+      fit_tiling_sizes(&[0.5, 0.5], &[0., 0.], &[Some(0.3), Some(0.3)]),
+      &[0.5, 0.5],
+    );
+  }
+
+  #[test]
+  fn fails_when_mins_do_not_fit() {
+    assert!(
+      fit_tiling_sizes(&[0.5, 0.5], &[0.6, 0.6], &[None; 2]).is_none()
+    );
+  }
+}
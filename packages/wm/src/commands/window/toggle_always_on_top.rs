@@ -0,0 +1,45 @@
+use anyhow::bail;
+use tracing::info;
+use wm_common::{FloatingStateConfig, WindowState, WmEvent};
+
+use crate::{
+  models::WindowContainer,
+  traits::{CommonGetters, WindowGetters},
+  wm_state::WmState,
+};
+
+/// Toggles whether a floating window is shown above all other windows.
+///
+/// The flag is kept as part of the window's floating state, such that it
+/// is reapplied whenever the window is redrawn. It's cleared from the OS
+/// window once the window is no longer floating.
+pub fn toggle_always_on_top(
+  window: &WindowContainer,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let WindowState::Floating(floating_state) = window.state() else {
+    bail!("Only floating windows can be shown always on top.");
+  };
+
+  let is_always_on_top = !floating_state.shown_on_top;
+
+  if is_always_on_top {
+    info!("Showing window always on top: {window}");
+  } else {
+    info!("No longer showing window always on top: {window}");
+  }
+
+  window.set_state(WindowState::Floating(FloatingStateConfig {
+    shown_on_top: is_always_on_top,
+    ..floating_state
+  }));
+
+  state.pending_sync.queue_container_to_redraw(window.clone());
+
+  state.emit_event(WmEvent::WindowAlwaysOnTopChanged {
+    updated_window: window.to_dto()?,
+    is_always_on_top,
+  });
+
+  Ok(())
+}
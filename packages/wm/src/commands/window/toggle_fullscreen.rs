@@ -0,0 +1,163 @@
+use anyhow::Context;
+use wm_common::{FullscreenStateConfig, Rect, WindowState};
+
+use super::update_window_state;
+use crate::{
+  models::WindowContainer,
+  traits::{CommonGetters, PositionGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Toggles a window between fullscreen and its previous state.
+///
+/// The window's tiling position and floating placement are left untouched
+/// while it's fullscreen, so they're restored when toggling back. If the
+/// window changed monitors while fullscreen, it's restored on its current
+/// monitor instead.
+pub fn toggle_fullscreen(
+  window: WindowContainer,
+  maximized: Option<bool>,
+  shown_on_top: Option<bool>,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<WindowContainer> {
+  let fullscreen_defaults =
+    &config.value.window_behavior.state_defaults.fullscreen;
+
+  let target_state = WindowState::Fullscreen(FullscreenStateConfig {
+    maximized: maximized.unwrap_or(fullscreen_defaults.maximized),
+    shown_on_top: shown_on_top.unwrap_or(fullscreen_defaults.shown_on_top),
+  });
+
+  let toggled_state = window.toggled_state(target_state, config);
+
+  if matches!(window.state(), WindowState::Fullscreen(_)) {
+    prepare_restore(&window)?;
+  }
+
+  update_window_state(window, toggled_state, state, config)
+}
+
+/// Adjusts the remembered placement of a fullscreen window for the
+/// monitor it's currently on.
+fn prepare_restore(window: &WindowContainer) -> anyhow::Result<()> {
+  let workspace = window.workspace().context("No workspace.")?;
+
+  if let WindowContainer::NonTilingWindow(window) = window {
+    // Discard the previous tiling position if it's on another workspace,
+    // such that the window is tiled on its current workspace instead.
+    let is_stale_insertion_target =
+      window.insertion_target().is_some_and(|insertion_target| {
+        insertion_target.target_parent.workspace().map(|w| w.id())
+          != Some(workspace.id())
+      });
+
+    if is_stale_insertion_target {
+      window.set_insertion_target(None);
+    }
+  }
+
+  window.set_floating_placement(restored_placement(
+    &window.floating_placement(),
+    &workspace.to_rect()?,
+  ));
+
+  Ok(())
+}
+
+/// Gets the placement to restore a window to after fullscreen.
+///
+/// The original placement is kept if it's still on the workspace, and is
+/// otherwise centered within it.
+fn restored_placement(placement: &Rect, workspace_rect: &Rect) -> Rect {
+  if placement.intersection_area(workspace_rect) > 0 {
+    placement.clone()
+  } else {
+    placement.translate_to_center(workspace_rect)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{models::InsertionTarget, test_utils};
+
+  #[test]
+  fn round_trip_restores_original_placement() {
+    let placement = Rect::from_xy(100, 100, 800, 600);
+    let workspace_rect = Rect::from_xy(0, 0, 1920, 1080);
+
+    assert_eq!(restored_placement(&placement, &workspace_rect), placement);
+  }
+
+  #[test]
+  fn placement_is_centered_after_changing_monitors() {
+    let placement = Rect::from_xy(100, 100, 800, 600);
+    let workspace_rect = Rect::from_xy(1920, 0, 1920, 1080);
+
+    assert_eq!(
+      restored_placement(&placement, &workspace_rect),
+      Rect::from_xy(2480, 240, 800, 600),
+    );
+  }
+
+  #[test]
+  fn restores_on_monitor_changed_to_while_fullscreen() {
+    let (state, _event_rx) = test_utils::state();
+
+    let origin_monitor = test_utils::monitor(&state, 1, 0);
+    let origin_workspace = test_utils::workspace(&origin_monitor, "1");
+    let monitor = test_utils::monitor(&state, 2, 1920);
+    let workspace = test_utils::workspace(&monitor, "2");
+
+    // The window was made fullscreen on the origin monitor, and has since
+    // moved to the other one.
+    let window = test_utils::non_tiling_window(
+      &workspace,
+      1,
+      WindowState::Fullscreen(FullscreenStateConfig::default()),
+    );
+    window.set_insertion_target(Some(InsertionTarget {
+      target_parent: origin_workspace.into(),
+      target_index: 0,
+      prev_tiling_size: 1.0,
+      prev_sibling_count: 0,
+    }));
+
+    let placement = window.floating_placement();
+    prepare_restore(&window.clone().into()).unwrap();
+
+    assert!(window.insertion_target().is_none());
+    assert_eq!(
+      window.floating_placement(),
+      placement.translate_to_center(&workspace.to_rect().unwrap())
+    );
+  }
+
+  #[test]
+  fn keeps_tiling_position_on_same_workspace() {
+    let (state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+
+    let window = test_utils::non_tiling_window(
+      &workspace,
+      1,
+      WindowState::Fullscreen(FullscreenStateConfig::default()),
+    );
+    window.set_insertion_target(Some(InsertionTarget {
+      target_parent: workspace.into(),
+      target_index: 0,
+      prev_tiling_size: 1.0,
+      prev_sibling_count: 0,
+    }));
+
+    let placement = window.floating_placement();
+    prepare_restore(&window.clone().into()).unwrap();
+
+    assert!(window.insertion_target().is_some());
+    assert_eq!(window.floating_placement(), placement);
+  }
+}
@@ -0,0 +1,46 @@
+use anyhow::Context;
+use tracing::info;
+use wm_common::WmEvent;
+
+use crate::{
+  commands::container::set_focused_descendant, models::WindowContainer,
+  traits::CommonGetters, wm_state::WmState,
+};
+
+/// Toggles monocle for a tiling window. While in monocle, the window
+/// fills its workspace and the other tiling windows in the workspace are
+/// hidden.
+///
+/// Monocle is kept per workspace. Turning it on also focuses the window.
+pub fn toggle_monocle(
+  window: &WindowContainer,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  if !window.is_tiling_window() {
+    return Ok(());
+  }
+
+  let workspace = window.workspace().context("No workspace.")?;
+  let is_monocle = workspace.monocle_window_id() == Some(window.id());
+
+  if is_monocle {
+    info!("Exiting monocle for window: {window}");
+    workspace.set_monocle_window_id(None);
+  } else {
+    info!("Entering monocle for window: {window}");
+    workspace.set_monocle_window_id(Some(window.id()));
+
+    set_focused_descendant(&window.clone().into(), None);
+    state.pending_sync.queue_focus_change();
+  }
+
+  state
+    .pending_sync
+    .queue_container_to_redraw(workspace.clone());
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
@@ -3,11 +3,11 @@ use wm_common::{WindowState, WmEvent};
 
 use crate::{
   commands::container::{
-    detach_container, flatten_child_split_containers,
-    set_focused_descendant,
+    attach_container, detach_container, flatten_child_split_containers,
+    resize_tiling_container, set_focused_descendant,
   },
   models::WindowContainer,
-  traits::{CommonGetters, WindowGetters},
+  traits::{CommonGetters, TilingSizeGetters, WindowGetters},
   wm_state::WmState,
 };
 
@@ -22,6 +22,44 @@ pub fn unmanage_window(
   // Get container to switch focus to after the window has been removed.
   let focus_target = state.focus_target_after_removal(&window.clone());
 
+  state.cursor_positions.remove(&window.id());
+  state.initial_frame_positions.remove(&window.id());
+  state.title_rule_matches.remove(&window.id());
+  state.title_bar_removals.remove(&window.id());
+  state.pending_title_changes.remove(&window.id());
+  state.self_hidden_windows.remove(&window.id());
+  state.border_overlays.remove(&window.id());
+  state.pending_closes.remove(&window.id());
+  state.focus_history.remove(window.id());
+
+  // Restore the window's frame in case it's still alive (e.g. when it
+  // became unmanageable).
+  _ = window.native().set_frame_visibility(true);
+
+  // Restore any window that was swallowed by this window into its slot.
+  let swallowed = state.swallowed_windows.remove(&window.id());
+
+  if let Some(swallowed) = &swallowed {
+    attach_container(
+      &swallowed.window.clone().into(),
+      &window.parent().context("No parent.")?,
+      Some(window.index()),
+    )?;
+  }
+
+  let tiling_size = window
+    .as_tiling_container()
+    .map(|window| window.tiling_size())
+    .ok();
+
+  // Focus the restored window if the swallowing window had focus.
+  let focus_target = match &swallowed {
+    Some(swallowed) if focus_target.is_some() => {
+      Some(swallowed.window.clone().into())
+    }
+    _ => focus_target,
+  };
+
   detach_container(window.clone().into())?;
 
   // After detaching the container, flatten any redundant split containers.
@@ -31,6 +69,19 @@ pub fn unmanage_window(
     flatten_child_split_containers(ancestor)?;
   }
 
+  if let Some(swallowed) = &swallowed {
+    if let Some(tiling_size) = tiling_size {
+      resize_tiling_container(
+        &swallowed.window.clone().into(),
+        tiling_size,
+      );
+    }
+
+    state.pending_sync.queue_container_to_redraw(
+      swallowed.window.parent().context("No parent.")?,
+    );
+  }
+
   state.emit_event(WmEvent::WindowUnmanaged {
     unmanaged_id: window.id(),
     unmanaged_handle: window.native().handle,
@@ -2,6 +2,7 @@ use anyhow::Context;
 use tracing::{info, warn};
 use wm_common::WindowState;
 
+use super::apply_floating_rule;
 use crate::{
   commands::container::{
     move_container_within_tree, replace_container, resize_tiling_container,
@@ -29,10 +30,68 @@ pub fn update_window_state(
 
   info!("Updating window state: {:?}.", target_state);
 
-  match target_state {
+  // Opacity and borders depend on the window state, so effects need to
+  // be reapplied.
+  if config.value.window_effects.opacity.enabled
+    || config.value.window_effects.borders.enabled
+  {
+    state.pending_sync.queue_all_effects_update();
+  }
+
+  // Tiling windows that are floated temporarily while being dragged keep
+  // their remembered floating placement.
+  let is_tiling_drag = window
+    .active_drag()
+    .is_some_and(|active_drag| active_drag.is_from_tiling);
+
+  let was_floating = matches!(window.state(), WindowState::Floating(_));
+  let is_floating = matches!(target_state, WindowState::Floating(_));
+
+  // Remember where the window was while floating, such that it gets
+  // placed there again the next time it's floated.
+  if was_floating && !is_tiling_drag {
+    window.set_last_floating_placement(Some(window.floating_placement()));
+  }
+
+  let window = match target_state {
     WindowState::Tiling => set_tiling(&window, state, config),
     _ => set_non_tiling(window, target_state, state),
+  }?;
+
+  if is_floating && !was_floating && !is_tiling_drag {
+    // Windows that haven't floated before are placed based on the
+    // matching window rule, if any.
+    if window.last_floating_placement().is_some() {
+      restore_floating_placement(&window)?;
+    } else if let Some(rule) = config.floating_placement_rule(&window) {
+      apply_floating_rule(&window, &rule, state)?;
+    }
   }
+
+  Ok(window)
+}
+
+/// Moves the window to the placement it last had while floating, clamped
+/// to the working area of its current monitor.
+///
+/// The placement is marked as custom, such that it's used instead of
+/// centering the window. Windows that haven't floated before are left as
+/// is.
+fn restore_floating_placement(
+  window: &WindowContainer,
+) -> anyhow::Result<()> {
+  let Some(placement) = window.last_floating_placement() else {
+    return Ok(());
+  };
+
+  let monitor = window.monitor().context("No monitor.")?;
+
+  window.set_floating_placement(
+    placement.translate_within(&monitor.working_rect()?),
+  );
+  window.set_has_custom_floating_placement(true);
+
+  Ok(())
 }
 
 /// Updates the state of a window to be `WindowState::Tiling`.
@@ -49,13 +108,16 @@ fn set_tiling(
   let workspace =
     window.workspace().context("Window has no workspace.")?;
 
-  // Check whether insertion target is still valid.
+  // Check whether insertion target is still valid. Windows restored on
+  // a hidden workspace keep their position within it.
   let insertion_target =
     window.insertion_target().filter(|insertion_target| {
-      insertion_target
-        .target_parent
-        .workspace()
-        .is_some_and(|workspace| workspace.is_displayed())
+      insertion_target.target_parent.workspace().is_some_and(
+        |target_workspace| {
+          target_workspace.is_displayed()
+            || target_workspace.id() == workspace.id()
+        },
+      )
     });
 
   // Get the position in the tree to insert the new tiling window. This
@@ -124,8 +186,9 @@ fn set_non_tiling(
   state: &mut WmState,
 ) -> anyhow::Result<WindowContainer> {
   // A window can only be updated to a minimized state if it is
-  // natively minimized.
+  // natively minimized, or if it has hidden itself.
   if target_state == WindowState::Minimized
+    && !state.self_hidden_windows.contains(&window.id())
     && !window.native().is_minimized()?
   {
     info!("No window state update. Minimizing window.");
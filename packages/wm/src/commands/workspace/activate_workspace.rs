@@ -1,12 +1,12 @@
 use anyhow::Context;
 use tracing::info;
-use wm_common::{TilingDirection, WmEvent, WorkspaceConfig};
+use wm_common::{Rect, TilingDirection, WmEvent, WorkspaceConfig};
 
 use super::sort_workspaces;
 use crate::{
   commands::container::attach_container,
   models::{Monitor, Workspace},
-  traits::{CommonGetters, PositionGetters},
+  traits::PositionGetters,
   user_config::UserConfig,
   wm_state::WmState,
 };
@@ -35,27 +35,17 @@ pub fn activate_workspace(
     .or_else(|| {
       workspace_config
         .bind_to_monitor
-        .and_then(|index| {
-          state
-            .monitors()
-            .into_iter()
-            .find(|monitor| monitor.index() == index as usize)
-        })
-        .or_else(|| {
-          state
-            .focused_container()
-            .and_then(|focused| focused.monitor())
-        })
+        .as_ref()
+        .and_then(|binding| state.bound_monitor(binding))
+        .or_else(|| state.focused_monitor())
     })
     .context("Failed to get a target monitor for the workspace.")?;
 
-  let monitor_rect = target_monitor.to_rect()?;
-
-  let tiling_direction = if monitor_rect.height() > monitor_rect.width() {
-    TilingDirection::Vertical
-  } else {
-    TilingDirection::Horizontal
-  };
+  let tiling_direction = initial_tiling_direction(
+    &workspace_config,
+    config.value.general.default_tiling_direction.as_ref(),
+    &target_monitor.to_rect()?,
+  );
 
   let workspace = Workspace::new(
     workspace_config.clone(),
@@ -70,7 +60,7 @@ pub fn activate_workspace(
     None,
   )?;
 
-  sort_workspaces(&target_monitor, config)?;
+  sort_workspaces(&target_monitor, config);
 
   info!("Activating workspace: {workspace}");
 
@@ -81,6 +71,29 @@ pub fn activate_workspace(
   Ok(())
 }
 
+/// Gets the tiling direction of a newly activated workspace.
+///
+/// The workspace's own config takes precedence over the global default.
+/// Otherwise, portrait monitors tile vertically and others horizontally.
+fn initial_tiling_direction(
+  workspace_config: &WorkspaceConfig,
+  global_default: Option<&TilingDirection>,
+  monitor_rect: &Rect,
+) -> TilingDirection {
+  workspace_config
+    .default_tiling_direction
+    .as_ref()
+    .or(global_default)
+    .cloned()
+    .unwrap_or_else(|| {
+      if monitor_rect.height() > monitor_rect.width() {
+        TilingDirection::Vertical
+      } else {
+        TilingDirection::Horizontal
+      }
+    })
+}
+
 /// Gets config for the workspace to activate.
 fn workspace_config(
   workspace_name: Option<&str>,
@@ -100,10 +113,7 @@ fn workspace_config(
       }),
     None => target_monitor
       .and_then(|target_monitor| {
-        config.workspace_config_for_monitor(
-          &target_monitor,
-          &state.workspaces(),
-        )
+        config.workspace_config_for_monitor(&target_monitor, state)
       })
       .or_else(|| {
         config.next_inactive_workspace_config(&state.workspaces())
@@ -113,3 +123,62 @@ fn workspace_config(
 
   found_config.cloned()
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::WorkspaceLayout;
+
+  use super::*;
+
+  fn workspace_config(
+    default_tiling_direction: Option<TilingDirection>,
+  ) -> WorkspaceConfig {
+    WorkspaceConfig {
+      name: "1".to_string(),
+      display_name: None,
+      bind_to_monitor: None,
+      monitor_position: None,
+      keep_alive: false,
+      default_tiling_direction,
+      layout: WorkspaceLayout::Manual,
+      master_ratio: 0.55,
+      master_count: 1,
+    }
+  }
+
+  #[test]
+  fn vertical_workspace_tiles_top_to_bottom() {
+    let landscape_rect = Rect::from_xy(0, 0, 1920, 1080);
+
+    assert_eq!(
+      initial_tiling_direction(
+        &workspace_config(Some(TilingDirection::Vertical)),
+        Some(&TilingDirection::Horizontal),
+        &landscape_rect,
+      ),
+      TilingDirection::Vertical,
+    );
+  }
+
+  #[test]
+  fn falls_back_to_global_default_then_orientation() {
+    let portrait_rect = Rect::from_xy(0, 0, 1080, 1920);
+
+    assert_eq!(
+      initial_tiling_direction(
+        &workspace_config(None),
+        Some(&TilingDirection::Horizontal),
+        &portrait_rect,
+      ),
+      TilingDirection::Horizontal,
+    );
+    assert_eq!(
+      initial_tiling_direction(
+        &workspace_config(None),
+        None,
+        &portrait_rect
+      ),
+      TilingDirection::Vertical,
+    );
+  }
+}
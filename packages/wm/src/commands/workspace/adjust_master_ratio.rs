@@ -0,0 +1,53 @@
+use anyhow::{bail, Context};
+use wm_common::{LengthValue, TilingDirection, WmEvent};
+
+use crate::{
+  models::{Container, Workspace},
+  traits::{CommonGetters, PositionGetters, TilingDirectionGetters},
+  wm_state::WmState,
+};
+
+/// Grows the master area of the container's workspace by the given
+/// amount. Percentages are relative to the workspace's length in its
+/// tiling direction.
+pub fn adjust_master_ratio(
+  container: &Container,
+  amount: &LengthValue,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let workspace = container.workspace().context("No workspace.")?;
+
+  if !workspace.layout().is_master_stack() {
+    bail!("Workspace does not use the master-stack layout.");
+  }
+
+  let workspace_rect = workspace.to_rect()?;
+  let workspace_length = match workspace.tiling_direction() {
+    TilingDirection::Horizontal => workspace_rect.width(),
+    TilingDirection::Vertical => workspace_rect.height(),
+  };
+
+  let master_ratio =
+    workspace.master_ratio() + amount.to_percentage(workspace_length);
+
+  set_master_ratio(&workspace, master_ratio, state)
+}
+
+/// Sets the master ratio of a master-stack workspace and redraws it.
+pub fn set_master_ratio(
+  workspace: &Workspace,
+  master_ratio: f32,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  workspace.set_master_ratio(master_ratio);
+
+  state
+    .pending_sync
+    .queue_container_to_redraw(workspace.clone());
+
+  state.emit_event(WmEvent::WorkspaceUpdated {
+    updated_workspace: workspace.to_dto()?,
+  });
+
+  Ok(())
+}
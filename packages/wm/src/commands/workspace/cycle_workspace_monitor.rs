@@ -0,0 +1,58 @@
+use anyhow::Context;
+
+use super::{move_workspace_to_monitor_impl, MoveWorkspaceOptions};
+use crate::{
+  models::Workspace, traits::CommonGetters, user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves the given workspace to the next monitor, wrapping around to the
+/// first monitor after the last one.
+///
+/// Monitors are ordered the same as for `move-workspace --monitor`. This
+/// is a no-op when there's only a single monitor.
+pub fn cycle_workspace_monitor(
+  workspace: &Workspace,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let monitors = state.monitors();
+  let origin_monitor = workspace.monitor().context("No monitor.")?;
+
+  let origin_index = monitors
+    .iter()
+    .position(|monitor| monitor.id() == origin_monitor.id())
+    .context("Monitor not found.")?;
+
+  let target_monitor = &monitors[next_index(origin_index, monitors.len())];
+
+  // Skip if there's no other monitor to move to.
+  if target_monitor.id() == origin_monitor.id() {
+    return Ok(());
+  }
+
+  move_workspace_to_monitor_impl(
+    workspace,
+    target_monitor,
+    state,
+    config,
+    MoveWorkspaceOptions::SINGLE,
+  )
+}
+
+/// Gets the index after `index`, wrapping around to 0 after the last.
+fn next_index(index: usize, len: usize) -> usize {
+  (index + 1) % len
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn wraps_after_last_monitor() {
+    assert_eq!(next_index(0, 3), 1);
+    assert_eq!(next_index(2, 3), 0);
+    assert_eq!(next_index(0, 1), 0);
+  }
+}
@@ -1,4 +1,4 @@
-use anyhow::Context;
+use anyhow::{bail, Context};
 use tracing::info;
 
 use super::activate_workspace;
@@ -46,6 +46,13 @@ pub fn focus_workspace(
   }?;
 
   if let Some(target_workspace) = target_workspace {
+    if target_workspace
+      .monitor()
+      .is_some_and(|monitor| monitor.is_standby())
+    {
+      bail!("Cannot focus workspace on a monitor in standby.");
+    }
+
     info!("Focusing workspace: {target_workspace}");
 
     // Get the currently displayed workspace on the same monitor that the
@@ -0,0 +1,127 @@
+use anyhow::Context;
+use tracing::info;
+
+use super::deactivate_workspace;
+use crate::{
+  commands::{
+    container::set_focused_descendant, window::move_window_to_workspace,
+  },
+  models::WorkspaceTarget,
+  traits::CommonGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves every managed window onto the focused workspace.
+///
+/// Windows keep their state (e.g. floating windows stay floating), and
+/// emptied workspaces are deactivated unless they're `keep_alive` or
+/// displayed. Events of all moves are emitted as a single coalesced
+/// batch.
+pub fn gather_all_windows(
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  let target_workspace =
+    focused_container.workspace().context("No workspace.")?;
+
+  let windows = state
+    .windows()
+    .into_iter()
+    .filter(|window| {
+      window
+        .workspace()
+        .is_some_and(|workspace| workspace.id() != target_workspace.id())
+    })
+    .collect::<Vec<_>>();
+
+  if windows.is_empty() {
+    return Ok(());
+  }
+
+  info!(
+    "Gathering {} windows onto workspace: {target_workspace}",
+    windows.len()
+  );
+
+  state.with_event_batch(|state| {
+    for window in windows {
+      move_window_to_workspace(
+        &window,
+        &WorkspaceTarget::Name(target_workspace.config().name),
+        false,
+        state,
+        config,
+      )?;
+    }
+
+    // Focus is unaffected by the moves since none of the gathered windows
+    // were focused, but is restored in case a move reset it.
+    set_focused_descendant(&focused_container, None);
+
+    let workspaces_to_destroy =
+      state.workspaces().into_iter().filter(|workspace| {
+        !workspace.config().keep_alive
+          && !workspace.has_children()
+          && !workspace.is_displayed()
+      });
+
+    for workspace in workspaces_to_destroy {
+      deactivate_workspace(workspace, state)?;
+    }
+
+    anyhow::Ok(())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{FloatingStateConfig, WindowState};
+
+  use super::*;
+  use crate::{test_utils, traits::WindowGetters};
+
+  #[test]
+  fn gathers_windows_and_deactivates_emptied_workspaces() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let target = test_utils::workspace(&monitor, "1");
+    let origin = test_utils::workspace(&monitor, "2");
+
+    let focused = test_utils::tiling_window(&target.clone().into(), 1);
+    let tiling = test_utils::tiling_window(&origin.clone().into(), 2);
+    let floating = test_utils::non_tiling_window(
+      &origin,
+      3,
+      WindowState::Floating(FloatingStateConfig::default()),
+    );
+    set_focused_descendant(&focused.clone().into(), None);
+
+    gather_all_windows(&mut state, &config).unwrap();
+
+    for native in [tiling.native(), floating.native()] {
+      let window = state.window_from_native(&native).unwrap();
+      assert_eq!(window.workspace().map(|w| w.id()), Some(target.id()));
+    }
+
+    // Windows keep their state.
+    assert!(matches!(
+      state
+        .window_from_native(&floating.native())
+        .unwrap()
+        .state(),
+      WindowState::Floating(_)
+    ));
+
+    assert_eq!(
+      state.focused_container().map(|c| c.id()),
+      Some(focused.id())
+    );
+    assert!(state.workspace_by_name("2").is_none());
+  }
+}
@@ -1,13 +1,23 @@
 mod activate_workspace;
+mod adjust_master_ratio;
+mod cycle_workspace_monitor;
 mod deactivate_workspace;
 mod focus_workspace;
+mod gather_all_windows;
 mod move_workspace_in_direction;
 mod move_workspace_to_monitor;
+mod move_workspaces;
 mod sort_workspaces;
+mod swap_all_monitor_workspaces;
 
 pub use activate_workspace::*;
+pub use adjust_master_ratio::*;
+pub use cycle_workspace_monitor::*;
 pub use deactivate_workspace::*;
 pub use focus_workspace::*;
+pub use gather_all_windows::*;
 pub use move_workspace_in_direction::*;
 pub use move_workspace_to_monitor::*;
+pub use move_workspaces::*;
 pub use sort_workspaces::*;
+pub use swap_all_monitor_workspaces::*;
@@ -38,7 +38,10 @@ pub fn move_workspace_in_direction(
       .filter_map(|descendant| descendant.as_window_container().ok());
 
     for window in windows {
-      window.set_has_pending_dpi_adjustment(true);
+      window.queue_dpi_adjustment(
+        origin_monitor
+          .dpi_adjustment_to(&target_monitor.clone().into())?,
+      );
 
       window.set_floating_placement(
         window
@@ -81,7 +84,7 @@ pub fn move_workspace_in_direction(
       deactivate_workspace(workspace, state)?;
     }
 
-    sort_workspaces(&target_monitor, config)?;
+    sort_workspaces(&target_monitor, config);
 
     state.emit_event(WmEvent::WorkspaceUpdated {
       updated_workspace: workspace.to_dto()?,
@@ -1,15 +1,41 @@
 use anyhow::Context;
-use wm_common::WmEvent;
+use wm_common::{Rect, WmEvent};
 
 use super::{activate_workspace, deactivate_workspace, sort_workspaces};
 use crate::{
   commands::container::move_container_within_tree,
-  models::{Monitor, Workspace},
+  models::{Monitor, PendingDpiAdjustment, Workspace},
   traits::{CommonGetters, PositionGetters, WindowGetters},
   user_config::UserConfig,
   wm_state::WmState,
 };
 
+/// Follow-up steps of a workspace move that only need to run once after
+/// moving several workspaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveWorkspaceOptions {
+  /// Whether to sort the workspaces of the target monitor.
+  pub sort: bool,
+  /// Whether to jump the cursor to the moved workspace.
+  pub jump_cursor: bool,
+}
+
+impl MoveWorkspaceOptions {
+  /// Sorts and jumps the cursor right away, for moving a single
+  /// workspace.
+  pub const SINGLE: Self = Self {
+    sort: true,
+    jump_cursor: true,
+  };
+
+  /// Leaves sorting and the cursor jump to the caller, for moving several
+  /// workspaces at once.
+  pub const DEFERRED: Self = Self {
+    sort: false,
+    jump_cursor: false,
+  };
+}
+
 /// Moves the given workspace to the target monitor by its index.
 pub fn move_workspace_to_monitor(
   workspace: &Workspace,
@@ -17,11 +43,7 @@ pub fn move_workspace_to_monitor(
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
-  let monitors = state.monitors();
-
-  let target_monitor = monitors.get(monitor_index).with_context(|| {
-    format!("Monitor at index {monitor_index} was not found.")
-  })?;
+  let target_monitor = state.active_monitor_by_index(monitor_index)?;
 
   // Skip if already on the target monitor.
   let origin_monitor = workspace.monitor().context("No monitor.")?;
@@ -29,15 +51,41 @@ pub fn move_workspace_to_monitor(
     return Ok(());
   }
 
-  move_workspace_to_monitor_impl(workspace, target_monitor, state, config)
+  move_workspace_to_monitor_impl(
+    workspace,
+    &target_monitor,
+    state,
+    config,
+    MoveWorkspaceOptions::SINGLE,
+  )
 }
 
 /// Internal implementation for moving a workspace to a specific monitor.
+///
+/// Callers moving several workspaces at once can skip sorting and the
+/// cursor jump via `MoveWorkspaceOptions::DEFERRED`, and instead sort
+/// each monitor and jump the cursor once afterwards.
+///
+/// Events of the move (including workspaces activated or destroyed to
+/// keep monitors populated) are emitted as a single coalesced batch.
 pub fn move_workspace_to_monitor_impl(
   workspace: &Workspace,
   target_monitor: &Monitor,
   state: &mut WmState,
   config: &UserConfig,
+  options: MoveWorkspaceOptions,
+) -> anyhow::Result<()> {
+  state.with_event_batch(|state| {
+    move_workspace(workspace, target_monitor, state, config, options)
+  })
+}
+
+fn move_workspace(
+  workspace: &Workspace,
+  target_monitor: &Monitor,
+  state: &mut WmState,
+  config: &UserConfig,
+  options: MoveWorkspaceOptions,
 ) -> anyhow::Result<()> {
   let origin_monitor = workspace.monitor().context("No monitor.")?;
 
@@ -48,19 +96,11 @@ pub fn move_workspace_to_monitor_impl(
     state,
   )?;
 
-  let windows = workspace
-    .descendants()
-    .filter_map(|descendant| descendant.as_window_container().ok());
-
-  for window in windows {
-    window.set_has_pending_dpi_adjustment(true);
-
-    window.set_floating_placement(
-      window
-        .floating_placement()
-        .translate_to_center(&workspace.to_rect()?),
-    );
-  }
+  update_moved_windows(
+    workspace,
+    origin_monitor.dpi_adjustment_to(&target_monitor.clone().into())?,
+    &workspace.to_rect()?,
+  );
 
   // Get currently displayed workspace on the target monitor.
   let displayed_workspace = target_monitor
@@ -69,10 +109,13 @@ pub fn move_workspace_to_monitor_impl(
 
   state
     .pending_sync
-    .queue_cursor_jump()
     .queue_container_to_redraw(workspace.clone())
     .queue_container_to_redraw(displayed_workspace);
 
+  if options.jump_cursor {
+    state.pending_sync.queue_cursor_jump();
+  }
+
   match origin_monitor.child_count() {
     0 => {
       // Prevent origin monitor from having no workspaces.
@@ -91,17 +134,20 @@ pub fn move_workspace_to_monitor_impl(
   // Get empty workspace to destroy (if one is found). Cannot destroy
   // empty workspaces if they're the only workspace on the monitor.
   let workspace_to_destroy =
-    target_monitor.workspaces().into_iter().find(|workspace| {
-      !workspace.config().keep_alive
-        && !workspace.has_children()
-        && !workspace.is_displayed()
+    target_monitor.workspaces().into_iter().find(|other| {
+      other.id() != workspace.id()
+        && !other.config().keep_alive
+        && !other.has_children()
+        && !other.is_displayed()
     });
 
   if let Some(workspace) = workspace_to_destroy {
     deactivate_workspace(workspace, state)?;
   }
 
-  sort_workspaces(target_monitor, config)?;
+  if options.sort {
+    sort_workspaces(target_monitor, config);
+  }
 
   state.emit_event(WmEvent::WorkspaceUpdated {
     updated_workspace: workspace.to_dto()?,
@@ -109,3 +155,193 @@ pub fn move_workspace_to_monitor_impl(
 
   Ok(())
 }
+
+/// Queues the DPI adjustment for every window of a moved workspace, and
+/// recenters their floating placements within the workspace.
+///
+/// The adjustment and workspace rect are the same for every window, and
+/// are resolved once by the caller since each involves querying the OS.
+fn update_moved_windows(
+  workspace: &Workspace,
+  dpi_adjustment: PendingDpiAdjustment,
+  workspace_rect: &Rect,
+) {
+  let windows = workspace
+    .descendants()
+    .filter_map(|descendant| descendant.as_window_container().ok());
+
+  for window in windows {
+    window.queue_dpi_adjustment(dpi_adjustment);
+    window.set_floating_placement(
+      window
+        .floating_placement()
+        .translate_to_center(workspace_rect),
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::ParsedConfig;
+
+  use super::*;
+  use crate::{commands::container::set_focused_descendant, test_utils};
+
+  /// Builds a tree of two monitors with a workspace each, plus an
+  /// unfocused workspace holding `window_count` tiling windows on the
+  /// first monitor.
+  fn synthetic_tree(window_count: usize) -> (WmState, Monitor, Workspace) {
+    let (state, _event_rx) = test_utils::state();
+
+    let origin_monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&origin_monitor, "origin");
+    let target_monitor = test_utils::monitor(&state, 2, 1920);
+    test_utils::workspace(&target_monitor, "target");
+
+    let moved_workspace = test_utils::workspace_with_windows(
+      &origin_monitor,
+      "moved",
+      window_count,
+    );
+
+    (state, target_monitor, moved_workspace)
+  }
+
+  fn window_handles(workspace: &Workspace) -> Vec<isize> {
+    workspace
+      .descendants()
+      .filter_map(|container| container.as_window_container().ok())
+      .map(|window| window.native().handle)
+      .collect()
+  }
+
+  #[test]
+  fn updates_every_moved_window() {
+    let (state, target_monitor, moved_workspace) = synthetic_tree(3);
+    let adjustment = PendingDpiAdjustment::new(1.0, 1.5);
+
+    move_container_within_tree(
+      &moved_workspace.clone().into(),
+      &target_monitor.clone().into(),
+      target_monitor.child_count(),
+      &state,
+    )
+    .unwrap();
+
+    update_moved_windows(
+      &moved_workspace,
+      adjustment,
+      &Rect::from_xy(1000, 0, 1000, 1000),
+    );
+
+    assert_eq!(
+      moved_workspace.monitor().map(|monitor| monitor.id()),
+      Some(target_monitor.id())
+    );
+
+    for window in moved_workspace
+      .descendants()
+      .filter_map(|container| container.as_window_container().ok())
+    {
+      assert_eq!(window.take_pending_dpi_adjustment(), Some(adjustment));
+      assert_eq!(
+        window.floating_placement(),
+        Rect::from_xy(1300, 350, 400, 300)
+      );
+    }
+  }
+
+  #[test]
+  fn moves_workspace_with_many_windows() {
+    let (state, target_monitor, moved_workspace) = synthetic_tree(4000);
+    let handles = window_handles(&moved_workspace);
+
+    move_container_within_tree(
+      &moved_workspace.clone().into(),
+      &target_monitor.clone().into(),
+      target_monitor.child_count(),
+      &state,
+    )
+    .unwrap();
+
+    // The workspace is moved as a whole, keeping its windows in order.
+    assert_eq!(window_handles(&moved_workspace), handles);
+    assert_eq!(
+      target_monitor
+        .workspaces()
+        .iter()
+        .map(|workspace| workspace.config().name)
+        .collect::<Vec<_>>(),
+      ["target", "moved"]
+    );
+    assert!(moved_workspace
+      .descendants()
+      .filter_map(|container| container.as_window_container().ok())
+      .all(|window| window
+        .monitor()
+        .is_some_and(|monitor| monitor.id() == target_monitor.id())));
+  }
+
+  #[test]
+  fn emits_coalesced_events_within_outer_batch() {
+    let (mut state, mut event_rx) = test_utils::state();
+
+    let workspaces = ["moved", "target", "empty", "spare"]
+      .into_iter()
+      .map(|name| {
+        serde_yaml::from_str(&format!("name: '{name}'")).unwrap()
+      })
+      .collect();
+
+    let config = UserConfig::from_value(ParsedConfig {
+      workspaces,
+      ..ParsedConfig::default()
+    });
+
+    let origin_monitor = test_utils::monitor(&state, 1, 0);
+    let moved_workspace = test_utils::workspace(&origin_monitor, "moved");
+    let target_monitor = test_utils::monitor(&state, 2, 1920);
+    let target_workspace =
+      test_utils::workspace(&target_monitor, "target");
+    let empty_workspace = test_utils::workspace(&target_monitor, "empty");
+
+    // Keep the moved workspace hidden on the target monitor, by having
+    // focus there already.
+    set_focused_descendant(&target_workspace.into(), None);
+
+    state.with_event_batch(|state| {
+      move_workspace_to_monitor_impl(
+        &moved_workspace,
+        &target_monitor,
+        state,
+        &config,
+        MoveWorkspaceOptions::SINGLE,
+      )
+      .unwrap();
+
+      // The move's own batch is merged into the outer one, so nothing
+      // is emitted until the outer batch ends.
+      assert!(test_utils::emitted_events(&mut event_rx).is_empty());
+    });
+
+    let spare_workspace = origin_monitor.displayed_workspace().unwrap();
+    assert_eq!(spare_workspace.config().name, "spare");
+
+    // The emptied origin monitor gets a workspace activated, and the
+    // target monitor's empty, hidden workspace is destroyed.
+    let events = test_utils::emitted_events(&mut event_rx);
+    assert!(
+      matches!(
+        events.as_slice(),
+        [
+          WmEvent::WorkspaceActivated { activated_workspace },
+          WmEvent::WorkspaceDeactivated { deactivated_id, .. },
+          WmEvent::WorkspaceUpdated { updated_workspace },
+        ] if activated_workspace.id() == spare_workspace.id()
+          && *deactivated_id == empty_workspace.id()
+          && updated_workspace.id() == moved_workspace.id()
+      ),
+      "Unexpected events: {events:?}"
+    );
+  }
+}
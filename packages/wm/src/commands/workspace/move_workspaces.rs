@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use uuid::Uuid;
+use wm_common::WorkspaceMove;
+
+use super::{
+  deactivate_workspace, move_workspace_to_monitor_impl, sort_workspaces,
+  MoveWorkspaceOptions,
+};
+use crate::{
+  commands::container::set_focused_descendant,
+  models::{Monitor, Workspace},
+  traits::CommonGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Moves multiple workspaces between monitors as a single operation.
+///
+/// All moves are validated up-front via `validate_workspace_moves`, and
+/// none are applied if any is invalid. The focused container stays
+/// focused, and the cursor jumps at most once at the end. Events of all
+/// moves are emitted as a single coalesced batch.
+pub fn move_workspaces(
+  moves: &[WorkspaceMove],
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let planned_moves = validate_workspace_moves(moves, state)?;
+
+  if planned_moves.is_empty() {
+    return Ok(());
+  }
+
+  state.with_event_batch(|state| {
+    apply_workspace_moves(&planned_moves, state, config)
+  })
+}
+
+fn apply_workspace_moves(
+  planned_moves: &[(Workspace, Monitor)],
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  // Get every monitor that a workspace is moved to or from.
+  let mut affected_monitors = Vec::<Monitor>::new();
+
+  for (workspace, target_monitor) in planned_moves {
+    let origin_monitor = workspace.monitor().context("No monitor.")?;
+
+    for monitor in [origin_monitor, target_monitor.clone()] {
+      if !affected_monitors.iter().any(|m| m.id() == monitor.id()) {
+        affected_monitors.push(monitor);
+      }
+    }
+  }
+
+  for (workspace, target_monitor) in planned_moves {
+    move_workspace_to_monitor_impl(
+      workspace,
+      target_monitor,
+      state,
+      config,
+      MoveWorkspaceOptions::DEFERRED,
+    )?;
+  }
+
+  set_focused_descendant(&focused_container, None);
+
+  for monitor in &affected_monitors {
+    // Clean up any empty workspace that was activated to fill a monitor
+    // while its workspaces were being moved.
+    let workspaces_to_destroy =
+      monitor.workspaces().into_iter().filter(|workspace| {
+        !workspace.config().keep_alive
+          && !workspace.has_children()
+          && !workspace.is_displayed()
+      });
+
+    for workspace in workspaces_to_destroy {
+      deactivate_workspace(workspace, state)?;
+    }
+
+    sort_workspaces(monitor, config);
+
+    state.pending_sync.queue_container_to_redraw(
+      monitor
+        .displayed_workspace()
+        .context("No displayed workspace.")?,
+    );
+  }
+
+  state.pending_sync.queue_focus_change().queue_cursor_jump();
+
+  Ok(())
+}
+
+/// Checks that a batch of workspace moves can be applied, without
+/// modifying any state.
+///
+/// Returns the workspaces to move along with their target monitors, in
+/// the order they should be moved. Moves of workspaces that are already
+/// on their target monitor are omitted.
+pub fn validate_workspace_moves(
+  moves: &[WorkspaceMove],
+  state: &WmState,
+) -> anyhow::Result<Vec<(Workspace, Monitor)>> {
+  let mut planned_moves = Vec::<(Workspace, Monitor)>::new();
+
+  for workspace_move in moves {
+    let workspace = state
+      .container_by_id(workspace_move.workspace_id)
+      .and_then(|container| container.as_workspace().cloned())
+      .with_context(|| {
+        format!(
+          "No workspace found with the ID '{}'.",
+          workspace_move.workspace_id
+        )
+      })?;
+
+    let target_monitor = state
+      .container_by_id(workspace_move.monitor_id)
+      .and_then(|container| container.as_monitor().cloned())
+      .with_context(|| {
+        format!(
+          "No monitor found with the ID '{}'.",
+          workspace_move.monitor_id
+        )
+      })?;
+
+    if moves
+      .iter()
+      .filter(|other| other.workspace_id == workspace.id())
+      .count()
+      > 1
+    {
+      bail!(
+        "Workspace '{}' is moved more than once.",
+        workspace.config().name
+      );
+    }
+
+    let origin_monitor = workspace.monitor().context("No monitor.")?;
+
+    if origin_monitor.id() != target_monitor.id() {
+      planned_moves.push((workspace, target_monitor));
+    }
+  }
+
+  let workspace_counts = state
+    .monitors()
+    .into_iter()
+    .map(|monitor| (monitor.id(), monitor.child_count()))
+    .collect::<HashMap<_, _>>();
+
+  let monitor_ids = planned_moves
+    .iter()
+    .map(|(workspace, target_monitor)| {
+      let origin_monitor = workspace.monitor().context("No monitor.")?;
+      Ok((origin_monitor.id(), target_monitor.id()))
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+  let order = move_order(&workspace_counts, &monitor_ids)?;
+
+  Ok(
+    order
+      .into_iter()
+      .map(|index| planned_moves[index].clone())
+      .collect(),
+  )
+}
+
+/// Gets the order in which to apply moves, given as pairs of origin and
+/// target monitor IDs.
+///
+/// Moves off of monitors with other workspaces remaining are applied
+/// first, so that monitors run out of workspaces midway only if the
+/// moves form a cycle. Errors if a monitor would be left without any
+/// workspaces after all moves.
+fn move_order(
+  workspace_counts: &HashMap<Uuid, usize>,
+  monitor_ids: &[(Uuid, Uuid)],
+) -> anyhow::Result<Vec<usize>> {
+  let mut counts = workspace_counts.clone();
+
+  for (origin_id, target_id) in monitor_ids {
+    *counts.entry(*origin_id).or_default() -= 1;
+    *counts.entry(*target_id).or_default() += 1;
+  }
+
+  if let Some((monitor_id, _)) =
+    counts.iter().find(|(_, count)| **count == 0)
+  {
+    bail!("Monitor '{monitor_id}' would be left without any workspaces.");
+  }
+
+  let mut counts = workspace_counts.clone();
+  let mut remaining = (0..monitor_ids.len()).collect::<Vec<_>>();
+  let mut order = Vec::with_capacity(monitor_ids.len());
+
+  while !remaining.is_empty() {
+    let position = remaining
+      .iter()
+      .position(|index| {
+        counts.get(&monitor_ids[*index].0).copied().unwrap_or(0) > 1
+      })
+      .unwrap_or(0);
+
+    let index = remaining.remove(position);
+    let (origin_id, target_id) = monitor_ids[index];
+
+    *counts.entry(origin_id).or_default() -= 1;
+    *counts.entry(target_id).or_default() += 1;
+    order.push(index);
+  }
+
+  Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils;
+
+  fn workspace_move(
+    workspace: &Workspace,
+    monitor: &Monitor,
+  ) -> WorkspaceMove {
+    WorkspaceMove {
+      workspace_id: workspace.id(),
+      monitor_id: monitor.id(),
+    }
+  }
+
+  #[test]
+  fn moves_off_crowded_monitors_first() {
+    let monitor_a = Uuid::new_v4();
+    let monitor_b = Uuid::new_v4();
+    let counts = HashMap::from([(monitor_a, 1), (monitor_b, 2)]);
+
+    // Moving the only workspace off of monitor A first would leave it
+    // empty, so the move onto it is applied first.
+    let order = move_order(
+      &counts,
+      &[(monitor_a, monitor_b), (monitor_b, monitor_a)],
+    )
+    .unwrap();
+
+    assert_eq!(order, vec![1, 0]);
+  }
+
+  #[test]
+  fn rejects_leaving_monitor_empty() {
+    let monitor_a = Uuid::new_v4();
+    let monitor_b = Uuid::new_v4();
+    let counts = HashMap::from([(monitor_a, 1), (monitor_b, 1)]);
+
+    assert!(move_order(&counts, &[(monitor_a, monitor_b)]).is_err());
+  }
+
+  #[test]
+  fn moves_workspaces_and_keeps_focus() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor_a = test_utils::monitor(&state, 1, 0);
+    let focused_workspace = test_utils::workspace(&monitor_a, "1");
+    let focused =
+      test_utils::tiling_window(&focused_workspace.clone().into(), 1);
+    let moved_workspace = test_utils::workspace(&monitor_a, "2");
+    test_utils::tiling_window(&moved_workspace.clone().into(), 2);
+
+    let monitor_b = test_utils::monitor(&state, 2, 1920);
+    let other_workspace = test_utils::workspace(&monitor_b, "3");
+    test_utils::tiling_window(&other_workspace.into(), 3);
+
+    set_focused_descendant(&focused.clone().into(), None);
+
+    move_workspaces(
+      &[workspace_move(&moved_workspace, &monitor_b)],
+      &mut state,
+      &config,
+    )
+    .unwrap();
+
+    assert_eq!(
+      moved_workspace.monitor().map(|monitor| monitor.id()),
+      Some(monitor_b.id())
+    );
+    assert_eq!(monitor_a.child_count(), 1);
+    assert_eq!(
+      state.focused_container().map(|container| container.id()),
+      Some(focused.id())
+    );
+  }
+
+  #[test]
+  fn applies_no_moves_if_any_is_invalid() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor_a = test_utils::monitor(&state, 1, 0);
+    let workspace_a = test_utils::workspace(&monitor_a, "1");
+    let extra_workspace = test_utils::workspace(&monitor_a, "2");
+    let monitor_b = test_utils::monitor(&state, 2, 1920);
+    let workspace_b = test_utils::workspace(&monitor_b, "3");
+
+    // Moving both workspaces off of monitor A would leave it empty.
+    let result = move_workspaces(
+      &[
+        workspace_move(&workspace_a, &monitor_b),
+        workspace_move(&extra_workspace, &monitor_b),
+      ],
+      &mut state,
+      &config,
+    );
+
+    assert!(result.is_err());
+    for (workspace, monitor) in [
+      (&workspace_a, &monitor_a),
+      (&extra_workspace, &monitor_a),
+      (&workspace_b, &monitor_b),
+    ] {
+      assert_eq!(workspace.monitor().map(|m| m.id()), Some(monitor.id()));
+    }
+  }
+}
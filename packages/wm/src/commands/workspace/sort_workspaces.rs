@@ -1,4 +1,3 @@
-use anyhow::Context;
 use wm_common::VecDequeExt;
 
 use crate::{
@@ -6,23 +5,17 @@ use crate::{
 };
 
 /// Sorts a monitor's workspaces by config order.
-pub fn sort_workspaces(
-  monitor: &Monitor,
-  config: &UserConfig,
-) -> anyhow::Result<()> {
+///
+/// See `UserConfig::sort_workspaces` for how workspaces are ordered.
+pub fn sort_workspaces(monitor: &Monitor, config: &UserConfig) {
   let mut workspaces = monitor.workspaces();
   config.sort_workspaces(&mut workspaces);
 
-  for workspace in &workspaces {
-    let target_index = &workspaces
-      .iter()
-      .position(|sorted_workspace| sorted_workspace.id() == workspace.id())
-      .context("Failed to get workspace target index.")?;
-
+  // Moving each workspace into place in sorted order leaves the ones
+  // before it untouched.
+  for (target_index, workspace) in workspaces.into_iter().enumerate() {
     monitor
       .borrow_children_mut()
-      .shift_to_index(*target_index, workspace.clone().into());
+      .shift_to_index(target_index, workspace.into());
   }
-
-  Ok(())
 }
@@ -0,0 +1,215 @@
+use anyhow::Context;
+use tracing::warn;
+
+use super::{
+  deactivate_workspace, move_workspace_to_monitor_impl, sort_workspaces,
+  MoveWorkspaceOptions,
+};
+use crate::{
+  commands::container::set_focused_descendant,
+  models::{Monitor, Workspace},
+  traits::CommonGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Exchanges every workspace between two monitors by their indices.
+///
+/// Each monitor ends up displaying the workspace that was displayed on
+/// the other monitor, and the focused container stays focused.
+pub fn swap_all_monitor_workspaces(
+  monitor_indices: (usize, usize),
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let get_monitor = |index: usize| {
+    state
+      .monitor_by_index(index)
+      .with_context(|| format!("Monitor at index {index} was not found."))
+  };
+
+  let monitor_a = get_monitor(monitor_indices.0)?;
+  let monitor_b = get_monitor(monitor_indices.1)?;
+
+  if monitor_a.id() == monitor_b.id() {
+    return Ok(());
+  }
+
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  let displayed_a = monitor_a
+    .displayed_workspace()
+    .context("No displayed workspace.")?;
+
+  let displayed_b = monitor_b
+    .displayed_workspace()
+    .context("No displayed workspace.")?;
+
+  let others_a = without(monitor_a.workspaces(), &displayed_a);
+  let others_b = without(monitor_b.workspaces(), &displayed_b);
+
+  // Move the displayed workspaces last so that neither monitor runs out
+  // of workspaces midway.
+  let moves = others_a
+    .into_iter()
+    .map(|workspace| (workspace, &monitor_b))
+    .chain(
+      others_b
+        .into_iter()
+        .map(|workspace| (workspace, &monitor_a)),
+    )
+    .chain([
+      (displayed_a.clone(), &monitor_b),
+      (displayed_b.clone(), &monitor_a),
+    ]);
+
+  for (workspace, target_monitor) in moves {
+    warn_if_bound(&workspace, target_monitor, state);
+
+    move_workspace_to_monitor_impl(
+      &workspace,
+      target_monitor,
+      state,
+      config,
+      MoveWorkspaceOptions::DEFERRED,
+    )?;
+  }
+
+  // Show the previously displayed workspaces on their new monitors.
+  for (monitor, workspace) in
+    [(&monitor_a, &displayed_b), (&monitor_b, &displayed_a)]
+  {
+    set_focused_descendant(
+      &workspace.clone().into(),
+      Some(&monitor.clone().into()),
+    );
+  }
+
+  set_focused_descendant(&focused_container, None);
+
+  for monitor in [&monitor_a, &monitor_b] {
+    // Clean up any empty workspace that was activated to fill a monitor
+    // while its workspaces were being moved.
+    let workspaces_to_destroy =
+      monitor.workspaces().into_iter().filter(|workspace| {
+        !workspace.config().keep_alive
+          && !workspace.has_children()
+          && !workspace.is_displayed()
+      });
+
+    for workspace in workspaces_to_destroy {
+      deactivate_workspace(workspace, state)?;
+    }
+
+    sort_workspaces(monitor, config);
+  }
+
+  state
+    .pending_sync
+    .queue_focus_change()
+    .queue_cursor_jump()
+    .queue_container_to_redraw(displayed_a)
+    .queue_container_to_redraw(displayed_b);
+
+  Ok(())
+}
+
+fn without(
+  workspaces: Vec<Workspace>,
+  excluded: &Workspace,
+) -> Vec<Workspace> {
+  workspaces
+    .into_iter()
+    .filter(|workspace| workspace.id() != excluded.id())
+    .collect()
+}
+
+/// Logs a warning if the workspace is bound to a monitor other than the
+/// one it's being moved to. The binding is re-applied when monitors are
+/// next added.
+fn warn_if_bound(
+  workspace: &Workspace,
+  target_monitor: &Monitor,
+  state: &WmState,
+) {
+  let workspace_config = workspace.config();
+
+  let Some(bound_monitor) = workspace_config
+    .bind_to_monitor
+    .as_ref()
+    .and_then(|binding| state.bound_monitor(binding))
+  else {
+    return;
+  };
+
+  if bound_monitor.id() != target_monitor.id() {
+    warn!(
+      "Workspace '{}' is bound to monitor {} but is being moved to \
+      monitor {}.",
+      workspace_config.name,
+      bound_monitor.index(),
+      target_monitor.index()
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_utils;
+
+  fn workspace_names(monitor: &Monitor) -> Vec<String> {
+    let mut names = monitor
+      .workspaces()
+      .iter()
+      .map(|workspace| workspace.config().name)
+      .collect::<Vec<_>>();
+
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn swaps_workspaces_and_keeps_focus() {
+    let (mut state, _event_rx) = test_utils::state();
+    let config = test_utils::config();
+
+    let monitor_a = test_utils::monitor(&state, 1, 0);
+    let monitor_b = test_utils::monitor(&state, 2, 1920);
+
+    let mut windows = Vec::new();
+    for (monitor, names) in
+      [(&monitor_a, ["1", "2"]), (&monitor_b, ["3", "4"])]
+    {
+      for (handle, name) in (windows.len()..).zip(names) {
+        let workspace = test_utils::workspace(monitor, name);
+        windows.push(test_utils::tiling_window(
+          &workspace.into(),
+          isize::try_from(handle).unwrap(),
+        ));
+      }
+    }
+
+    set_focused_descendant(&windows[0].clone().into(), None);
+
+    swap_all_monitor_workspaces((0, 1), &mut state, &config).unwrap();
+
+    assert_eq!(workspace_names(&monitor_a), ["3", "4"]);
+    assert_eq!(workspace_names(&monitor_b), ["1", "2"]);
+
+    // Each monitor displays the workspace the other one displayed.
+    assert_eq!(
+      monitor_a.displayed_workspace().map(|w| w.config().name),
+      Some("3".to_string())
+    );
+    assert_eq!(
+      monitor_b.displayed_workspace().map(|w| w.config().name),
+      Some("1".to_string())
+    );
+    assert_eq!(
+      state.focused_container().map(|focused| focused.id()),
+      Some(windows[0].id())
+    );
+  }
+}
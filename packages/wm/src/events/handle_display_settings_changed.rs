@@ -1,16 +1,22 @@
+use std::collections::HashMap;
+
 use anyhow::Context;
 use tracing::info;
+use wm_common::WmEvent;
 use wm_platform::Platform;
 
 use crate::{
   commands::monitor::{
-    add_monitor, remove_monitor, sort_monitors, update_monitor,
+    add_monitor, remove_monitor, resume_monitor, sort_monitors,
+    standby_monitor, update_monitor,
   },
+  models::PendingDpiAdjustment,
   traits::{CommonGetters, PositionGetters, WindowGetters},
   user_config::UserConfig,
   wm_state::WmState,
 };
 
+#[allow(clippy::too_many_lines)]
 pub fn handle_display_settings_changed(
   state: &mut WmState,
   config: &UserConfig,
@@ -26,6 +32,14 @@ pub fn handle_display_settings_changed(
     .cloned()
     .collect::<Vec<_>>();
 
+  // Snapshot the existing monitors to later diff against for
+  // `WmEvent::MonitorUpdated`.
+  let mut monitor_snapshots = state
+    .monitors()
+    .into_iter()
+    .map(|monitor| anyhow::Ok((monitor.id(), monitor.snapshot()?)))
+    .try_collect::<HashMap<_, _>>()?;
+
   let mut pending_monitors = state.monitors();
   let mut new_native_monitors = Vec::new();
 
@@ -76,7 +90,7 @@ pub fn handle_display_settings_changed(
           pending_monitors.remove(index);
         }
 
-        update_monitor(&found_monitor, native_monitor, state)?;
+        update_monitor(&found_monitor, native_monitor);
       }
       None => {
         new_native_monitors.push(native_monitor);
@@ -88,22 +102,58 @@ pub fn handle_display_settings_changed(
     match pending_monitors.first() {
       Some(_) => {
         let monitor = pending_monitors.remove(0);
-        update_monitor(&monitor, native_monitor, state)
+        update_monitor(&monitor, native_monitor);
+        Ok(())
       }
       // Add monitor if it doesn't exist in state.
       None => add_monitor(native_monitor, state, config),
     }?;
   }
 
+  // Resume monitors that were put in standby on disconnect and have
+  // since been matched to a connected monitor.
+  for monitor in state.monitors() {
+    let is_reconnected = monitor.is_disconnected()
+      && !pending_monitors.iter().any(|m| m.id() == monitor.id());
+
+    if is_reconnected {
+      monitor.set_disconnected(false);
+      resume_monitor(&monitor, state)?;
+
+      // `resume_monitor` already emits the standby change.
+      if let Some(snapshot) = monitor_snapshots.get_mut(&monitor.id()) {
+        snapshot.is_standby = false;
+      }
+    }
+  }
+
   // Remove any monitors that no longer exist and move their workspaces
-  // to other monitors.
+  // to other monitors, or put them in standby if enabled.
   //
   // Prevent removal of the last monitor (i.e. for when all monitors are
   // disconnected). This will cause the WM's monitors to mismatch the OS
   // monitor state, however, it'll be updated correctly when a new monitor
   // is connected again.
   for pending_monitor in pending_monitors {
-    if state.monitors().len() != 1 {
+    if config.value.general.standby_disconnected_monitors {
+      let has_other_active = state.monitors_iter().any(|monitor| {
+        monitor.id() != pending_monitor.id() && !monitor.is_standby()
+      });
+
+      // Monitors already in standby are left as is, such that they
+      // aren't resumed on reconnect.
+      if has_other_active && !pending_monitor.is_standby() {
+        standby_monitor(&pending_monitor, state)?;
+        pending_monitor.set_disconnected(true);
+
+        // `standby_monitor` already emits the standby change.
+        if let Some(snapshot) =
+          monitor_snapshots.get_mut(&pending_monitor.id())
+        {
+          snapshot.is_standby = true;
+        }
+      }
+    } else if state.monitors().len() != 1 {
       remove_monitor(pending_monitor, state, config)?;
     }
   }
@@ -111,10 +161,34 @@ pub fn handle_display_settings_changed(
   // Sort monitors by position.
   sort_monitors(&state.root_container)?;
 
+  // Emit update events for monitors that had any of their properties
+  // changed (including their index from re-sorting).
+  for monitor in state.monitors() {
+    if let Some(snapshot) = monitor_snapshots.get(&monitor.id()) {
+      let changed = snapshot.changed_fields(&monitor.snapshot()?);
+
+      if !changed.is_empty() {
+        state.emit_event(WmEvent::MonitorUpdated {
+          updated_monitor: monitor.to_dto()?,
+          changed,
+        });
+      }
+    }
+  }
+
   for window in state.windows() {
+    // Windows on a monitor in standby are left as they were, since the
+    // monitor might no longer be connected.
+    let monitor = window.monitor().context("No monitor.")?;
+    if monitor.is_standby() {
+      continue;
+    }
+
     // Display setting changes can spread windows out sporadically, so mark
     // all windows as needing a DPI adjustment (just in case).
-    window.set_has_pending_dpi_adjustment(true);
+    window.queue_dpi_adjustment(PendingDpiAdjustment::unknown_source(
+      monitor.native().scale_factor()?,
+    ));
 
     // Need to update floating position of moved windows when a monitor is
     // disconnected or if the primary display is changed. The primary
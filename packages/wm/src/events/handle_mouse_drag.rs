@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+
+use tracing::info;
+use wm_common::{Point, WindowState};
+use wm_platform::{
+  MouseDragEvent, MouseDragPhase, NativeWindow, Platform,
+};
+
+use super::{
+  handle_window_moved_or_resized_end, handle_window_moved_or_resized_start,
+};
+use crate::{
+  commands::container::set_focused_descendant,
+  models::{MouseDrag, WindowContainer},
+  traits::WindowGetters,
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Handles a window being moved or resized by dragging it with the mouse
+/// binding modifier held.
+///
+/// The drag is handled the same as the user dragging the window by its
+/// title bar or drag handles. Tiling windows that are moved are thus
+/// reordered on drop, and tiling windows that are resized have their
+/// tiling size adjusted.
+pub fn handle_mouse_drag(
+  event: &MouseDragEvent,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  match event.phase {
+    MouseDragPhase::Start => start_mouse_drag(event, state, config),
+    MouseDragPhase::Update => update_mouse_drag(&event.point, state),
+    MouseDragPhase::End => end_mouse_drag(state, config),
+  }
+}
+
+fn start_mouse_drag(
+  event: &MouseDragEvent,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  if state.is_paused {
+    return Ok(());
+  }
+
+  let Some(window) = Platform::window_from_point(&event.point)
+    .and_then(|window| Platform::root_ancestor(&window))
+    .map(|root| state.window_from_native(&root))?
+  else {
+    return Ok(());
+  };
+
+  if !is_mouse_draggable(&window, config) {
+    return Ok(());
+  }
+
+  info!("Mouse binding {:?} started: {window}", event.action);
+
+  // The click starting the drag isn't passed on to the window, so focus
+  // it here instead.
+  set_focused_descendant(&window.clone().into(), None);
+  state.pending_sync.queue_focus_change();
+
+  let native = window.native().clone();
+  let start_rect = native.refresh_border_position()?;
+
+  state.edge_drag = None;
+  state.mouse_drag = Some(MouseDrag::new(
+    native.handle,
+    event.action,
+    event.point.clone(),
+    start_rect,
+  ));
+
+  handle_window_moved_or_resized_start(&native, state);
+
+  Ok(())
+}
+
+fn update_mouse_drag(
+  point: &Point,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let Some(mouse_drag) = &state.mouse_drag else {
+    return Ok(());
+  };
+
+  // End the drag if the window has since been unmanaged.
+  let Some(window) = dragged_window(mouse_drag, state) else {
+    state.mouse_drag = None;
+    return Ok(());
+  };
+
+  // Location changes of the window are handled the same as when the user
+  // drags the window, which e.g. updates the drop hint.
+  window
+    .native()
+    .set_border_position(&mouse_drag.rect_at(point))?;
+
+  Ok(())
+}
+
+fn end_mouse_drag(
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let Some(mouse_drag) = state.mouse_drag.take() else {
+    return Ok(());
+  };
+
+  let Some(window) = dragged_window(&mouse_drag, state) else {
+    return Ok(());
+  };
+
+  info!("Mouse binding {:?} ended: {window}", mouse_drag.action);
+
+  let native = window.native().clone();
+  handle_window_moved_or_resized_end(&native, state, config)
+}
+
+/// Gets the handles of windows that can be dragged via mouse bindings.
+///
+/// Is empty while the WM is paused.
+pub fn mouse_draggable_windows(
+  state: &WmState,
+  config: &UserConfig,
+) -> HashSet<isize> {
+  if state.is_paused || !config.value.mouse_bindings.enabled {
+    return HashSet::new();
+  }
+
+  state
+    .windows()
+    .into_iter()
+    .filter(|window| is_mouse_draggable(window, config))
+    .map(|window| window.native().handle)
+    .collect()
+}
+
+/// Whether the window can be dragged via mouse bindings.
+fn is_mouse_draggable(
+  window: &WindowContainer,
+  config: &UserConfig,
+) -> bool {
+  match window.state() {
+    WindowState::Tiling => config.value.mouse_bindings.tiling_windows,
+    WindowState::Floating(_) => true,
+    _ => false,
+  }
+}
+
+/// Gets the window being dragged, if it's still managed.
+fn dragged_window(
+  mouse_drag: &MouseDrag,
+  state: &WmState,
+) -> Option<WindowContainer> {
+  state.window_from_native(&NativeWindow::new(mouse_drag.handle))
+}
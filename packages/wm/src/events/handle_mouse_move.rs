@@ -1,9 +1,18 @@
+use std::time::{Duration, Instant};
+
 use anyhow::Context;
+use wm_common::{Point, TilingDirection};
 use wm_platform::{MouseMoveEvent, Platform};
 
 use crate::{
-  commands::container::set_focused_descendant, traits::CommonGetters,
-  user_config::UserConfig, wm_state::WmState,
+  commands::container::set_focused_descendant,
+  models::{Container, DirectionContainer, EdgeDrag},
+  traits::{
+    CommonGetters, PositionGetters, TilingDirectionGetters,
+    TilingSizeGetters, WindowGetters,
+  },
+  user_config::UserConfig,
+  wm_state::WmState,
 };
 
 pub fn handle_mouse_move(
@@ -11,48 +20,402 @@ pub fn handle_mouse_move(
   state: &mut WmState,
   config: &UserConfig,
 ) -> anyhow::Result<()> {
+  update_edge_drag(event, state, config)?;
+
   // Ignore event if left/right-click is down. Otherwise, this causes focus
   // to jitter when a window is being resized by its drag handles.
   // Also ignore if the OS focused window isn't the same as the WM's
   // focused window.
   if event.is_mouse_down
     || !state.is_focus_synced
+    || state.is_paused
     || !config.value.general.focus_follows_cursor
   {
+    state.cursor_focus_candidate = None;
+    return Ok(());
+  }
+
+  let Some(target) = cursor_focus_target(&event.point, state)? else {
+    state.cursor_focus_candidate = None;
+    return Ok(());
+  };
+
+  // Only focus the target once the cursor has stayed over it for the
+  // configured delay. Moving over a different container restarts the
+  // delay.
+  let hovered_since = match state.cursor_focus_candidate {
+    Some((id, hovered_since)) if id == target.id() => hovered_since,
+    _ => Instant::now(),
+  };
+
+  let delay = Duration::from_millis(u64::from(
+    config.value.general.focus_follows_cursor_delay_ms,
+  ));
+
+  if hovered_since.elapsed() < delay {
+    state.cursor_focus_candidate = Some((target.id(), hovered_since));
+    return Ok(());
+  }
+
+  state.cursor_focus_candidate = None;
+  set_focused_descendant(&target, None);
+  state.pending_sync.queue_cursor_focus_change();
+
+  Ok(())
+}
+
+/// Starts, continues, or ends a drag of the edge shared by two tiling
+/// containers.
+///
+/// Mouse events are throttled by the platform, so the containers are
+/// redrawn at most once per event while dragging.
+fn update_edge_drag(
+  event: &MouseMoveEvent,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let was_mouse_down =
+    std::mem::replace(&mut state.is_mouse_down, event.is_mouse_down);
+
+  if !event.is_mouse_down
+    || state.is_paused
+    || state.mouse_drag.is_some()
+    || !config.value.window_behavior.edge_resize.enabled
+  {
+    state.edge_drag = None;
+    return Ok(());
+  }
+
+  // A drag can only start when the mouse is first pressed.
+  if !was_mouse_down {
+    state.edge_drag = edge_drag_at(&event.point, state, config)?;
     return Ok(());
   }
 
-  let window_under_cursor = Platform::window_from_point(&event.point)
+  let Some(edge_drag) = &state.edge_drag else {
+    return Ok(());
+  };
+
+  let tiling_container = |id| {
+    state
+      .container_by_id(id)
+      .and_then(|container| container.as_tiling_container().ok())
+  };
+
+  // End the drag if either container has since been removed.
+  let (Some(before), Some(after)) = (
+    tiling_container(edge_drag.before_id),
+    tiling_container(edge_drag.after_id),
+  ) else {
+    state.edge_drag = None;
+    return Ok(());
+  };
+
+  let (before_size, after_size) = edge_drag.sizes_at(&event.point);
+  before.set_tiling_size(before_size);
+  after.set_tiling_size(after_size);
+
+  if let Some(parent) = before.parent() {
+    state.pending_sync.queue_container_to_redraw(parent);
+  }
+
+  Ok(())
+}
+
+/// Gets the edge drag to start with the cursor pressed at the given
+/// point. When several edges are within the threshold, the closest one
+/// is dragged.
+///
+/// Returns `None` if the point isn't near the edge between two adjacent
+/// tiling containers.
+fn edge_drag_at(
+  point: &Point,
+  state: &WmState,
+  config: &UserConfig,
+) -> anyhow::Result<Option<EdgeDrag>> {
+  let Some(monitor) = state.monitor_at(point) else {
+    return Ok(None);
+  };
+
+  let Some(workspace) = monitor.displayed_workspace() else {
+    return Ok(None);
+  };
+
+  // Edges aren't shown while a window is in monocle.
+  if monitor.is_standby() || workspace.monocle_window_id().is_some() {
+    return Ok(None);
+  }
+
+  let threshold =
+    config.value.window_behavior.edge_resize.threshold.to_px(
+      monitor.to_rect()?.width(),
+      Some(monitor.native().scale_factor()?),
+    );
+
+  // Children of a master-stack workspace are arranged by the layout
+  // rather than by their tiling sizes, and children of stacking and
+  // tabbed containers overlap.
+  let direction_containers =
+    std::iter::once(DirectionContainer::Workspace(workspace.clone()))
+      .filter(|_| !workspace.layout().is_master_stack())
+      .chain(workspace.descendants().filter_map(|descendant| {
+        descendant
+          .as_split()
+          .filter(|split| {
+            !split.layout().is_stacked() && !split.is_hidden_in_stack()
+          })
+          .map(|split| DirectionContainer::Split(split.clone()))
+      }));
+
+  let mut closest: Option<(i32, EdgeDrag)> = None;
+
+  for parent in direction_containers {
+    let tiling_children = parent.tiling_children().collect::<Vec<_>>();
+    let tiling_direction = parent.tiling_direction();
+
+    for pair in tiling_children.windows(2) {
+      let [before, after] = pair else {
+        continue;
+      };
+
+      let Some(distance) = EdgeDrag::edge_distance(
+        &before.to_rect()?,
+        &after.to_rect()?,
+        &tiling_direction,
+        point,
+        threshold,
+      ) else {
+        continue;
+      };
+
+      if closest.as_ref().is_some_and(|(min, _)| *min <= distance) {
+        continue;
+      }
+
+      let parent_rect = parent.to_rect()?;
+      let (horizontal_gap, vertical_gap) = before.inner_gaps()?;
+
+      let (parent_length, inner_gap) = match tiling_direction {
+        TilingDirection::Horizontal => {
+          (parent_rect.width(), horizontal_gap)
+        }
+        TilingDirection::Vertical => (parent_rect.height(), vertical_gap),
+      };
+
+      #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+      )]
+      let available_length =
+        parent_length - inner_gap * (tiling_children.len() as i32 - 1);
+
+      closest = Some((
+        distance,
+        EdgeDrag {
+          before_id: before.id(),
+          after_id: after.id(),
+          tiling_direction: tiling_direction.clone(),
+          start_point: point.clone(),
+          start_sizes: (before.tiling_size(), after.tiling_size()),
+          available_length,
+        },
+      ));
+    }
+  }
+
+  Ok(closest.map(|(_, edge_drag)| edge_drag))
+}
+
+/// Gets the container that should be focused with the cursor at the given
+/// point. This is the window under the cursor, or the monitor if there's
+/// no window under the cursor.
+///
+/// Returns `None` if the container is already focused, or if the focused
+/// window has an open menu or dialog that focus shouldn't be taken from.
+fn cursor_focus_target(
+  point: &Point,
+  state: &WmState,
+) -> anyhow::Result<Option<Container>> {
+  let focused_container =
+    state.focused_container().context("No focused container.")?;
+
+  if let Ok(focused_window) = focused_container.as_window_container() {
+    let native = focused_window.native();
+
+    let is_dialog = native
+      .owner()
+      .is_some_and(|owner| owner.is_visible().unwrap_or(false));
+
+    if is_dialog || native.is_menu_open() {
+      return Ok(None);
+    }
+  }
+
+  let window_under_cursor = Platform::window_from_point(point)
     .and_then(|window| Platform::root_ancestor(&window))
     .map(|root| state.window_from_native(&root))?;
 
-  // Set focus to whichever window is currently under the cursor.
   if let Some(window) = window_under_cursor {
-    let focused_container =
-      state.focused_container().context("No focused container.")?;
+    return Ok(
+      (focused_container.id() != window.id())
+        .then(|| window.as_container()),
+    );
+  }
+
+  let cursor_monitor = state
+    .monitor_at(point)
+    .context("No monitor under cursor.")?;
+
+  let focused_monitor =
+    state.focused_monitor().context("No focused monitor.")?;
+
+  // Avoid setting focus to the same monitor.
+  Ok(
+    (cursor_monitor.id() != focused_monitor.id())
+      .then(|| cursor_monitor.as_container()),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::sync::mpsc;
+  use wm_common::{
+    GapsConfig, LengthValue, ParsedConfig, Rect, RectDelta,
+  };
+  use wm_platform::{NativeMonitor, NativeWindow};
+
+  use super::*;
+  use crate::{
+    commands::container::{attach_container, detach_container},
+    models::{Monitor, TilingWindow, Workspace},
+  };
+
+  fn tiling_window(handle: isize) -> TilingWindow {
+    let no_delta = LengthValue::from_px(0);
+
+    TilingWindow::new(
+      None,
+      NativeWindow::new(handle),
+      None,
+      RectDelta::new(
+        no_delta.clone(),
+        no_delta.clone(),
+        no_delta.clone(),
+        no_delta,
+      ),
+      Rect::from_xy(0, 0, 400, 300),
+      false,
+      GapsConfig::default(),
+      Vec::new(),
+      None,
+    )
+  }
+
+  fn config(is_enabled: bool) -> UserConfig {
+    let mut value = ParsedConfig::default();
+    value.window_behavior.edge_resize.enabled = is_enabled;
+    UserConfig::from_value(value)
+  }
 
-    if focused_container.id() != window.id() {
-      set_focused_descendant(&window.as_container(), None);
-      state.pending_sync.queue_focus_change();
+  /// Builds a state with two side-by-side tiling windows, where the edge
+  /// between them is already being dragged.
+  fn dragging_state() -> (WmState, TilingWindow, TilingWindow) {
+    let mut state = WmState::new(
+      mpsc::unbounded_channel().0,
+      mpsc::unbounded_channel().0,
+    );
+
+    let monitor = Monitor::new(NativeMonitor::new(1));
+    attach_container(
+      &monitor.clone().into(),
+      &state.root_container.clone().into(),
+      None,
+    )
+    .unwrap();
+
+    let workspace = Workspace::new(
+      serde_yaml::from_str("name: '1'").unwrap(),
+      GapsConfig::default(),
+      TilingDirection::Horizontal,
+    );
+    attach_container(&workspace.clone().into(), &monitor.into(), None)
+      .unwrap();
+
+    let before = tiling_window(1);
+    let after = tiling_window(2);
+
+    for window in [&before, &after] {
+      attach_container(
+        &window.clone().into(),
+        &workspace.clone().into(),
+        None,
+      )
+      .unwrap();
     }
-  } else {
-    // Focus the monitor if no window is under the cursor.
-    let cursor_monitor = state
-      .monitor_at_point(&event.point)
-      .context("No monitor under cursor.")?;
-
-    let focused_monitor = state
-      .focused_container()
-      .context("No focused container.")?
-      .monitor()
-      .context("Focused container has no monitor.")?;
-
-    // Avoid setting focus to the same monitor.
-    if cursor_monitor.id() != focused_monitor.id() {
-      set_focused_descendant(&cursor_monitor.as_container(), None);
-      state.pending_sync.queue_focus_change();
+
+    state.is_mouse_down = true;
+    state.edge_drag = Some(EdgeDrag {
+      before_id: before.id(),
+      after_id: after.id(),
+      tiling_direction: TilingDirection::Horizontal,
+      start_point: Point { x: 500, y: 300 },
+      start_sizes: (0.5, 0.5),
+      available_length: 1000,
+    });
+
+    (state, before, after)
+  }
+
+  fn mouse_move(x: i32, is_mouse_down: bool) -> MouseMoveEvent {
+    MouseMoveEvent {
+      point: Point { x, y: 300 },
+      is_mouse_down,
     }
   }
 
-  Ok(())
+  #[test]
+  fn resizes_both_windows_while_dragging() {
+    let (mut state, before, after) = dragging_state();
+
+    update_edge_drag(&mouse_move(600, true), &mut state, &config(true))
+      .unwrap();
+
+    assert!((before.tiling_size() - 0.6).abs() < f32::EPSILON);
+    assert!((after.tiling_size() - 0.4).abs() < f32::EPSILON);
+    assert!(state.edge_drag.is_some());
+  }
+
+  #[test]
+  fn ends_drag_on_mouse_up() {
+    let (mut state, before, _) = dragging_state();
+
+    update_edge_drag(&mouse_move(600, false), &mut state, &config(true))
+      .unwrap();
+
+    assert!(state.edge_drag.is_none());
+    assert!(!state.is_mouse_down);
+    assert!((before.tiling_size() - 0.5).abs() < f32::EPSILON);
+  }
+
+  #[test]
+  fn ends_drag_when_window_is_removed() {
+    let (mut state, before, _) = dragging_state();
+    detach_container(before.into()).unwrap();
+
+    update_edge_drag(&mouse_move(600, true), &mut state, &config(true))
+      .unwrap();
+
+    assert!(state.edge_drag.is_none());
+  }
+
+  #[test]
+  fn ignores_drag_when_disabled() {
+    let (mut state, before, _) = dragging_state();
+
+    update_edge_drag(&mouse_move(600, true), &mut state, &config(false))
+      .unwrap();
+
+    assert!(state.edge_drag.is_none());
+    assert!((before.tiling_size() - 0.5).abs() < f32::EPSILON);
+  }
 }
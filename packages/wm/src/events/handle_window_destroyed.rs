@@ -4,7 +4,7 @@ use wm_platform::NativeWindow;
 
 use crate::{
   commands::{window::unmanage_window, workspace::deactivate_workspace},
-  traits::CommonGetters,
+  traits::{CommonGetters, WindowGetters},
   wm_state::WmState,
 };
 
@@ -14,6 +14,19 @@ pub fn handle_window_destroyed(
 ) -> anyhow::Result<()> {
   let found_window = state.window_from_native(native_window);
 
+  // Forget the window if it was closed while swallowed or ignored.
+  state
+    .swallowed_windows
+    .retain(|_, swallowed| *swallowed.window.native() != *native_window);
+
+  state
+    .ignored_windows
+    .retain(|ignored_window| ignored_window != native_window);
+
+  state
+    .runtime_ignored_windows
+    .retain(|ignored_window| ignored_window != native_window);
+
   // Unmanage the window if it's currently managed.
   if let Some(window) = found_window {
     let workspace = window.workspace().context("No workspace.")?;
@@ -0,0 +1,53 @@
+use std::time::Instant;
+
+use anyhow::Context;
+use tracing::info;
+use wm_common::WmEvent;
+use wm_platform::NativeWindow;
+
+use crate::{
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Marks a window flashing in the taskbar as urgent, until it gets
+/// focused.
+pub fn handle_window_flashed(
+  native_window: &NativeWindow,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  let found_window = state.window_from_native(native_window);
+
+  if let Some(window) = found_window {
+    let is_focused = state
+      .focused_container()
+      .is_some_and(|focused| focused.id() == window.id());
+
+    if is_focused || window.is_urgent() {
+      return Ok(());
+    }
+
+    info!("Window requesting attention: {window}");
+
+    window.set_urgent_since(Some(Instant::now()));
+
+    if config.value.window_effects.borders.enabled {
+      state.pending_sync.queue_all_effects_update();
+    }
+
+    state.emit_event(WmEvent::WindowUrgent {
+      urgent_window: window.to_dto()?,
+    });
+
+    state.emit_event(WmEvent::WorkspaceUpdated {
+      updated_workspace: window
+        .workspace()
+        .context("No workspace.")?
+        .to_dto()?,
+    });
+  }
+
+  Ok(())
+}
@@ -59,10 +59,16 @@ pub fn handle_window_focused(
   if let Some(window) = found_window {
     let workspace = window.workspace().context("No workspace")?;
 
-    // Native focus has been synced to the WM's focused container.
+    // Native focus has been synced to the WM's focused container. Keep
+    // the z-order as is if focus came from focus follows cursor.
     if focused_container == window.clone().into() {
       state.is_focus_synced = true;
-      state.pending_sync.queue_workspace_to_reorder(workspace);
+
+      if !state.is_focus_from_cursor || config.value.general.raise_on_focus
+      {
+        state.pending_sync.queue_workspace_to_reorder(workspace);
+      }
+
       return Ok(());
     }
 
@@ -93,6 +99,7 @@ pub fn handle_window_focused(
     )?;
 
     state.is_focus_synced = true;
+    state.is_focus_from_cursor = false;
     state.pending_sync.queue_workspace_to_reorder(workspace);
 
     // Broadcast the focus change event.
@@ -1,15 +1,22 @@
 use tracing::info;
-use wm_common::DisplayState;
+use wm_common::{DisplayState, OpacityValue, WindowState};
 use wm_platform::NativeWindow;
 
 use crate::{
-  commands::window::unmanage_window, traits::WindowGetters,
+  commands::{
+    container::set_focused_descendant,
+    window::{unmanage_window, update_window_state},
+  },
+  models::WindowContainer,
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
   wm_state::WmState,
 };
 
 pub fn handle_window_hidden(
   native_window: &NativeWindow,
   state: &mut WmState,
+  config: &UserConfig,
 ) -> anyhow::Result<()> {
   let found_window = state.window_from_native(native_window);
 
@@ -28,9 +35,93 @@ pub fn handle_window_hidden(
     if window.display_state() == DisplayState::Shown
       && !window.native().is_visible().unwrap_or(false)
     {
+      if config.value.window_behavior.keep_hidden_windows {
+        return keep_hidden_window(&window, state, config);
+      }
+
+      // Don't leave the window translucent if it's shown again later.
+      if config.value.window_effects.opacity.enabled {
+        _ = window
+          .native()
+          .set_transparency(&OpacityValue::from_alpha(u8::MAX));
+      }
+
       unmanage_window(window, state)?;
     }
   }
 
   Ok(())
 }
+
+/// Keeps a window that hid itself on its workspace as minimized, such
+/// that it can be restored to its position once it's shown again.
+fn keep_hidden_window(
+  window: &WindowContainer,
+  state: &mut WmState,
+  config: &UserConfig,
+) -> anyhow::Result<()> {
+  info!("Keeping self-hidden window as minimized: {window}");
+
+  state.self_hidden_windows.insert(window.id());
+  window.set_display_state(DisplayState::Hidden);
+
+  // Focus has already been reassigned if the window was minimized before
+  // hiding itself.
+  if window.state() == WindowState::Minimized {
+    return Ok(());
+  }
+
+  let window = update_window_state(
+    window.clone(),
+    WindowState::Minimized,
+    state,
+    config,
+  )?;
+
+  // Focus should be reassigned after a window has been minimized.
+  if let Some(focus_target) = state.focus_target_after_removal(&window) {
+    set_focused_descendant(&focus_target, None);
+    state.pending_sync.queue_focus_change().queue_cursor_jump();
+    state.unmanaged_or_minimized_timestamp =
+      Some(std::time::Instant::now());
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{events::handle_window_shown, test_utils};
+
+  #[test]
+  fn restores_self_hidden_window_to_its_position() {
+    let (mut state, _event_rx) = test_utils::state();
+    let mut config = test_utils::config();
+    config.value.window_behavior.keep_hidden_windows = true;
+
+    // The window is on a workspace that isn't displayed.
+    let monitor = test_utils::monitor(&state, 1, 0);
+    test_utils::workspace(&monitor, "1");
+    let workspace = test_utils::workspace(&monitor, "2");
+    test_utils::tiling_window(&workspace.clone().into(), 1);
+    let window = test_utils::tiling_window(&workspace.clone().into(), 2);
+    test_utils::tiling_window(&workspace.clone().into(), 3);
+
+    let native = window.native().clone();
+    handle_window_hidden(&native, &mut state, &config).unwrap();
+
+    let hidden_window = state.window_from_native(&native).unwrap();
+    assert_eq!(hidden_window.state(), WindowState::Minimized);
+    assert_eq!(hidden_window.parent(), Some(workspace.clone().into()));
+    assert!(state.self_hidden_windows.contains(&window.id()));
+
+    handle_window_shown(native.clone(), &mut state, &mut config).unwrap();
+
+    let shown_window = state.window_from_native(&native).unwrap();
+    assert_eq!(shown_window.state(), WindowState::Tiling);
+    assert_eq!(shown_window.parent(), Some(workspace.into()));
+    assert_eq!(shown_window.index(), 1);
+    assert!(state.self_hidden_windows.is_empty());
+  }
+}
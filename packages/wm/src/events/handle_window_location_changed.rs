@@ -1,15 +1,17 @@
 use anyhow::Context;
-use tracing::info;
+use tracing::{info, warn};
 use wm_common::{
   try_warn, ActiveDrag, ActiveDragOperation, FloatingStateConfig,
-  FullscreenStateConfig, Rect, WindowState,
+  FullscreenStateConfig, MaximizeBehavior, Rect, WindowState, WmEvent,
 };
-use wm_platform::NativeWindow;
+use wm_platform::{NativeWindow, Platform};
 
 use crate::{
   commands::{
     container::{flatten_split_container, move_container_within_tree},
-    window::update_window_state,
+    window::{
+      snap_floating_placement, toggle_monocle, update_window_state,
+    },
   },
   models::{TilingWindow, WindowContainer},
   traits::{CommonGetters, PositionGetters, WindowGetters},
@@ -52,7 +54,7 @@ pub fn handle_window_location_changed(
     }
 
     let nearest_monitor = state
-      .nearest_monitor(&window.native())
+      .window_monitor(&window.native())
       .context("Failed to get workspace of nearest monitor.")?;
 
     // TODO: Include this as part of the `match` statement below.
@@ -70,17 +72,30 @@ pub fn handle_window_location_changed(
       .displayed_workspace()
       .context("No Workspace")?;
 
-    let monitor_rect = if config
-      .outer_gaps_for_workspace(&nearest_workspace)
-      .is_significant()
+    // Fullscreen windows that leave the reserved space visible are
+    // compared against the working area, such that they aren't treated
+    // as restored from fullscreen.
+    let monitor_rect = if config.is_fullscreen_within_working_area(&window)
+      || config
+        .outer_gaps_for_workspace(&nearest_workspace)
+        .is_significant()
     {
-      nearest_monitor.native().working_rect()?.clone()
+      nearest_monitor.working_rect()?
     } else {
       nearest_monitor.to_rect()?
     };
 
     let is_fullscreen = window.native().is_fullscreen(&monitor_rect)?;
 
+    if old_is_maximized != is_maximized {
+      state.emit_event(WmEvent::WindowMaximizedChanged {
+        updated_window: window.to_dto()?,
+        is_maximized,
+      });
+    }
+
+    let on_maximize = config.value.window_behavior.on_maximize;
+
     match window.state() {
       WindowState::Fullscreen(fullscreen_state) => {
         // Restore the window if it's no longer fullscreen *or* for the
@@ -110,7 +125,12 @@ pub fn handle_window_location_changed(
         }
       }
       _ => {
-        if is_maximized || is_fullscreen {
+        if is_maximized
+          && on_maximize != MaximizeBehavior::Fullscreen
+          && !config.is_keep_maximized_window(&window)
+        {
+          restore_from_maximized(&window, on_maximize, state)?;
+        } else if is_maximized || is_fullscreen {
           info!("Window fullscreened: {window}");
 
           // Update the window to be fullscreen.
@@ -126,9 +146,49 @@ pub fn handle_window_location_changed(
         } else if matches!(window.state(), WindowState::Floating(_)) {
           // Update state with the new location of the floating window.
           info!("Updating floating window position: {window}");
-          window.set_floating_placement(frame_position);
+
+          // Snap windows that are being dragged by the user. The snapped
+          // placement gets applied once the drag ends.
+          let is_dragged_move = window.active_drag().is_some()
+            && frame_position.width() == old_frame_position.width()
+            && frame_position.height() == old_frame_position.height();
+
+          let is_snap_bypassed = Platform::is_key_down(
+            &config.value.window_behavior.snapping.bypass_key,
+          )
+          .unwrap_or(false);
+
+          let placement = if is_dragged_move && !is_snap_bypassed {
+            snap_floating_placement(
+              &window,
+              &frame_position,
+              &nearest_workspace,
+              None,
+              config,
+            )?
+          } else {
+            frame_position
+          };
+
+          window.set_floating_placement(placement);
           window.set_has_custom_floating_placement(true);
 
+          if is_dragged_move
+            && config.value.window_behavior.drop_hint.enabled
+          {
+            // The hint is only cosmetic, so failing to show it shouldn't
+            // interrupt the move.
+            if let Err(err) = update_drop_hint(&window, state) {
+              warn!("Failed to update drop hint: {:?}", err);
+            }
+          }
+
+          // Move the border overlay along with the window. The window
+          // being moved is normally the focused one.
+          if state.border_overlays.contains_key(&window.id()) {
+            state.pending_sync.queue_focused_effect_update();
+          }
+
           let monitor = window.monitor().context("No monitor.")?;
 
           // Update the window's workspace if it goes out of bounds of its
@@ -161,6 +221,66 @@ pub fn handle_window_location_changed(
   Ok(())
 }
 
+/// Restores a window that was maximized by the application (e.g. via its
+/// maximize button), based on the `on_maximize` behavior.
+///
+/// With `MaximizeBehavior::Monocle`, tiling windows are additionally put
+/// into monocle on their workspace.
+fn restore_from_maximized(
+  window: &WindowContainer,
+  on_maximize: MaximizeBehavior,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let is_monocle = window.workspace().is_some_and(|workspace| {
+    workspace.monocle_window_id() == Some(window.id())
+  });
+
+  if on_maximize == MaximizeBehavior::Monocle
+    && window.is_tiling_window()
+    && !is_monocle
+  {
+    info!("Window maximized, entering monocle: {window}");
+    toggle_monocle(window, state)?;
+  } else {
+    info!("Window maximized, restoring: {window}");
+  }
+
+  // Redrawing the window restores it from its maximized state.
+  state.pending_sync.queue_container_to_redraw(window.clone());
+
+  Ok(())
+}
+
+/// Updates the drop hint of a tiling window that is being dragged, such
+/// that it's over where the window gets inserted on drop.
+fn update_drop_hint(
+  window: &WindowContainer,
+  state: &mut WmState,
+) -> anyhow::Result<()> {
+  let WindowContainer::NonTilingWindow(window) = window else {
+    return Ok(());
+  };
+
+  let is_tiling_drag = window.active_drag().is_some_and(|active_drag| {
+    active_drag.is_from_tiling
+      && active_drag.operation == Some(ActiveDragOperation::Moving)
+  });
+
+  if !is_tiling_drag {
+    return Ok(());
+  }
+
+  let mouse_pos = Platform::mouse_position()?;
+  let drop_hint = state.drop_target(window, &mouse_pos)?.hint_rect()?;
+
+  if state.drop_hint.as_ref() != Some(&drop_hint) {
+    state.drop_hint = Some(drop_hint);
+    state.pending_sync.queue_drop_hint_update();
+  }
+
+  Ok(())
+}
+
 /// Updates the window operation based on changes in frame position.
 ///
 /// This function determines whether a window is being moved or resized and
@@ -1,18 +1,18 @@
 use anyhow::Context;
 use tracing::info;
-use wm_common::{
-  try_warn, ActiveDragOperation, LengthValue, Point, Rect,
-  TilingDirection, WindowState,
-};
+use wm_common::{try_warn, ActiveDragOperation, LengthValue, WindowState};
 use wm_platform::{NativeWindow, Platform};
 
 use crate::{
   commands::{
-    container::{move_container_within_tree, wrap_in_split_container},
+    container::{
+      move_container_within_tree, set_focused_descendant,
+      wrap_in_split_container,
+    },
     window::{resize_window, update_window_state},
   },
   models::{
-    DirectionContainer, NonTilingWindow, SplitContainer, TilingContainer,
+    DropPosition, Monitor, NonTilingWindow, SplitContainer,
     WindowContainer,
   },
   traits::{
@@ -51,12 +51,23 @@ pub fn handle_window_moved_or_resized_end(
     match &window {
       WindowContainer::NonTilingWindow(window) => {
         if let Some(active_drag) = window.active_drag() {
+          // Hide the drop hint shown while the window was dragged.
+          if state.drop_hint.take().is_some() {
+            state.pending_sync.queue_drop_hint_update();
+          }
+
           if active_drag.is_from_tiling
             && active_drag.operation == Some(ActiveDragOperation::Moving)
           {
             // Window is a temporary floating window that should be
             // reverted back to tiling.
             drop_as_tiling_window(window, state, config)?;
+          } else if matches!(window.state(), WindowState::Floating(_))
+            && window.floating_placement() != new_rect
+          {
+            // Apply the placement that the window got snapped to while
+            // being dragged.
+            state.pending_sync.queue_container_to_redraw(window.clone());
           }
         }
       }
@@ -87,7 +98,6 @@ pub fn handle_window_moved_or_resized_end(
 
 /// Handles transition from temporary floating window to tiling window on
 /// drag end.
-#[allow(clippy::too_many_lines)]
 fn drop_as_tiling_window(
   moved_window: &NonTilingWindow,
   state: &mut WmState,
@@ -99,33 +109,27 @@ fn drop_as_tiling_window(
   );
 
   let mouse_pos = Platform::mouse_position()?;
-  let mouse_workspace = state
-    .monitor_at_point(&mouse_pos)
-    .and_then(|monitor| monitor.displayed_workspace())
-    .or_else(|| moved_window.workspace())
-    .context("Couldn't find workspace for window drop.")?;
+  let drop_target = state.drop_target(moved_window, &mouse_pos)?;
+  let target_parent = drop_target.target_parent.clone();
 
-  // Get the workspace, split containers, and other windows under the
-  // dragged window.
-  let containers_at_pos = state
-    .containers_at_point(&mouse_workspace.clone().into(), &mouse_pos)
-    .into_iter()
-    .filter(|container| container.id() != moved_window.id());
+  let target_monitor = target_parent.monitor().context("No monitor.")?;
+  let focused_monitor =
+    state.focused_monitor().context("No focused monitor.")?;
 
-  // Get the deepest direction container under the dragged window.
-  let target_parent: DirectionContainer = containers_at_pos
-    .filter_map(|container| container.as_direction_container().ok())
-    .fold(mouse_workspace.into(), |acc, container| {
-      if container.ancestors().count() > acc.ancestors().count() {
-        container
-      } else {
-        acc
-      }
-    });
+  // The window is drawn on the monitor it was dragged onto, which can
+  // differ in DPI from where it gets dropped.
+  let window_monitor = state
+    .window_monitor(&moved_window.native())
+    .context("No monitor.")?;
+
+  moved_window.queue_dpi_adjustment(
+    window_monitor.dpi_adjustment_to(&target_monitor.clone().into())?,
+  );
 
   // If the target parent has no children (i.e. an empty workspace), then
   // add the window directly.
-  if target_parent.tiling_children().count() == 0 {
+  let Some((nearest_container, drop_position)) = &drop_target.nearest
+  else {
     move_container_within_tree(
       &moved_window.clone().into(),
       &target_parent.clone().into(),
@@ -135,34 +139,22 @@ fn drop_as_tiling_window(
 
     moved_window.set_insertion_target(None);
 
-    update_window_state(
+    let moved_window = update_window_state(
       moved_window.as_window_container()?,
       WindowState::Tiling,
       state,
       config,
     )?;
 
-    return Ok(());
-  }
-
-  let nearest_container = target_parent
-    .children()
-    .into_iter()
-    .filter_map(|container| container.as_tiling_container().ok())
-    .try_fold(None, |acc: Option<TilingContainer>, container| match acc {
-      Some(acc) => {
-        let is_nearer = acc.to_rect()?.distance_to_point(&mouse_pos)
-          < container.to_rect()?.distance_to_point(&mouse_pos);
-
-        anyhow::Ok(Some(if is_nearer { acc } else { container }))
-      }
-      None => Ok(Some(container)),
-    })?
-    .context("No nearest container.")?;
+    focus_dropped_window(
+      &moved_window,
+      &target_monitor,
+      &focused_monitor,
+      state,
+    );
 
-  let tiling_direction = target_parent.tiling_direction();
-  let drop_position =
-    drop_position(&mouse_pos, &nearest_container.to_rect()?);
+    return Ok(());
+  };
 
   let moved_window = update_window_state(
     moved_window.clone().into(),
@@ -171,28 +163,16 @@ fn drop_as_tiling_window(
     config,
   )?;
 
-  let should_split = nearest_container.is_tiling_window()
-    && match tiling_direction {
-      TilingDirection::Horizontal => {
-        drop_position == DropPosition::Top
-          || drop_position == DropPosition::Bottom
-      }
-      TilingDirection::Vertical => {
-        drop_position == DropPosition::Left
-          || drop_position == DropPosition::Right
-      }
-    };
-
-  if should_split {
+  if drop_target.should_split() {
     let split_container = SplitContainer::new(
-      tiling_direction.inverse(),
+      target_parent.tiling_direction().inverse(),
       config.value.gaps.clone(),
     );
 
     wrap_in_split_container(
       &split_container,
       &target_parent.clone().into(),
-      &[nearest_container],
+      std::slice::from_ref(nearest_container),
     )?;
 
     let target_index = match drop_position {
@@ -221,40 +201,26 @@ fn drop_as_tiling_window(
   }
 
   state.pending_sync.queue_container_to_redraw(target_parent);
+  focus_dropped_window(
+    &moved_window,
+    &target_monitor,
+    &focused_monitor,
+    state,
+  );
 
   Ok(())
 }
 
-/// Represents where the window was dropped over another.
-#[derive(Debug, Clone, PartialEq)]
-enum DropPosition {
-  Top,
-  Bottom,
-  Left,
-  Right,
-}
-
-/// Gets the drop position for a window based on the mouse position.
-///
-/// This approach divides the window rect into an "X", creating four
-/// triangular quadrants, to determine which side the cursor is closest to.
-fn drop_position(mouse_pos: &Point, rect: &Rect) -> DropPosition {
-  let delta_x = mouse_pos.x - rect.center_point().x;
-  let delta_y = mouse_pos.y - rect.center_point().y;
-
-  if delta_x.abs() > delta_y.abs() {
-    // Window is in the left or right triangle.
-    if delta_x > 0 {
-      DropPosition::Right
-    } else {
-      DropPosition::Left
-    }
-  } else {
-    // Window is in the top or bottom triangle.
-    if delta_y > 0 {
-      DropPosition::Bottom
-    } else {
-      DropPosition::Top
-    }
+/// Focuses a window that was dropped onto a different monitor than the
+/// focused one.
+fn focus_dropped_window(
+  window: &WindowContainer,
+  target_monitor: &Monitor,
+  focused_monitor: &Monitor,
+  state: &mut WmState,
+) {
+  if target_monitor.id() != focused_monitor.id() {
+    set_focused_descendant(&window.clone().into(), None);
+    state.pending_sync.queue_focus_change();
   }
 }
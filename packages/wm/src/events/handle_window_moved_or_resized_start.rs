@@ -17,6 +17,10 @@ pub fn handle_window_moved_or_resized_start(
   let found_window = state.window_from_native(native_window);
 
   if let Some(found_window) = found_window {
+    // Let the window's own drag handles take over from an edge drag that
+    // started on the window's border.
+    state.edge_drag = None;
+
     found_window.set_active_drag(Some(ActiveDrag {
       operation: None,
       is_from_tiling: found_window.is_tiling_window(),
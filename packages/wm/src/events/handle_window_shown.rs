@@ -1,10 +1,12 @@
 use tracing::info;
-use wm_common::DisplayState;
+use wm_common::{DisplayState, WindowState};
 use wm_platform::NativeWindow;
 
 use crate::{
-  commands::window::manage_window, traits::WindowGetters,
-  user_config::UserConfig, wm_state::WmState,
+  commands::window::{manage_window, update_window_state},
+  traits::{CommonGetters, WindowGetters},
+  user_config::UserConfig,
+  wm_state::WmState,
 };
 
 pub fn handle_window_shown(
@@ -18,6 +20,21 @@ pub fn handle_window_shown(
     Some(window) => {
       info!("Window shown: {window}");
 
+      // Restore windows that hid themselves to their previous state,
+      // unless they're still minimized.
+      let window = if state.self_hidden_windows.remove(&window.id())
+        && window.state() == WindowState::Minimized
+        && !window.native().refresh_is_minimized().unwrap_or(false)
+      {
+        let target_state = window
+          .prev_state()
+          .unwrap_or(WindowState::default_from_config(&config.value));
+
+        update_window_state(window, target_state, state, config)?
+      } else {
+        window
+      };
+
       // Update display state if window is already managed.
       if window.display_state() == DisplayState::Showing {
         window.set_display_state(DisplayState::Shown);
@@ -26,8 +43,13 @@ pub fn handle_window_shown(
       }
     }
     None => {
-      // If the window is not managed, manage it.
-      if native_window.is_manageable().unwrap_or(false) {
+      // If the window is not managed, manage it. Swallowed windows are
+      // left hidden until they're restored, and windows ignored via
+      // `ignore-window` stay unmanaged until `manage-window` is used.
+      if !state.is_swallowed(&native_window)
+        && !state.runtime_ignored_windows.contains(&native_window)
+        && native_window.is_manageable().unwrap_or(false)
+      {
         manage_window(native_window, None, state, config)?;
       }
     }
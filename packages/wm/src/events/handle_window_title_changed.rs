@@ -1,16 +1,21 @@
+use std::time::Instant;
+
 use tracing::info;
-use wm_common::{try_warn, WindowRuleEvent};
+use wm_common::try_warn;
 use wm_platform::NativeWindow;
 
 use crate::{
-  commands::window::run_window_rules, traits::WindowGetters,
-  user_config::UserConfig, wm_state::WmState,
+  traits::{CommonGetters, WindowGetters},
+  wm_state::WmState,
 };
 
+/// Handles the event for when a window's title changes.
+///
+/// Window rules are re-evaluated for the window once its title has
+/// settled. See `WindowManager::process_title_changes`.
 pub fn handle_window_title_changed(
   native_window: &NativeWindow,
   state: &mut WmState,
-  config: &mut UserConfig,
 ) -> anyhow::Result<()> {
   let found_window = state.window_from_native(native_window);
 
@@ -19,13 +24,9 @@ pub fn handle_window_title_changed(
 
     try_warn!(window.native().refresh_title());
 
-    // Run window rules for title change events.
-    run_window_rules(
-      window,
-      &WindowRuleEvent::TitleChange,
-      state,
-      config,
-    )?;
+    state
+      .pending_title_changes
+      .insert(window.id(), Instant::now());
   }
 
   Ok(())
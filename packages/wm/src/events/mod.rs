@@ -1,6 +1,8 @@
 mod handle_display_settings_changed;
+mod handle_mouse_drag;
 mod handle_mouse_move;
 mod handle_window_destroyed;
+mod handle_window_flashed;
 mod handle_window_focused;
 mod handle_window_hidden;
 mod handle_window_location_changed;
@@ -12,8 +14,10 @@ mod handle_window_shown;
 mod handle_window_title_changed;
 
 pub use handle_display_settings_changed::*;
+pub use handle_mouse_drag::*;
 pub use handle_mouse_move::*;
 pub use handle_window_destroyed::*;
+pub use handle_window_flashed::*;
 pub use handle_window_focused::*;
 pub use handle_window_hidden::*;
 pub use handle_window_location_changed::*;
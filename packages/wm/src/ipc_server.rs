@@ -1,30 +1,58 @@
-use std::{iter, net::SocketAddr};
+use std::{collections::VecDeque, iter, time::Duration};
 
 use anyhow::{bail, Context};
 use clap::Parser;
 use futures_util::{SinkExt, StreamExt};
 use tokio::{
-  net::{TcpListener, TcpStream},
+  io::{AsyncRead, AsyncWrite},
+  net::TcpListener,
   sync::{broadcast, mpsc},
   task,
+  time::{self, Instant},
 };
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 use tracing::{info, warn};
 use uuid::Uuid;
 use wm_common::{
-  AppCommand, AppMetadataData, BindingModesData, ClientResponseData,
-  ClientResponseMessage, CommandData, EventSubscribeData,
-  EventSubscriptionMessage, FocusedData, MonitorsData, QueryCommand,
-  ServerMessage, SubscribableEvent, TilingDirectionData, WindowsData,
-  WmEvent, WorkspacesData, DEFAULT_IPC_PORT,
+  AppCommand, AppMetadataData, BindingModesData, BuiltinStatusData,
+  BuiltinsData, ClientResponseData, ClientResponseMessage, CommandData,
+  EventSubscribeData, EventSubscriptionMessage, FocusedData,
+  InvokeCommand, IpcConfig, IpcEndpoint, MetricsData, MonitorData,
+  MonitorWorkspacesData, MonitorsData, Point, QueryCommand, Rect,
+  ServerMessage, SubscribableEvent, TilingDirectionData, TreeData,
+  WindowsData, WmEvent, WorkspaceLayoutData, WorkspacesData,
 };
 
 use crate::{
-  traits::{CommonGetters, TilingDirectionGetters},
+  commands::CommandError,
+  traits::{
+    with_cached_rects, CommonGetters, PositionGetters,
+    TilingDirectionGetters,
+  },
   user_config::UserConfig,
   wm::WindowManager,
 };
 
+/// Time given to clients to read the final events before their
+/// connection is closed on shutdown.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_millis(250);
+
+type MessageSender = mpsc::UnboundedSender<(
+  String,
+  mpsc::UnboundedSender<Message>,
+  broadcast::Sender<()>,
+)>;
+
+/// Channels shared with each IPC connection.
+#[derive(Clone)]
+struct ConnectionContext {
+  messages: MessageSender,
+  shutdown: broadcast::Sender<()>,
+  /// Held for as long as the connection is open, so that shutdown can
+  /// wait for all connections to close.
+  _open_guard: mpsc::Sender<()>,
+}
+
 pub struct IpcServer {
   abort_handle: task::AbortHandle,
   pub message_rx: mpsc::UnboundedReceiver<(
@@ -36,31 +64,88 @@ pub struct IpcServer {
   event_tx: broadcast::Sender<(SubscribableEvent, WmEvent)>,
   _unsubscribe_rx: broadcast::Receiver<Uuid>,
   unsubscribe_tx: broadcast::Sender<Uuid>,
+  /// Most recently emitted events, for replaying to new subscribers.
+  recent_events: VecDeque<(SubscribableEvent, WmEvent)>,
+  event_replay_size: usize,
+  shutdown_tx: broadcast::Sender<()>,
+  open_rx: mpsc::Receiver<()>,
 }
 
 impl IpcServer {
-  pub async fn start() -> anyhow::Result<Self> {
+  pub async fn start(endpoint: &IpcEndpoint) -> anyhow::Result<Self> {
     let (message_tx, message_rx) = mpsc::unbounded_channel();
     let (event_tx, _event_rx) = broadcast::channel(16);
     let (unsubscribe_tx, _unsubscribe_rx) = broadcast::channel(16);
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (open_tx, open_rx) = mpsc::channel(1);
+
+    let context = ConnectionContext {
+      messages: message_tx,
+      shutdown: shutdown_tx.clone(),
+      _open_guard: open_tx,
+    };
 
-    let server_addr = format!("127.0.0.1:{DEFAULT_IPC_PORT}");
-    let server = TcpListener::bind(server_addr.clone()).await?;
-    info!("IPC server started on: '{}'.", server_addr);
+    let task = match endpoint.clone() {
+      IpcEndpoint::Tcp(addr) => {
+        if !addr.ip().is_loopback() {
+          warn!(
+            "IPC server is bound to non-loopback address '{}' and is \
+            reachable from other machines.",
+            addr
+          );
+        }
 
-    let task = task::spawn(async move {
-      while let Ok((stream, addr)) = server.accept().await {
-        let message_tx = message_tx.clone();
+        let server = TcpListener::bind(addr).await?;
 
         task::spawn(async move {
-          if let Err(err) =
-            Self::handle_connection(stream, addr, message_tx).await
-          {
-            warn!("Error handling connection: {}", err);
+          while let Ok((stream, addr)) = server.accept().await {
+            Self::spawn_connection(stream, addr.to_string(), &context);
           }
-        });
+        })
       }
-    });
+      #[cfg(windows)]
+      IpcEndpoint::NamedPipe(name) => {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let mut server = ServerOptions::new()
+          .first_pipe_instance(true)
+          .create(&name)?;
+
+        task::spawn(async move {
+          while server.connect().await.is_ok() {
+            // Create the next pipe instance before handing off the
+            // connected one, so that clients can always connect.
+            let next_server = match ServerOptions::new().create(&name) {
+              Ok(next_server) => next_server,
+              Err(err) => {
+                warn!("Failed to create named pipe instance: {}", err);
+                break;
+              }
+            };
+
+            let stream = std::mem::replace(&mut server, next_server);
+            Self::spawn_connection(stream, name.clone(), &context);
+          }
+        })
+      }
+      #[cfg(unix)]
+      IpcEndpoint::UnixSocket(path) => {
+        // Remove a stale socket left behind by a previous run.
+        let _ = std::fs::remove_file(&path);
+        let server = tokio::net::UnixListener::bind(&path)?;
+
+        task::spawn(async move {
+          while let Ok((stream, _)) = server.accept().await {
+            let peer = path.display().to_string();
+            Self::spawn_connection(stream, peer, &context);
+          }
+        })
+      }
+      #[allow(unreachable_patterns)]
+      _ => bail!("IPC endpoint '{endpoint}' is not supported on this OS."),
+    };
+
+    info!("IPC server started on: '{}'.", endpoint);
 
     Ok(Self {
       abort_handle: task.abort_handle(),
@@ -71,19 +156,40 @@ impl IpcServer {
       unsubscribe_tx,
       #[allow(clippy::used_underscore_binding)]
       _unsubscribe_rx,
+      recent_events: VecDeque::new(),
+      event_replay_size: 0,
+      shutdown_tx,
+      open_rx,
     })
   }
 
-  async fn handle_connection(
-    stream: TcpStream,
-    addr: SocketAddr,
-    message_tx: mpsc::UnboundedSender<(
-      String,
-      mpsc::UnboundedSender<Message>,
-      broadcast::Sender<()>,
-    )>,
-  ) -> anyhow::Result<()> {
-    info!("Incoming IPC connection from: {}.", addr);
+  fn spawn_connection<S>(
+    stream: S,
+    peer: String,
+    context: &ConnectionContext,
+  ) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+  {
+    let context = context.clone();
+
+    task::spawn(async move {
+      if let Err(err) =
+        Self::handle_connection(stream, peer, context).await
+      {
+        warn!("Error handling connection: {}", err);
+      }
+    });
+  }
+
+  async fn handle_connection<S>(
+    stream: S,
+    peer: String,
+    context: ConnectionContext,
+  ) -> anyhow::Result<()>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
+    info!("Incoming IPC connection from: {}.", peer);
 
     let ws_stream = accept_async(stream)
       .await
@@ -92,6 +198,8 @@ impl IpcServer {
     let (mut outgoing, mut incoming) = ws_stream.split();
     let (response_tx, mut response_rx) = mpsc::unbounded_channel();
     let (disconnection_tx, _) = broadcast::channel(16);
+    let mut shutdown_rx = context.shutdown.subscribe();
+    let mut close_deadline = None;
 
     let res = async {
       loop {
@@ -99,11 +207,22 @@ impl IpcServer {
           Some(response) = response_rx.recv() => {
             outgoing.send(response).await?;
           }
+          Ok(()) = shutdown_rx.recv(), if close_deadline.is_none() => {
+            // Keep forwarding responses for a bit, so that the client
+            // receives the final events.
+            close_deadline = Some(Instant::now() + SHUTDOWN_GRACE_PERIOD);
+          }
+          () = time::sleep_until(
+            close_deadline.unwrap_or_else(Instant::now)
+          ), if close_deadline.is_some() => {
+            outgoing.send(Message::Close(None)).await?;
+            break Ok(());
+          }
           message = incoming.next() => {
             match message {
               Some(Ok(message)) => {
                 if message.is_text() || message.is_binary() {
-                  message_tx.send((
+                  context.messages.send((
                     message.to_text()?.to_string(),
                     response_tx.clone(),
                     disconnection_tx.clone(),
@@ -122,7 +241,7 @@ impl IpcServer {
     }
     .await;
 
-    info!("IPC disconnection from: {}.", addr);
+    info!("IPC disconnection from: {}.", peer);
 
     if let Err(err) = disconnection_tx.send(()) {
       warn!("Failed to broadcast disconnection: {}", err);
@@ -143,6 +262,14 @@ impl IpcServer {
       iter::once("").chain(message.split_whitespace()),
     );
 
+    let replay = match &app_command {
+      Ok(AppCommand::Sub {
+        events,
+        replay: Some(count),
+      }) => Some((events.clone(), *count)),
+      _ => None,
+    };
+
     let response_data =
       app_command
         .map_err(anyhow::Error::msg)
@@ -156,6 +283,13 @@ impl IpcServer {
           )
         });
 
+    let subscription_id = match &response_data {
+      Ok(ClientResponseData::EventSubscribe(data)) => {
+        Some(data.subscription_id)
+      }
+      _ => None,
+    };
+
     // Respond to the client with the result of the command.
     response_tx
       .send(Self::to_client_response_msg(message, response_data)?)
@@ -163,9 +297,69 @@ impl IpcServer {
         anyhow::anyhow!("Failed to send response: {}", err)
       })?;
 
+    // Replay recent events after the subscription response, so that
+    // the client knows the subscription ID. New events can't be emitted
+    // in the meantime, since events are processed on the same task.
+    if let (Some((events, count)), Some(subscription_id)) =
+      (replay, subscription_id)
+    {
+      for event in self.events_to_replay(&events, count) {
+        response_tx
+          .send(Self::to_event_subscription_msg(
+            subscription_id,
+            event,
+            true,
+          )?)
+          .map_err(|err| {
+            anyhow::anyhow!("Failed to replay event: {}", err)
+          })?;
+      }
+    }
+
     Ok(())
   }
 
+  /// Gets up to `count` of the most recent events that match the given
+  /// subscribed events, in the order they were emitted.
+  fn events_to_replay(
+    &self,
+    events: &[SubscribableEvent],
+    count: usize,
+  ) -> Vec<WmEvent> {
+    let mut matching = self
+      .recent_events
+      .iter()
+      .rev()
+      .filter(|(event_type, _)| {
+        events.contains(event_type)
+          || events.contains(&SubscribableEvent::All)
+      })
+      .take(count)
+      .map(|(_, event)| event.clone())
+      .collect::<Vec<_>>();
+
+    matching.reverse();
+    matching
+  }
+
+  /// Sets the number of recent events to retain for replaying. Older
+  /// events are discarded.
+  pub fn set_event_replay_size(&mut self, size: usize) {
+    if size > IpcConfig::MAX_EVENT_REPLAY_SIZE {
+      warn!(
+        "Event replay size {} exceeds the maximum of {}.",
+        size,
+        IpcConfig::MAX_EVENT_REPLAY_SIZE
+      );
+    }
+
+    self.event_replay_size = size.min(IpcConfig::MAX_EVENT_REPLAY_SIZE);
+
+    while self.recent_events.len() > self.event_replay_size {
+      self.recent_events.pop_front();
+    }
+  }
+
   #[allow(clippy::too_many_lines)]
   fn handle_app_command(
     &self,
@@ -197,6 +391,36 @@ impl IpcServer {
               .try_collect()?,
           })
         }
+        QueryCommand::Metrics => {
+          if !config.value.general.metrics {
+            bail!(
+              "Metrics are disabled. Enable `general.metrics` in the \
+               config to record them."
+            );
+          }
+
+          ClientResponseData::Metrics(MetricsData {
+            commands: wm.state.command_metrics.to_data(),
+          })
+        }
+        QueryCommand::MonitorAt {
+          x,
+          y,
+          width,
+          height,
+        } => {
+          let monitor = match width.zip(height) {
+            Some((width, height)) => wm
+              .state
+              .nearest_monitor(&Rect::from_xy(x, y, width, height)),
+            None => wm.state.monitor_at(&Point { x, y }),
+          }
+          .context("No monitor found at the given position.")?;
+
+          ClientResponseData::Monitor(MonitorData {
+            monitor: monitor.to_dto()?,
+          })
+        }
         QueryCommand::Monitors => {
           ClientResponseData::Monitors(MonitorsData {
             monitors: wm
@@ -242,22 +466,81 @@ impl IpcServer {
         QueryCommand::Paused => {
           ClientResponseData::Paused(wm.state.is_paused)
         }
+        QueryCommand::Tree => ClientResponseData::Tree(TreeData {
+          tree: with_cached_rects(|| wm.state.root_container.to_dto())?,
+        }),
       },
       AppCommand::Command {
         subject_container_id,
         command,
       } => {
+        let is_config_reload =
+          matches!(command, InvokeCommand::WmReloadConfig);
+
         let subject_container_id = wm.process_commands(
           &vec![command],
           subject_container_id,
           config,
         )?;
 
+        let subject_container_rect = wm
+          .state
+          .container_by_id(subject_container_id)
+          .and_then(|container| container.to_rect().ok());
+
+        let config_reload = is_config_reload
+          .then(|| wm.state.last_config_reload.take())
+          .flatten();
+
         ClientResponseData::Command(CommandData {
           subject_container_id,
+          subject_container_rect,
+          config_reload,
         })
       }
-      AppCommand::Sub { events } => {
+      AppCommand::BuiltinStart { name } => {
+        match &name {
+          Some(name) => wm_builtin::start_builtin(name)?,
+          None => wm_builtin::start_all_available_parallel()?,
+        }
+
+        Self::builtins_data(name.as_deref())?
+      }
+      AppCommand::BuiltinStop { name } => {
+        wm_builtin::stop_builtin(&name)?;
+        Self::builtins_data(Some(&name))?
+      }
+      AppCommand::BuiltinRestart { name } => {
+        wm_builtin::restart_builtin(&name)?;
+        Self::builtins_data(Some(&name))?
+      }
+      AppCommand::BuiltinReextract { name, restart } => {
+        wm_builtin::reextract_builtin(&name, restart)?;
+        Self::builtins_data(Some(&name))?
+      }
+      AppCommand::BuiltinStatus { name } => {
+        Self::builtins_data(name.as_deref())?
+      }
+      AppCommand::MoveWorkspaces { moves } => {
+        wm.process_workspace_moves(&moves, config)?;
+
+        let layout = wm
+          .state
+          .monitors()
+          .into_iter()
+          .map(|monitor| MonitorWorkspacesData {
+            monitor_id: monitor.id(),
+            workspaces: monitor
+              .workspaces()
+              .into_iter()
+              .map(|workspace| workspace.config().name)
+              .collect(),
+          })
+          .collect();
+
+        ClientResponseData::WorkspaceLayout(WorkspaceLayoutData { layout })
+      }
+      AppCommand::Sub { events, .. } => {
         let subscription_id = Uuid::new_v4();
         info!("New event subscription {}: {:?}", subscription_id, events);
 
@@ -285,6 +568,7 @@ impl IpcServer {
                   let res = Self::to_event_subscription_msg(
                     subscription_id,
                     event,
+                    false,
                   )
                   .map(|event_msg| response_tx.send(event_msg));
 
@@ -316,17 +600,52 @@ impl IpcServer {
     Ok(response_data)
   }
 
+  /// Gets the state of the given builtin program, or of all builtin
+  /// programs if no name is given.
+  fn builtins_data(
+    name: Option<&str>,
+  ) -> anyhow::Result<ClientResponseData> {
+    let statuses = match name {
+      Some(name) => vec![wm_builtin::builtin_status(name)?],
+      None => wm_builtin::builtin_statuses()?,
+    };
+
+    let builtins = statuses
+      .into_iter()
+      .map(|status| BuiltinStatusData {
+        name: status.program.name().to_string(),
+        available: status.available,
+        running: status.running,
+        pid: status.pid,
+        exe_path: status
+          .exe_path
+          .map(|path| path.to_string_lossy().into_owned()),
+        restart_in_ms: status.restart_in.map(|delay| {
+          u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)
+        }),
+      })
+      .collect();
+
+    Ok(ClientResponseData::Builtins(BuiltinsData { builtins }))
+  }
+
   fn to_client_response_msg(
     client_message: String,
     response_data: anyhow::Result<ClientResponseData>,
   ) -> anyhow::Result<Message> {
     let error = response_data.as_ref().err().map(ToString::to_string);
+    let error_code = response_data
+      .as_ref()
+      .err()
+      .and_then(|err| err.downcast_ref::<CommandError>())
+      .map(|err| err.code().to_string());
     let success = response_data.as_ref().is_ok();
 
     let message = ServerMessage::ClientResponse(ClientResponseMessage {
       client_message,
       data: response_data.ok(),
       error,
+      error_code,
       success,
     });
 
@@ -337,11 +656,13 @@ impl IpcServer {
   fn to_event_subscription_msg(
     subscription_id: Uuid,
     event: WmEvent,
+    is_replay: bool,
   ) -> anyhow::Result<Message> {
     let message =
       ServerMessage::EventSubscription(EventSubscriptionMessage {
         data: Some(event),
         error: None,
+        is_replay,
         subscription_id,
         success: true,
       });
@@ -356,6 +677,7 @@ impl IpcServer {
       WmEvent::BindingModesChanged { .. } => {
         SubscribableEvent::BindingModesChanged
       }
+      WmEvent::BuiltinExited { .. } => SubscribableEvent::BuiltinExited,
       WmEvent::FocusChanged { .. } => SubscribableEvent::FocusChanged,
       WmEvent::FocusedContainerMoved { .. } => {
         SubscribableEvent::FocusedContainerMoved
@@ -369,10 +691,23 @@ impl IpcServer {
       WmEvent::UserConfigChanged { .. } => {
         SubscribableEvent::UserConfigChanged
       }
+      WmEvent::WindowAlwaysOnTopChanged { .. } => {
+        SubscribableEvent::WindowAlwaysOnTopChanged
+      }
+      WmEvent::WindowCloseRequested { .. } => {
+        SubscribableEvent::WindowCloseRequested
+      }
+      WmEvent::WindowLayoutChanged { .. } => {
+        SubscribableEvent::WindowLayoutChanged
+      }
       WmEvent::WindowManaged { .. } => SubscribableEvent::WindowManaged,
+      WmEvent::WindowMaximizedChanged { .. } => {
+        SubscribableEvent::WindowMaximizedChanged
+      }
       WmEvent::WindowUnmanaged { .. } => {
         SubscribableEvent::WindowUnmanaged
       }
+      WmEvent::WindowUrgent { .. } => SubscribableEvent::WindowUrgent,
       WmEvent::WorkspaceActivated { .. } => {
         SubscribableEvent::WorkspaceActivated
       }
@@ -385,6 +720,20 @@ impl IpcServer {
       WmEvent::PauseChanged { .. } => SubscribableEvent::PauseChanged,
     };
 
+    if let WmEvent::UserConfigChanged { parsed_config, .. } = &event {
+      self.set_event_replay_size(parsed_config.ipc.event_replay_size);
+    }
+
+    if self.event_replay_size > 0 {
+      if self.recent_events.len() == self.event_replay_size {
+        self.recent_events.pop_front();
+      }
+
+      self
+        .recent_events
+        .push_back((event_type.clone(), event.clone()));
+    }
+
     self
       .event_tx
       .send((event_type, event))
@@ -393,6 +742,20 @@ impl IpcServer {
     Ok(())
   }
 
+  /// Stops accepting connections and closes open connections after
+  /// giving clients a grace period to read any final events.
+  pub async fn shutdown(&mut self) {
+    self.stop();
+
+    // Fails if there are no open connections.
+    if self.shutdown_tx.send(()).is_ok() {
+      // Receives `None` once every connection has dropped its sender.
+      let _ =
+        time::timeout(SHUTDOWN_GRACE_PERIOD * 2, self.open_rx.recv())
+          .await;
+    }
+  }
+
   pub fn stop(&self) {
     info!("Shutting down IPC server.");
     self.abort_handle.abort();
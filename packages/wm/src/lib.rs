@@ -0,0 +1,24 @@
+//! Core of the `glazewm` window manager.
+//!
+//! The `glazewm` binary runs the WM on top of this library. It's only a
+//! library such that benchmarks can reach the container tree.
+
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc, clippy::must_use_candidate)]
+#![feature(iterator_try_collect)]
+
+pub mod command_metrics;
+pub mod commands;
+pub mod events;
+pub mod ipc_server;
+pub mod models;
+pub mod pending_sync;
+pub mod runtime_rules;
+pub mod session;
+pub mod sys_tray;
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+pub mod traits;
+pub mod user_config;
+pub mod wm;
+pub mod wm_state;
@@ -7,35 +7,44 @@
   windows_subsystem = "windows"
 )]
 #![warn(clippy::all, clippy::pedantic)]
-#![feature(iterator_try_collect)]
 
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, time::Duration};
 
-use anyhow::{Context, Error};
-use tokio::{process::Command, signal};
+use anyhow::{bail, Context, Error};
+use tokio::{
+  process::Command,
+  signal, task,
+  time::{self, Instant, MissedTickBehavior},
+};
 use tracing::{debug, error, info, warn, Level};
 use tracing_subscriber::{
   fmt::{self, writer::MakeWriterExt},
   layer::SubscriberExt,
 };
-use wm_common::{AppCommand, InvokeCommand, Verbosity, WmEvent};
-use wm_platform::Platform;
-
-use crate::{
-  ipc_server::IpcServer, sys_tray::SystemTray, user_config::UserConfig,
+use wm::{
+  events::mouse_draggable_windows, ipc_server::IpcServer,
+  session::Session, sys_tray::SystemTray, user_config::UserConfig,
   wm::WindowManager,
 };
+use wm_common::{
+  AppCommand, InvokeCommand, IpcEndpoint, Verbosity, WmEvent,
+  IPC_ENDPOINT_ENV,
+};
+use wm_ipc_client::IpcClient;
+use wm_platform::{EventListener, Platform, SingleInstance};
+
+/// Time to wait for a running instance to exit when replacing it.
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval at which builtin programs are checked for having exited.
+const BUILTIN_REAP_INTERVAL: Duration = Duration::from_secs(1);
 
-mod commands;
-mod events;
-mod ipc_server;
-mod models;
-mod pending_sync;
-mod sys_tray;
-mod traits;
-mod user_config;
-mod wm;
-mod wm_state;
+/// Interval at which config files are checked for having been modified.
+const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval at which the session is saved, such that it can be restored
+/// after a crash.
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 /// Main entry point for the application.
 ///
@@ -49,9 +58,10 @@ async fn main() -> anyhow::Result<()> {
   match app_command {
     AppCommand::Start {
       config_path,
+      replace,
       verbosity,
     } => {
-      let res = start_wm(config_path, verbosity).await;
+      let res = start_wm(config_path, replace, verbosity).await;
 
       // If unable to start the WM, the error is fatal and a message dialog
       // is shown.
@@ -68,25 +78,39 @@ async fn main() -> anyhow::Result<()> {
 
 async fn start_wm(
   config_path: Option<PathBuf>,
+  replace: bool,
   verbosity: Verbosity,
 ) -> anyhow::Result<()> {
   setup_logging(&verbosity)?;
 
-  // Ensure that only one instance of the WM is running.
-  let _single_instance = Platform::new_single_instance()?;
-
   // Parse and validate user config.
   let mut config = UserConfig::new(config_path)?;
+  config.configure_builtins();
+
+  let ipc_endpoint = config.value.ipc.endpoint();
+
+  // Ensure that only one instance of the WM is running.
+  let _single_instance =
+    acquire_single_instance(replace, &ipc_endpoint).await?;
+
+  // Explain up-front why builtins that weren't bundled are missing.
+  wm_builtin::warn_unavailable_builtins();
 
   // Start watcher process for restoring hidden windows on crash.
-  start_watcher_process()?;
+  start_watcher_process(&ipc_endpoint)?;
 
   // Add application icon to system tray.
   let mut tray = SystemTray::new(&config.path)?;
 
   let mut wm = WindowManager::new(&mut config)?;
 
-  let mut ipc_server = IpcServer::start().await?;
+  let mut ipc_server = IpcServer::start(&ipc_endpoint).await?;
+  ipc_server.set_event_replay_size(config.value.ipc.event_replay_size);
+
+  // Advertise the endpoint to clients that aren't spawned by the WM.
+  if let Err(err) = ipc_endpoint.write_discovery_file() {
+    warn!("Failed to write IPC discovery file: {:?}", err);
+  }
 
   // Start listening for platform events after populating initial state.
   let mut event_listener = Platform::start_event_listener(&config.value)?;
@@ -95,7 +119,56 @@ async fn start_wm(
   let startup_commands = config.value.general.startup_commands.clone();
   wm.process_commands(&startup_commands, None, &mut config)?;
 
+  run_event_loop(
+    &mut wm,
+    &mut config,
+    &mut tray,
+    &mut event_listener,
+    &mut ipc_server,
+  )
+  .await;
+
+  let res = run_cleanup(&mut wm, &mut config, &mut ipc_server).await;
+
+  if let Err(err) = IpcEndpoint::remove_discovery_file() {
+    warn!("Failed to remove IPC discovery file: {:?}", err);
+  }
+
+  res
+}
+
+/// Creates an interval that doesn't try to catch up on missed ticks.
+fn delayed_interval(period: Duration) -> time::Interval {
+  let mut interval = time::interval(period);
+  interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+  interval
+}
+
+/// Processes platform events, IPC messages, and WM events until the WM
+/// is exited.
+async fn run_event_loop(
+  wm: &mut WindowManager,
+  config: &mut UserConfig,
+  tray: &mut SystemTray,
+  event_listener: &mut EventListener,
+  ipc_server: &mut IpcServer,
+) {
+  let mut builtin_reap_interval = delayed_interval(BUILTIN_REAP_INTERVAL);
+  let mut config_watch_interval = delayed_interval(CONFIG_WATCH_INTERVAL);
+  let mut session_save_interval = delayed_interval(SESSION_SAVE_INTERVAL);
+
   loop {
+    // The mouse hook decides whether to block a click before the WM gets
+    // to handle it, so it needs to know which windows can be dragged.
+    if config.value.mouse_bindings.enabled {
+      event_listener
+        .set_draggable_windows(mouse_draggable_windows(&wm.state, config));
+    }
+
+    let cursor_focus_deadline = wm.state.cursor_focus_deadline(config);
+    let title_change_deadline = wm.state.title_change_deadline();
+    let close_deadline = wm.state.close_deadline(config);
+
     let res = tokio::select! {
       Some(()) = tray.exit_rx.recv() => {
         info!("Exiting through system tray.");
@@ -111,21 +184,18 @@ async fn start_wm(
       },
       Some(event) = event_listener.event_rx.recv() => {
         debug!("Received platform event: {:?}", event);
-        wm.process_event(event, &mut config)
+        wm.process_event(event, config)
       },
-      Some((
-        message,
-        response_tx,
-        disconnection_tx
-      )) = ipc_server.message_rx.recv() => {
+      Some((message, response_tx, disconnection_tx)) =
+        ipc_server.message_rx.recv() => {
         info!("Received IPC message: {:?}", message);
 
         if let Err(err) = ipc_server.process_message(
           message,
           &response_tx,
           &disconnection_tx,
-          &mut wm,
-          &mut config,
+          wm,
+          config,
         ) {
           error!("{:?}", err);
         }
@@ -133,35 +203,59 @@ async fn start_wm(
         Ok(())
       },
       Some(wm_event) = wm.event_rx.recv() => {
-        debug!("Received WM event: {:?}", wm_event);
-
-        // Update event listener when keyboard or mouse listener needs to
-        // be changed.
-        if matches!(
-          wm_event,
-          WmEvent::UserConfigChanged { .. }
-            | WmEvent::BindingModesChanged { .. }
-            | WmEvent::PauseChanged { .. }
-        ) {
-          event_listener.update(
-            &config.value,
-            &wm.state.binding_modes,
-            wm.state.is_paused,
-          );
-        }
-
-        if let Err(err) = ipc_server.process_event(wm_event) {
-          error!("{:?}", err);
-        }
-
+        handle_wm_event(wm_event, wm, config, event_listener, ipc_server);
         Ok(())
       },
       Some(()) = tray.config_reload_rx.recv() => {
-        wm.process_commands(
-          &vec![InvokeCommand::WmReloadConfig],
-          None,
-          &mut config,
-        ).map(|_| ())
+        reload_config(wm, config)
+      },
+      // Flush redraws that were deferred due to the redraw budget. Other
+      // events that are ready get a chance to be processed first.
+      () = task::yield_now(),
+        if wm.state.pending_sync.has_deferred_redraws() => {
+        wm.process_deferred_redraws(config)
+      },
+      // Focus the container under the cursor once the cursor has stayed
+      // over it for `focus_follows_cursor_delay_ms`.
+      () = time::sleep_until(
+        cursor_focus_deadline.map_or_else(Instant::now, Instant::from_std)
+      ), if cursor_focus_deadline.is_some() => {
+        wm.process_cursor_focus(config)
+      },
+      // Re-evaluate window rules once a window's title has stopped
+      // changing.
+      () = time::sleep_until(
+        title_change_deadline.map_or_else(Instant::now, Instant::from_std)
+      ), if title_change_deadline.is_some() => {
+        wm.process_title_changes(config)
+      },
+      // Terminate windows closed with `--kill` that haven't closed within
+      // `window_behavior.force_close.timeout_ms`.
+      () = time::sleep_until(
+        close_deadline.map_or_else(Instant::now, Instant::from_std)
+      ), if close_deadline.is_some() => {
+        wm.process_close_timeouts(config);
+        Ok(())
+      },
+      // Detect builtin programs that have exited, such that a crash is
+      // noticed even if the program's status isn't queried, and restart
+      // them once their backoff has elapsed.
+      _ = builtin_reap_interval.tick() => wm.process_builtins(),
+      // Reload the config once the config file or any of its included
+      // files have been modified.
+      _ = config_watch_interval.tick(),
+        if config.value.general.reload_on_config_change => {
+        if config.has_source_changed() {
+          info!("Config file changed. Reloading config.");
+          reload_config(wm, config)
+        } else {
+          Ok(())
+        }
+      },
+      _ = session_save_interval.tick(),
+        if config.value.general.restore_session => {
+        save_session(wm);
+        Ok(())
       },
     };
 
@@ -170,8 +264,81 @@ async fn start_wm(
       Platform::show_error_dialog("Non-fatal error", &err.to_string());
     }
   }
+}
+
+/// Forwards a WM event to IPC subscribers.
+///
+/// Also updates the event listener when keyboard or mouse listeners need
+/// to be changed.
+fn handle_wm_event(
+  wm_event: WmEvent,
+  wm: &WindowManager,
+  config: &UserConfig,
+  event_listener: &mut EventListener,
+  ipc_server: &mut IpcServer,
+) {
+  debug!("Received WM event: {:?}", wm_event);
+
+  if matches!(
+    wm_event,
+    WmEvent::UserConfigChanged { .. }
+      | WmEvent::BindingModesChanged { .. }
+      | WmEvent::PauseChanged { .. }
+  ) {
+    event_listener.update(
+      &config.value,
+      &wm.state.binding_modes,
+      wm.state.is_paused,
+    );
+  }
+
+  if let Err(err) = ipc_server.process_event(wm_event) {
+    error!("{:?}", err);
+  }
+}
+
+/// Ensures that this is the only running instance of the WM.
+///
+/// If another instance is running and `replace` is set, it's told to exit
+/// via IPC, and this instance takes over once it has exited. The running
+/// instance is expected to use the same IPC endpoint.
+async fn acquire_single_instance(
+  replace: bool,
+  ipc_endpoint: &IpcEndpoint,
+) -> anyhow::Result<SingleInstance> {
+  if !SingleInstance::is_running() {
+    return Platform::new_single_instance();
+  }
+
+  if !replace {
+    bail!(
+      "GlazeWM is already running. Start with `--replace` to replace \
+       the running instance."
+    );
+  }
+
+  info!("Replacing running instance of the WM.");
+
+  let mut client = IpcClient::connect_to(ipc_endpoint)
+    .await
+    .context("Failed to connect to the running instance.")?;
+
+  client
+    .send(&format!("command {}", InvokeCommand::WmExit.name()))
+    .await
+    .context("Failed to tell the running instance to exit.")?;
+
+  let deadline = Instant::now() + REPLACE_TIMEOUT;
+
+  while SingleInstance::is_running() {
+    if Instant::now() >= deadline {
+      bail!("Timed out waiting for the running instance to exit.");
+    }
+
+    time::sleep(Duration::from_millis(100)).await;
+  }
 
-  run_cleanup(&mut wm, &mut config, &mut ipc_server)
+  Platform::new_single_instance()
 }
 
 /// Initialize logging with the specified verbosity level.
@@ -212,24 +379,52 @@ fn setup_logging(verbosity: &Verbosity) -> anyhow::Result<()> {
 ///
 /// This assumes the watcher binary exists in the same directory as the WM
 /// binary.
-fn start_watcher_process() -> anyhow::Result<tokio::process::Child, Error>
-{
+fn start_watcher_process(
+  ipc_endpoint: &IpcEndpoint,
+) -> anyhow::Result<tokio::process::Child, Error> {
   let watcher_path = env::current_exe()?
     .parent()
     .context("Failed to resolve path to the watcher process.")?
     .join("glazewm-watcher");
 
   Command::new(&watcher_path)
+    .env(IPC_ENDPOINT_ENV, ipc_endpoint.to_string())
     .spawn()
     .context("Failed to start watcher process.")
 }
 
+/// Reloads the user config via the `wm-reload-config` command.
+fn reload_config(
+  wm: &mut WindowManager,
+  config: &mut UserConfig,
+) -> anyhow::Result<()> {
+  wm.process_commands(&vec![InvokeCommand::WmReloadConfig], None, config)
+    .map(|_| ())
+}
+
+/// Saves the current window arrangement to the session file.
+fn save_session(wm: &WindowManager) {
+  let res = Session::default_path().and_then(|path| {
+    Session::from_state(&wm.state).and_then(|session| session.write(&path))
+  });
+
+  if let Err(err) = res {
+    warn!("Failed to save session: {:?}", err);
+  }
+}
+
 /// Runs cleanup tasks when the WM is exiting.
-fn run_cleanup(
+async fn run_cleanup(
   wm: &mut WindowManager,
   config: &mut UserConfig,
   ipc_server: &mut IpcServer,
 ) -> anyhow::Result<()> {
+  // Save the window arrangement before shutdown commands get a chance to
+  // change it.
+  if config.value.general.restore_session {
+    save_session(wm);
+  }
+
   // Ensure that the WM is unpaused, otherwise, shutdown commands won't get
   // executed.
   wm.state.is_paused = false;
@@ -238,6 +433,10 @@ fn run_cleanup(
   let shutdown_commands = config.value.general.shutdown_commands.clone();
   wm.process_commands(&shutdown_commands, None, config)?;
 
+  // Stop builtin programs, such that a replacing instance can start them
+  // anew.
+  wm_builtin::stop_all_builtins();
+
   wm.state.emit_event(WmEvent::ApplicationExiting);
 
   // Emit remaining WM events before exiting.
@@ -249,5 +448,9 @@ fn run_cleanup(
     }
   }
 
+  // Give subscribers a chance to receive `ApplicationExiting` before
+  // their connections close.
+  ipc_server.shutdown().await;
+
   Ok(())
 }
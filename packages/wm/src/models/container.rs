@@ -1,6 +1,7 @@
 use std::{
   cell::{Ref, RefMut},
   collections::VecDeque,
+  time::Instant,
 };
 
 use ambassador::Delegate;
@@ -8,15 +9,16 @@ use enum_as_inner::EnumAsInner;
 use uuid::Uuid;
 use wm_common::{
   ActiveDrag, ContainerDto, Direction, DisplayState, GapsConfig, Rect,
-  RectDelta, TilingDirection, WindowRuleConfig, WindowState,
+  RectDelta, SizeConstraints, TilingDirection, WindowRuleConfig,
+  WindowState,
 };
 use wm_platform::NativeWindow;
 
 #[allow(clippy::wildcard_imports)]
 use crate::{
   models::{
-    Monitor, NonTilingWindow, RootContainer, SplitContainer, TilingWindow,
-    Workspace,
+    Monitor, NonTilingWindow, PendingDpiAdjustment, RootContainer,
+    SplitContainer, TilingWindow, Workspace,
   },
   traits::*,
   user_config::UserConfig,
@@ -139,7 +141,7 @@ impl std::fmt::Display for WindowContainer {
     // Truncate title if longer than 20 chars. Need to use `chars()`
     // instead of byte slices to handle invalid byte indices.
     let title = if title.len() > 20 {
-      format!("{}...", &title.chars().take(17).collect::<String>())
+      format!("{}...", title.chars().take(17).collect::<String>())
     } else {
       title
     };
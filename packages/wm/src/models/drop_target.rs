@@ -0,0 +1,143 @@
+use wm_common::{Point, Rect, TilingDirection};
+
+use crate::{
+  models::{DirectionContainer, TilingContainer},
+  traits::{PositionGetters, TilingDirectionGetters},
+};
+
+/// Where a dragged tiling window gets inserted when dropped.
+#[derive(Debug, Clone)]
+pub struct DropTarget {
+  /// Deepest direction container under the cursor.
+  pub target_parent: DirectionContainer,
+
+  /// Tiling child of the target parent that is nearest to the cursor,
+  /// along with the side of it that the cursor is over.
+  ///
+  /// `None` if the target parent has no tiling children.
+  pub nearest: Option<(TilingContainer, DropPosition)>,
+}
+
+impl DropTarget {
+  /// Whether the dropped window gets wrapped in a split container
+  /// together with the nearest container. This is the case when dropped
+  /// on a side of a window that is perpendicular to the parent's tiling
+  /// direction.
+  #[must_use]
+  pub fn should_split(&self) -> bool {
+    let Some((nearest_container, drop_position)) = &self.nearest else {
+      return false;
+    };
+
+    nearest_container.is_tiling_window()
+      && match self.target_parent.tiling_direction() {
+        TilingDirection::Horizontal => {
+          matches!(drop_position, DropPosition::Top | DropPosition::Bottom)
+        }
+        TilingDirection::Vertical => {
+          matches!(drop_position, DropPosition::Left | DropPosition::Right)
+        }
+      }
+  }
+
+  /// Gets the rect to highlight as a hint of where the window gets
+  /// inserted.
+  pub fn hint_rect(&self) -> anyhow::Result<Rect> {
+    match &self.nearest {
+      Some((nearest_container, drop_position)) => {
+        Ok(drop_position.half_of(&nearest_container.to_rect()?))
+      }
+      None => self.target_parent.to_rect(),
+    }
+  }
+}
+
+/// Represents where the window was dropped over another.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropPosition {
+  Top,
+  Bottom,
+  Left,
+  Right,
+}
+
+impl DropPosition {
+  /// Gets the drop position for a window based on the mouse position.
+  ///
+  /// This approach divides the window rect into an "X", creating four
+  /// triangular quadrants, to determine which side the cursor is closest
+  /// to.
+  #[must_use]
+  pub fn from_point(mouse_pos: &Point, rect: &Rect) -> Self {
+    let delta_x = mouse_pos.x - rect.center_point().x;
+    let delta_y = mouse_pos.y - rect.center_point().y;
+
+    if delta_x.abs() > delta_y.abs() {
+      // Window is in the left or right triangle.
+      if delta_x > 0 {
+        DropPosition::Right
+      } else {
+        DropPosition::Left
+      }
+    } else {
+      // Window is in the top or bottom triangle.
+      if delta_y > 0 {
+        DropPosition::Bottom
+      } else {
+        DropPosition::Top
+      }
+    }
+  }
+
+  /// Gets the half of the rect on this side.
+  #[must_use]
+  pub fn half_of(&self, rect: &Rect) -> Rect {
+    let center = rect.center_point();
+
+    match self {
+      DropPosition::Top => {
+        Rect::from_ltrb(rect.left, rect.top, rect.right, center.y)
+      }
+      DropPosition::Bottom => {
+        Rect::from_ltrb(rect.left, center.y, rect.right, rect.bottom)
+      }
+      DropPosition::Left => {
+        Rect::from_ltrb(rect.left, rect.top, center.x, rect.bottom)
+      }
+      DropPosition::Right => {
+        Rect::from_ltrb(center.x, rect.top, rect.right, rect.bottom)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn drop_position_from_nearest_side() {
+    let rect = Rect::from_xy(0, 0, 400, 200);
+
+    let position = |x, y| DropPosition::from_point(&Point { x, y }, &rect);
+
+    assert_eq!(position(20, 100), DropPosition::Left);
+    assert_eq!(position(380, 100), DropPosition::Right);
+    assert_eq!(position(200, 10), DropPosition::Top);
+    assert_eq!(position(200, 190), DropPosition::Bottom);
+  }
+
+  #[test]
+  fn half_of_rect_on_drop_side() {
+    let rect = Rect::from_xy(100, 100, 400, 200);
+
+    assert_eq!(
+      DropPosition::Top.half_of(&rect),
+      Rect::from_xy(100, 100, 400, 100)
+    );
+    assert_eq!(
+      DropPosition::Right.half_of(&rect),
+      Rect::from_xy(300, 100, 200, 200)
+    );
+  }
+}
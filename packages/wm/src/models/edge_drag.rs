@@ -0,0 +1,170 @@
+use uuid::Uuid;
+use wm_common::{Point, Rect, TilingDirection};
+
+use crate::traits::MIN_TILING_SIZE;
+
+/// A drag of the edge shared by two adjacent tiling containers, which
+/// resizes both containers as the cursor moves.
+#[derive(Clone, Debug)]
+pub struct EdgeDrag {
+  /// ID of the container before the dragged edge.
+  pub before_id: Uuid,
+
+  /// ID of the container after the dragged edge.
+  pub after_id: Uuid,
+
+  /// Tiling direction of the containers' parent.
+  pub tiling_direction: TilingDirection,
+
+  /// Position of the cursor when the drag started.
+  pub start_point: Point,
+
+  /// Tiling sizes of the two containers when the drag started.
+  pub start_sizes: (f32, f32),
+
+  /// Length in pixels that the tiling sizes of the parent's children
+  /// add up to, excluding the gaps between them.
+  pub available_length: i32,
+}
+
+impl EdgeDrag {
+  /// Gets the distance from `point` to the middle of the gap between two
+  /// adjacent rects.
+  ///
+  /// Returns `None` if the point isn't within `threshold` of the gap, or
+  /// isn't alongside both rects.
+  #[must_use]
+  pub fn edge_distance(
+    before: &Rect,
+    after: &Rect,
+    tiling_direction: &TilingDirection,
+    point: &Point,
+    threshold: i32,
+  ) -> Option<i32> {
+    let (gap_start, gap_end, position, cross_start, cross_end, cross) =
+      match tiling_direction {
+        TilingDirection::Horizontal => (
+          before.right,
+          after.left,
+          point.x,
+          before.top.max(after.top),
+          before.bottom.min(after.bottom),
+          point.y,
+        ),
+        TilingDirection::Vertical => (
+          before.bottom,
+          after.top,
+          point.y,
+          before.left.max(after.left),
+          before.right.min(after.right),
+          point.x,
+        ),
+      };
+
+    let is_within = position >= gap_start - threshold
+      && position <= gap_end + threshold
+      && cross >= cross_start
+      && cross <= cross_end;
+
+    is_within.then(|| (position - i32::midpoint(gap_start, gap_end)).abs())
+  }
+
+  /// Gets the tiling sizes of the two containers with the cursor at
+  /// `point`.
+  ///
+  /// The combined size of the containers stays the same, and neither
+  /// container gets smaller than the minimum tiling size.
+  #[must_use]
+  pub fn sizes_at(&self, point: &Point) -> (f32, f32) {
+    let delta = match self.tiling_direction {
+      TilingDirection::Horizontal => point.x - self.start_point.x,
+      TilingDirection::Vertical => point.y - self.start_point.y,
+    };
+
+    #[allow(clippy::cast_precision_loss)]
+    let size_delta = delta as f32 / self.available_length.max(1) as f32;
+
+    let (before_size, after_size) = self.start_sizes;
+    let total_size = before_size + after_size;
+
+    let before_size = (before_size + size_delta)
+      .clamp(MIN_TILING_SIZE, total_size - MIN_TILING_SIZE);
+
+    (before_size, total_size - before_size)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn drag() -> EdgeDrag {
+    EdgeDrag {
+      before_id: Uuid::new_v4(),
+      after_id: Uuid::new_v4(),
+      tiling_direction: TilingDirection::Horizontal,
+      start_point: Point { x: 500, y: 300 },
+      start_sizes: (0.5, 0.5),
+      available_length: 1000,
+    }
+  }
+
+  #[test]
+  fn hits_gap_between_rects() {
+    let before = Rect::from_xy(0, 0, 490, 600);
+    let after = Rect::from_xy(510, 0, 490, 600);
+    let direction = TilingDirection::Horizontal;
+
+    let hit = |x| {
+      EdgeDrag::edge_distance(
+        &before,
+        &after,
+        &direction,
+        &Point { x, y: 300 },
+        4,
+      )
+    };
+
+    assert_eq!(hit(500), Some(0));
+    assert_eq!(hit(487), Some(13));
+    assert_eq!(hit(485), None);
+    assert_eq!(hit(515), None);
+  }
+
+  #[test]
+  fn misses_point_beside_shared_edge() {
+    let before = Rect::from_xy(0, 0, 600, 290);
+    let after = Rect::from_xy(0, 310, 600, 290);
+
+    let distance = EdgeDrag::edge_distance(
+      &before,
+      &after,
+      &TilingDirection::Vertical,
+      &Point { x: 700, y: 300 },
+      4,
+    );
+
+    assert_eq!(distance, None);
+  }
+
+  #[test]
+  fn resizes_both_containers_by_drag_delta() {
+    let (before_size, after_size) =
+      drag().sizes_at(&Point { x: 600, y: 50 });
+
+    assert!((before_size - 0.6).abs() < f32::EPSILON);
+    assert!((after_size - 0.4).abs() < f32::EPSILON);
+  }
+
+  #[test]
+  fn clamps_to_minimum_size() {
+    let (before_size, after_size) =
+      drag().sizes_at(&Point { x: 5000, y: 300 });
+
+    assert!((before_size - (1. - MIN_TILING_SIZE)).abs() < f32::EPSILON);
+    assert!((after_size - MIN_TILING_SIZE).abs() < f32::EPSILON);
+
+    let (before_size, _) = drag().sizes_at(&Point { x: -5000, y: 300 });
+    assert!((before_size - MIN_TILING_SIZE).abs() < f32::EPSILON);
+  }
+}
@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+
+use uuid::Uuid;
+
+/// IDs of managed windows in the order they were last focused, most
+/// recent first.
+///
+/// Used for the `focus-last` and `focus-nth-last` commands.
+#[derive(Clone, Debug, Default)]
+pub struct FocusHistory {
+  window_ids: VecDeque<Uuid>,
+}
+
+impl FocusHistory {
+  /// Moves the window to the front of the history.
+  pub fn push(&mut self, window_id: Uuid) {
+    if self.window_ids.front() == Some(&window_id) {
+      return;
+    }
+
+    self.remove(window_id);
+    self.window_ids.push_front(window_id);
+  }
+
+  /// Removes the window from the history, e.g. once it's unmanaged.
+  pub fn remove(&mut self, window_id: Uuid) {
+    self.window_ids.retain(|id| *id != window_id);
+  }
+
+  /// Gets the `n`th most recently focused window, skipping the currently
+  /// focused container. An `n` of 1 is the previously focused window.
+  ///
+  /// IDs for which `is_window` returns false are skipped as well, in case
+  /// a window left the tree without being removed from the history.
+  pub fn nth_last(
+    &self,
+    n: usize,
+    focused_id: Uuid,
+    is_window: impl Fn(Uuid) -> bool,
+  ) -> Option<Uuid> {
+    self
+      .window_ids
+      .iter()
+      .copied()
+      .filter(|id| *id != focused_id && is_window(*id))
+      .nth(n.checked_sub(1)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn toggles_between_two_most_recent() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut history = FocusHistory::default();
+
+    for id in ids {
+      history.push(id);
+    }
+
+    assert_eq!(history.nth_last(1, ids[2], |_| true), Some(ids[1]));
+
+    history.push(ids[1]);
+    assert_eq!(history.nth_last(1, ids[1], |_| true), Some(ids[2]));
+    assert_eq!(history.nth_last(2, ids[1], |_| true), Some(ids[0]));
+    assert_eq!(history.nth_last(3, ids[1], |_| true), None);
+    assert_eq!(history.nth_last(0, ids[1], |_| true), None);
+  }
+
+  #[test]
+  fn skips_removed_windows() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut history = FocusHistory::default();
+
+    for id in ids {
+      history.push(id);
+    }
+
+    history.remove(ids[1]);
+
+    // Focus isn't on a window (e.g. an empty workspace is focused).
+    let workspace_id = Uuid::new_v4();
+    assert_eq!(history.nth_last(1, workspace_id, |_| true), Some(ids[2]));
+    assert_eq!(history.nth_last(2, workspace_id, |_| true), Some(ids[0]));
+  }
+
+  #[test]
+  fn skips_windows_not_in_tree() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut history = FocusHistory::default();
+
+    for id in ids {
+      history.push(id);
+    }
+
+    let is_window = |id| id != ids[1];
+    assert_eq!(history.nth_last(1, ids[2], is_window), Some(ids[0]));
+    assert_eq!(history.nth_last(2, ids[2], is_window), None);
+  }
+}
@@ -1,19 +1,35 @@
 mod container;
+mod drop_target;
+mod edge_drag;
+mod focus_history;
 mod insertion_target;
 mod monitor;
+mod mouse_drag;
 mod non_tiling_window;
+mod pending_close;
+mod pending_dpi_adjustment;
+mod pending_workspace_assignment;
 mod root_container;
 mod split_container;
+mod swallowed_window;
 mod tiling_window;
 mod workspace;
 mod workspace_target;
 
 pub use container::*;
+pub use drop_target::*;
+pub use edge_drag::*;
+pub use focus_history::*;
 pub use insertion_target::*;
 pub use monitor::*;
+pub use mouse_drag::*;
 pub use non_tiling_window::*;
+pub use pending_close::*;
+pub use pending_dpi_adjustment::*;
+pub use pending_workspace_assignment::*;
 pub use root_container::*;
 pub use split_container::*;
+pub use swallowed_window::*;
 pub use tiling_window::*;
 pub use workspace::*;
 pub use workspace_target::*;
@@ -6,14 +6,16 @@ use std::{
 
 use anyhow::Context;
 use uuid::Uuid;
-use wm_common::{ContainerDto, MonitorDto, Rect};
+use wm_common::{
+  ContainerDto, MonitorDto, MonitorSelector, MonitorSnapshot, Rect,
+};
 use wm_platform::NativeMonitor;
 
 use crate::{
   impl_common_getters, impl_container_debug,
   models::{
-    Container, DirectionContainer, TilingContainer, WindowContainer,
-    Workspace,
+    Container, DirectionContainer, PendingDpiAdjustment, TilingContainer,
+    WindowContainer, Workspace,
   },
   traits::{CommonGetters, PositionGetters},
 };
@@ -27,6 +29,8 @@ struct MonitorInner {
   children: VecDeque<Container>,
   child_focus_order: VecDeque<Uuid>,
   native: NativeMonitor,
+  is_standby: bool,
+  is_disconnected: bool,
 }
 
 impl Monitor {
@@ -37,6 +41,8 @@ impl Monitor {
       children: VecDeque::new(),
       child_focus_order: VecDeque::new(),
       native: native_monitor,
+      is_standby: false,
+      is_disconnected: false,
     };
 
     Self(Rc::new(RefCell::new(monitor)))
@@ -50,6 +56,26 @@ impl Monitor {
     self.0.borrow_mut().native = native;
   }
 
+  /// Whether the monitor's workspaces are parked, such that none of
+  /// its windows are shown until it's resumed.
+  pub fn is_standby(&self) -> bool {
+    self.0.borrow().is_standby
+  }
+
+  pub fn set_standby(&self, is_standby: bool) {
+    self.0.borrow_mut().is_standby = is_standby;
+  }
+
+  /// Whether the monitor was put in standby because it got disconnected,
+  /// in which case it's resumed once connected again.
+  pub fn is_disconnected(&self) -> bool {
+    self.0.borrow().is_disconnected
+  }
+
+  pub fn set_disconnected(&self, is_disconnected: bool) {
+    self.0.borrow_mut().is_disconnected = is_disconnected;
+  }
+
   pub fn displayed_workspace(&self) -> Option<Workspace> {
     self
       .child_focus_order()
@@ -65,20 +91,62 @@ impl Monitor {
       .collect()
   }
 
-  /// Whether there is a difference in DPI between this monitor and the
-  /// parent monitor of another container.
-  pub fn has_dpi_difference(
+  /// Gets the area of the monitor that windows can be tiled in. This
+  /// excludes space reserved by the OS, such as the taskbar.
+  pub fn working_rect(&self) -> anyhow::Result<Rect> {
+    self.0.borrow().native.working_rect().cloned()
+  }
+
+  /// Whether this is the OS primary monitor.
+  pub fn is_primary(&self) -> bool {
+    self.native().is_primary().unwrap_or(false)
+  }
+
+  /// Whether this is the monitor given by the selector.
+  pub fn is_selected_by(&self, selector: &MonitorSelector) -> bool {
+    match selector {
+      MonitorSelector::Index(index) => self.index() == *index as usize,
+      MonitorSelector::Name(name) => {
+        let native = self.native();
+
+        native
+          .device_name()
+          .is_ok_and(|device_name| device_name == name)
+          || native.hardware_id().ok().flatten() == Some(name)
+      }
+    }
+  }
+
+  /// Gets the DPI adjustment for a window moving from this monitor to
+  /// the parent monitor of another container.
+  pub fn dpi_adjustment_to(
     &self,
     other: &Container,
-  ) -> anyhow::Result<bool> {
-    let dpi = self.native().dpi()?;
+  ) -> anyhow::Result<PendingDpiAdjustment> {
+    let scale_factor = self.native().scale_factor()?;
 
-    let other_dpi = other
+    let other_scale_factor = other
       .monitor()
-      .and_then(|monitor| monitor.native().dpi().ok())
-      .context("Failed to get DPI of other monitor.")?;
+      .and_then(|monitor| monitor.native().scale_factor().ok())
+      .context("Failed to get scale factor of other monitor.")?;
+
+    Ok(PendingDpiAdjustment::new(scale_factor, other_scale_factor))
+  }
+
+  /// Gets the current values of the properties that are diffed for
+  /// `WmEvent::MonitorUpdated`.
+  pub fn snapshot(&self) -> anyhow::Result<MonitorSnapshot> {
+    let native = self.native();
 
-    Ok(dpi != other_dpi)
+    Ok(MonitorSnapshot {
+      rect: native.rect()?.clone(),
+      working_rect: native.working_rect()?.clone(),
+      scale_factor: native.scale_factor()?,
+      is_primary: native.is_primary()?,
+      device_name: native.device_name()?.clone(),
+      index: self.index(),
+      is_standby: self.is_standby(),
+    })
   }
 
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
@@ -106,6 +174,8 @@ impl Monitor {
       device_path: self.native().device_path()?.cloned(),
       hardware_id: self.native().hardware_id()?.cloned(),
       working_rect: self.native().working_rect()?.clone(),
+      is_primary: self.native().is_primary()?,
+      is_standby: self.is_standby(),
     }))
   }
 }
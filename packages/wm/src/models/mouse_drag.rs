@@ -0,0 +1,158 @@
+use wm_common::{Point, Rect};
+use wm_platform::MouseDragAction;
+
+/// Minimum width and height in pixels that a window can be resized to
+/// via a mouse binding.
+const MIN_RESIZE_LENGTH: i32 = 50;
+
+/// A move or resize of a window by dragging it with the mouse binding
+/// modifier held.
+#[derive(Clone, Debug)]
+pub struct MouseDrag {
+  /// Handle of the dragged window. The window's container gets replaced
+  /// when a tiling window is dragged out of its tile, so the handle is
+  /// used to find the window instead.
+  pub handle: isize,
+
+  /// Whether the window is being moved or resized.
+  pub action: MouseDragAction,
+
+  /// Position of the cursor when the drag started.
+  pub start_point: Point,
+
+  /// Position of the window (including its shadow borders) when the drag
+  /// started.
+  pub start_rect: Rect,
+
+  /// Whether the left edge is resized rather than the right edge.
+  pub is_left: bool,
+
+  /// Whether the top edge is resized rather than the bottom edge.
+  pub is_top: bool,
+}
+
+impl MouseDrag {
+  /// Creates a drag of the window at `start_rect`. Resizing moves the
+  /// corner nearest to `start_point`.
+  #[must_use]
+  pub fn new(
+    handle: isize,
+    action: MouseDragAction,
+    start_point: Point,
+    start_rect: Rect,
+  ) -> Self {
+    let center = start_rect.center_point();
+
+    Self {
+      handle,
+      action,
+      is_left: start_point.x < center.x,
+      is_top: start_point.y < center.y,
+      start_point,
+      start_rect,
+    }
+  }
+
+  /// Gets the position of the window with the cursor at `point`.
+  ///
+  /// When resizing, the edges opposite to the dragged corner stay in
+  /// place, and the window doesn't get smaller than a minimum size.
+  #[must_use]
+  pub fn rect_at(&self, point: &Point) -> Rect {
+    let delta_x = point.x - self.start_point.x;
+    let delta_y = point.y - self.start_point.y;
+    let rect = &self.start_rect;
+
+    match self.action {
+      MouseDragAction::Move => Rect::from_xy(
+        rect.x() + delta_x,
+        rect.y() + delta_y,
+        rect.width(),
+        rect.height(),
+      ),
+      MouseDragAction::Resize => {
+        let (left, right) = if self.is_left {
+          (
+            (rect.left + delta_x).min(rect.right - MIN_RESIZE_LENGTH),
+            rect.right,
+          )
+        } else {
+          (
+            rect.left,
+            (rect.right + delta_x).max(rect.left + MIN_RESIZE_LENGTH),
+          )
+        };
+
+        let (top, bottom) = if self.is_top {
+          (
+            (rect.top + delta_y).min(rect.bottom - MIN_RESIZE_LENGTH),
+            rect.bottom,
+          )
+        } else {
+          (
+            rect.top,
+            (rect.bottom + delta_y).max(rect.top + MIN_RESIZE_LENGTH),
+          )
+        };
+
+        Rect::from_ltrb(left, top, right, bottom)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn drag_from(start_point: Point, action: MouseDragAction) -> MouseDrag {
+    MouseDrag::new(
+      0,
+      action,
+      start_point,
+      Rect::from_xy(100, 100, 400, 300),
+    )
+  }
+
+  #[test]
+  fn moves_window_by_drag_delta() {
+    let drag = drag_from(Point { x: 150, y: 150 }, MouseDragAction::Move);
+
+    assert_eq!(
+      drag.rect_at(&Point { x: 250, y: 100 }),
+      Rect::from_xy(200, 50, 400, 300)
+    );
+  }
+
+  #[test]
+  fn resizes_from_nearest_corner() {
+    // Near the bottom-right corner.
+    let drag =
+      drag_from(Point { x: 450, y: 350 }, MouseDragAction::Resize);
+
+    assert_eq!(
+      drag.rect_at(&Point { x: 500, y: 300 }),
+      Rect::from_xy(100, 100, 450, 250)
+    );
+
+    // Near the top-left corner.
+    let drag =
+      drag_from(Point { x: 150, y: 150 }, MouseDragAction::Resize);
+
+    assert_eq!(
+      drag.rect_at(&Point { x: 100, y: 200 }),
+      Rect::from_ltrb(50, 150, 500, 400)
+    );
+  }
+
+  #[test]
+  fn resize_keeps_minimum_size() {
+    let drag =
+      drag_from(Point { x: 150, y: 350 }, MouseDragAction::Resize);
+
+    assert_eq!(
+      drag.rect_at(&Point { x: 5000, y: -5000 }),
+      Rect::from_ltrb(450, 100, 500, 150)
+    );
+  }
+}
@@ -2,21 +2,22 @@ use std::{
   cell::{Ref, RefCell, RefMut},
   collections::VecDeque,
   rc::Rc,
+  time::Instant,
 };
 
 use anyhow::Context;
 use uuid::Uuid;
 use wm_common::{
   ActiveDrag, ContainerDto, DisplayState, GapsConfig, Rect, RectDelta,
-  WindowDto, WindowRuleConfig, WindowState,
+  SizeConstraints, WindowDto, WindowRuleConfig, WindowState,
 };
 use wm_platform::NativeWindow;
 
 use crate::{
   impl_common_getters, impl_container_debug, impl_window_getters,
   models::{
-    Container, DirectionContainer, InsertionTarget, TilingContainer,
-    TilingWindow, WindowContainer,
+    Container, DirectionContainer, InsertionTarget, PendingDpiAdjustment,
+    TilingContainer, TilingWindow, WindowContainer,
   },
   traits::{CommonGetters, PositionGetters, WindowGetters},
 };
@@ -35,11 +36,15 @@ struct NonTilingWindowInner {
   insertion_target: Option<InsertionTarget>,
   display_state: DisplayState,
   border_delta: RectDelta,
-  has_pending_dpi_adjustment: bool,
+  pending_dpi_adjustment: Option<PendingDpiAdjustment>,
   floating_placement: Rect,
   has_custom_floating_placement: bool,
   done_window_rules: Vec<WindowRuleConfig>,
   active_drag: Option<ActiveDrag>,
+  urgent_since: Option<Instant>,
+  size_constraints: SizeConstraints,
+  last_floating_placement: Option<Rect>,
+  is_fullscreen_within_working_area: bool,
 }
 
 impl NonTilingWindow {
@@ -67,11 +72,15 @@ impl NonTilingWindow {
       insertion_target,
       display_state: DisplayState::Shown,
       border_delta,
-      has_pending_dpi_adjustment: false,
+      pending_dpi_adjustment: None,
       floating_placement,
       has_custom_floating_placement,
       done_window_rules,
       active_drag,
+      urgent_since: None,
+      size_constraints: SizeConstraints::default(),
+      last_floating_placement: None,
+      is_fullscreen_within_working_area: false,
     };
 
     Self(Rc::new(RefCell::new(window)))
@@ -88,8 +97,23 @@ impl NonTilingWindow {
     self.0.borrow_mut().insertion_target = insertion_target;
   }
 
+  /// Whether the window is sized to its monitor's working area while
+  /// fullscreen, rather than covering the entire monitor. Updated from
+  /// the user config whenever the window is redrawn.
+  pub fn is_fullscreen_within_working_area(&self) -> bool {
+    self.0.borrow().is_fullscreen_within_working_area
+  }
+
+  pub fn set_fullscreen_within_working_area(
+    &self,
+    is_fullscreen_within_working_area: bool,
+  ) {
+    self.0.borrow_mut().is_fullscreen_within_working_area =
+      is_fullscreen_within_working_area;
+  }
+
   pub fn to_tiling(&self, gaps_config: GapsConfig) -> TilingWindow {
-    TilingWindow::new(
+    let window = TilingWindow::new(
       Some(self.id()),
       self.native().clone(),
       Some(self.state()),
@@ -99,7 +123,12 @@ impl NonTilingWindow {
       gaps_config,
       self.done_window_rules(),
       self.active_drag(),
-    )
+    );
+
+    window.set_urgent_since(self.urgent_since());
+    window.set_size_constraints(self.size_constraints());
+    window.set_last_floating_placement(self.last_floating_placement());
+    window
   }
 
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
@@ -119,11 +148,14 @@ impl NonTilingWindow {
       display_state: self.display_state(),
       border_delta: self.border_delta(),
       floating_placement: self.floating_placement(),
+      last_floating_placement: self.last_floating_placement(),
       handle: self.native().handle,
       title: self.native().title()?,
       class_name: self.native().class_name()?,
       process_name: self.native().process_name()?,
       active_drag: self.active_drag(),
+      is_urgent: self.is_urgent(),
+      is_maximized: self.native().is_maximized().unwrap_or(false),
     }))
   }
 }
@@ -136,9 +168,69 @@ impl PositionGetters for NonTilingWindow {
   fn to_rect(&self) -> anyhow::Result<Rect> {
     match self.state() {
       WindowState::Fullscreen(_) => {
-        self.monitor().context("No monitor.")?.to_rect()
+        let monitor = self.monitor().context("No monitor.")?;
+
+        if self.is_fullscreen_within_working_area() {
+          monitor.working_rect()
+        } else {
+          monitor.to_rect()
+        }
       }
       _ => Ok(self.floating_placement()),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{FloatingStateConfig, FullscreenStateConfig};
+
+  use super::*;
+  use crate::test_utils;
+
+  fn fullscreen_window(
+    state: &crate::wm_state::WmState,
+  ) -> NonTilingWindow {
+    let monitor = test_utils::monitor_with_working_rect(
+      state,
+      1,
+      Rect::from_xy(0, 0, 1920, 1080),
+      Rect::from_xy(0, 0, 1920, 1040),
+    );
+    let workspace = test_utils::workspace(&monitor, "1");
+
+    test_utils::non_tiling_window(
+      &workspace,
+      1,
+      WindowState::Fullscreen(FullscreenStateConfig::default()),
+    )
+  }
+
+  #[test]
+  fn fullscreen_rect_covers_monitor_by_default() {
+    let (state, _event_rx) = test_utils::state();
+    let window = fullscreen_window(&state);
+
+    assert_eq!(window.to_rect().unwrap(), Rect::from_xy(0, 0, 1920, 1080));
+  }
+
+  #[test]
+  fn fullscreen_rect_within_working_area() {
+    let (state, _event_rx) = test_utils::state();
+    let window = fullscreen_window(&state);
+    window.set_fullscreen_within_working_area(true);
+
+    assert_eq!(window.to_rect().unwrap(), Rect::from_xy(0, 0, 1920, 1040));
+  }
+
+  #[test]
+  fn floating_rect_ignores_working_area() {
+    let (state, _event_rx) = test_utils::state();
+    let window = fullscreen_window(&state);
+    window.set_fullscreen_within_working_area(true);
+    window
+      .set_state(WindowState::Floating(FloatingStateConfig::default()));
+
+    assert_eq!(window.to_rect().unwrap(), window.floating_placement());
+  }
+}
@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+/// A window that was asked to close, but hasn't closed yet.
+///
+/// `close --force` escalates to terminating the window's process once the
+/// window has had time to close, rather than asking it to close again.
+#[derive(Clone, Debug)]
+pub struct PendingClose {
+  /// When the window was first asked to close.
+  pub requested_at: Instant,
+
+  /// Whether to terminate the window's process automatically once the
+  /// timeout has passed.
+  pub kill_on_timeout: bool,
+
+  /// Whether the window's process can be terminated even if it owns
+  /// other managed windows.
+  pub force_all: bool,
+}
+
+impl PendingClose {
+  /// Whether the window has been given `timeout` to close.
+  #[must_use]
+  pub fn has_timed_out(&self, timeout: Duration) -> bool {
+    self.requested_at.elapsed() >= timeout
+  }
+}
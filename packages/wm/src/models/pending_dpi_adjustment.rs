@@ -0,0 +1,154 @@
+/// A DPI adjustment that is applied to a window on its next redraw.
+///
+/// Windows that cross monitors with different scale factors need to be
+/// repositioned twice for the OS to correctly apply the new DPI.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PendingDpiAdjustment {
+  /// Scale factor of the monitor that the window was last drawn on.
+  ///
+  /// `None` if the window's current scale factor is unknown (e.g. after
+  /// display settings change), in which case the adjustment is always
+  /// applied.
+  pub source_scale_factor: Option<f32>,
+
+  /// Scale factor of the monitor that the window is moving to.
+  pub target_scale_factor: f32,
+}
+
+impl PendingDpiAdjustment {
+  #[must_use]
+  pub fn new(source_scale_factor: f32, target_scale_factor: f32) -> Self {
+    Self {
+      source_scale_factor: Some(source_scale_factor),
+      target_scale_factor,
+    }
+  }
+
+  /// Creates an adjustment for a window whose current scale factor is
+  /// unknown.
+  #[must_use]
+  pub fn unknown_source(target_scale_factor: f32) -> Self {
+    Self {
+      source_scale_factor: None,
+      target_scale_factor,
+    }
+  }
+
+  /// Whether the window needs to be adjusted for DPI.
+  #[must_use]
+  pub fn is_needed(&self) -> bool {
+    self.source_scale_factor.is_none_or(|source| {
+      (source - self.target_scale_factor).abs() > f32::EPSILON
+    })
+  }
+
+  /// Combines an existing pending adjustment with a new one.
+  ///
+  /// The window hasn't been redrawn in between, so it's still on the
+  /// source of the existing adjustment. The result is recomputed against
+  /// the latest target rather than stacking adjustments, and is `None` if
+  /// no adjustment is needed.
+  #[must_use]
+  pub fn merge(existing: Option<Self>, new: Self) -> Option<Self> {
+    let source_scale_factor = match existing {
+      // An unknown source on either side makes the merged source unknown.
+      Some(existing) => {
+        new.source_scale_factor.and(existing.source_scale_factor)
+      }
+      None => new.source_scale_factor,
+    };
+
+    let merged = Self {
+      source_scale_factor,
+      target_scale_factor: new.target_scale_factor,
+    };
+
+    merged.is_needed().then_some(merged)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Simulates the pending adjustment slot of a window.
+  #[derive(Default)]
+  struct Slot(Option<PendingDpiAdjustment>);
+
+  impl Slot {
+    fn queue(&mut self, adjustment: PendingDpiAdjustment) {
+      self.0 = PendingDpiAdjustment::merge(self.0, adjustment);
+    }
+
+    fn redraw(&mut self) -> bool {
+      self.0.take().is_some()
+    }
+  }
+
+  #[test]
+  fn move_then_redraw_adjusts_once() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::new(1.0, 1.5));
+
+    assert!(slot.redraw());
+    assert!(!slot.redraw());
+  }
+
+  #[test]
+  fn move_between_equal_scale_factors_is_skipped() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::new(1.5, 1.5));
+
+    assert!(!slot.redraw());
+  }
+
+  #[test]
+  fn move_back_before_redraw_cancels_adjustment() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::new(1.0, 1.5));
+    slot.queue(PendingDpiAdjustment::new(1.5, 1.0));
+
+    assert!(!slot.redraw());
+  }
+
+  #[test]
+  fn repeated_moves_use_latest_target() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::new(1.0, 1.5));
+    slot.queue(PendingDpiAdjustment::new(1.5, 2.0));
+
+    assert_eq!(slot.0, Some(PendingDpiAdjustment::new(1.0, 2.0)));
+    assert!(slot.redraw());
+    assert!(!slot.redraw());
+  }
+
+  #[test]
+  fn move_then_display_change_adjusts_once() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::new(1.0, 1.0));
+    slot.queue(PendingDpiAdjustment::unknown_source(1.0));
+
+    assert_eq!(slot.0, Some(PendingDpiAdjustment::unknown_source(1.0)));
+    assert!(slot.redraw());
+    assert!(!slot.redraw());
+  }
+
+  #[test]
+  fn display_change_then_move_keeps_unknown_source() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::unknown_source(1.5));
+    slot.queue(PendingDpiAdjustment::new(1.5, 1.5));
+
+    assert!(slot.redraw());
+  }
+
+  #[test]
+  fn move_after_redraw_starts_fresh() {
+    let mut slot = Slot::default();
+    slot.queue(PendingDpiAdjustment::unknown_source(1.5));
+    assert!(slot.redraw());
+
+    slot.queue(PendingDpiAdjustment::new(1.5, 1.5));
+    assert!(!slot.redraw());
+  }
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// A recently launched process whose new windows get moved to a target
+/// workspace.
+///
+/// Keyed by process ID in `WmState::pending_workspace_assignments`.
+/// Windows of child processes spawned by it are moved as well.
+#[derive(Clone, Debug)]
+pub struct PendingWorkspaceAssignment {
+  /// Name of the workspace to move windows to.
+  pub workspace_name: String,
+
+  /// When the process was launched, or when its first window matched an
+  /// `initial_workspace` window rule.
+  pub created_at: Instant,
+
+  /// Number of windows that are still to be moved.
+  pub remaining_windows: usize,
+}
+
+impl PendingWorkspaceAssignment {
+  #[must_use]
+  pub fn new(workspace_name: String, remaining_windows: usize) -> Self {
+    Self {
+      workspace_name,
+      created_at: Instant::now(),
+      remaining_windows,
+    }
+  }
+
+  /// Whether the assignment no longer applies to new windows.
+  #[must_use]
+  pub fn is_expired(&self, timeout: Duration) -> bool {
+    self.created_at.elapsed() >= timeout
+  }
+
+  /// Consumes the assignment for a newly managed window, and returns the
+  /// workspace name to move it to.
+  ///
+  /// Returns `None` once all windows have been moved, or once the
+  /// assignment has expired.
+  pub fn take(&mut self, timeout: Duration) -> Option<String> {
+    if self.remaining_windows == 0 || self.is_expired(timeout) {
+      return None;
+    }
+
+    self.remaining_windows -= 1;
+    Some(self.workspace_name.clone())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TIMEOUT: Duration = Duration::from_secs(30);
+
+  #[test]
+  fn take_stops_after_remaining_windows() {
+    let mut assignment =
+      PendingWorkspaceAssignment::new("3".to_string(), 2);
+
+    assert_eq!(assignment.take(TIMEOUT).as_deref(), Some("3"));
+    assert_eq!(assignment.take(TIMEOUT).as_deref(), Some("3"));
+    assert_eq!(assignment.take(TIMEOUT), None);
+  }
+
+  #[test]
+  fn take_stops_after_timeout() {
+    let mut assignment =
+      PendingWorkspaceAssignment::new("3".to_string(), 2);
+
+    assert_eq!(assignment.take(Duration::ZERO), None);
+    assert_eq!(assignment.remaining_windows, 2);
+  }
+
+  #[test]
+  fn expires_after_timeout() {
+    let assignment = PendingWorkspaceAssignment::new("3".to_string(), 1);
+
+    assert!(!assignment.is_expired(TIMEOUT));
+    assert!(assignment.is_expired(Duration::ZERO));
+  }
+}
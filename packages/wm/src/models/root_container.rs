@@ -47,11 +47,28 @@ impl RootContainer {
   }
 
   pub fn monitors(&self) -> Vec<Monitor> {
-    self
-      .children()
-      .into_iter()
-      .filter_map(|container| container.as_monitor().cloned())
-      .collect()
+    self.monitors_iter().collect()
+  }
+
+  /// Iterates over the monitors without collecting them up-front.
+  ///
+  /// The root's children stay borrowed until the iterator is dropped, so
+  /// the tree must not be modified while iterating.
+  pub fn monitors_iter(&self) -> impl Iterator<Item = Monitor> + '_ {
+    let children = self.borrow_children();
+    let mut index = 0;
+
+    std::iter::from_fn(move || {
+      while let Some(child) = children.get(index) {
+        index += 1;
+
+        if let Some(monitor) = child.as_monitor() {
+          return Some(monitor.clone());
+        }
+      }
+
+      None
+    })
   }
 
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
@@ -78,3 +95,32 @@ impl PositionGetters for RootContainer {
     bail!("Root container does not have a position.")
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_platform::NativeMonitor;
+
+  use super::*;
+  use crate::commands::container::attach_container;
+
+  #[test]
+  fn monitors_iter_matches_monitors() {
+    let root = RootContainer::new();
+
+    for handle in 0..3 {
+      let monitor = Monitor::new(NativeMonitor::new(handle));
+      attach_container(&monitor.into(), &root.clone().into(), None)
+        .unwrap();
+    }
+
+    let ids = |monitors: Vec<Monitor>| {
+      monitors.iter().map(CommonGetters::id).collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids(root.monitors_iter().collect()), ids(root.monitors()));
+    assert_eq!(
+      root.monitors_iter().nth(2).map(|monitor| monitor.native()),
+      Some(NativeMonitor::new(2))
+    );
+  }
+}
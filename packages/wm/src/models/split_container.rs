@@ -7,7 +7,8 @@ use std::{
 use anyhow::Context;
 use uuid::Uuid;
 use wm_common::{
-  ContainerDto, GapsConfig, Rect, SplitContainerDto, TilingDirection,
+  ContainerDto, GapsConfig, Rect, SplitContainerDto, SplitLayout,
+  TilingDirection,
 };
 
 use crate::{
@@ -33,6 +34,7 @@ struct SplitContainerInner {
   child_focus_order: VecDeque<Uuid>,
   tiling_size: f32,
   tiling_direction: TilingDirection,
+  layout: SplitLayout,
   gaps_config: GapsConfig,
 }
 
@@ -48,12 +50,21 @@ impl SplitContainer {
       child_focus_order: VecDeque::new(),
       tiling_size: 1.0,
       tiling_direction,
+      layout: SplitLayout::default(),
       gaps_config,
     };
 
     Self(Rc::new(RefCell::new(split)))
   }
 
+  pub fn layout(&self) -> SplitLayout {
+    self.0.borrow().layout
+  }
+
+  pub fn set_layout(&self, layout: SplitLayout) {
+    self.0.borrow_mut().layout = layout;
+  }
+
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
     let rect = self.to_rect()?;
     let children = self
@@ -70,6 +81,7 @@ impl SplitContainer {
       has_focus: self.has_focus(None),
       tiling_size: self.tiling_size(),
       tiling_direction: self.tiling_direction(),
+      layout: self.layout(),
       width: rect.width(),
       height: rect.height(),
       x: rect.x(),
@@ -83,3 +95,46 @@ impl_common_getters!(SplitContainer);
 impl_tiling_size_getters!(SplitContainer);
 impl_tiling_direction_getters!(SplitContainer);
 impl_position_getters_as_resizable!(SplitContainer);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::commands::container::{
+    attach_container, set_focused_descendant,
+  };
+
+  fn split_container() -> SplitContainer {
+    SplitContainer::new(TilingDirection::Horizontal, GapsConfig::default())
+  }
+
+  fn attach(child: &SplitContainer, parent: &SplitContainer) {
+    attach_container(&child.clone().into(), &parent.clone().into(), None)
+      .unwrap();
+  }
+
+  #[test]
+  fn only_most_recently_focused_child_of_stack_is_shown() {
+    let stack = split_container();
+    stack.set_layout(SplitLayout::Tabbed);
+
+    let (first, second, nested) =
+      (split_container(), split_container(), split_container());
+
+    attach(&first, &stack);
+    attach(&second, &stack);
+    attach(&nested, &second);
+
+    assert!(!first.is_hidden_in_stack());
+    assert!(second.is_hidden_in_stack());
+    assert!(nested.is_hidden_in_stack());
+
+    set_focused_descendant(&nested.clone().into(), None);
+
+    assert!(first.is_hidden_in_stack());
+    assert!(!second.is_hidden_in_stack());
+    assert!(!nested.is_hidden_in_stack());
+
+    stack.set_layout(SplitLayout::Split);
+    assert!(!first.is_hidden_in_stack());
+  }
+}
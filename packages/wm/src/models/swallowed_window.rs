@@ -0,0 +1,15 @@
+use super::{TilingWindow, Workspace};
+
+/// A tiling window that is hidden while another window (one of its
+/// descendant processes) takes its place in the tree.
+///
+/// The swallowed window is detached from the tree, and is restored into
+/// the swallowing window's slot once that window is unmanaged.
+#[derive(Clone)]
+pub struct SwallowedWindow {
+  pub window: TilingWindow,
+
+  /// Workspace that the window was swallowed on. The window is released
+  /// back to this workspace if the swallowing window leaves it.
+  pub workspace: Workspace,
+}
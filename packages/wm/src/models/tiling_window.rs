@@ -2,13 +2,15 @@ use std::{
   cell::{Ref, RefCell, RefMut},
   collections::VecDeque,
   rc::Rc,
+  time::Instant,
 };
 
 use anyhow::Context;
 use uuid::Uuid;
 use wm_common::{
   ActiveDrag, ContainerDto, DisplayState, GapsConfig, Rect, RectDelta,
-  TilingDirection, WindowDto, WindowRuleConfig, WindowState,
+  SizeConstraints, TilingDirection, WindowDto, WindowRuleConfig,
+  WindowState,
 };
 use wm_platform::NativeWindow;
 
@@ -18,7 +20,7 @@ use crate::{
   impl_window_getters,
   models::{
     Container, DirectionContainer, InsertionTarget, NonTilingWindow,
-    TilingContainer, WindowContainer,
+    PendingDpiAdjustment, TilingContainer, WindowContainer,
   },
   traits::{
     CommonGetters, PositionGetters, TilingDirectionGetters,
@@ -40,12 +42,15 @@ struct TilingWindowInner {
   prev_state: Option<WindowState>,
   display_state: DisplayState,
   border_delta: RectDelta,
-  has_pending_dpi_adjustment: bool,
+  pending_dpi_adjustment: Option<PendingDpiAdjustment>,
   floating_placement: Rect,
   has_custom_floating_placement: bool,
   gaps_config: GapsConfig,
   done_window_rules: Vec<WindowRuleConfig>,
   active_drag: Option<ActiveDrag>,
+  urgent_since: Option<Instant>,
+  size_constraints: SizeConstraints,
+  last_floating_placement: Option<Rect>,
 }
 
 impl TilingWindow {
@@ -72,12 +77,15 @@ impl TilingWindow {
       prev_state,
       display_state: DisplayState::Shown,
       border_delta,
-      has_pending_dpi_adjustment: false,
+      pending_dpi_adjustment: None,
       floating_placement,
       has_custom_floating_placement,
       gaps_config,
       done_window_rules,
       active_drag,
+      urgent_since: None,
+      size_constraints: SizeConstraints::default(),
+      last_floating_placement: None,
     };
 
     Self(Rc::new(RefCell::new(window)))
@@ -88,7 +96,7 @@ impl TilingWindow {
     state: WindowState,
     insertion_target: Option<InsertionTarget>,
   ) -> NonTilingWindow {
-    NonTilingWindow::new(
+    let window = NonTilingWindow::new(
       Some(self.id()),
       self.native().clone(),
       state,
@@ -99,7 +107,12 @@ impl TilingWindow {
       self.has_custom_floating_placement(),
       self.done_window_rules(),
       self.active_drag(),
-    )
+    );
+
+    window.set_urgent_since(self.urgent_since());
+    window.set_size_constraints(self.size_constraints());
+    window.set_last_floating_placement(self.last_floating_placement());
+    window
   }
 
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
@@ -119,11 +132,14 @@ impl TilingWindow {
       display_state: self.display_state(),
       border_delta: self.border_delta(),
       floating_placement: self.floating_placement(),
+      last_floating_placement: self.last_floating_placement(),
       handle: self.native().handle,
       title: self.native().title()?,
       class_name: self.native().class_name()?,
       process_name: self.native().process_name()?,
       active_drag: self.active_drag(),
+      is_urgent: self.is_urgent(),
+      is_maximized: self.native().is_maximized().unwrap_or(false),
     }))
   }
 }
@@ -7,8 +7,8 @@ use std::{
 use anyhow::Context;
 use uuid::Uuid;
 use wm_common::{
-  ContainerDto, GapsConfig, Rect, TilingDirection, WorkspaceConfig,
-  WorkspaceDto,
+  ContainerDto, GapsConfig, InsertionHint, Rect, TilingDirection,
+  WorkspaceConfig, WorkspaceDto, WorkspaceLayout,
 };
 
 use crate::{
@@ -17,9 +17,16 @@ use crate::{
   models::{
     Container, DirectionContainer, TilingContainer, WindowContainer,
   },
-  traits::{CommonGetters, PositionGetters, TilingDirectionGetters},
+  traits::{
+    cached_rect, CommonGetters, PositionGetters, TilingDirectionGetters,
+    WindowGetters,
+  },
 };
 
+/// Smallest share of a workspace that either the master area or the
+/// stack can take up.
+const MIN_MASTER_RATIO: f32 = 0.05;
+
 #[derive(Clone)]
 pub struct Workspace(Rc<RefCell<WorkspaceInner>>);
 
@@ -32,6 +39,9 @@ struct WorkspaceInner {
   config: WorkspaceConfig,
   gaps_config: GapsConfig,
   tiling_direction: TilingDirection,
+  monocle_window_id: Option<Uuid>,
+  insertion_hint: Option<InsertionHint>,
+  master_ratio: f32,
 }
 
 impl Workspace {
@@ -45,9 +55,12 @@ impl Workspace {
       parent: None,
       children: VecDeque::new(),
       child_focus_order: VecDeque::new(),
+      master_ratio: clamp_master_ratio(config.master_ratio),
       config,
       gaps_config,
       tiling_direction,
+      monocle_window_id: None,
+      insertion_hint: None,
     };
 
     Self(Rc::new(RefCell::new(workspace)))
@@ -71,10 +84,110 @@ impl Workspace {
       .is_some_and(|workspace| workspace.id() == self.id())
   }
 
+  /// ID of the tiling window that fills the workspace while its tiling
+  /// siblings are hidden, if monocle is on.
+  pub fn monocle_window_id(&self) -> Option<Uuid> {
+    self.0.borrow().monocle_window_id
+  }
+
+  pub fn set_monocle_window_id(&self, window_id: Option<Uuid>) {
+    self.0.borrow_mut().monocle_window_id = window_id;
+  }
+
+  /// Where the next tiling window gets inserted, if set via the `insert`
+  /// command.
+  pub fn insertion_hint(&self) -> Option<InsertionHint> {
+    self.0.borrow().insertion_hint.clone()
+  }
+
+  pub fn set_insertion_hint(&self, insertion_hint: Option<InsertionHint>) {
+    self.0.borrow_mut().insertion_hint = insertion_hint;
+  }
+
+  /// How the workspace arranges its tiling children.
+  pub fn layout(&self) -> WorkspaceLayout {
+    self.0.borrow().config.layout
+  }
+
+  /// Share of the workspace taken up by the master area when using the
+  /// master-stack layout. Starts out as the configured ratio and is
+  /// changed by resizing.
+  pub fn master_ratio(&self) -> f32 {
+    self.0.borrow().master_ratio
+  }
+
+  pub fn set_master_ratio(&self, master_ratio: f32) {
+    self.0.borrow_mut().master_ratio = clamp_master_ratio(master_ratio);
+  }
+
+  /// Number of windows in the master area when using the master-stack
+  /// layout.
+  pub fn master_count(&self) -> usize {
+    self.0.borrow().config.master_count
+  }
+
+  /// Whether the given tiling child is in the master area when using the
+  /// master-stack layout.
+  pub fn is_in_master_area(&self, child_id: Uuid) -> bool {
+    self
+      .tiling_children()
+      .take(self.master_count())
+      .any(|child| child.id() == child_id)
+  }
+
+  /// Gets the rect of a tiling child when using the master-stack layout.
+  pub fn master_stack_rect(
+    &self,
+    child_id: Uuid,
+    inner_gaps: (i32, i32),
+  ) -> anyhow::Result<Rect> {
+    let tiling_children = self.tiling_children().collect::<Vec<_>>();
+
+    let index = tiling_children
+      .iter()
+      .position(|child| child.id() == child_id)
+      .context("Container is not a tiling child of the workspace.")?;
+
+    Ok(master_stack_rect(
+      &self.to_rect()?,
+      index,
+      tiling_children.len(),
+      self.master_count(),
+      self.master_ratio(),
+      &self.tiling_direction(),
+      inner_gaps,
+    ))
+  }
+
   pub fn set_gaps_config(&self, gaps_config: GapsConfig) {
     self.0.borrow_mut().gaps_config = gaps_config;
   }
 
+  /// Whether the workspace shows at most one tiling window, either
+  /// because it's the only one or because a window is in monocle.
+  pub fn has_single_tiling_window(&self) -> bool {
+    self.monocle_window_id().is_some()
+      || self
+        .descendants()
+        .filter(Container::is_tiling_window)
+        .nth(1)
+        .is_none()
+  }
+
+  /// Whether the workspace's gaps are removed via `gaps.smart_gaps`.
+  pub fn has_smart_gaps(&self) -> bool {
+    self.0.borrow().gaps_config.smart_gaps
+      && self.has_single_tiling_window()
+  }
+
+  /// Whether the workspace's gaps config has both `smart_gaps` and
+  /// `smart_borders` enabled. Unlike `has_smart_gaps`, this doesn't
+  /// depend on the workspace's windows.
+  pub fn has_smart_borders_enabled(&self) -> bool {
+    let gaps_config = &self.0.borrow().gaps_config;
+    gaps_config.smart_gaps && gaps_config.smart_borders
+  }
+
   pub fn to_dto(&self) -> anyhow::Result<ContainerDto> {
     let rect = self.to_rect()?;
     let config = self.config();
@@ -99,6 +212,15 @@ impl Workspace {
       x: rect.x(),
       y: rect.y(),
       tiling_direction: self.tiling_direction(),
+      monocle_window_id: self.monocle_window_id(),
+      layout: self.layout(),
+      master_ratio: self.master_ratio(),
+      master_count: self.master_count(),
+      has_urgent_window: self
+        .descendants()
+        .filter_map(|descendant| descendant.as_window_container().ok())
+        .any(|window| window.is_urgent()),
+      insertion_hint: self.insertion_hint(),
     }))
   }
 }
@@ -109,23 +231,35 @@ impl_tiling_direction_getters!(Workspace);
 
 impl PositionGetters for Workspace {
   fn to_rect(&self) -> anyhow::Result<Rect> {
+    cached_rect(self.id(), || self.calculate_rect())
+  }
+}
+
+impl Workspace {
+  fn calculate_rect(&self) -> anyhow::Result<Rect> {
     let monitor =
       self.monitor().context("Workspace has no parent monitor.")?;
 
-    let gaps_config = &self.0.borrow().gaps_config;
-    let scale_factor = match &gaps_config.scale_with_dpi {
-      true => monitor.native().scale_factor()?,
-      false => 1.,
-    };
-
     // Get delta between monitor bounds and its working area.
     let working_delta = monitor
-      .native()
       .working_rect()
       .context("Failed to get working area of parent monitor.")?
       .delta(&monitor.to_rect()?);
 
-    let is_single_window = self.tiling_children().nth(1).is_none();
+    // Smart gaps leave the single window with the full working area.
+    if self.has_smart_gaps() {
+      return Ok(monitor.to_rect()?.apply_delta(&working_delta, None));
+    }
+
+    let gaps_config = &self.0.borrow().gaps_config;
+    let scale_factor = match &gaps_config.scale_with_dpi {
+      true => monitor.native().scale_factor()?,
+      false => 1.,
+    };
+
+    // A window in monocle is shown as the only tiling window.
+    let is_single_window = self.monocle_window_id().is_some()
+      || self.tiling_children().nth(1).is_none();
 
     let gaps = if is_single_window {
       gaps_config
@@ -146,6 +280,97 @@ impl PositionGetters for Workspace {
   }
 }
 
+fn clamp_master_ratio(master_ratio: f32) -> f32 {
+  master_ratio.clamp(MIN_MASTER_RATIO, 1. - MIN_MASTER_RATIO)
+}
+
+/// Gets the rect of the child at `index` out of `count` tiling children
+/// arranged into a master area and a stack.
+///
+/// The master area comes first in the tiling direction, and children
+/// within either area are tiled in the inverse direction. If every
+/// child fits in the master area (or `master_count` is 0), a single area
+/// takes up the whole rect.
+#[allow(
+  clippy::cast_precision_loss,
+  clippy::cast_possible_truncation,
+  clippy::cast_possible_wrap
+)]
+fn master_stack_rect(
+  rect: &Rect,
+  index: usize,
+  count: usize,
+  master_count: usize,
+  master_ratio: f32,
+  tiling_direction: &TilingDirection,
+  (horizontal_gap, vertical_gap): (i32, i32),
+) -> Rect {
+  // Lengths along the tiling direction are "main", and lengths along the
+  // inverse direction are "cross".
+  let (main_start, main_length, cross_start, cross_length) =
+    match tiling_direction {
+      TilingDirection::Horizontal => {
+        (rect.x(), rect.width(), rect.y(), rect.height())
+      }
+      TilingDirection::Vertical => {
+        (rect.y(), rect.height(), rect.x(), rect.width())
+      }
+    };
+
+  let (main_gap, cross_gap) = match tiling_direction {
+    TilingDirection::Horizontal => (horizontal_gap, vertical_gap),
+    TilingDirection::Vertical => (vertical_gap, horizontal_gap),
+  };
+
+  let master_count = master_count.min(count);
+  let is_master = index < master_count;
+
+  let (area_start, area_length) =
+    if master_count == 0 || master_count == count {
+      (main_start, main_length)
+    } else {
+      let available_length = main_length - main_gap;
+      let master_length =
+        (available_length as f32 * master_ratio).round() as i32;
+
+      if is_master {
+        (main_start, master_length)
+      } else {
+        (
+          main_start + master_length + main_gap,
+          available_length - master_length,
+        )
+      }
+    };
+
+  let (area_count, area_index) = if is_master {
+    (master_count, index)
+  } else {
+    (count - master_count, index - master_count)
+  };
+
+  // Split the area evenly, with the last child taking any remainder.
+  let available_length =
+    cross_length - cross_gap * (area_count as i32 - 1);
+  let length = available_length / area_count as i32;
+  let start = cross_start + (length + cross_gap) * area_index as i32;
+
+  let length = if area_index == area_count - 1 {
+    available_length - length * (area_count as i32 - 1)
+  } else {
+    length
+  };
+
+  match tiling_direction {
+    TilingDirection::Horizontal => {
+      Rect::from_xy(area_start, start, area_length, length)
+    }
+    TilingDirection::Vertical => {
+      Rect::from_xy(start, area_start, length, area_length)
+    }
+  }
+}
+
 impl std::fmt::Display for Workspace {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
@@ -156,3 +381,177 @@ impl std::fmt::Display for Workspace {
     )
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{LengthValue, RectDelta};
+  use wm_platform::NativeWindow;
+
+  use super::*;
+  use crate::{
+    commands::container::attach_container,
+    models::{SplitContainer, TilingWindow},
+  };
+
+  fn workspace() -> Workspace {
+    Workspace::new(
+      WorkspaceConfig {
+        name: "1".to_string(),
+        display_name: None,
+        bind_to_monitor: None,
+        monitor_position: None,
+        keep_alive: false,
+        default_tiling_direction: None,
+        layout: WorkspaceLayout::Manual,
+        master_ratio: 0.55,
+        master_count: 1,
+      },
+      GapsConfig::default(),
+      TilingDirection::Horizontal,
+    )
+  }
+
+  fn attach_split(parent: &Container) -> SplitContainer {
+    let split = SplitContainer::new(
+      TilingDirection::Horizontal,
+      GapsConfig::default(),
+    );
+
+    attach_container(&split.clone().into(), parent, None).unwrap();
+    split
+  }
+
+  #[test]
+  fn monocle_hides_tiling_containers_without_the_monocle_window() {
+    let workspace = workspace();
+    let first = attach_split(&workspace.clone().into());
+    let second = attach_split(&workspace.clone().into());
+    let nested = attach_split(&second.clone().into());
+
+    workspace.set_monocle_window_id(Some(nested.id()));
+
+    assert!(first.is_hidden_by_monocle());
+    assert!(!second.is_hidden_by_monocle());
+    assert!(!nested.is_hidden_by_monocle());
+
+    workspace.set_monocle_window_id(None);
+    assert!(!first.is_hidden_by_monocle());
+  }
+
+  #[test]
+  fn smart_gaps_apply_to_single_tiling_window() {
+    let workspace = workspace();
+    workspace.set_gaps_config(GapsConfig {
+      smart_gaps: true,
+      ..GapsConfig::default()
+    });
+
+    let attach_window = |parent: &Container, handle| {
+      let window = TilingWindow::new(
+        None,
+        NativeWindow::new(handle),
+        None,
+        RectDelta::new(
+          LengthValue::from_px(0),
+          LengthValue::from_px(0),
+          LengthValue::from_px(0),
+          LengthValue::from_px(0),
+        ),
+        Rect::from_xy(0, 0, 100, 100),
+        false,
+        GapsConfig::default(),
+        Vec::new(),
+        None,
+      );
+
+      attach_container(&window.clone().into(), parent, None).unwrap();
+      window
+    };
+
+    let split = attach_split(&workspace.clone().into());
+    let first = attach_window(&split.clone().into(), 1);
+    assert!(workspace.has_smart_gaps());
+
+    // Windows nested in split containers are counted as well.
+    attach_window(&split.clone().into(), 2);
+    assert!(!workspace.has_smart_gaps());
+
+    workspace.set_monocle_window_id(Some(first.id()));
+    assert!(workspace.has_smart_gaps());
+
+    workspace.set_gaps_config(GapsConfig::default());
+    assert!(!workspace.has_smart_gaps());
+  }
+
+  #[test]
+  fn smart_borders_follow_workspace_gaps_config() {
+    let workspace = workspace();
+
+    workspace.set_gaps_config(GapsConfig {
+      smart_borders: true,
+      ..GapsConfig::default()
+    });
+    assert!(!workspace.has_smart_borders_enabled());
+
+    workspace.set_gaps_config(GapsConfig {
+      smart_gaps: true,
+      smart_borders: true,
+      ..GapsConfig::default()
+    });
+    assert!(workspace.has_smart_borders_enabled());
+  }
+
+  #[test]
+  fn master_stack_splits_master_area_from_stack() {
+    let rect = Rect::from_xy(0, 0, 1000, 600);
+    let layout = |index, count| {
+      master_stack_rect(
+        &rect,
+        index,
+        count,
+        1,
+        0.6,
+        &TilingDirection::Horizontal,
+        (20, 10),
+      )
+    };
+
+    assert_eq!(layout(0, 3), Rect::from_xy(0, 0, 588, 600));
+    assert_eq!(layout(1, 3), Rect::from_xy(608, 0, 392, 295));
+    assert_eq!(layout(2, 3), Rect::from_xy(608, 305, 392, 295));
+
+    // A lone master fills the workspace.
+    assert_eq!(layout(0, 1), rect);
+  }
+
+  #[test]
+  fn vertical_master_stack_puts_master_area_on_top() {
+    let rect = Rect::from_xy(0, 0, 1000, 1000);
+    let layout = |index, count| {
+      master_stack_rect(
+        &rect,
+        index,
+        count,
+        2,
+        0.5,
+        &TilingDirection::Vertical,
+        (0, 0),
+      )
+    };
+
+    assert_eq!(layout(0, 3), Rect::from_xy(0, 0, 500, 500));
+    assert_eq!(layout(1, 3), Rect::from_xy(500, 0, 500, 500));
+    assert_eq!(layout(2, 3), Rect::from_xy(0, 500, 1000, 500));
+  }
+
+  #[test]
+  fn master_ratio_is_clamped() {
+    let workspace = workspace();
+
+    workspace.set_master_ratio(1.5);
+    assert!((workspace.master_ratio() - 0.95).abs() < f32::EPSILON);
+
+    workspace.set_master_ratio(-0.2);
+    assert!((workspace.master_ratio() - 0.05).abs() < f32::EPSILON);
+  }
+}
@@ -1,5 +1,6 @@
 use wm_common::Direction;
 
+#[derive(Clone, Debug)]
 pub enum WorkspaceTarget {
   Name(String),
   Recent,
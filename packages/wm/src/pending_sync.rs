@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use uuid::Uuid;
 
@@ -10,17 +10,25 @@ use crate::{
 #[derive(Default)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct PendingSync {
-  /// Containers (and their descendants) that have a pending redraw.
+  /// Containers (and their descendants) that have a pending redraw. Keyed
+  /// by ID, such that a container queued several times is only redrawn
+  /// once.
   containers_to_redraw: HashMap<Uuid, Container>,
 
   /// Workspaces where z-order should be updated. Windows that match the
   /// focused window's state should be brought to the front.
+  ///
+  /// Kept in the order they were queued, without duplicates.
   workspaces_to_reorder: Vec<Workspace>,
 
   /// Whether native focus should be reassigned to the WM's focused
   /// container.
   needs_focus_update: bool,
 
+  /// Whether the pending focus change is from focus follows cursor. The
+  /// cursor doesn't jump for such focus changes.
+  is_cursor_focus: bool,
+
   /// Whether window effect for the focused window should be updated.
   needs_focused_effect_update: bool,
 
@@ -30,6 +38,19 @@ pub struct PendingSync {
   /// Whether to jump the cursor to the focused container (if enabled in
   /// user config).
   needs_cursor_jump: bool,
+
+  /// Whether the drop hint for a dragged tiling window should be shown,
+  /// moved, or hidden.
+  needs_drop_hint_update: bool,
+
+  /// Whether the insertion hint overlays of workspaces should be shown,
+  /// moved, or hidden.
+  needs_insertion_hint_update: bool,
+
+  /// IDs of containers whose redraw was deferred from a previous sync due
+  /// to exceeding the redraw budget. These are redrawn before any newly
+  /// queued containers so that they can't be starved.
+  deferred_redraws: HashSet<Uuid>,
 }
 
 impl PendingSync {
@@ -40,15 +61,39 @@ impl PendingSync {
       || self.needs_focused_effect_update
       || self.needs_all_effects_update
       || self.needs_cursor_jump
+      || self.needs_drop_hint_update
+      || self.needs_insertion_hint_update
   }
 
   pub fn clear(&mut self) -> &mut Self {
     self.containers_to_redraw.clear();
     self.workspaces_to_reorder.clear();
     self.needs_focus_update = false;
+    self.is_cursor_focus = false;
     self.needs_focused_effect_update = false;
     self.needs_all_effects_update = false;
     self.needs_cursor_jump = false;
+    self.needs_drop_hint_update = false;
+    self.needs_insertion_hint_update = false;
+    self.deferred_redraws.clear();
+    self
+  }
+
+  /// Queues containers to redraw that were skipped in the current sync.
+  pub fn defer_containers_to_redraw<I, T>(
+    &mut self,
+    containers: I,
+  ) -> &mut Self
+  where
+    I: IntoIterator<Item = T>,
+    T: Into<Container>,
+  {
+    for container in containers {
+      let container: Container = container.into();
+      self.deferred_redraws.insert(container.id());
+      self.containers_to_redraw.insert(container.id(), container);
+    }
+
     self
   }
 
@@ -84,7 +129,9 @@ impl PendingSync {
   where
     T: Into<Container>,
   {
-    self.containers_to_redraw.remove(&container.into().id());
+    let id = container.into().id();
+    self.containers_to_redraw.remove(&id);
+    self.deferred_redraws.remove(&id);
     self
   }
 
@@ -92,12 +139,28 @@ impl PendingSync {
     &mut self,
     workspace: Workspace,
   ) -> &mut Self {
-    self.workspaces_to_reorder.push(workspace);
+    if !self
+      .workspaces_to_reorder
+      .iter()
+      .any(|queued| queued.id() == workspace.id())
+    {
+      self.workspaces_to_reorder.push(workspace);
+    }
+
     self
   }
 
   pub fn queue_focus_change(&mut self) -> &mut Self {
     self.needs_focus_update = true;
+    self.is_cursor_focus = false;
+    self
+  }
+
+  /// Queues a focus change from focus follows cursor. Gets overridden by
+  /// any other focus change queued afterwards.
+  pub fn queue_cursor_focus_change(&mut self) -> &mut Self {
+    self.needs_focus_update = true;
+    self.is_cursor_focus = true;
     self
   }
 
@@ -116,10 +179,29 @@ impl PendingSync {
     self
   }
 
+  pub fn dequeue_cursor_jump(&mut self) -> &mut Self {
+    self.needs_cursor_jump = false;
+    self
+  }
+
+  pub fn queue_drop_hint_update(&mut self) -> &mut Self {
+    self.needs_drop_hint_update = true;
+    self
+  }
+
+  pub fn queue_insertion_hint_update(&mut self) -> &mut Self {
+    self.needs_insertion_hint_update = true;
+    self
+  }
+
   pub fn needs_focus_update(&self) -> bool {
     self.needs_focus_update
   }
 
+  pub fn is_cursor_focus(&self) -> bool {
+    self.is_cursor_focus
+  }
+
   pub fn needs_focused_effect_update(&self) -> bool {
     self.needs_focused_effect_update
   }
@@ -132,6 +214,22 @@ impl PendingSync {
     self.needs_cursor_jump
   }
 
+  pub fn needs_drop_hint_update(&self) -> bool {
+    self.needs_drop_hint_update
+  }
+
+  pub fn needs_insertion_hint_update(&self) -> bool {
+    self.needs_insertion_hint_update
+  }
+
+  pub fn is_redraw_deferred(&self, id: &Uuid) -> bool {
+    self.deferred_redraws.contains(id)
+  }
+
+  pub fn has_deferred_redraws(&self) -> bool {
+    !self.deferred_redraws.is_empty()
+  }
+
   pub fn containers_to_redraw(&self) -> &HashMap<Uuid, Container> {
     &self.containers_to_redraw
   }
@@ -140,3 +238,91 @@ impl PendingSync {
     &self.workspaces_to_reorder
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    GapsConfig, TilingDirection, WorkspaceConfig, WorkspaceLayout,
+  };
+
+  use super::*;
+  use crate::models::SplitContainer;
+
+  fn split_container() -> SplitContainer {
+    SplitContainer::new(TilingDirection::Horizontal, GapsConfig::default())
+  }
+
+  fn workspace() -> Workspace {
+    Workspace::new(
+      WorkspaceConfig {
+        name: "1".to_string(),
+        display_name: None,
+        bind_to_monitor: None,
+        monitor_position: None,
+        keep_alive: false,
+        default_tiling_direction: None,
+        layout: WorkspaceLayout::Manual,
+        master_ratio: 0.55,
+        master_count: 1,
+      },
+      GapsConfig::default(),
+      TilingDirection::Horizontal,
+    )
+  }
+
+  #[test]
+  fn containers_are_queued_to_redraw_once() {
+    let (first, second) = (split_container(), split_container());
+    let mut pending_sync = PendingSync::default();
+
+    pending_sync
+      .queue_container_to_redraw(first.clone())
+      .queue_containers_to_redraw([first.clone(), second.clone()])
+      .defer_containers_to_redraw([second.clone()])
+      .queue_container_to_redraw(first.clone());
+
+    let ids = pending_sync
+      .containers_to_redraw()
+      .keys()
+      .copied()
+      .collect::<HashSet<_>>();
+
+    assert_eq!(pending_sync.containers_to_redraw().len(), 2);
+    assert_eq!(ids, HashSet::from([first.id(), second.id()]));
+  }
+
+  #[test]
+  fn workspaces_are_queued_to_reorder_once() {
+    let (first, second) = (workspace(), workspace());
+    let mut pending_sync = PendingSync::default();
+
+    pending_sync
+      .queue_workspace_to_reorder(first.clone())
+      .queue_workspace_to_reorder(second.clone())
+      .queue_workspace_to_reorder(first.clone());
+
+    let ids = pending_sync
+      .workspaces_to_reorder()
+      .iter()
+      .map(CommonGetters::id)
+      .collect::<Vec<_>>();
+
+    assert_eq!(ids, vec![first.id(), second.id()]);
+  }
+
+  #[test]
+  fn later_focus_change_overrides_cursor_focus() {
+    let mut pending_sync = PendingSync::default();
+
+    pending_sync.queue_cursor_focus_change();
+    assert!(pending_sync.needs_focus_update());
+    assert!(pending_sync.is_cursor_focus());
+
+    pending_sync.queue_focus_change();
+    assert!(pending_sync.needs_focus_update());
+    assert!(!pending_sync.is_cursor_focus());
+
+    pending_sync.queue_cursor_focus_change().clear();
+    assert!(!pending_sync.is_cursor_focus());
+  }
+}
@@ -0,0 +1,220 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use wm_common::{
+  InvokeCommand, MatchType, WindowMatchConfig, WindowRuleConfig,
+  WindowRuleEvent,
+};
+use wm_platform::NativeWindow;
+
+/// Name of the file that rules added at runtime are saved to. It's kept
+/// in the same directory as the user config.
+const RUNTIME_RULES_FILE: &str = "runtime-rules.yaml";
+
+/// Header written at the top of the runtime rules file.
+const RUNTIME_RULES_HEADER: &str = "# Generated by GlazeWM. Windows \
+  ignored via `ignore-window --persist`\n# are added here, and removed \
+  again via `manage-window`.\n";
+
+/// Identity of a window that is ignored across restarts.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct IgnoredWindow {
+  pub window_class: String,
+  pub window_process_path: String,
+}
+
+impl IgnoredWindow {
+  pub fn from_native(
+    native_window: &NativeWindow,
+  ) -> anyhow::Result<Self> {
+    Ok(Self {
+      window_class: native_window.class_name()?,
+      window_process_path: native_window.process_path()?,
+    })
+  }
+}
+
+/// Window rules that are added at runtime via commands, rather than
+/// being part of the user config.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RuntimeRules {
+  #[serde(default)]
+  pub ignored_windows: Vec<IgnoredWindow>,
+}
+
+impl RuntimeRules {
+  /// Path to the runtime rules file for the given user config path.
+  pub fn path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name(RUNTIME_RULES_FILE)
+  }
+
+  /// Reads the runtime rules saved alongside the given user config.
+  ///
+  /// Returns no rules if none have been saved yet.
+  pub fn read(config_path: &Path) -> anyhow::Result<Self> {
+    let path = Self::path(config_path);
+
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+
+    let rules_str = fs::read_to_string(&path).with_context(|| {
+      format!("Unable to read runtime rules from {}.", path.display())
+    })?;
+
+    serde_yaml::from_str(&rules_str).with_context(|| {
+      format!("Invalid runtime rules in {}.", path.display())
+    })
+  }
+
+  /// Reads the runtime rules saved alongside the given user config, or
+  /// returns no rules if they can't be read.
+  ///
+  /// An invalid rules file shouldn't prevent the user config from
+  /// loading, so the error is only logged.
+  pub fn read_or_default(config_path: &Path) -> Self {
+    Self::read(config_path).unwrap_or_else(|err| {
+      warn!("Ignoring runtime rules: {err:?}");
+      Self::default()
+    })
+  }
+
+  /// Saves the runtime rules alongside the given user config.
+  pub fn write(&self, config_path: &Path) -> anyhow::Result<()> {
+    let path = Self::path(config_path);
+    let rules_str = serde_yaml::to_string(self)?;
+
+    fs::write(&path, format!("{RUNTIME_RULES_HEADER}{rules_str}"))
+      .with_context(|| {
+        format!("Unable to write runtime rules to {}.", path.display())
+      })
+  }
+
+  /// Gets the window rules to apply on top of the ones in the user
+  /// config.
+  pub fn window_rules(&self) -> Vec<WindowRuleConfig> {
+    if self.ignored_windows.is_empty() {
+      return Vec::new();
+    }
+
+    let match_window = self
+      .ignored_windows
+      .iter()
+      .map(|ignored| WindowMatchConfig {
+        window_class: Some(MatchType::Equals {
+          equals: ignored.window_class.clone(),
+        }),
+        window_process_path: Some(MatchType::Equals {
+          equals: ignored.window_process_path.clone(),
+        }),
+        ..WindowMatchConfig::default()
+      })
+      .collect();
+
+    vec![WindowRuleConfig {
+      commands: vec![InvokeCommand::Ignore],
+      match_window,
+      on: vec![WindowRuleEvent::Manage],
+      run_once: true,
+      swallow: false,
+      ignore_effects: false,
+      exclusive_fullscreen: false,
+      keep_maximized: false,
+      remove_title_bar: None,
+      float_size: None,
+      float_position: None,
+      on_title_change: true,
+      initial_workspace: None,
+      initial_workspace_windows: 1,
+    }]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ignored(
+    window_class: &str,
+    window_process_path: &str,
+  ) -> IgnoredWindow {
+    IgnoredWindow {
+      window_class: window_class.to_string(),
+      window_process_path: window_process_path.to_string(),
+    }
+  }
+
+  #[test]
+  fn round_trips_through_file() {
+    let dir = std::env::temp_dir()
+      .join(format!("glazewm-runtime-rules-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let config_path = dir.join("config.yaml");
+    assert_eq!(
+      RuntimeRules::read(&config_path).unwrap(),
+      RuntimeRules::default()
+    );
+
+    let rules = RuntimeRules {
+      ignored_windows: vec![ignored(
+        "Chrome_WidgetWin_1",
+        r"C:\Apps\Spotify.exe",
+      )],
+    };
+
+    rules.write(&config_path).unwrap();
+    assert_eq!(RuntimeRules::read(&config_path).unwrap(), rules);
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn reads_invalid_file_as_default() {
+    let dir = std::env::temp_dir().join(format!(
+      "glazewm-invalid-runtime-rules-{}",
+      std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+
+    let config_path = dir.join("config.yaml");
+    fs::write(RuntimeRules::path(&config_path), "ignored_windows: 5")
+      .unwrap();
+
+    assert!(RuntimeRules::read(&config_path).is_err());
+    assert_eq!(
+      RuntimeRules::read_or_default(&config_path),
+      RuntimeRules::default()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn ignores_windows_by_class_and_process_path() {
+    let rules = RuntimeRules {
+      ignored_windows: vec![
+        ignored("Chrome_WidgetWin_1", r"C:\Apps\Spotify.exe"),
+        ignored("ApplicationFrameWindow", r"C:\Apps\Calculator.exe"),
+      ],
+    };
+
+    let window_rules = rules.window_rules();
+    assert_eq!(window_rules.len(), 1);
+    assert_eq!(window_rules[0].commands, vec![InvokeCommand::Ignore]);
+    assert_eq!(window_rules[0].match_window.len(), 2);
+    assert_eq!(
+      window_rules[0].match_window[1].window_process_path,
+      Some(MatchType::Equals {
+        equals: r"C:\Apps\Calculator.exe".to_string()
+      })
+    );
+
+    assert!(RuntimeRules::default().window_rules().is_empty());
+  }
+}
@@ -0,0 +1,273 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use wm_common::{
+  ContainerDto, MonitorDto, RootContainerDto, WindowDto, WorkspaceDto,
+};
+use wm_platform::NativeWindow;
+
+use crate::wm_state::WmState;
+
+/// Arrangement of monitors, workspaces, and windows that is saved while
+/// the WM is running and on exit, such that it can be restored on the
+/// next startup.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Session {
+  pub root: RootContainerDto,
+}
+
+impl Session {
+  /// Path to the session file, which is `~/.glzr/glazewm/session.json`.
+  pub fn default_path() -> anyhow::Result<PathBuf> {
+    let home_dir =
+      home::home_dir().context("Unable to get home directory.")?;
+
+    Ok(home_dir.join(".glzr/glazewm/session.json"))
+  }
+
+  /// Creates a session from the current container tree.
+  pub fn from_state(state: &WmState) -> anyhow::Result<Self> {
+    match state.root_container.to_dto()? {
+      ContainerDto::Root(root) => Ok(Self { root }),
+      _ => bail!("Expected a root container DTO."),
+    }
+  }
+
+  /// Reads the session saved at the given path, and removes the file
+  /// such that the session is only restored once.
+  ///
+  /// Returns `None` if no session has been saved.
+  pub fn take(path: &Path) -> anyhow::Result<Option<Self>> {
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    let session_str = fs::read_to_string(path).with_context(|| {
+      format!("Unable to read session from {}.", path.display())
+    })?;
+
+    fs::remove_file(path).with_context(|| {
+      format!("Unable to remove session file {}.", path.display())
+    })?;
+
+    serde_json::from_str(&session_str)
+      .map(Some)
+      .with_context(|| format!("Invalid session in {}.", path.display()))
+  }
+
+  /// Saves the session to the given path.
+  pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+    let session_str = serde_json::to_string(self)?;
+
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, session_str).with_context(|| {
+      format!("Unable to write session to {}.", path.display())
+    })
+  }
+
+  /// Gets the saved workspaces along with the monitor they were on, in
+  /// tree order.
+  pub fn workspaces(&self) -> Vec<(&MonitorDto, &WorkspaceDto)> {
+    self
+      .root
+      .children
+      .iter()
+      .filter_map(|child| match child {
+        ContainerDto::Monitor(monitor) => Some(monitor),
+        _ => None,
+      })
+      .flat_map(|monitor| {
+        monitor
+          .workspaces()
+          .map(move |workspace| (monitor, workspace))
+      })
+      .collect()
+  }
+}
+
+/// Gets the windows within the given containers in tree order (i.e.
+/// depth-first).
+pub fn saved_windows(children: &[ContainerDto]) -> Vec<&WindowDto> {
+  children
+    .iter()
+    .flat_map(|child| match child {
+      ContainerDto::Window(window) => vec![window],
+      ContainerDto::Split(split) => saved_windows(&split.children),
+      _ => Vec::new(),
+    })
+    .collect()
+}
+
+/// Identity of a window, used to match running windows with the windows
+/// of a saved session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowIdentity {
+  pub handle: isize,
+  pub process_name: String,
+  pub class_name: String,
+  pub title: String,
+}
+
+impl WindowIdentity {
+  pub fn from_native(
+    native_window: &NativeWindow,
+  ) -> anyhow::Result<Self> {
+    Ok(Self {
+      handle: native_window.handle,
+      process_name: native_window.process_name()?,
+      class_name: native_window.class_name()?,
+      title: native_window.title()?,
+    })
+  }
+}
+
+impl From<&WindowDto> for WindowIdentity {
+  fn from(window: &WindowDto) -> Self {
+    Self {
+      handle: window.handle,
+      process_name: window.process_name.clone(),
+      class_name: window.class_name.clone(),
+      title: window.title.clone(),
+    }
+  }
+}
+
+/// Matches saved windows to running windows, and returns the matched
+/// pairs as `(saved_index, running_index)`.
+///
+/// Windows that are still open keep their handle across restarts, so
+/// those are matched first. Remaining windows are matched by their
+/// process, class, and title, and lastly by only their process and
+/// class. Each window is matched at most once.
+pub fn match_windows(
+  saved: &[WindowIdentity],
+  running: &[WindowIdentity],
+) -> Vec<(usize, usize)> {
+  let passes: [fn(&WindowIdentity, &WindowIdentity) -> bool; 3] = [
+    |saved, running| {
+      saved.handle == running.handle
+        && saved.process_name == running.process_name
+    },
+    |saved, running| {
+      saved.process_name == running.process_name
+        && saved.class_name == running.class_name
+        && saved.title == running.title
+    },
+    |saved, running| {
+      saved.process_name == running.process_name
+        && saved.class_name == running.class_name
+    },
+  ];
+
+  let mut saved_matched = vec![false; saved.len()];
+  let mut running_matched = vec![false; running.len()];
+  let mut matches = Vec::new();
+
+  for is_match in passes {
+    for (saved_index, saved_window) in saved.iter().enumerate() {
+      if saved_matched[saved_index] {
+        continue;
+      }
+
+      let running_index = (0..running.len()).find(|&index| {
+        !running_matched[index] && is_match(saved_window, &running[index])
+      });
+
+      if let Some(running_index) = running_index {
+        saved_matched[saved_index] = true;
+        running_matched[running_index] = true;
+        matches.push((saved_index, running_index));
+      }
+    }
+  }
+
+  matches
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+
+  fn identity(
+    handle: isize,
+    process_name: &str,
+    title: &str,
+  ) -> WindowIdentity {
+    WindowIdentity {
+      handle,
+      process_name: process_name.to_string(),
+      class_name: format!("{process_name}_class"),
+      title: title.to_string(),
+    }
+  }
+
+  #[test]
+  fn matches_by_handle_then_title_then_process() {
+    let saved = [
+      identity(1, "code", "main.rs"),
+      identity(2, "firefox", "Docs"),
+      identity(3, "firefox", "Mail"),
+      identity(4, "spotify", "Spotify"),
+    ];
+
+    let running = [
+      identity(30, "firefox", "News"),
+      identity(20, "firefox", "Docs"),
+      identity(1, "code", "lib.rs"),
+    ];
+
+    let mut matches = match_windows(&saved, &running);
+    matches.sort_unstable();
+
+    // Saved window 3 takes the remaining Firefox window, and Spotify is no
+    // longer running.
+    assert_eq!(matches, vec![(0, 2), (1, 1), (2, 0)]);
+  }
+
+  #[test]
+  fn ignores_handle_reused_by_other_process() {
+    let saved = [identity(1, "code", "main.rs")];
+    let running = [identity(1, "notepad", "main.rs")];
+
+    assert!(match_windows(&saved, &running).is_empty());
+  }
+
+  #[test]
+  fn round_trips_through_file() {
+    let dir = std::env::temp_dir()
+      .join(format!("glazewm-session-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("session.json");
+    assert!(Session::take(&path).unwrap().is_none());
+
+    let root_id = Uuid::new_v4();
+    let session = Session {
+      root: RootContainerDto {
+        id: root_id,
+        parent_id: None,
+        children: Vec::new(),
+        child_focus_order: Vec::new(),
+      },
+    };
+
+    session.write(&path).unwrap();
+
+    let restored = Session::take(&path).unwrap().unwrap();
+    assert_eq!(restored.root.id, root_id);
+
+    // The session is only restored once.
+    assert!(Session::take(&path).unwrap().is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,182 @@
+//! Helpers for building a container tree in tests, using windows and
+//! monitors whose details are made up rather than read from the OS.
+
+// Helpers unwrap since a tree that can't be built is a bug in the test.
+#![allow(clippy::missing_panics_doc)]
+
+use tokio::sync::mpsc;
+use wm_common::{
+  GapsConfig, LengthValue, ParsedConfig, Rect, RectDelta, TilingDirection,
+  WindowState, WmEvent,
+};
+use wm_platform::{NativeMonitor, NativeWindow};
+
+use crate::{
+  commands::container::attach_container,
+  models::{
+    Container, Monitor, NonTilingWindow, SplitContainer, TilingWindow,
+    Workspace,
+  },
+  user_config::UserConfig,
+  wm_state::WmState,
+};
+
+/// Creates an initialized state without any monitors, along with the
+/// receiver of its emitted events.
+pub fn state() -> (WmState, mpsc::UnboundedReceiver<WmEvent>) {
+  let (event_tx, event_rx) = mpsc::unbounded_channel();
+  let mut state = WmState::new(event_tx, mpsc::unbounded_channel().0);
+  state.set_initialized();
+
+  (state, event_rx)
+}
+
+/// Creates a user config with the default options.
+pub fn config() -> UserConfig {
+  UserConfig::from_value(ParsedConfig::default())
+}
+
+/// Gets the events that have been emitted so far.
+pub fn emitted_events(
+  event_rx: &mut mpsc::UnboundedReceiver<WmEvent>,
+) -> Vec<WmEvent> {
+  std::iter::from_fn(|| event_rx.try_recv().ok()).collect()
+}
+
+/// Adds a 1920x1080 monitor at the given x-coordinate.
+pub fn monitor(state: &WmState, handle: isize, x: i32) -> Monitor {
+  let rect = Rect::from_xy(x, 0, 1920, 1080);
+  monitor_with_working_rect(state, handle, rect.clone(), rect)
+}
+
+/// Adds a monitor with the given rect, of which only `working_rect` isn't
+/// reserved (e.g. for the taskbar).
+pub fn monitor_with_working_rect(
+  state: &WmState,
+  handle: isize,
+  rect: Rect,
+  working_rect: Rect,
+) -> Monitor {
+  let monitor =
+    Monitor::new(NativeMonitor::with_rects(handle, rect, working_rect));
+
+  attach(
+    &monitor.clone().into(),
+    &state.root_container.clone().into(),
+  );
+  monitor
+}
+
+/// Adds a workspace with the given name to a monitor. The first
+/// workspace added to a monitor is the displayed one.
+pub fn workspace(monitor: &Monitor, name: &str) -> Workspace {
+  let workspace = Workspace::new(
+    serde_yaml::from_str(&format!("name: '{name}'")).unwrap(),
+    GapsConfig::default(),
+    TilingDirection::Horizontal,
+  );
+
+  attach(&workspace.clone().into(), &monitor.clone().into());
+  workspace
+}
+
+/// Adds a workspace holding `window_count` tiling windows to a monitor.
+///
+/// Windows are grouped into vertical splits of up to 50 windows, since
+/// too many siblings would shrink below the minimum tiling size.
+pub fn workspace_with_windows(
+  monitor: &Monitor,
+  name: &str,
+  window_count: usize,
+) -> Workspace {
+  let workspace = workspace(monitor, name);
+  let mut split = None;
+
+  for handle in 0..window_count {
+    if handle % 50 == 0 {
+      let new_split = SplitContainer::new(
+        TilingDirection::Vertical,
+        GapsConfig::default(),
+      );
+
+      attach(&new_split.clone().into(), &workspace.clone().into());
+      split = Some(new_split);
+    }
+
+    if let Some(split) = &split {
+      tiling_window(
+        &split.clone().into(),
+        isize::try_from(handle).unwrap(),
+      );
+    }
+  }
+
+  workspace
+}
+
+/// Adds a tiling window to the given parent.
+pub fn tiling_window(parent: &Container, handle: isize) -> TilingWindow {
+  let window = TilingWindow::new(
+    None,
+    native_window(handle),
+    None,
+    no_delta(),
+    Rect::from_xy(0, 0, 400, 300),
+    false,
+    GapsConfig::default(),
+    Vec::new(),
+    None,
+  );
+
+  attach(&window.clone().into(), parent);
+  window
+}
+
+/// Adds a non-tiling window with the given state to the given workspace.
+pub fn non_tiling_window(
+  workspace: &Workspace,
+  handle: isize,
+  state: WindowState,
+) -> NonTilingWindow {
+  let window = NonTilingWindow::new(
+    None,
+    native_window(handle),
+    state,
+    None,
+    no_delta(),
+    None,
+    Rect::from_xy(0, 0, 400, 300),
+    false,
+    Vec::new(),
+    None,
+  );
+
+  attach(&window.clone().into(), &workspace.clone().into());
+  window
+}
+
+/// Creates a native window with a title and process name of the form
+/// `window-{handle}` and `process-{handle}`.
+pub fn native_window(handle: isize) -> NativeWindow {
+  NativeWindow::with_details(
+    handle,
+    &format!("window-{handle}"),
+    "class",
+    &format!("process-{handle}"),
+  )
+}
+
+fn no_delta() -> RectDelta {
+  let no_delta = LengthValue::from_px(0);
+
+  RectDelta::new(
+    no_delta.clone(),
+    no_delta.clone(),
+    no_delta.clone(),
+    no_delta,
+  )
+}
+
+fn attach(child: &Container, parent: &Container) {
+  attach_container(child, parent, None).unwrap();
+}
@@ -8,7 +8,7 @@ use uuid::Uuid;
 use wm_common::ContainerDto;
 
 use crate::models::{
-  Container, DirectionContainer, Monitor, TilingContainer,
+  Container, DirectionContainer, Monitor, SplitContainer, TilingContainer,
   WindowContainer, Workspace,
 };
 
@@ -274,6 +274,33 @@ pub trait CommonGetters {
       .chain(end_ancestor.clone())
       .all(|ancestor| ancestor.focus_index() == 0)
   }
+
+  /// Whether this container is hidden behind a sibling in a stacking or
+  /// tabbed split container. Only the most recently focused child of such
+  /// a container is shown.
+  fn is_hidden_in_stack(&self) -> bool {
+    self.self_and_ancestors().any(|container| {
+      container
+        .parent()
+        .and_then(|parent| parent.as_split().map(SplitContainer::layout))
+        .is_some_and(|layout| layout.is_stacked())
+        && container.focus_index() != 0
+    })
+  }
+
+  /// Whether this container is a tiling container that is hidden because
+  /// another window in its workspace is in monocle.
+  fn is_hidden_by_monocle(&self) -> bool {
+    self.as_tiling_container().is_ok()
+      && self
+        .workspace()
+        .and_then(|workspace| workspace.monocle_window_id())
+        .is_some_and(|monocle_id| {
+          !self
+            .self_and_descendants()
+            .any(|descendant| descendant.id() == monocle_id)
+        })
+  }
 }
 
 /// An iterator over ancestors of a given container.
@@ -1,9 +1,84 @@
+use std::{cell::RefCell, collections::HashMap};
+
 use ambassador::delegatable_trait;
-use wm_common::Rect;
+use uuid::Uuid;
+use wm_common::{Point, Rect};
+
+thread_local! {
+  /// Rects memoized by container ID while within `with_cached_rects`.
+  static RECT_CACHE: RefCell<Option<HashMap<Uuid, Rect>>> =
+    const { RefCell::new(None) };
+}
 
 #[delegatable_trait]
 pub trait PositionGetters {
   fn to_rect(&self) -> anyhow::Result<Rect>;
+
+  /// Whether the point is within the container's rect. Points on the
+  /// edges of the rect are included.
+  ///
+  /// Returns `false` if the container's rect can't be resolved.
+  fn contains_point(&self, x: i32, y: i32) -> bool {
+    self
+      .to_rect()
+      .is_ok_and(|rect| rect.contains_point(&Point { x, y }))
+  }
+}
+
+/// Runs the closure with container rects memoized, such that each rect
+/// is only calculated once.
+///
+/// Resolving the rect of a tiling container otherwise recalculates the
+/// rects of its ancestors and previous siblings, which adds up when
+/// resolving every rect in the tree (e.g. for `query tree`). The
+/// container tree must not be changed within the closure.
+pub fn with_cached_rects<T>(callback: impl FnOnce() -> T) -> T {
+  let is_outermost = RECT_CACHE.with(|cache| {
+    let mut cache = cache.borrow_mut();
+    let is_outermost = cache.is_none();
+
+    if is_outermost {
+      *cache = Some(HashMap::new());
+    }
+
+    is_outermost
+  });
+
+  let result = callback();
+
+  if is_outermost {
+    RECT_CACHE.with(|cache| *cache.borrow_mut() = None);
+  }
+
+  result
+}
+
+/// Gets the rect of the container with the given ID from the cache if
+/// within `with_cached_rects`, otherwise calculates it.
+pub fn cached_rect(
+  id: Uuid,
+  calculate: impl FnOnce() -> anyhow::Result<Rect>,
+) -> anyhow::Result<Rect> {
+  let cached = RECT_CACHE.with(|cache| {
+    cache
+      .borrow()
+      .as_ref()
+      .and_then(|rects| rects.get(&id).cloned())
+  });
+
+  if let Some(rect) = cached {
+    return Ok(rect);
+  }
+
+  let rect = calculate()?;
+
+  RECT_CACHE.with(|cache| {
+    if let Some(rects) = cache.borrow_mut().as_mut() {
+      rects.insert(id, rect.clone());
+    }
+  });
+
+  Ok(rect)
 }
 
 /// Implements the `PositionGetters` trait for tiling containers that can
@@ -16,13 +91,43 @@ macro_rules! impl_position_getters_as_resizable {
   ($struct_name:ident) => {
     impl PositionGetters for $struct_name {
       fn to_rect(&self) -> anyhow::Result<Rect> {
+        $crate::traits::cached_rect(self.id(), || self.calculate_rect())
+      }
+    }
+
+    impl $struct_name {
+      fn calculate_rect(&self) -> anyhow::Result<Rect> {
+        // A window in monocle fills its workspace.
+        if let Some(workspace) = self.workspace().filter(|workspace| {
+          workspace.monocle_window_id() == Some(self.id())
+        }) {
+          return workspace.to_rect();
+        }
+
         let parent = self
           .parent()
           .and_then(|parent| parent.as_direction_container().ok())
           .context("Parent does not have a tiling direction.")?;
 
+        // Tiling children of a master-stack workspace are arranged by the
+        // workspace rather than by their tiling sizes.
+        if let DirectionContainer::Workspace(workspace) = &parent {
+          if workspace.layout().is_master_stack() {
+            return workspace
+              .master_stack_rect(self.id(), self.inner_gaps()?);
+          }
+        }
+
         let parent_rect = parent.to_rect()?;
 
+        // Children of stacking and tabbed containers each fill the
+        // container.
+        if let DirectionContainer::Split(split_parent) = &parent {
+          if split_parent.layout().is_stacked() {
+            return Ok(parent_rect);
+          }
+        }
+
         let (horizontal_gap, vertical_gap) = self.inner_gaps()?;
         let inner_gap = match parent.tiling_direction() {
           TilingDirection::Vertical => vertical_gap,
@@ -84,3 +189,62 @@ macro_rules! impl_position_getters_as_resizable {
     }
   };
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Positioned(Option<Rect>);
+
+  impl PositionGetters for Positioned {
+    fn to_rect(&self) -> anyhow::Result<Rect> {
+      self.0.clone().ok_or_else(|| anyhow::anyhow!("No rect."))
+    }
+  }
+
+  #[test]
+  fn rects_are_cached_within_scope() {
+    let id = Uuid::new_v4();
+    let rect = Rect::from_xy(0, 0, 100, 100);
+    let other_rect = Rect::from_xy(0, 0, 200, 200);
+
+    with_cached_rects(|| {
+      assert_eq!(cached_rect(id, || Ok(rect.clone())).unwrap(), rect);
+      assert_eq!(
+        cached_rect(id, || Ok(other_rect.clone())).unwrap(),
+        rect
+      );
+    });
+
+    // Rects are calculated anew outside of the scope.
+    assert_eq!(
+      cached_rect(id, || Ok(other_rect.clone())).unwrap(),
+      other_rect
+    );
+  }
+
+  #[test]
+  fn contains_points_within_and_on_edges() {
+    let container = Positioned(Some(Rect::from_xy(0, 0, 1920, 1080)));
+
+    assert!(container.contains_point(960, 540));
+    assert!(container.contains_point(0, 0));
+    assert!(container.contains_point(1920, 1080));
+    assert!(container.contains_point(1920, 0));
+  }
+
+  #[test]
+  fn excludes_points_outside() {
+    let container = Positioned(Some(Rect::from_xy(-1920, 0, 1920, 1080)));
+
+    assert!(!container.contains_point(1, 540));
+    assert!(!container.contains_point(-1921, 540));
+    assert!(!container.contains_point(-960, -1));
+    assert!(!container.contains_point(-960, 1081));
+  }
+
+  #[test]
+  fn unresolved_rect_contains_nothing() {
+    assert!(!Positioned(None).contains_point(0, 0));
+  }
+}
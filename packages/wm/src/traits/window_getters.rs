@@ -1,13 +1,13 @@
-use std::cell::Ref;
+use std::{cell::Ref, time::Instant};
 
 use ambassador::delegatable_trait;
 use wm_common::{
-  ActiveDrag, DisplayState, LengthValue, Rect, RectDelta,
+  ActiveDrag, DisplayState, LengthValue, Rect, RectDelta, SizeConstraints,
   WindowRuleConfig, WindowState,
 };
 use wm_platform::NativeWindow;
 
-use crate::user_config::UserConfig;
+use crate::{models::PendingDpiAdjustment, user_config::UserConfig};
 
 #[delegatable_trait]
 pub trait WindowGetters {
@@ -92,12 +92,12 @@ pub trait WindowGetters {
 
   fn set_display_state(&self, display_state: DisplayState);
 
-  fn has_pending_dpi_adjustment(&self) -> bool;
+  /// Queues a DPI adjustment for the next redraw. Any adjustment that is
+  /// already pending is recomputed against the new target.
+  fn queue_dpi_adjustment(&self, adjustment: PendingDpiAdjustment);
 
-  fn set_has_pending_dpi_adjustment(
-    &self,
-    has_pending_dpi_adjustment: bool,
-  );
+  /// Removes and returns the pending DPI adjustment.
+  fn take_pending_dpi_adjustment(&self) -> Option<PendingDpiAdjustment>;
 
   fn floating_placement(&self) -> Rect;
 
@@ -120,12 +120,45 @@ pub trait WindowGetters {
   fn active_drag(&self) -> Option<ActiveDrag>;
 
   fn set_active_drag(&self, active_drag: Option<ActiveDrag>);
+
+  /// Gets when the window started requesting attention (i.e. flashing in
+  /// the taskbar), if it's urgent.
+  fn urgent_since(&self) -> Option<Instant>;
+
+  fn set_urgent_since(&self, urgent_since: Option<Instant>);
+
+  /// Gets the minimum and maximum size reported by the window's
+  /// application.
+  fn size_constraints(&self) -> SizeConstraints;
+
+  fn set_size_constraints(&self, size_constraints: SizeConstraints);
+
+  /// Gets the placement that the window last had while floating, if it
+  /// has floated before.
+  fn last_floating_placement(&self) -> Option<Rect>;
+
+  fn set_last_floating_placement(&self, placement: Option<Rect>);
+
+  /// Whether the window is requesting attention. Windows stop being
+  /// urgent once they get focused.
+  fn is_urgent(&self) -> bool {
+    self.urgent_since().is_some()
+  }
+
+  /// Whether the window is shown above all other windows.
+  fn is_shown_on_top(&self) -> bool {
+    match self.state() {
+      WindowState::Floating(config) => config.shown_on_top,
+      WindowState::Fullscreen(config) => config.shown_on_top,
+      _ => false,
+    }
+  }
 }
 
 /// Implements the `WindowGetters` trait for a given struct.
 ///
 /// Expects that the struct has a wrapping `RefCell` containing a struct
-/// with a `state`, `prev_state`, `native`, `has_pending_dpi_adjustment`,
+/// with a `state`, `prev_state`, `native`, `pending_dpi_adjustment`,
 /// `border_delta`, `display_state`, and a `done_window_rules` field.
 #[macro_export]
 macro_rules! impl_window_getters {
@@ -167,16 +200,18 @@ macro_rules! impl_window_getters {
         self.0.borrow_mut().display_state = display_state;
       }
 
-      fn has_pending_dpi_adjustment(&self) -> bool {
-        self.0.borrow().has_pending_dpi_adjustment
+      fn queue_dpi_adjustment(&self, adjustment: PendingDpiAdjustment) {
+        let mut inner = self.0.borrow_mut();
+        inner.pending_dpi_adjustment = PendingDpiAdjustment::merge(
+          inner.pending_dpi_adjustment,
+          adjustment,
+        );
       }
 
-      fn set_has_pending_dpi_adjustment(
+      fn take_pending_dpi_adjustment(
         &self,
-        has_pending_dpi_adjustment: bool,
-      ) {
-        self.0.borrow_mut().has_pending_dpi_adjustment =
-          has_pending_dpi_adjustment;
+      ) -> Option<PendingDpiAdjustment> {
+        self.0.borrow_mut().pending_dpi_adjustment.take()
       }
 
       fn floating_placement(&self) -> Rect {
@@ -217,6 +252,30 @@ macro_rules! impl_window_getters {
       fn set_active_drag(&self, active_drag: Option<ActiveDrag>) {
         self.0.borrow_mut().active_drag = active_drag;
       }
+
+      fn urgent_since(&self) -> Option<Instant> {
+        self.0.borrow().urgent_since
+      }
+
+      fn set_urgent_since(&self, urgent_since: Option<Instant>) {
+        self.0.borrow_mut().urgent_since = urgent_since;
+      }
+
+      fn size_constraints(&self) -> SizeConstraints {
+        self.0.borrow().size_constraints
+      }
+
+      fn set_size_constraints(&self, size_constraints: SizeConstraints) {
+        self.0.borrow_mut().size_constraints = size_constraints;
+      }
+
+      fn last_floating_placement(&self) -> Option<Rect> {
+        self.0.borrow().last_floating_placement.clone()
+      }
+
+      fn set_last_floating_placement(&self, placement: Option<Rect>) {
+        self.0.borrow_mut().last_floating_placement = placement;
+      }
     }
   };
 }
@@ -1,20 +1,35 @@
-use std::{collections::HashMap, env, fs, path::PathBuf};
+use std::{
+  collections::HashMap,
+  env, fs,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use tracing::warn;
+use wm_builtin::{BuiltinDescriptor, BuiltinProgram, FileNamePolicy};
 use wm_common::{
-  InvokeCommand, MatchType, ParsedConfig, WindowMatchConfig,
-  WindowRuleConfig, WindowRuleEvent, WorkspaceConfig,
+  BuiltinConfig, ConfigSection, InvokeCommand, LengthValue, MatchType,
+  ParsedConfig, RectDelta, WindowMatchConfig, WindowRuleConfig,
+  WindowRuleEvent, WindowState, WmEvent, WorkspaceConfig,
 };
+use wm_platform::NativeWindow;
 
 use crate::{
   models::{Monitor, WindowContainer, Workspace},
+  runtime_rules::{IgnoredWindow, RuntimeRules},
   traits::{CommonGetters, WindowGetters},
+  wm_state::WmState,
 };
 
 /// Resource string for the sample config file.
 const SAMPLE_CONFIG: &str =
   include_str!("../../../resources/assets/sample-config.yaml");
 
+/// Top-level key of config files that lists other config files to
+/// include.
+const INCLUDE_KEY: &str = "include";
+
 #[derive(Debug)]
 pub struct UserConfig {
   /// Path to the user config file.
@@ -23,9 +38,18 @@ pub struct UserConfig {
   /// Parsed user config value.
   pub value: ParsedConfig,
 
-  /// Unparsed user config string.
+  /// Unparsed user config string. If the config includes other files,
+  /// this is the merged config rather than the contents of the root file.
   pub value_str: String,
 
+  /// Paths of the config file and of every file it includes, along with
+  /// when they were last modified.
+  source_files: Vec<(PathBuf, Option<SystemTime>)>,
+
+  /// Window rules added at runtime, which are saved alongside the user
+  /// config.
+  pub runtime_rules: RuntimeRules,
+
   /// Hashmap of window rule event types (e.g. `WindowRuleEvent::Manage`)
   /// and the corresponding window rules of that type.
   window_rules_by_event: HashMap<WindowRuleEvent, Vec<WindowRuleConfig>>,
@@ -45,24 +69,32 @@ impl UserConfig {
       .or_else(|| env::var("GLAZEWM_CONFIG_PATH").ok().map(PathBuf::from))
       .unwrap_or(default_config_path);
 
-    let (config_value, config_str) = Self::read(&config_path)?;
+    let (config_value, config_str, source_paths) =
+      Self::read(&config_path)?;
+    let runtime_rules = RuntimeRules::read_or_default(&config_path);
 
-    let window_rules_by_event = Self::window_rules_by_event(&config_value);
+    let window_rules_by_event =
+      Self::window_rules_by_event(&config_value, &runtime_rules);
 
     Ok(Self {
       path: config_path,
       value: config_value,
       value_str: config_str,
+      source_files: Self::source_files(source_paths),
+      runtime_rules,
       window_rules_by_event,
     })
   }
 
-  /// Reads and validates the user config from the given path.
+  /// Reads and validates the user config from the given path, along with
+  /// any files it includes.
   ///
-  /// Creates a new config file from sample if it doesn't exist.
+  /// Creates a new config file from sample if it doesn't exist. Returns
+  /// the unparsed config with its included files merged in, along with
+  /// the paths of all files that were read.
   fn read(
     config_path: &PathBuf,
-  ) -> anyhow::Result<(ParsedConfig, String)> {
+  ) -> anyhow::Result<(ParsedConfig, String, Vec<PathBuf>)> {
     if !config_path.exists() {
       Self::create_sample(config_path)?;
     }
@@ -70,11 +102,153 @@ impl UserConfig {
     let config_str = fs::read_to_string(config_path)
       .context("Unable to read config file.")?;
 
+    let mut source_paths = Vec::new();
+
+    let config_yaml = Self::resolve_includes(
+      config_path,
+      &config_str,
+      &mut Vec::new(),
+      &mut source_paths,
+    )?;
+
+    // Keep the file as written (including comments) unless other files
+    // were merged into it.
+    let config_str = if source_paths.len() > 1 {
+      serde_yaml::to_string(&config_yaml)?
+    } else {
+      config_str
+    };
+
     // TODO: Improve error formatting of serde_yaml errors. Something
     // similar to https://github.com/AlexanderThaller/format_serde_error
-    let config_value = serde_yaml::from_str(&config_str)?;
+    let config_value = serde_yaml::from_value(config_yaml)?;
+
+    Ok((config_value, config_str, source_paths))
+  }
+
+  /// Parses a config file and merges in the files listed under its
+  /// top-level `include` key.
+  ///
+  /// Included paths are relative to the including file. Files are merged
+  /// in order, with the including file last, such that later files
+  /// override earlier scalar values and append to top-level lists (e.g.
+  /// `keybindings` and `window_rules`).
+  fn resolve_includes(
+    path: &Path,
+    config_str: &str,
+    include_stack: &mut Vec<PathBuf>,
+    source_paths: &mut Vec<PathBuf>,
+  ) -> anyhow::Result<serde_yaml::Value> {
+    let canonical_path =
+      fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    if let Some(index) = include_stack
+      .iter()
+      .position(|other| *other == canonical_path)
+    {
+      let cycle = include_stack[index..]
+        .iter()
+        .chain(std::iter::once(&canonical_path))
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+
+      bail!("Config files include each other: {}.", cycle.join(" -> "));
+    }
+
+    include_stack.push(canonical_path);
+    source_paths.push(path.to_path_buf());
+
+    let mut config_yaml =
+      serde_yaml::from_str(config_str).with_context(|| {
+        format!("Invalid config file {}.", path.display())
+      })?;
+
+    // An empty file is parsed as null rather than an empty mapping.
+    if config_yaml == serde_yaml::Value::Null {
+      config_yaml = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    }
+
+    let includes = match config_yaml
+      .as_mapping_mut()
+      .and_then(|mapping| mapping.remove(INCLUDE_KEY))
+    {
+      Some(includes) => serde_yaml::from_value::<Vec<String>>(includes)
+        .with_context(|| {
+          format!(
+            "Invalid `{INCLUDE_KEY}` in {}. Must be a list of paths.",
+            path.display()
+          )
+        })?,
+      None => Vec::new(),
+    };
+
+    let mut merged_yaml =
+      serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+
+    for include in includes {
+      let include_path = path
+        .parent()
+        .context("Invalid config path.")?
+        .join(&include);
+
+      let include_str =
+        fs::read_to_string(&include_path).with_context(|| {
+          format!(
+            "Unable to read config file {} included at {}:{}.",
+            include_path.display(),
+            path.display(),
+            include_line(config_str, &include).unwrap_or(1),
+          )
+        })?;
+
+      let included_yaml = Self::resolve_includes(
+        &include_path,
+        &include_str,
+        include_stack,
+        source_paths,
+      )?;
+
+      merge_config_yaml(&mut merged_yaml, included_yaml, 0);
+    }
+
+    merge_config_yaml(&mut merged_yaml, config_yaml, 0);
+    include_stack.pop();
+
+    Ok(merged_yaml)
+  }
+
+  /// Gets the last modified time of each of the given config files.
+  fn source_files(
+    source_paths: Vec<PathBuf>,
+  ) -> Vec<(PathBuf, Option<SystemTime>)> {
+    source_paths
+      .into_iter()
+      .map(|path| {
+        let modified =
+          fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+
+        (path, modified)
+      })
+      .collect()
+  }
 
-    Ok((config_value, config_str))
+  /// Whether the config file or any of its included files have been
+  /// modified since they were last checked.
+  pub fn has_source_changed(&mut self) -> bool {
+    let source_files = Self::source_files(
+      self
+        .source_files
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect(),
+    );
+
+    if source_files == self.source_files {
+      return false;
+    }
+
+    self.source_files = source_files;
+    true
   }
 
   /// Initializes a new config file from the sample config resource.
@@ -83,7 +257,7 @@ impl UserConfig {
       config_path.parent().context("Invalid config path.")?;
 
     fs::create_dir_all(parent_dir).with_context(|| {
-      format!("Unable to create directory {}.", &config_path.display())
+      format!("Unable to create directory {}.", config_path.display())
     })?;
 
     fs::write(config_path, SAMPLE_CONFIG).with_context(|| {
@@ -94,16 +268,187 @@ impl UserConfig {
   }
 
   pub fn reload(&mut self) -> anyhow::Result<()> {
-    let (config_value, config_str) = Self::read(&self.path)?;
+    let (config_value, config_str, source_paths) = Self::read(&self.path)?;
+    let runtime_rules = RuntimeRules::read_or_default(&self.path);
 
     self.window_rules_by_event =
-      Self::window_rules_by_event(&config_value);
+      Self::window_rules_by_event(&config_value, &runtime_rules);
     self.value = config_value;
     self.value_str = config_str;
+    self.source_files = Self::source_files(source_paths);
+    self.runtime_rules = runtime_rules;
+
+    Ok(())
+  }
+
+  /// Saves a rule that ignores windows with the given identity, and
+  /// applies it to windows managed from now on.
+  pub fn add_ignored_window(
+    &mut self,
+    ignored_window: IgnoredWindow,
+  ) -> anyhow::Result<()> {
+    if self.runtime_rules.ignored_windows.contains(&ignored_window) {
+      return Ok(());
+    }
+
+    self.runtime_rules.ignored_windows.push(ignored_window);
+    self.update_runtime_rules()
+  }
+
+  /// Removes a saved rule that ignores windows with the given identity.
+  ///
+  /// Returns whether such a rule existed.
+  pub fn remove_ignored_window(
+    &mut self,
+    ignored_window: &IgnoredWindow,
+  ) -> anyhow::Result<bool> {
+    let prev_len = self.runtime_rules.ignored_windows.len();
+
+    self
+      .runtime_rules
+      .ignored_windows
+      .retain(|ignored| ignored != ignored_window);
+
+    if self.runtime_rules.ignored_windows.len() == prev_len {
+      return Ok(false);
+    }
+
+    self.update_runtime_rules()?;
+    Ok(true)
+  }
+
+  fn update_runtime_rules(&mut self) -> anyhow::Result<()> {
+    self.runtime_rules.write(&self.path)?;
+
+    self.window_rules_by_event =
+      Self::window_rules_by_event(&self.value, &self.runtime_rules);
 
     Ok(())
   }
 
+  /// Applies the `builtins` section to the extraction settings of builtin
+  /// programs. Programs without an entry are reset to their defaults.
+  pub fn configure_builtins(&self) {
+    for builtin in &self.value.builtins {
+      if BuiltinProgram::from_str(&builtin.name).is_none() {
+        warn!("Unknown builtin program in config: '{}'.", builtin.name);
+      }
+    }
+
+    for program in BuiltinProgram::ALL {
+      let descriptor = self
+        .value
+        .builtins
+        .iter()
+        .find(|builtin| {
+          BuiltinProgram::from_str(&builtin.name) == Some(program)
+        })
+        .map_or_else(
+          || Ok(BuiltinDescriptor::new(program)),
+          |builtin| Self::builtin_descriptor(program, builtin),
+        );
+
+      if let Err(err) = descriptor.and_then(wm_builtin::configure_builtin)
+      {
+        warn!("Failed to configure builtin {:?}: {}", program, err);
+      }
+    }
+  }
+
+  fn builtin_descriptor(
+    program: BuiltinProgram,
+    builtin: &BuiltinConfig,
+  ) -> Result<BuiltinDescriptor> {
+    let descriptor = match &builtin.file_name {
+      Some(file_name) => {
+        BuiltinDescriptor::new(program).with_file_name(file_name)?
+      }
+      None => BuiltinDescriptor::new(program),
+    };
+
+    let file_name_policy = if builtin.versioned_file_name {
+      FileNamePolicy::Versioned
+    } else {
+      FileNamePolicy::Stable
+    };
+
+    Ok(descriptor.with_file_name_policy(file_name_policy))
+  }
+
+  /// Gets a `WmEvent::UserConfigChanged` event with the current config.
+  pub fn changed_event(&self) -> anyhow::Result<WmEvent> {
+    Ok(WmEvent::UserConfigChanged {
+      config_path: self
+        .path
+        .to_str()
+        .context("Invalid config path.")?
+        .to_string(),
+      config_string: self.value_str.clone(),
+      parsed_config: Box::new(self.value.clone()),
+    })
+  }
+
+  /// Gets the top-level config sections that differ between two parsed
+  /// configs.
+  pub fn changed_sections(
+    old_config: &ParsedConfig,
+    new_config: &ParsedConfig,
+  ) -> anyhow::Result<Vec<ConfigSection>> {
+    let mut changed_sections = Vec::new();
+
+    for section in ConfigSection::ALL {
+      if Self::section_value(old_config, section)?
+        != Self::section_value(new_config, section)?
+      {
+        changed_sections.push(section);
+      }
+    }
+
+    Ok(changed_sections)
+  }
+
+  /// Gets the value of a config section for comparison. Config values
+  /// aren't comparable directly, so their serialized form is used.
+  fn section_value(
+    config_value: &ParsedConfig,
+    section: ConfigSection,
+  ) -> serde_json::Result<serde_json::Value> {
+    match section {
+      ConfigSection::BindingModes => {
+        serde_json::to_value(&config_value.binding_modes)
+      }
+      ConfigSection::Builtins => {
+        serde_json::to_value(&config_value.builtins)
+      }
+      ConfigSection::Gaps => serde_json::to_value(&config_value.gaps),
+      ConfigSection::General => {
+        serde_json::to_value(&config_value.general)
+      }
+      ConfigSection::Ipc => serde_json::to_value(&config_value.ipc),
+      ConfigSection::Keybindings => {
+        serde_json::to_value(&config_value.keybindings)
+      }
+      ConfigSection::Monitors => {
+        serde_json::to_value(&config_value.monitors)
+      }
+      ConfigSection::MouseBindings => {
+        serde_json::to_value(&config_value.mouse_bindings)
+      }
+      ConfigSection::WindowBehavior => {
+        serde_json::to_value(&config_value.window_behavior)
+      }
+      ConfigSection::WindowEffects => {
+        serde_json::to_value(&config_value.window_effects)
+      }
+      ConfigSection::WindowRules => {
+        serde_json::to_value(&config_value.window_rules)
+      }
+      ConfigSection::Workspaces => {
+        serde_json::to_value(&config_value.workspaces)
+      }
+    }
+  }
+
   fn default_window_rules(
     config_value: &ParsedConfig,
   ) -> Vec<WindowRuleConfig> {
@@ -140,6 +485,16 @@ impl UserConfig {
       ],
       on: vec![WindowRuleEvent::Manage],
       run_once: true,
+      swallow: false,
+      ignore_effects: false,
+      exclusive_fullscreen: false,
+      keep_maximized: false,
+      remove_title_bar: None,
+      float_size: None,
+      float_position: None,
+      on_title_change: true,
+      initial_workspace: None,
+      initial_workspace_windows: 1,
     });
 
     // Default ignore rules.
@@ -188,6 +543,16 @@ impl UserConfig {
       ],
       on: vec![WindowRuleEvent::Manage],
       run_once: true,
+      swallow: false,
+      ignore_effects: false,
+      exclusive_fullscreen: false,
+      keep_maximized: false,
+      remove_title_bar: None,
+      float_size: None,
+      float_position: None,
+      on_title_change: true,
+      initial_workspace: None,
+      initial_workspace_windows: 1,
     });
 
     window_rules
@@ -195,18 +560,27 @@ impl UserConfig {
 
   fn window_rules_by_event(
     config_value: &ParsedConfig,
+    runtime_rules: &RuntimeRules,
   ) -> HashMap<WindowRuleEvent, Vec<WindowRuleConfig>> {
     let mut window_rules_by_event = HashMap::new();
 
-    // Combine user-defined window rules with the default ones.
+    // Combine user-defined window rules with the runtime and default
+    // ones.
+    let runtime_window_rules = runtime_rules.window_rules();
     let default_window_rules = Self::default_window_rules(config_value);
     let all_window_rules = config_value
       .window_rules
       .iter()
+      .chain(runtime_window_rules.iter())
       .chain(default_window_rules.iter());
 
     for window_rule in all_window_rules {
-      for event_type in &window_rule.on {
+      let events = window_rule.on.iter().filter(|event_type| {
+        window_rule.on_title_change
+          || **event_type != WindowRuleEvent::TitleChange
+      });
+
+      for event_type in events {
         window_rules_by_event
           .entry(event_type.clone())
           .or_insert_with(Vec::new)
@@ -224,45 +598,267 @@ impl UserConfig {
     window: &WindowContainer,
     event: &WindowRuleEvent,
   ) -> anyhow::Result<Vec<WindowRuleConfig>> {
+    let done_window_rules = window.done_window_rules();
+
+    // Skip rules that the window has already ran.
+    let pending_window_rules = self
+      .matching_window_rules(window, event)?
+      .into_iter()
+      .filter(|rule| !done_window_rules.contains(rule))
+      .collect::<Vec<_>>();
+
+    Ok(pending_window_rules)
+  }
+
+  /// Window rules for the given event that the window matches, including
+  /// ones that it has already ran.
+  pub fn matching_window_rules(
+    &self,
+    window: &WindowContainer,
+    event: &WindowRuleEvent,
+  ) -> anyhow::Result<Vec<WindowRuleConfig>> {
+    let Some(window_rules) = self.window_rules_by_event.get(event) else {
+      return Ok(Vec::new());
+    };
+
     let window_title = window.native().title()?;
     let window_class = window.native().class_name()?;
     let window_process = window.native().process_name()?;
 
-    let pending_window_rules = self
-      .window_rules_by_event
-      .get(event)
-      .unwrap_or(&Vec::new())
+    let matching_window_rules = window_rules
       .iter()
       .filter(|rule| {
-        // Skip if window has already ran the rule.
-        if window.done_window_rules().contains(rule) {
-          return false;
-        }
+        Self::is_rule_match(
+          rule,
+          &window.native(),
+          &window_title,
+          &window_class,
+          &window_process,
+        )
+      })
+      .cloned()
+      .collect::<Vec<_>>();
 
-        // Check if the window matches the rule.
-        rule.match_window.iter().any(|match_config| {
-          let is_process_match = match_config
-            .window_process
-            .as_ref()
-            .is_none_or(|match_type| match_type.is_match(&window_process));
+    Ok(matching_window_rules)
+  }
 
-          let is_class_match = match_config
-            .window_class
-            .as_ref()
-            .is_none_or(|match_type| match_type.is_match(&window_class));
+  /// Whether the window matches any user-defined window rule with
+  /// `swallow` enabled.
+  pub fn is_swallow_window(
+    &self,
+    window: &WindowContainer,
+  ) -> anyhow::Result<bool> {
+    self.is_flagged_rule_match(window, |rule| rule.swallow)
+  }
 
-          let is_title_match = match_config
-            .window_title
-            .as_ref()
-            .is_none_or(|match_type| match_type.is_match(&window_title));
+  /// Whether the window matches any user-defined window rule with
+  /// `ignore_effects` enabled.
+  pub fn is_ignore_effects_window(
+    &self,
+    window: &WindowContainer,
+  ) -> anyhow::Result<bool> {
+    self.is_flagged_rule_match(window, |rule| rule.ignore_effects)
+  }
 
-          is_process_match && is_class_match && is_title_match
-        })
+  /// Whether the window is fullscreen and sized to its monitor's working
+  /// area, rather than covering the space reserved on the monitor.
+  pub fn is_fullscreen_within_working_area(
+    &self,
+    window: &WindowContainer,
+  ) -> bool {
+    matches!(window.state(), WindowState::Fullscreen(_))
+      && !self.value.general.fullscreen_covers_reserved_space
+      && !self
+        .is_flagged_rule_match(window, |rule| rule.exclusive_fullscreen)
+        .unwrap_or(true)
+  }
+
+  /// Whether the window stays maximized when maximized via its own
+  /// maximize button, regardless of `window_behavior.on_maximize`.
+  pub fn is_keep_maximized_window(
+    &self,
+    window: &WindowContainer,
+  ) -> bool {
+    self
+      .is_flagged_rule_match(window, |rule| rule.keep_maximized)
+      .unwrap_or(false)
+  }
+
+  /// Whether the native title bar and frame of the window should be
+  /// removed while tiling.
+  ///
+  /// The first matching window rule with `remove_title_bar` set takes
+  /// precedence over `window_behavior.remove_title_bars`.
+  pub fn is_title_bar_removed_window(
+    &self,
+    window: &WindowContainer,
+  ) -> bool {
+    self
+      .rule_value(window, |rule| rule.remove_title_bar)
+      .unwrap_or_else(|err| {
+        warn!("Failed to match window rules for title bar: {}", err);
+        None
       })
-      .cloned()
-      .collect::<Vec<_>>();
+      .unwrap_or(self.value.window_behavior.remove_title_bars)
+  }
 
-    Ok(pending_window_rules)
+  /// Gets the first matching window rule with a `float_size` or
+  /// `float_position` set.
+  pub fn floating_placement_rule(
+    &self,
+    window: &WindowContainer,
+  ) -> Option<WindowRuleConfig> {
+    self
+      .rule_value(window, |rule| {
+        (rule.float_size.is_some() || rule.float_position.is_some())
+          .then(|| rule.clone())
+      })
+      .unwrap_or_else(|err| {
+        warn!("Failed to match window rules for placement: {}", err);
+        None
+      })
+  }
+
+  /// Whether the window matches any user-defined window rule for which
+  /// `is_flagged` returns true.
+  fn is_flagged_rule_match(
+    &self,
+    window: &WindowContainer,
+    is_flagged: impl Fn(&WindowRuleConfig) -> bool,
+  ) -> anyhow::Result<bool> {
+    Ok(
+      self
+        .rule_value(window, |rule| is_flagged(rule).then_some(()))?
+        .is_some(),
+    )
+  }
+
+  /// Gets the value of the first user-defined window rule that matches
+  /// the window and for which `value` returns `Some`.
+  fn rule_value<T>(
+    &self,
+    window: &WindowContainer,
+    value: impl Fn(&WindowRuleConfig) -> Option<T>,
+  ) -> anyhow::Result<Option<T>> {
+    let mut valued_rules = self
+      .value
+      .window_rules
+      .iter()
+      .filter_map(|rule| value(rule).map(|value| (rule, value)))
+      .peekable();
+
+    // Avoid querying the window's properties if there's nothing to match.
+    if valued_rules.peek().is_none() {
+      return Ok(None);
+    }
+
+    let window_title = window.native().title()?;
+    let window_class = window.native().class_name()?;
+    let window_process = window.native().process_name()?;
+
+    Ok(valued_rules.find_map(|(rule, value)| {
+      Self::is_rule_match(
+        rule,
+        &window.native(),
+        &window_title,
+        &window_class,
+        &window_process,
+      )
+      .then_some(value)
+    }))
+  }
+
+  /// Whether the window matches the given match config, in the same way
+  /// as for window rules.
+  pub fn is_window_match(
+    window: &WindowContainer,
+    match_config: &WindowMatchConfig,
+  ) -> anyhow::Result<bool> {
+    let native_window = window.native();
+
+    Ok(Self::is_match_config_match(
+      match_config,
+      &native_window,
+      &native_window.title()?,
+      &native_window.class_name()?,
+      &native_window.process_name()?,
+    ))
+  }
+
+  /// Whether the given window properties match any of the rule's match
+  /// configs.
+  fn is_rule_match(
+    rule: &WindowRuleConfig,
+    native_window: &NativeWindow,
+    window_title: &str,
+    window_class: &str,
+    window_process: &str,
+  ) -> bool {
+    rule.match_window.iter().any(|match_config| {
+      Self::is_match_config_match(
+        match_config,
+        native_window,
+        window_title,
+        window_class,
+        window_process,
+      )
+    })
+  }
+
+  /// Whether the given window properties match the match config.
+  ///
+  /// The process path and command line are only queried for match
+  /// configs that use them, and are cached on the native window. Windows
+  /// whose process can't be queried never match on them.
+  fn is_match_config_match(
+    match_config: &WindowMatchConfig,
+    native_window: &NativeWindow,
+    window_title: &str,
+    window_class: &str,
+    window_process: &str,
+  ) -> bool {
+    let is_process_match = match_config
+      .window_process
+      .as_ref()
+      .is_none_or(|match_type| match_type.is_match(window_process));
+
+    let is_class_match = match_config
+      .window_class
+      .as_ref()
+      .is_none_or(|match_type| match_type.is_match(window_class));
+
+    let is_title_match = match_config
+      .window_title
+      .as_ref()
+      .is_none_or(|match_type| match_type.is_match(window_title));
+
+    let is_process_path_match = || {
+      match_config
+        .window_process_path
+        .as_ref()
+        .is_none_or(|match_type| {
+          native_window
+            .process_path()
+            .is_ok_and(|path| match_type.is_match(&path))
+        })
+    };
+
+    let is_command_line_match = || {
+      match_config
+        .window_command_line
+        .as_ref()
+        .is_none_or(|match_type| {
+          native_window
+            .command_line()
+            .is_ok_and(|command_line| match_type.is_match(&command_line))
+        })
+    };
+
+    is_process_match
+      && is_class_match
+      && is_title_match
+      && is_process_path_match()
+      && is_command_line_match()
   }
 
   pub fn inactive_workspace_configs(
@@ -281,21 +877,23 @@ impl UserConfig {
       .collect()
   }
 
+  /// Gets the first inactive workspace config that's bound to the given
+  /// monitor, i.e. the monitor is the most preferred connected monitor of
+  /// its binding.
   pub fn workspace_config_for_monitor(
     &self,
     monitor: &Monitor,
-    active_workspaces: &[Workspace],
+    state: &WmState,
   ) -> Option<&WorkspaceConfig> {
     let inactive_configs =
-      self.inactive_workspace_configs(active_workspaces);
+      self.inactive_workspace_configs(&state.workspaces());
 
     inactive_configs.into_iter().find(|&config| {
       config
         .bind_to_monitor
         .as_ref()
-        .is_some_and(|monitor_index| {
-          monitor.index() == *monitor_index as usize
-        })
+        .and_then(|binding| state.bound_monitor(binding))
+        .is_some_and(|bound_monitor| bound_monitor.id() == monitor.id())
     })
   }
 
@@ -326,16 +924,55 @@ impl UserConfig {
       .position(|config| config.name == workspace_name)
   }
 
+  /// Sorts workspaces by the order they're defined in the config.
+  ///
+  /// Bound workspaces with a `monitor_position` come first, ordered by
+  /// their position. Workspaces that aren't defined in the config are
+  /// placed after the rest, ordered by name. The sort is stable and only
+  /// depends on the set of workspaces, such that repeated calls always
+  /// give the same order.
   pub fn sort_workspaces(&self, workspaces: &mut [Workspace]) {
-    workspaces.sort_by_key(|workspace| {
-      self.workspace_config_index(&workspace.config().name)
+    workspaces.sort_by_cached_key(|workspace| {
+      let name = workspace.config().name;
+
+      match self.workspace_config_index(&name) {
+        Some(index) => match self.workspace_monitor_position(index) {
+          Some(position) => (0, position, index, String::new()),
+          None => (1, 0, index, String::new()),
+        },
+        None => (2, 0, 0, name),
+      }
     });
   }
 
+  /// Gets the `monitor_position` of the workspace config at the given
+  /// index, if the workspace is bound to a monitor.
+  fn workspace_monitor_position(
+    &self,
+    config_index: usize,
+  ) -> Option<u32> {
+    self
+      .value
+      .workspaces
+      .get(config_index)
+      .filter(|config| config.bind_to_monitor.is_some())
+      .and_then(|config| config.monitor_position)
+  }
+
   pub fn outer_gaps_for_workspace(
     &self,
     workspace: &Workspace,
-  ) -> &wm_common::RectDelta {
+  ) -> RectDelta {
+    // Smart gaps leave no outer gaps.
+    if workspace.has_smart_gaps() {
+      return RectDelta::new(
+        LengthValue::from_px(0),
+        LengthValue::from_px(0),
+        LengthValue::from_px(0),
+        LengthValue::from_px(0),
+      );
+    }
+
     let is_single_window = workspace.tiling_children().nth(1).is_none();
 
     if is_single_window {
@@ -343,10 +980,528 @@ impl UserConfig {
         .value
         .gaps
         .single_window_outer_gap
-        .as_ref()
-        .unwrap_or(&self.value.gaps.outer_gap)
+        .clone()
+        .unwrap_or_else(|| self.value.gaps.outer_gap.clone())
     } else {
-      &self.value.gaps.outer_gap
+      self.value.gaps.outer_gap.clone()
+    }
+  }
+}
+
+/// Merges the values of a config file into the values of the files
+/// before it. Top-level lists are appended to, whereas other values are
+/// overridden.
+fn merge_config_yaml(
+  base: &mut serde_yaml::Value,
+  overlay: serde_yaml::Value,
+  depth: usize,
+) {
+  match (base, overlay) {
+    (
+      serde_yaml::Value::Mapping(base),
+      serde_yaml::Value::Mapping(overlay),
+    ) => {
+      for (key, value) in overlay {
+        match base.get_mut(&key) {
+          Some(base_value) => {
+            merge_config_yaml(base_value, value, depth + 1);
+          }
+          None => {
+            base.insert(key, value);
+          }
+        }
+      }
+    }
+    (
+      serde_yaml::Value::Sequence(base),
+      serde_yaml::Value::Sequence(overlay),
+    ) if depth == 1 => base.extend(overlay),
+    (base, overlay) => *base = overlay,
+  }
+}
+
+/// Gets the line number of an entry under the top-level `include` key of
+/// a config file.
+fn include_line(config_str: &str, include: &str) -> Option<usize> {
+  config_str
+    .lines()
+    .enumerate()
+    .skip_while(|(_, line)| !line.starts_with(INCLUDE_KEY))
+    .find(|(_, line)| line.contains(include))
+    .map(|(index, _)| index + 1)
+}
+
+#[cfg(any(test, feature = "test_utils"))]
+impl UserConfig {
+  /// Creates an instance of `UserConfig` from an already parsed config,
+  /// without reading from disk.
+  pub fn from_value(value: ParsedConfig) -> Self {
+    let runtime_rules = RuntimeRules::default();
+    let window_rules_by_event =
+      Self::window_rules_by_event(&value, &runtime_rules);
+
+    Self {
+      path: PathBuf::new(),
+      value,
+      value_str: String::new(),
+      source_files: Vec::new(),
+      runtime_rules,
+      window_rules_by_event,
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use wm_common::{
+    FloatAnchor, FloatPosition, MonitorBinding, MonitorSelector,
+    TilingDirection,
+  };
+
+  use super::*;
+
+  #[test]
+  fn parses_rules_mixing_old_and_new_matchers() {
+    let rules = serde_yaml::from_str::<Vec<WindowRuleConfig>>(
+      "
+      - commands: ['ignore']
+        match:
+          - window_process: { equals: 'Taskmgr' }
+          - window_class: { equals: 'ConsoleWindowClass' }
+            window_command_line: { regex: '--profile work' }
+          - match_process_path: { regex: 'Program Files\\\\App' }
+      ",
+    )
+    .unwrap();
+
+    let match_window = &rules[0].match_window;
+    assert_eq!(match_window.len(), 3);
+    assert!(match_window[0].window_command_line.is_none());
+    assert_eq!(
+      match_window[1].window_command_line,
+      Some(MatchType::Regex {
+        regex: "--profile work".to_string()
+      })
+    );
+    assert!(match_window[2].window_process_path.is_some());
+  }
+
+  #[test]
+  fn parses_remove_title_bar_override() {
+    let rules = serde_yaml::from_str::<Vec<WindowRuleConfig>>(
+      "
+      - remove_title_bar: false
+        match:
+          - window_process: { equals: 'Spotify' }
+      - match:
+          - window_process: { equals: 'Code' }
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(rules[0].remove_title_bar, Some(false));
+    assert_eq!(rules[1].remove_title_bar, None);
+  }
+
+  #[test]
+  fn parses_floating_placement() {
+    let rules = serde_yaml::from_str::<Vec<WindowRuleConfig>>(
+      "
+      - float_size: [400, '50%']
+        float_position: top_right
+        match:
+          - window_process: { equals: 'CalculatorApp' }
+      - float_position: [20, '10%']
+        match:
+          - window_process: { equals: 'vlc' }
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(
+      rules[0].float_size,
+      Some([
+        LengthValue::from_px(400),
+        LengthValue::from_str("50%").unwrap()
+      ])
+    );
+    assert_eq!(
+      rules[0].float_position,
+      Some(FloatPosition::Anchor(FloatAnchor::TopRight))
+    );
+    assert_eq!(
+      rules[1].float_position,
+      Some(FloatPosition::Offset([
+        LengthValue::from_px(20),
+        LengthValue::from_str("10%").unwrap()
+      ]))
+    );
+  }
+
+  #[test]
+  fn sorts_workspaces_deterministically() {
+    let workspace_configs = serde_yaml::from_str::<Vec<WorkspaceConfig>>(
+      "
+      - name: '2'
+      - name: '1'
+      - name: '3'
+      ",
+    )
+    .unwrap();
+
+    let config = UserConfig {
+      path: PathBuf::new(),
+      value: ParsedConfig {
+        workspaces: workspace_configs.clone(),
+        ..ParsedConfig::default()
+      },
+      value_str: String::new(),
+      source_files: Vec::new(),
+      runtime_rules: RuntimeRules::default(),
+      window_rules_by_event: HashMap::new(),
+    };
+
+    let workspace = |name: &str| {
+      let workspace_config = WorkspaceConfig {
+        name: name.to_string(),
+        ..workspace_configs[0].clone()
+      };
+
+      Workspace::new(
+        workspace_config,
+        config.value.gaps.clone(),
+        TilingDirection::Horizontal,
+      )
+    };
+
+    let names = |workspaces: &[Workspace]| {
+      workspaces
+        .iter()
+        .map(|workspace| workspace.config().name)
+        .collect::<Vec<_>>()
+    };
+
+    let mut workspaces = ["b", "3", "a", "1", "2"].map(workspace).to_vec();
+    config.sort_workspaces(&mut workspaces);
+    assert_eq!(names(&workspaces), ["2", "1", "3", "a", "b"]);
+
+    // Sorting again, or from a different starting order, gives the same
+    // result.
+    config.sort_workspaces(&mut workspaces);
+    assert_eq!(names(&workspaces), ["2", "1", "3", "a", "b"]);
+
+    workspaces.reverse();
+    config.sort_workspaces(&mut workspaces);
+    assert_eq!(names(&workspaces), ["2", "1", "3", "a", "b"]);
+  }
+
+  #[test]
+  fn sorts_bound_workspaces_by_monitor_position() {
+    let workspace_configs = serde_yaml::from_str::<Vec<WorkspaceConfig>>(
+      "
+      - name: '1'
+      - name: 'mail'
+        bind_to_monitor: 1
+        monitor_position: 2
+      - name: 'chat'
+        bind_to_monitor: 1
+        monitor_position: 1
+      - name: 'music'
+        monitor_position: 0
+      ",
+    )
+    .unwrap();
+
+    let config = UserConfig {
+      path: PathBuf::new(),
+      value: ParsedConfig {
+        workspaces: workspace_configs.clone(),
+        ..ParsedConfig::default()
+      },
+      value_str: String::new(),
+      source_files: Vec::new(),
+      runtime_rules: RuntimeRules::default(),
+      window_rules_by_event: HashMap::new(),
+    };
+
+    let mut workspaces = workspace_configs
+      .into_iter()
+      .map(|workspace_config| {
+        Workspace::new(
+          workspace_config,
+          config.value.gaps.clone(),
+          TilingDirection::Horizontal,
+        )
+      })
+      .collect::<Vec<_>>();
+
+    config.sort_workspaces(&mut workspaces);
+
+    // Positions are ignored for workspaces that aren't bound to a monitor.
+    assert_eq!(
+      workspaces
+        .iter()
+        .map(|workspace| workspace.config().name)
+        .collect::<Vec<_>>(),
+      ["chat", "mail", "1", "music"]
+    );
+  }
+
+  #[test]
+  fn parses_single_and_prioritized_monitor_bindings() {
+    let workspace_configs = serde_yaml::from_str::<Vec<WorkspaceConfig>>(
+      "
+      - name: '1'
+        bind_to_monitor: 1
+      - name: '2'
+        bind_to_monitor: [2, '\\\\.\\DISPLAY1', 0]
+      - name: '3'
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(
+      workspace_configs[0].bind_to_monitor,
+      Some(MonitorBinding(vec![MonitorSelector::Index(1)]))
+    );
+    assert_eq!(
+      workspace_configs[1].bind_to_monitor,
+      Some(MonitorBinding(vec![
+        MonitorSelector::Index(2),
+        MonitorSelector::Name("\\\\.\\DISPLAY1".to_string()),
+        MonitorSelector::Index(0),
+      ]))
+    );
+    assert_eq!(workspace_configs[2].bind_to_monitor, None);
+  }
+
+  #[test]
+  fn detects_changed_config_sections() {
+    let old_config = serde_yaml::from_str::<ParsedConfig>(
+      "
+      gaps:
+        inner_gap: '10px'
+      workspaces:
+        - name: '1'
+      ",
+    )
+    .unwrap();
+
+    let new_config = serde_yaml::from_str::<ParsedConfig>(
+      "
+      gaps:
+        inner_gap: '20px'
+      workspaces:
+        - name: '1'
+      keybindings:
+        - commands: ['focus --direction left']
+          bindings: ['alt+h']
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(
+      UserConfig::changed_sections(&old_config, &new_config).unwrap(),
+      [ConfigSection::Gaps, ConfigSection::Keybindings]
+    );
+    assert!(UserConfig::changed_sections(&old_config, &old_config)
+      .unwrap()
+      .is_empty());
+  }
+
+  #[test]
+  fn title_change_opt_out_skips_title_change_events() {
+    let window_rules = serde_yaml::from_str::<Vec<WindowRuleConfig>>(
+      "
+      - commands: ['set-floating']
+        match:
+          - window_title: { includes: 'Picture-in-Picture' }
+      - commands: ['set-tiling']
+        on_title_change: false
+        match:
+          - window_title: { includes: 'Editor' }
+      ",
+    )
+    .unwrap();
+
+    let config_value = ParsedConfig {
+      window_rules,
+      ..ParsedConfig::default()
+    };
+
+    let rules_by_event = UserConfig::window_rules_by_event(
+      &config_value,
+      &RuntimeRules::default(),
+    );
+
+    assert_eq!(rules_by_event[&WindowRuleEvent::Manage].len(), 4);
+    assert_eq!(rules_by_event[&WindowRuleEvent::TitleChange].len(), 1);
+  }
+
+  #[test]
+  fn merges_included_config_files() {
+    let dir = std::env::temp_dir()
+      .join(format!("glazewm-config-includes-{}", std::process::id()));
+    fs::create_dir_all(dir.join("rules")).unwrap();
+
+    fs::write(
+      dir.join("keybindings.yaml"),
+      "
+      gaps:
+        inner_gap: '5px'
+        outer_gap: { top: '5px', right: '5px', bottom: '5px', left: '5px' }
+      keybindings:
+        - commands: ['close']
+          bindings: ['alt+q']
+      ",
+    )
+    .unwrap();
+
+    fs::write(
+      dir.join("rules/window_rules.yaml"),
+      "
+      window_rules:
+        - commands: ['ignore']
+          match:
+            - window_process: { equals: 'zebar' }
+      ",
+    )
+    .unwrap();
+
+    let config_path = dir.join("config.yaml");
+    let config_str = "
+      include: ['keybindings.yaml', 'rules/window_rules.yaml']
+      gaps:
+        inner_gap: '10px'
+        outer_gap: { top: '20px' }
+      keybindings:
+        - commands: ['toggle-floating']
+          bindings: ['alt+f']
+      ";
+
+    let mut source_paths = Vec::new();
+    let config_value = serde_yaml::from_value::<ParsedConfig>(
+      UserConfig::resolve_includes(
+        &config_path,
+        config_str,
+        &mut Vec::new(),
+        &mut source_paths,
+      )
+      .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(source_paths.len(), 3);
+    assert_eq!(config_value.gaps.inner_gap, LengthValue::from_px(10));
+    assert_eq!(config_value.gaps.outer_gap.top, LengthValue::from_px(20));
+    assert_eq!(config_value.gaps.outer_gap.left, LengthValue::from_px(5));
+    assert_eq!(
+      config_value
+        .keybindings
+        .iter()
+        .map(|keybinding| keybinding.bindings[0].as_str())
+        .collect::<Vec<_>>(),
+      ["alt+q", "alt+f"]
+    );
+    assert_eq!(config_value.window_rules.len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_invalid_includes() {
+    let dir = std::env::temp_dir().join(format!(
+      "glazewm-config-invalid-includes-{}",
+      std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(dir.join("a.yaml"), "include: ['b.yaml']").unwrap();
+    fs::write(dir.join("b.yaml"), "include: ['a.yaml']").unwrap();
+
+    let error = UserConfig::resolve_includes(
+      &dir.join("config.yaml"),
+      "include: ['a.yaml']",
+      &mut Vec::new(),
+      &mut Vec::new(),
+    )
+    .unwrap_err();
+
+    assert!(error.to_string().contains("include each other"));
+
+    let error = UserConfig::resolve_includes(
+      &dir.join("config.yaml"),
+      "gaps: {}\ninclude:\n  - 'missing.yaml'\n",
+      &mut Vec::new(),
+      &mut Vec::new(),
+    )
+    .unwrap_err();
+
+    assert!(error.to_string().contains("config.yaml:3."));
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn reads_merged_config_string() {
+    let dir = std::env::temp_dir().join(format!(
+      "glazewm-config-merged-string-{}",
+      std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(
+      dir.join("rules.yaml"),
+      "window_rules: [{ commands: ['ignore'], match: [{ window_process: { equals: 'zebar' } }] }]",
+    )
+    .unwrap();
+
+    let config_path = dir.join("config.yaml");
+    fs::write(
+      &config_path,
+      "include: ['rules.yaml']
+",
+    )
+    .unwrap();
+
+    // The string sent with `UserConfigChanged` includes the window rule
+    // from the included file.
+    let (_, config_str, _) = UserConfig::read(&config_path).unwrap();
+    let merged_value =
+      serde_yaml::from_str::<ParsedConfig>(&config_str).unwrap();
+
+    assert_eq!(merged_value.window_rules.len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn parses_builtin_descriptors() {
+    let builtins = serde_yaml::from_str::<Vec<BuiltinConfig>>(
+      "
+      - name: 'zebar'
+        file_name: 'my-bar.exe'
+        versioned_file_name: true
+      - name: 'zebar'
+        file_name: 'bin/zebar.exe'
+      ",
+    )
+    .unwrap();
+
+    let descriptor =
+      UserConfig::builtin_descriptor(BuiltinProgram::Zebar, &builtins[0])
+        .unwrap();
+
+    assert_eq!(descriptor.file_name(), "my-bar.exe");
+    assert_eq!(descriptor.file_name_policy, FileNamePolicy::Versioned);
+
+    // File names with path separators are rejected.
+    assert!(UserConfig::builtin_descriptor(
+      BuiltinProgram::Zebar,
+      &builtins[1]
+    )
+    .is_err());
+  }
+}
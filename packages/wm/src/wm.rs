@@ -1,40 +1,59 @@
+use std::time::Instant;
+
 use anyhow::{bail, Context};
 use tokio::sync::mpsc::{self};
-use tracing::warn;
+use tracing::{info, warn};
 use uuid::Uuid;
 use wm_common::{
   FloatingStateConfig, FullscreenStateConfig, InvokeCommand, LengthValue,
-  RectDelta, TitleBarVisibility, WindowState, WmEvent,
+  RectDelta, TitleBarVisibility, WindowState, WmEvent, WorkspaceMove,
 };
-use wm_platform::PlatformEvent;
+use wm_platform::{MouseMoveEvent, Platform, PlatformEvent};
 
 use crate::{
   commands::{
     container::{
-      focus_container_by_id, focus_in_direction, set_tiling_direction,
+      balance_windows, clear_insertion_hint, focus_container_by_id,
+      focus_in_direction, focus_in_stack, set_insertion_hint,
+      set_sibling_insertion_hint, set_split_layout, set_tiling_direction,
       toggle_tiling_direction,
     },
     general::{
       cycle_focus, disable_binding_mode, enable_binding_mode,
-      platform_sync, reload_config, shell_exec, toggle_pause,
+      platform_sync, reload_config, shell_exec,
+      toggle_focus_follows_cursor,
+      toggle_fullscreen_covers_reserved_space, toggle_opacity_effect,
+      toggle_pause,
     },
-    monitor::focus_monitor,
+    monitor::{focus_monitor, toggle_monitor_standby},
     window::{
-      ignore_window, move_window_in_direction, move_window_to_workspace,
-      resize_window, set_window_position, set_window_size,
+      close_window, cycle_window_focus, float_matching, focus_last_window,
+      focus_urgent_window, force_close_window, ignore_window,
+      ignore_window_on_request, manage_ignored_window,
+      move_window_in_direction, move_window_to_monitor,
+      move_window_to_next_slot, move_window_to_workspace,
+      promote_to_master, resize_window, run_title_change_rules,
+      set_window_position, set_window_size, swap_window,
+      toggle_always_on_top, toggle_fullscreen, toggle_monocle,
       update_window_state, WindowPositionTarget,
     },
-    workspace::{focus_workspace, move_workspace_in_direction, move_workspace_to_monitor},
+    workspace::{
+      adjust_master_ratio, cycle_workspace_monitor, focus_workspace,
+      gather_all_windows, move_workspace_in_direction,
+      move_workspace_to_monitor, move_workspaces,
+      swap_all_monitor_workspaces,
+    },
   },
   events::{
-    handle_display_settings_changed, handle_mouse_move,
-    handle_window_destroyed, handle_window_focused, handle_window_hidden,
-    handle_window_location_changed, handle_window_minimize_ended,
-    handle_window_minimized, handle_window_moved_or_resized_end,
+    handle_display_settings_changed, handle_mouse_drag, handle_mouse_move,
+    handle_window_destroyed, handle_window_flashed, handle_window_focused,
+    handle_window_hidden, handle_window_location_changed,
+    handle_window_minimize_ended, handle_window_minimized,
+    handle_window_moved_or_resized_end,
     handle_window_moved_or_resized_start, handle_window_shown,
     handle_window_title_changed,
   },
-  models::{Container, WorkspaceTarget},
+  models::{Container, PendingWorkspaceAssignment, WorkspaceTarget},
   traits::{CommonGetters, WindowGetters},
   user_config::UserConfig,
   wm_state::WmState,
@@ -78,17 +97,23 @@ impl WindowManager {
         // Return early since we don't want to redraw twice.
         return Ok(());
       }
+      PlatformEvent::MouseDrag(event) => {
+        handle_mouse_drag(&event, state, config)
+      }
       PlatformEvent::MouseMove(event) => {
         handle_mouse_move(&event, state, config)
       }
       PlatformEvent::WindowDestroyed(window) => {
         handle_window_destroyed(&window, state)
       }
+      PlatformEvent::WindowFlashed(window) => {
+        handle_window_flashed(&window, state, config)
+      }
       PlatformEvent::WindowFocused(window) => {
         handle_window_focused(&window, state, config)
       }
       PlatformEvent::WindowHidden(window) => {
-        handle_window_hidden(&window, state)
+        handle_window_hidden(&window, state, config)
       }
       PlatformEvent::WindowLocationChanged(window) => {
         handle_window_location_changed(&window, state, config)
@@ -110,7 +135,7 @@ impl WindowManager {
         handle_window_shown(window, state, config)
       }
       PlatformEvent::WindowTitleChanged(window) => {
-        handle_window_title_changed(&window, state, config)
+        handle_window_title_changed(&window, state)
       }
     }?;
 
@@ -153,6 +178,131 @@ impl WindowManager {
     Ok(new_subject_container_id)
   }
 
+  /// Moves multiple workspaces between monitors, applying either all of
+  /// the moves or none of them.
+  pub fn process_workspace_moves(
+    &mut self,
+    moves: &[WorkspaceMove],
+    config: &UserConfig,
+  ) -> anyhow::Result<()> {
+    let state = &mut self.state;
+    move_workspaces(moves, state, config)?;
+
+    if state.pending_sync.has_changes() {
+      platform_sync(state, config)?;
+    }
+
+    Ok(())
+  }
+
+  /// Checks the container under the cursor once the focus follows cursor
+  /// delay has elapsed. This is needed since no mouse events are received
+  /// while the cursor is at rest.
+  pub fn process_cursor_focus(
+    &mut self,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<()> {
+    let event = MouseMoveEvent {
+      point: Platform::mouse_position()?,
+      is_mouse_down: false,
+    };
+
+    self.process_event(PlatformEvent::MouseMove(event), config)
+  }
+
+  /// Re-evaluates window rules for windows whose title has settled after
+  /// changing.
+  pub fn process_title_changes(
+    &mut self,
+    config: &mut UserConfig,
+  ) -> anyhow::Result<()> {
+    let state = &mut self.state;
+
+    for window_id in state.take_settled_title_changes() {
+      let window = state
+        .container_by_id(window_id)
+        .and_then(|container| container.as_window_container().ok());
+
+      // The window is no longer pending, so failing for one window
+      // shouldn't skip the others.
+      if let Some(window) = window {
+        // Window rules might now match differently, in which case the
+        // window is redrawn to add or remove its title bar.
+        if let Some(was_removed) = state.title_bar_removals.get(&window_id)
+        {
+          let is_removed = config.is_title_bar_removed_window(&window);
+
+          if is_removed != *was_removed {
+            state.title_bar_removals.insert(window_id, is_removed);
+            state.pending_sync.queue_container_to_redraw(window.clone());
+          }
+        }
+
+        if let Err(err) = run_title_change_rules(window, state, config) {
+          warn!("Failed to run window rules on title change: {}", err);
+        }
+      }
+    }
+
+    if !state.is_paused && state.pending_sync.has_changes() {
+      platform_sync(state, config)?;
+    }
+
+    Ok(())
+  }
+
+  /// Terminates the processes of windows that were closed with `--kill`,
+  /// and that haven't closed within the timeout.
+  pub fn process_close_timeouts(&mut self, config: &UserConfig) {
+    let state = &mut self.state;
+
+    for (window_id, pending_close) in state.take_expired_closes(config) {
+      let window = state
+        .container_by_id(window_id)
+        .and_then(|container| container.as_window_container().ok());
+
+      if let Some(window) = window {
+        if let Err(err) =
+          force_close_window(&window, pending_close.force_all, state)
+        {
+          warn!("{:?}", err);
+        }
+      }
+    }
+  }
+
+  /// Emits an event for each builtin program that has exited, and
+  /// restarts the ones whose scheduled restart is due.
+  pub fn process_builtins(&mut self) -> anyhow::Result<()> {
+    for (program, status) in wm_builtin::reap_builtins()? {
+      self.state.emit_event(WmEvent::BuiltinExited {
+        name: program.name().to_string(),
+        exit_code: status.code(),
+      });
+    }
+
+    for program in wm_builtin::poll_builtin_restarts()? {
+      info!("Restarted builtin {}.", program.name());
+    }
+
+    Ok(())
+  }
+
+  /// Redraws containers that were deferred from a previous sync due to
+  /// exceeding the redraw budget.
+  pub fn process_deferred_redraws(
+    &mut self,
+    config: &UserConfig,
+  ) -> anyhow::Result<()> {
+    let state = &mut self.state;
+
+    if !state.is_paused && state.pending_sync.has_deferred_redraws() {
+      platform_sync(state, config)?;
+    }
+
+    Ok(())
+  }
+
   pub fn run_commands(
     commands: &Vec<InvokeCommand>,
     subject_container: Container,
@@ -162,6 +312,9 @@ impl WindowManager {
     let mut current_subject_container = subject_container;
 
     for command in commands {
+      let start = config.value.general.metrics.then(Instant::now);
+      let needs_cursor_jump = state.pending_sync.needs_cursor_jump();
+
       WindowManager::run_command(
         command,
         current_subject_container.clone(),
@@ -169,6 +322,25 @@ impl WindowManager {
         config,
       )?;
 
+      // Drop any cursor jump queued by the command if it's suppressed.
+      if !needs_cursor_jump
+        && config
+          .value
+          .general
+          .cursor_jump
+          .suppressed_commands
+          .iter()
+          .any(|name| name == command.name())
+      {
+        state.pending_sync.dequeue_cursor_jump();
+      }
+
+      if let Some(start) = start {
+        state
+          .command_metrics
+          .record(command.name(), start.elapsed());
+      }
+
       // Update the subject container in case the container type changes.
       // For example, when going from a tiling to a floating window.
       current_subject_container =
@@ -221,22 +393,39 @@ impl WindowManager {
           _ => Ok(()),
         }
       }
-      InvokeCommand::Close => {
-        match subject_container.as_window_container() {
-          Ok(window) => {
-            // Window handle might no longer be valid here.
-            if let Err(err) = window.native().close() {
-              warn!("Failed to close window: {:?}", err);
-            }
-
-            Ok(())
-          }
-          _ => Ok(()),
+      InvokeCommand::AdjustMasterRatio { amount } => {
+        adjust_master_ratio(&subject_container, amount, state)
+      }
+      InvokeCommand::BalanceWindows { recursive } => {
+        balance_windows(&subject_container, *recursive, state)
+      }
+      InvokeCommand::Close {
+        force,
+        kill,
+        force_all,
+      } => match subject_container.as_window_container() {
+        Ok(window) => {
+          close_window(&window, *force, *kill, *force_all, state, config)
         }
+        _ => Ok(()),
+      },
+      InvokeCommand::CycleWorkspaceMonitor => {
+        let workspace =
+          subject_container.workspace().context("No workspace.")?;
+
+        cycle_workspace_monitor(&workspace, state, config)
+      }
+      InvokeCommand::FloatMatching(args) => {
+        float_matching(&args.match_config(), !args.tiling, state, config)
       }
       InvokeCommand::Focus(args) => {
         if let Some(direction) = &args.direction {
-          focus_in_direction(&subject_container, direction, state)?;
+          focus_in_direction(
+            &subject_container,
+            direction,
+            state,
+            config,
+          )?;
         }
 
         if let Some(direction) = &args.workspace_in_direction {
@@ -283,6 +472,14 @@ impl WindowManager {
           focus_workspace(WorkspaceTarget::Recent, state, config)?;
         }
 
+        if args.next_in_stack {
+          focus_in_stack(&subject_container, false, state);
+        }
+
+        if args.prev_in_stack {
+          focus_in_stack(&subject_container, true, state);
+        }
+
         if args.next_active_workspace_on_monitor {
           focus_workspace(
             WorkspaceTarget::NextActiveInMonitor,
@@ -301,19 +498,63 @@ impl WindowManager {
 
         Ok(())
       }
+      InvokeCommand::FocusLast => focus_last_window(1, state, config),
+      InvokeCommand::FocusNextWindow { include_minimized } => {
+        cycle_window_focus(true, *include_minimized, state, config)
+      }
+      InvokeCommand::FocusNthLast { n } => {
+        focus_last_window(*n, state, config)
+      }
+      InvokeCommand::FocusPrevWindow { include_minimized } => {
+        cycle_window_focus(false, *include_minimized, state, config)
+      }
+      InvokeCommand::FocusUrgent => focus_urgent_window(state, config),
+      InvokeCommand::GatherAllWindows => gather_all_windows(state, config),
       InvokeCommand::Ignore => {
         match subject_container.as_window_container() {
-          Ok(window) => ignore_window(window, state),
+          Ok(window) => ignore_window(window, state, config),
           _ => Ok(()),
         }
       }
+      InvokeCommand::IgnoreWindow { persist } => {
+        match subject_container.as_window_container() {
+          Ok(window) => {
+            ignore_window_on_request(window, *persist, state, config)
+          }
+          _ => Ok(()),
+        }
+      }
+      InvokeCommand::Insert(args) => {
+        if let Some(direction) = &args.direction {
+          set_insertion_hint(&subject_container, direction, state)
+        } else if args.before || args.after {
+          set_sibling_insertion_hint(&subject_container, args.after, state)
+        } else {
+          let workspace =
+            subject_container.workspace().context("No workspace.")?;
+
+          clear_insertion_hint(&workspace, state)
+        }
+      }
+      InvokeCommand::Layout { layout } => {
+        set_split_layout(&subject_container, *layout, state, config)
+      }
+      InvokeCommand::ManageWindow { window_handle } => {
+        manage_ignored_window(*window_handle, state, config)
+      }
       InvokeCommand::Move(args) => {
         match subject_container.as_window_container() {
           Ok(window) => {
+            // Focus follows windows moved in a direction by default, but
+            // not windows moved to another workspace.
+            let general = &config.value.general;
+            let follow = general.follows_moved_window(args.follow, false);
+
             if let Some(direction) = &args.direction {
               move_window_in_direction(
                 window.clone(),
                 direction,
+                general.follows_moved_window(args.follow, true),
                 state,
                 config,
               )?;
@@ -321,8 +562,9 @@ impl WindowManager {
 
             if let Some(direction) = &args.workspace_in_direction {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::Direction(direction.clone()),
+                &window,
+                &WorkspaceTarget::Direction(direction.clone()),
+                follow,
                 state,
                 config,
               )?;
@@ -330,8 +572,9 @@ impl WindowManager {
 
             if let Some(name) = &args.workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::Name(name.clone()),
+                &window,
+                &WorkspaceTarget::Name(name.clone()),
+                follow,
                 state,
                 config,
               )?;
@@ -339,8 +582,9 @@ impl WindowManager {
 
             if args.next_active_workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::NextActive,
+                &window,
+                &WorkspaceTarget::NextActive,
+                follow,
                 state,
                 config,
               )?;
@@ -348,8 +592,9 @@ impl WindowManager {
 
             if args.prev_active_workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::PreviousActive,
+                &window,
+                &WorkspaceTarget::PreviousActive,
+                follow,
                 state,
                 config,
               )?;
@@ -357,8 +602,9 @@ impl WindowManager {
 
             if args.next_workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::Next,
+                &window,
+                &WorkspaceTarget::Next,
+                follow,
                 state,
                 config,
               )?;
@@ -366,8 +612,9 @@ impl WindowManager {
 
             if args.prev_workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::Previous,
+                &window,
+                &WorkspaceTarget::Previous,
+                follow,
                 state,
                 config,
               )?;
@@ -375,8 +622,9 @@ impl WindowManager {
 
             if args.recent_workspace {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::Recent,
+                &window,
+                &WorkspaceTarget::Recent,
+                follow,
                 state,
                 config,
               )?;
@@ -384,8 +632,9 @@ impl WindowManager {
 
             if args.next_active_workspace_on_monitor {
               move_window_to_workspace(
-                window.clone(),
-                WorkspaceTarget::NextActiveInMonitor,
+                &window,
+                &WorkspaceTarget::NextActiveInMonitor,
+                follow,
                 state,
                 config,
               )?;
@@ -393,8 +642,9 @@ impl WindowManager {
 
             if args.prev_active_workspace_on_monitor {
               move_window_to_workspace(
-                window,
-                WorkspaceTarget::PreviousActiveInMonitor,
+                &window,
+                &WorkspaceTarget::PreviousActiveInMonitor,
+                follow,
                 state,
                 config,
               )?;
@@ -405,6 +655,44 @@ impl WindowManager {
           _ => Ok(()),
         }
       }
+      InvokeCommand::MoveToNextSlot => {
+        match subject_container.as_window_container() {
+          Ok(window) => move_window_to_next_slot(&window, state, config),
+          _ => Ok(()),
+        }
+      }
+      InvokeCommand::MoveWindowToMonitor(args) => {
+        match subject_container.as_window_container() {
+          Ok(window) => {
+            let current_monitor =
+              window.monitor().context("No monitor.")?;
+
+            let target_monitor = match (&args.direction, args.monitor) {
+              (Some(direction), _) => {
+                state.monitor_in_direction(&current_monitor, direction)?
+              }
+              (None, Some(monitor_index)) => {
+                Some(state.active_monitor_by_index(monitor_index)?)
+              }
+              (None, None) => None,
+            };
+
+            match target_monitor {
+              Some(target_monitor) => move_window_to_monitor(
+                window,
+                &target_monitor,
+                config
+                  .value
+                  .general
+                  .follows_moved_window(args.follow, false),
+                state,
+              ),
+              None => Ok(()),
+            }
+          }
+          _ => Ok(()),
+        }
+      }
       InvokeCommand::MoveWorkspace(args) => {
         let workspace =
           subject_container.workspace().context("No workspace.")?;
@@ -412,7 +700,12 @@ impl WindowManager {
         if let Some(direction) = &args.direction {
           move_workspace_in_direction(&workspace, direction, state, config)
         } else if let Some(monitor_index) = args.monitor {
-          move_workspace_to_monitor(&workspace, monitor_index, state, config)
+          move_workspace_to_monitor(
+            &workspace,
+            monitor_index,
+            state,
+            config,
+          )
         } else {
           Ok(())
         }
@@ -437,6 +730,12 @@ impl WindowManager {
           _ => Ok(()),
         }
       }
+      InvokeCommand::PromoteToMaster => {
+        match subject_container.as_window_container() {
+          Ok(window) => promote_to_master(&window, state),
+          _ => Ok(()),
+        }
+      }
       InvokeCommand::Resize(args) => {
         match subject_container.as_window_container() {
           Ok(window) => resize_window(
@@ -586,8 +885,24 @@ impl WindowManager {
       }
       InvokeCommand::ShellExec {
         hide_window,
+        workspace,
         command,
-      } => shell_exec(&command.join(" "), *hide_window),
+      } => {
+        let process_id = shell_exec(&command.join(" "), *hide_window)?;
+
+        // Move the first window of the launched process once it's
+        // managed. Not possible if no process was launched.
+        if let (Some(workspace), Some(process_id)) =
+          (workspace, process_id)
+        {
+          state.pending_workspace_assignments.insert(
+            process_id,
+            PendingWorkspaceAssignment::new(workspace.clone(), 1),
+          );
+        }
+
+        Ok(())
+      }
       InvokeCommand::Size(args) => {
         match subject_container.as_window_container() {
           Ok(window) => set_window_size(
@@ -599,12 +914,32 @@ impl WindowManager {
           _ => Ok(()),
         }
       }
-      InvokeCommand::StartBuiltin { name } => {
-        wm_builtin::start_builtin(name)
+      InvokeCommand::StartBuiltin { name } => match name {
+        Some(name) => wm_builtin::start_builtin(name),
+        None => wm_builtin::start_all_available_parallel(),
+      },
+      InvokeCommand::RestartBuiltin { name } => {
+        wm_builtin::restart_builtin(name)
       }
       InvokeCommand::StopBuiltin { name } => {
         wm_builtin::stop_builtin(name)
       }
+      InvokeCommand::SwapAllMonitorWorkspaces { monitors } => {
+        match monitors.as_slice() {
+          [monitor_a, monitor_b] => swap_all_monitor_workspaces(
+            (*monitor_a, *monitor_b),
+            state,
+            config,
+          ),
+          _ => bail!("Expected exactly two monitor indices."),
+        }
+      }
+      InvokeCommand::SwapWindow { direction } => {
+        match subject_container.as_window_container() {
+          Ok(window) => swap_window(window, direction, state),
+          _ => Ok(()),
+        }
+      }
       InvokeCommand::ToggleFloating {
         centered,
         shown_on_top,
@@ -644,20 +979,10 @@ impl WindowManager {
         shown_on_top,
       } => match subject_container.as_window_container() {
         Ok(window) => {
-          let fullscreen_defaults =
-            &config.value.window_behavior.state_defaults.fullscreen;
-
-          let target_state =
-            WindowState::Fullscreen(FullscreenStateConfig {
-              maximized: maximized
-                .unwrap_or(fullscreen_defaults.maximized),
-              shown_on_top: shown_on_top
-                .unwrap_or(fullscreen_defaults.shown_on_top),
-            });
-
-          update_window_state(
-            window.clone(),
-            window.toggled_state(target_state, config),
+          toggle_fullscreen(
+            window,
+            *maximized,
+            *shown_on_top,
             state,
             config,
           )?;
@@ -666,6 +991,12 @@ impl WindowManager {
         }
         _ => Ok(()),
       },
+      InvokeCommand::ToggleAlwaysOnTop => {
+        match subject_container.as_window_container() {
+          Ok(window) => toggle_always_on_top(&window, state),
+          _ => Ok(()),
+        }
+      }
       InvokeCommand::ToggleMinimized => {
         match subject_container.as_window_container() {
           Ok(window) => {
@@ -681,6 +1012,15 @@ impl WindowManager {
           _ => Ok(()),
         }
       }
+      InvokeCommand::ToggleMonocle => {
+        match subject_container.as_window_container() {
+          Ok(window) => toggle_monocle(&window, state),
+          _ => Ok(()),
+        }
+      }
+      InvokeCommand::ToggleMonitorStandby { monitor } => {
+        toggle_monitor_standby(*monitor, state)
+      }
       InvokeCommand::ToggleTiling => {
         match subject_container.as_window_container() {
           Ok(window) => {
@@ -736,6 +1076,15 @@ impl WindowManager {
         Ok(())
       }
       InvokeCommand::WmReloadConfig => reload_config(state, config),
+      InvokeCommand::WmToggleFocusFollowsCursor => {
+        toggle_focus_follows_cursor(state, config)
+      }
+      InvokeCommand::WmToggleFullscreenCoversReservedSpace => {
+        toggle_fullscreen_covers_reserved_space(state, config)
+      }
+      InvokeCommand::WmToggleOpacityEffect => {
+        toggle_opacity_effect(state, config)
+      }
       InvokeCommand::WmTogglePause => {
         toggle_pause(state);
         Ok(())
@@ -1,28 +1,49 @@
-use std::time::Instant;
+use std::{
+  cell::RefCell,
+  cmp::Reverse,
+  collections::{HashMap, HashSet},
+  time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use tokio::sync::mpsc::{self};
 use tracing::warn;
 use uuid::Uuid;
 use wm_common::{
-  BindingModeConfig, Direction, Point, WindowState, WmEvent,
+  BindingModeConfig, ConfigReloadData, Direction, MonitorBinding, Point,
+  Rect, UniqueExt, WindowRuleConfig, WindowState, WmEvent,
+};
+use wm_platform::{
+  BorderOverlay, NativeMonitor, NativeWindow, Platform, ZOrder,
 };
-use wm_platform::{NativeMonitor, NativeWindow, Platform};
 
 use crate::{
+  command_metrics::CommandMetrics,
   commands::{
-    container::set_focused_descendant, general::platform_sync,
-    monitor::add_monitor, window::manage_window,
+    container::set_focused_descendant,
+    general::{platform_sync, restore_session},
+    monitor::add_monitor,
+    window::manage_window,
+    CommandError,
   },
   models::{
-    Container, Monitor, RootContainer, WindowContainer, Workspace,
-    WorkspaceTarget,
+    Container, DirectionContainer, DropPosition, DropTarget, EdgeDrag,
+    FocusHistory, Monitor, MouseDrag, NonTilingWindow, PendingClose,
+    PendingWorkspaceAssignment, RootContainer, SwallowedWindow,
+    TilingContainer, WindowContainer, Workspace, WorkspaceTarget,
   },
   pending_sync::PendingSync,
+  session::Session,
   traits::{CommonGetters, PositionGetters, WindowGetters},
   user_config::UserConfig,
 };
 
+/// Time that a window's title must stay unchanged before window rules
+/// are re-evaluated for it, such that rapid title changes (e.g. of
+/// terminals) don't thrash.
+const TITLE_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct WmState {
   /// Root node of the container tree. Monitors are the children of the
   /// root node, followed by workspaces, then split containers/windows.
@@ -30,12 +51,20 @@ pub struct WmState {
 
   pub pending_sync: PendingSync,
 
+  /// Timings of commands, if enabled via `general.metrics`.
+  pub command_metrics: CommandMetrics,
+
   /// Name of the most recently focused workspace.
   ///
   /// Used for the `general.toggle_workspace_on_refocus` option on
   /// workspace focus.
   pub recent_workspace_name: Option<String>,
 
+  /// Managed windows in the order they were last focused.
+  ///
+  /// Used for the `focus-last` and `focus-nth-last` commands.
+  pub focus_history: FocusHistory,
+
   /// The previously focused window that had focus effects applied.
   ///
   /// Used to efficiently update window effects by only removing focus
@@ -55,12 +84,99 @@ pub struct WmState {
   /// `ignore` command.
   pub ignored_windows: Vec<NativeWindow>,
 
+  /// Windows that were ignored via the `ignore-window` command. Unlike
+  /// other ignored windows, these aren't managed again when shown, until
+  /// the `manage-window` command is used.
+  pub runtime_ignored_windows: Vec<NativeWindow>,
+
+  /// Windows that are hidden while a window launched from them takes
+  /// their place, keyed by the ID of the swallowing window.
+  pub swallowed_windows: HashMap<Uuid, SwallowedWindow>,
+
+  /// Last position of the cursor within each window when focus left it,
+  /// relative to the window's size (0.0 to 1.0 on both axes).
+  pub cursor_positions: HashMap<Uuid, (f32, f32)>,
+
+  /// Position of each window from before it was managed. Used to restore
+  /// windows that are ignored via the `ignore-window` command.
+  pub initial_frame_positions: HashMap<Uuid, Rect>,
+
+  /// Windows whose title has changed since window rules were last
+  /// evaluated for them, along with when the title last changed.
+  pub pending_title_changes: HashMap<Uuid, Instant>,
+
+  /// Title change rules that each window matched when they were last
+  /// evaluated for it.
+  pub title_rule_matches: HashMap<Uuid, Vec<WindowRuleConfig>>,
+
+  /// Whether each window has its title bar removed while tiling, as last
+  /// decided from the user config. Avoids matching window rules against
+  /// every window on each redraw.
+  pub title_bar_removals: HashMap<Uuid, bool>,
+
+  /// Summary of the changes applied by the last config reload. Taken
+  /// when responding to the IPC client that requested the reload.
+  pub last_config_reload: Option<ConfigReloadData>,
+
+  /// Windows that hid themselves and are kept as minimized on their
+  /// workspace until they're shown again. Enabled via
+  /// `window_behavior.keep_hidden_windows`.
+  pub self_hidden_windows: HashSet<Uuid>,
+
+  /// Windows that were asked to close via the `close` command and haven't
+  /// closed yet.
+  pub pending_closes: HashMap<Uuid, PendingClose>,
+
+  /// Processes whose new windows get moved to a target workspace, keyed
+  /// by process ID.
+  pub pending_workspace_assignments:
+    HashMap<u32, PendingWorkspaceAssignment>,
+
+  /// Overlays drawing the borders of windows whose border can't be drawn
+  /// via DWM.
+  pub border_overlays: HashMap<Uuid, BorderOverlay>,
+
+  /// Workspaces whose gaps were removed via `gaps.smart_gaps` as of the
+  /// last platform sync.
+  pub smart_gaps_workspaces: HashSet<Uuid>,
+
+  /// Area highlighted as where a dragged tiling window gets inserted on
+  /// drop. Enabled via `window_behavior.drop_hint`.
+  pub drop_hint: Option<Rect>,
+
+  /// Overlay drawing the drop hint. Created the first time a drop hint
+  /// is shown.
+  pub drop_hint_overlay: Option<BorderOverlay>,
+
+  /// Overlays marking where the next tiling window gets inserted, keyed
+  /// by the ID of the workspace whose insertion hint they show. Only
+  /// displayed workspaces have one.
+  pub insertion_hint_overlays: HashMap<Uuid, BorderOverlay>,
+
   /// Whether the WM is paused.
   pub is_paused: bool,
 
   /// Whether the OS focused window is the same as the WM focused window.
   pub is_focus_synced: bool,
 
+  /// Whether the WM's focused container was last focused by focus follows
+  /// cursor.
+  pub is_focus_from_cursor: bool,
+
+  /// Container under the cursor that focus follows cursor is waiting to
+  /// focus, along with when the cursor first moved over it.
+  pub cursor_focus_candidate: Option<(Uuid, Instant)>,
+
+  /// Whether left or right-click was pressed as of the last mouse event.
+  pub is_mouse_down: bool,
+
+  /// Edge between two tiling containers that is currently being dragged
+  /// to resize them.
+  pub edge_drag: Option<EdgeDrag>,
+
+  /// Window that is currently being moved or resized via a mouse binding.
+  pub mouse_drag: Option<MouseDrag>,
+
   /// Whether the initial state has been populated.
   has_initialized: bool,
 
@@ -69,6 +185,9 @@ pub struct WmState {
 
   /// Sender for gracefully shutting down the WM.
   exit_tx: mpsc::UnboundedSender<()>,
+
+  /// Events held back while running `with_event_batch`.
+  event_batch: RefCell<Option<Vec<WmEvent>>>,
 }
 
 impl WmState {
@@ -79,16 +198,40 @@ impl WmState {
     Self {
       root_container: RootContainer::new(),
       pending_sync: PendingSync::default(),
+      command_metrics: CommandMetrics::default(),
       prev_effects_window: None,
       recent_workspace_name: None,
+      focus_history: FocusHistory::default(),
       unmanaged_or_minimized_timestamp: None,
       binding_modes: Vec::new(),
       ignored_windows: Vec::new(),
+      runtime_ignored_windows: Vec::new(),
+      swallowed_windows: HashMap::new(),
+      cursor_positions: HashMap::new(),
+      initial_frame_positions: HashMap::new(),
+      pending_title_changes: HashMap::new(),
+      title_rule_matches: HashMap::new(),
+      title_bar_removals: HashMap::new(),
+      last_config_reload: None,
+      self_hidden_windows: HashSet::new(),
+      pending_closes: HashMap::new(),
+      pending_workspace_assignments: HashMap::new(),
+      border_overlays: HashMap::new(),
+      smart_gaps_workspaces: HashSet::new(),
+      drop_hint: None,
+      drop_hint_overlay: None,
+      insertion_hint_overlays: HashMap::new(),
       is_paused: false,
       is_focus_synced: false,
+      is_focus_from_cursor: false,
+      cursor_focus_candidate: None,
+      is_mouse_down: false,
+      edge_drag: None,
+      mouse_drag: None,
       has_initialized: false,
       event_tx,
       exit_tx,
+      event_batch: RefCell::new(None),
     }
   }
 
@@ -112,7 +255,7 @@ impl WmState {
     for native_window in Platform::manageable_windows()?.into_iter().rev()
     {
       let nearest_workspace = self
-        .nearest_monitor(&native_window)
+        .window_monitor(&native_window)
         .and_then(|m| m.displayed_workspace());
 
       if let Some(workspace) = nearest_workspace {
@@ -125,11 +268,34 @@ impl WmState {
       }
     }
 
+    if config.value.general.restore_session {
+      match Session::default_path().and_then(|path| Session::take(&path)) {
+        Ok(Some(session)) => restore_session(&session, self, config),
+        Ok(None) => {}
+        Err(err) => warn!("Failed to read session: {:?}", err),
+      }
+    }
+
+    // Windows might've been moved to hidden workspaces when restoring the
+    // session, so only focus a window that is displayed.
+    let is_displayed = |window: &WindowContainer| {
+      window
+        .workspace()
+        .is_some_and(|workspace| workspace.is_displayed())
+    };
+
     let container_to_focus = self
       .window_from_native(&foreground_window)
+      .filter(is_displayed)
       .map(|c| c.as_container())
-      .or(self.windows().pop().map(Into::into))
-      .or(self.workspaces().pop().map(Into::into))
+      .or_else(|| {
+        self
+          .windows()
+          .into_iter()
+          .rfind(is_displayed)
+          .map(Into::into)
+      })
+      .or_else(|| self.workspaces().pop().map(Into::into))
       .context("Failed to get container to focus.")?;
 
     set_focused_descendant(&container_to_focus, None);
@@ -150,21 +316,101 @@ impl WmState {
     Ok(())
   }
 
+  /// Whether the initial state has been populated.
+  pub fn has_initialized(&self) -> bool {
+    self.has_initialized
+  }
+
+  /// Marks the state as populated without reading any monitors or
+  /// windows, such that events get emitted.
+  #[cfg(any(test, feature = "test_utils"))]
+  pub fn set_initialized(&mut self) {
+    self.has_initialized = true;
+  }
+
   pub fn monitors(&self) -> Vec<Monitor> {
     self.root_container.monitors()
   }
 
+  /// Iterates over the monitors without allocating. Prefer this over
+  /// `monitors` when only a single lookup is needed.
+  pub fn monitors_iter(&self) -> impl Iterator<Item = Monitor> + '_ {
+    self.root_container.monitors_iter()
+  }
+
+  /// Gets the monitor at the given index.
+  pub fn monitor_by_index(&self, index: usize) -> Option<Monitor> {
+    self.monitors_iter().nth(index)
+  }
+
+  /// Gets the monitor at the given index for commands to target, which
+  /// excludes monitors in standby.
+  pub fn active_monitor_by_index(
+    &self,
+    index: usize,
+  ) -> Result<Monitor, CommandError> {
+    let monitor = self.monitor_by_index(index).ok_or_else(|| {
+      CommandError::MonitorIndexOutOfRange {
+        requested: index,
+        available: self.monitors().len(),
+      }
+    })?;
+
+    if monitor.is_standby() {
+      return Err(CommandError::MonitorInStandby { index });
+    }
+
+    Ok(monitor)
+  }
+
+  /// Gets the connected monitors of a workspace's monitor binding, in
+  /// order of preference. Monitors in standby are excluded.
+  pub fn bound_monitors(&self, binding: &MonitorBinding) -> Vec<Monitor> {
+    let monitors = self
+      .monitors_iter()
+      .filter(|monitor| !monitor.is_standby())
+      .collect::<Vec<_>>();
+
+    binding
+      .0
+      .iter()
+      .filter_map(|selector| {
+        monitors
+          .iter()
+          .find(|monitor| monitor.is_selected_by(selector))
+          .cloned()
+      })
+      .collect()
+  }
+
+  /// Gets the most preferred connected monitor of a workspace's monitor
+  /// binding.
+  pub fn bound_monitor(
+    &self,
+    binding: &MonitorBinding,
+  ) -> Option<Monitor> {
+    self.bound_monitors(binding).into_iter().next()
+  }
+
   pub fn workspaces(&self) -> Vec<Workspace> {
     self
-      .monitors()
-      .iter()
-      .flat_map(Monitor::workspaces)
+      .monitors_iter()
+      .flat_map(|monitor| monitor.workspaces())
       .collect()
   }
 
-  /// Gets workspaces sorted by their position in the user config.
-  pub fn sorted_workspaces(&self, config: &UserConfig) -> Vec<Workspace> {
-    let mut workspaces = self.workspaces();
+  /// Gets workspaces sorted by their position in the user config,
+  /// excluding those on monitors in standby.
+  fn sorted_displayable_workspaces(
+    &self,
+    config: &UserConfig,
+  ) -> Vec<Workspace> {
+    let mut workspaces = self
+      .monitors_iter()
+      .filter(|monitor| !monitor.is_standby())
+      .flat_map(|monitor| monitor.workspaces())
+      .collect::<Vec<_>>();
+
     config.sort_workspaces(&mut workspaces);
     workspaces
   }
@@ -178,16 +424,50 @@ impl WmState {
   }
 
   /// Gets the monitor that encompasses the largest portion of a given
-  /// window.
+  /// window, as reported by the OS.
   ///
   /// Defaults to the first monitor if the nearest monitor is invalid.
-  pub fn nearest_monitor(
+  pub fn window_monitor(
     &self,
     native_window: &NativeWindow,
   ) -> Option<Monitor> {
     self
       .monitor_from_native(&Platform::nearest_monitor(native_window))
-      .or(self.monitors().first().cloned())
+      .or_else(|| self.primary_monitor())
+  }
+
+  /// Whether the given rect doesn't overlap any monitor, e.g. the frame
+  /// of a window that was created or left off-screen.
+  ///
+  /// Such windows aren't assigned to any monitor by position, and are
+  /// placed on the primary monitor.
+  pub fn is_unassigned_rect(&self, rect: &Rect) -> bool {
+    !self.monitors_iter().any(|monitor| {
+      monitor
+        .to_rect()
+        .is_ok_and(|monitor_rect| monitor_rect.intersection_area(rect) > 0)
+    })
+  }
+
+  /// Gets the OS primary monitor.
+  ///
+  /// Falls back to the first monitor if none are flagged as primary (e.g.
+  /// while display settings are mid-change).
+  pub fn primary_monitor(&self) -> Option<Monitor> {
+    self
+      .monitors_iter()
+      .find(Monitor::is_primary)
+      .or_else(|| self.monitor_by_index(0))
+  }
+
+  /// Gets the monitor of the focused container.
+  ///
+  /// Returns `None` if nothing is focused (e.g. while monitors are being
+  /// added or removed).
+  pub fn focused_monitor(&self) -> Option<Monitor> {
+    self
+      .focused_container()
+      .and_then(|focused| focused.monitor())
   }
 
   /// Gets monitor that corresponds to the given `NativeMonitor`.
@@ -196,12 +476,12 @@ impl WmState {
     native_monitor: &NativeMonitor,
   ) -> Option<Monitor> {
     self
-      .monitors()
-      .into_iter()
+      .monitors_iter()
       .find(|monitor| monitor.native() == *native_monitor)
   }
 
-  /// Gets the closest monitor in a given direction.
+  /// Gets the closest monitor in a given direction, skipping monitors in
+  /// standby.
   ///
   /// Uses i3wm's algorithm for finding best guess.
   pub fn monitor_in_direction(
@@ -213,8 +493,8 @@ impl WmState {
 
     // Create a tuple of monitors and their rect.
     let monitors_with_rect = self
-      .monitors()
-      .into_iter()
+      .monitors_iter()
+      .filter(|monitor| !monitor.is_standby())
       .map(|monitor| {
         let rect = monitor.native().rect()?.clone();
         anyhow::Ok((monitor, rect))
@@ -304,7 +584,7 @@ impl WmState {
           .and_then(|name| self.workspace_by_name(name)),
       ),
       WorkspaceTarget::NextActive => {
-        let active_workspaces = self.sorted_workspaces(config);
+        let active_workspaces = self.sorted_displayable_workspaces(config);
         let origin_index = active_workspaces
           .iter()
           .position(|workspace| workspace.id() == origin_workspace.id())
@@ -320,7 +600,7 @@ impl WmState {
         )
       }
       WorkspaceTarget::PreviousActive => {
-        let active_workspaces = self.sorted_workspaces(config);
+        let active_workspaces = self.sorted_displayable_workspaces(config);
         let origin_index = active_workspaces
           .iter()
           .position(|workspace| workspace.id() == origin_workspace.id())
@@ -460,6 +740,9 @@ impl WmState {
       .values()
       .flat_map(CommonGetters::self_and_descendants)
       .filter(|container| !container.is_detached())
+      // A window is reached more than once if both it and an ancestor
+      // are queued.
+      .unique_by(CommonGetters::id)
       .filter_map(|container| container.try_into().ok())
       .collect()
   }
@@ -477,6 +760,41 @@ impl WmState {
   /// from being emitted via IPC server before the initial state is
   /// prepared.
   pub fn emit_event(&self, event: WmEvent) {
+    if let Some(batch) = self.event_batch.borrow_mut().as_mut() {
+      batch.push(event);
+      return;
+    }
+
+    self.send_event(event);
+  }
+
+  /// Runs the given operation while holding back emitted events, then
+  /// emits them coalesced via `coalesce_events`. This way, subscribers
+  /// see the final state of a multi-step operation rather than its
+  /// intermediate churn.
+  ///
+  /// Nested batches are merged into the outermost one.
+  pub fn with_event_batch<T>(
+    &mut self,
+    operation: impl FnOnce(&mut Self) -> T,
+  ) -> T {
+    if self.event_batch.borrow().is_some() {
+      return operation(self);
+    }
+
+    *self.event_batch.borrow_mut() = Some(Vec::new());
+    let result = operation(self);
+
+    let events = self.event_batch.borrow_mut().take().unwrap_or_default();
+
+    for event in coalesce_events(events) {
+      self.send_event(event);
+    }
+
+    result
+  }
+
+  fn send_event(&self, event: WmEvent) {
     if self.has_initialized
       && (!self.is_paused || matches!(event, WmEvent::PauseChanged { .. }))
     {
@@ -499,6 +817,107 @@ impl WmState {
       .find(|container| container.id() == id)
   }
 
+  /// Gets when the pending focus follows cursor candidate should be
+  /// focused, if there is one.
+  pub fn cursor_focus_deadline(
+    &self,
+    config: &UserConfig,
+  ) -> Option<Instant> {
+    let delay = Duration::from_millis(u64::from(
+      config.value.general.focus_follows_cursor_delay_ms,
+    ));
+
+    self
+      .cursor_focus_candidate
+      .map(|(_, hovered_since)| hovered_since + delay)
+  }
+
+  /// When window rules are next due to be re-evaluated for a window whose
+  /// title has changed.
+  pub fn title_change_deadline(&self) -> Option<Instant> {
+    self
+      .pending_title_changes
+      .values()
+      .min()
+      .map(|changed_at| *changed_at + TITLE_CHANGE_DEBOUNCE)
+  }
+
+  /// Gets the earliest time at which a window that was closed with
+  /// `--kill` should have its process terminated.
+  pub fn close_deadline(&self, config: &UserConfig) -> Option<Instant> {
+    let timeout = Duration::from_millis(u64::from(
+      config.value.window_behavior.force_close.timeout_ms,
+    ));
+
+    self
+      .pending_closes
+      .values()
+      .filter(|pending_close| pending_close.kill_on_timeout)
+      .map(|pending_close| pending_close.requested_at + timeout)
+      .min()
+  }
+
+  /// Removes and returns the windows closed with `--kill` that haven't
+  /// closed within `window_behavior.force_close.timeout_ms`.
+  pub fn take_expired_closes(
+    &mut self,
+    config: &UserConfig,
+  ) -> Vec<(Uuid, PendingClose)> {
+    let timeout = Duration::from_millis(u64::from(
+      config.value.window_behavior.force_close.timeout_ms,
+    ));
+
+    let expired_ids = self
+      .pending_closes
+      .iter()
+      .filter(|(_, pending_close)| {
+        pending_close.kill_on_timeout
+          && pending_close.has_timed_out(timeout)
+      })
+      .map(|(id, _)| *id)
+      .collect::<Vec<_>>();
+
+    expired_ids
+      .into_iter()
+      .filter_map(|id| {
+        self
+          .pending_closes
+          .remove(&id)
+          .map(|pending_close| (id, pending_close))
+      })
+      .collect()
+  }
+
+  /// Removes and returns the windows whose title hasn't changed for at
+  /// least `TITLE_CHANGE_DEBOUNCE`.
+  pub fn take_settled_title_changes(&mut self) -> Vec<Uuid> {
+    let now = Instant::now();
+
+    let settled_ids = self
+      .pending_title_changes
+      .iter()
+      .filter(|(_, changed_at)| {
+        now >= **changed_at + TITLE_CHANGE_DEBOUNCE
+      })
+      .map(|(id, _)| *id)
+      .collect::<Vec<_>>();
+
+    for id in &settled_ids {
+      self.pending_title_changes.remove(id);
+    }
+
+    settled_ids
+  }
+
+  /// Whether the window is hidden because it's been swallowed by another
+  /// window.
+  pub fn is_swallowed(&self, native_window: &NativeWindow) -> bool {
+    self
+      .swallowed_windows
+      .values()
+      .any(|swallowed| *swallowed.window.native() == *native_window)
+  }
+
   /// Gets container to focus after the given window is unmanaged,
   /// minimized, or moved to another workspace.
   pub fn focus_target_after_removal(
@@ -547,7 +966,8 @@ impl WmState {
       .or(Some(workspace.into()))
   }
 
-  /// Returns all containers that contain the given point.
+  /// Returns all containers that contain the given point. Containers
+  /// hidden in a stacking or tabbed container or by monocle are skipped.
   #[allow(clippy::unused_self)]
   pub fn containers_at_point(
     &self,
@@ -557,39 +977,440 @@ impl WmState {
     origin_container
       .descendants()
       .filter(|descendant| {
-        descendant
-          .to_rect()
-          .map(|rect| rect.contains_point(point))
-          .unwrap_or(false)
+        descendant.contains_point(point.x, point.y)
+          && !descendant.is_hidden_in_stack()
+          && !descendant.is_hidden_by_monocle()
       })
       .collect()
   }
 
-  /// Returns the monitor that contains the given point.
-  pub fn monitor_at_point(&self, point: &Point) -> Option<Monitor> {
-    self
-      .monitors()
+  /// Gets where a dragged tiling window gets inserted if dropped with the
+  /// cursor at the given point.
+  ///
+  /// The target is within the workspace of the monitor under the cursor,
+  /// falling back to the window's current workspace.
+  pub fn drop_target(
+    &self,
+    moved_window: &NonTilingWindow,
+    point: &Point,
+  ) -> anyhow::Result<DropTarget> {
+    let point_workspace = self
+      .monitor_at(point)
+      .and_then(|monitor| monitor.displayed_workspace())
+      .or_else(|| moved_window.workspace())
+      .context("Couldn't find workspace for window drop.")?;
+
+    // Get the workspace, split containers, and other windows under the
+    // dragged window.
+    let containers_at_point = self
+      .containers_at_point(&point_workspace.clone().into(), point)
+      .into_iter()
+      .filter(|container| container.id() != moved_window.id());
+
+    // Get the deepest direction container under the dragged window.
+    let target_parent: DirectionContainer = containers_at_point
+      .filter_map(|container| container.as_direction_container().ok())
+      .fold(point_workspace.into(), |acc, container| {
+        if container.ancestors().count() > acc.ancestors().count() {
+          container
+        } else {
+          acc
+        }
+      });
+
+    let nearest_container = target_parent
+      .children()
+      .into_iter()
+      .filter(|container| container.id() != moved_window.id())
+      .filter_map(|container| container.as_tiling_container().ok())
+      .try_fold(
+        None,
+        |acc: Option<TilingContainer>, container| match acc {
+          Some(acc) => {
+            let is_nearer = acc.to_rect()?.distance_to_point(point)
+              < container.to_rect()?.distance_to_point(point);
+
+            anyhow::Ok(Some(if is_nearer { acc } else { container }))
+          }
+          None => Ok(Some(container)),
+        },
+      )?;
+
+    let nearest = nearest_container
+      .map(|container| {
+        let drop_position =
+          DropPosition::from_point(point, &container.to_rect()?);
+
+        anyhow::Ok((container, drop_position))
+      })
+      .transpose()?;
+
+    Ok(DropTarget {
+      target_parent,
+      nearest,
+    })
+  }
+
+  /// Gets the monitor whose working area contains the given point.
+  ///
+  /// Points outside of every working area (e.g. over the taskbar) resolve
+  /// to the monitor whose bounds contain the point. If several monitors
+  /// match, the one with the lowest index is returned.
+  pub fn monitor_at(&self, point: &Point) -> Option<Monitor> {
+    let find_containing = |get_rect: fn(&Monitor) -> Option<Rect>| {
+      self.monitors_iter().find(|monitor| {
+        get_rect(monitor).is_some_and(|rect| rect.contains_point(point))
+      })
+    };
+
+    find_containing(|monitor| monitor.working_rect().ok())
+      .or_else(|| find_containing(|monitor| monitor.to_rect().ok()))
+  }
+
+  /// Gets the monitor whose working area has the largest intersection
+  /// with the given rect.
+  ///
+  /// If the rect doesn't intersect any working area, the monitor closest
+  /// to the rect's center is returned. Ties resolve to the monitor with
+  /// the lowest index. Monitors in standby are skipped.
+  pub fn nearest_monitor(&self, rect: &Rect) -> Option<Monitor> {
+    let monitors = self
+      .monitors_iter()
+      .filter(|monitor| !monitor.is_standby())
+      .filter_map(|monitor| {
+        let working_rect = monitor.working_rect().ok()?;
+        Some((monitor, working_rect))
+      })
+      .collect::<Vec<_>>();
+
+    // `min_by_key` returns the first of equal elements, which keeps the
+    // lowest index on ties.
+    let largest_intersection = monitors
       .iter()
-      .find(|monitor| {
-        monitor
-          .to_rect()
-          .map(|rect| rect.contains_point(point))
-          .unwrap_or(false)
+      .map(|(monitor, working_rect)| {
+        (monitor, working_rect.intersection_area(rect))
       })
-      .cloned()
+      .filter(|(_, area)| *area > 0)
+      .min_by_key(|(_, area)| Reverse(*area))
+      .map(|(monitor, _)| monitor.clone());
+
+    largest_intersection.or_else(|| {
+      let center = rect.center_point();
+
+      monitors
+        .iter()
+        .min_by_key(|(_, working_rect)| {
+          working_rect.squared_distance_to_point(&center)
+        })
+        .map(|(monitor, _)| monitor.clone())
+    })
   }
 }
 
 impl Drop for WmState {
   fn drop(&mut self) {
+    // Windows that hid themselves are left hidden.
     let managed_windows = self
       .windows()
       .into_iter()
+      .filter(|window| !self.self_hidden_windows.contains(&window.id()))
       .map(|window| window.native().clone())
+      .chain(
+        self
+          .swallowed_windows
+          .values()
+          .map(|swallowed| swallowed.window.native().clone()),
+      )
       .collect::<Vec<_>>();
 
     for window in managed_windows {
       window.cleanup();
     }
+
+    // Don't leave any windows that were toggled on top above all others.
+    for window in self.windows() {
+      if window.is_shown_on_top() {
+        _ = window.native().set_z_order(&ZOrder::Normal);
+      }
+    }
+  }
+}
+
+/// Coalesces a batch of events into the ones needed to reach the same
+/// final state.
+///
+/// Workspaces that are both activated and deactivated within the batch
+/// never existed as far as subscribers are concerned, so all of their
+/// events are dropped. Of multiple updates to a workspace, only the last
+/// one is kept, and none are kept if the workspace is deactivated
+/// afterwards.
+fn coalesce_events(events: Vec<WmEvent>) -> Vec<WmEvent> {
+  let mut activated_ids = HashSet::new();
+  let mut transient_ids = HashSet::new();
+  let mut deactivated_ids = HashSet::new();
+
+  for event in &events {
+    match event {
+      WmEvent::WorkspaceActivated {
+        activated_workspace,
+      } => {
+        activated_ids.insert(activated_workspace.id());
+      }
+      WmEvent::WorkspaceDeactivated { deactivated_id, .. } => {
+        if activated_ids.contains(deactivated_id) {
+          transient_ids.insert(*deactivated_id);
+        }
+
+        deactivated_ids.insert(*deactivated_id);
+      }
+      _ => {}
+    }
+  }
+
+  let mut seen_update_ids = HashSet::new();
+
+  // Iterate in reverse to find the last update to each workspace.
+  let mut coalesced = events
+    .into_iter()
+    .rev()
+    .filter(|event| match event {
+      WmEvent::WorkspaceActivated {
+        activated_workspace,
+      } => !transient_ids.contains(&activated_workspace.id()),
+      WmEvent::WorkspaceDeactivated { deactivated_id, .. } => {
+        !transient_ids.contains(deactivated_id)
+      }
+      WmEvent::WorkspaceUpdated { updated_workspace } => {
+        let id = updated_workspace.id();
+        !deactivated_ids.contains(&id) && seen_update_ids.insert(id)
+      }
+      _ => true,
+    })
+    .collect::<Vec<_>>();
+
+  coalesced.reverse();
+  coalesced
+}
+
+#[cfg(test)]
+mod tests {
+  use wm_common::{
+    ContainerDto, ParsedConfig, TilingDirection, WorkspaceDto,
+    WorkspaceLayout,
+  };
+
+  use super::*;
+  use crate::test_utils;
+
+  fn state() -> WmState {
+    WmState::new(mpsc::unbounded_channel().0, mpsc::unbounded_channel().0)
+  }
+
+  fn pending_close(age: Duration, kill_on_timeout: bool) -> PendingClose {
+    PendingClose {
+      requested_at: Instant::now().checked_sub(age).unwrap(),
+      kill_on_timeout,
+      force_all: false,
+    }
+  }
+
+  fn workspace_dto(id: Uuid) -> ContainerDto {
+    ContainerDto::Workspace(WorkspaceDto {
+      id,
+      name: id.to_string(),
+      display_name: None,
+      parent_id: None,
+      children: Vec::new(),
+      child_focus_order: Vec::new(),
+      has_focus: false,
+      is_displayed: true,
+      width: 1920,
+      height: 1080,
+      x: 0,
+      y: 0,
+      tiling_direction: TilingDirection::Horizontal,
+      monocle_window_id: None,
+      layout: WorkspaceLayout::Manual,
+      master_ratio: 0.55,
+      master_count: 1,
+      has_urgent_window: false,
+      insertion_hint: None,
+    })
+  }
+
+  fn activated(id: Uuid) -> WmEvent {
+    WmEvent::WorkspaceActivated {
+      activated_workspace: workspace_dto(id),
+    }
+  }
+
+  fn deactivated(id: Uuid) -> WmEvent {
+    WmEvent::WorkspaceDeactivated {
+      deactivated_id: id,
+      deactivated_name: id.to_string(),
+    }
+  }
+
+  fn updated(id: Uuid) -> WmEvent {
+    WmEvent::WorkspaceUpdated {
+      updated_workspace: workspace_dto(id),
+    }
+  }
+
+  #[test]
+  fn simple_move_emits_each_change_once() {
+    let moved_id = Uuid::new_v4();
+    let filler_id = Uuid::new_v4();
+    let empty_id = Uuid::new_v4();
+
+    // Moving the only workspace off of a monitor activates a filler
+    // workspace on it, and destroys an empty workspace on the target.
+    let events = coalesce_events(vec![
+      updated(moved_id),
+      activated(filler_id),
+      deactivated(empty_id),
+      updated(moved_id),
+    ]);
+
+    assert_eq!(events.len(), 3);
+    assert!(matches!(
+      events.as_slice(),
+      [
+        WmEvent::WorkspaceActivated { .. },
+        WmEvent::WorkspaceDeactivated { .. },
+        WmEvent::WorkspaceUpdated { .. },
+      ]
+    ));
+  }
+
+  #[test]
+  fn drops_workspaces_that_only_existed_within_batch() {
+    let moved_id = Uuid::new_v4();
+    let filler_id = Uuid::new_v4();
+
+    let events = coalesce_events(vec![
+      activated(filler_id),
+      updated(filler_id),
+      updated(moved_id),
+      deactivated(filler_id),
+    ]);
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+      &events[0],
+      WmEvent::WorkspaceUpdated { updated_workspace }
+        if updated_workspace.id() == moved_id
+    ));
+  }
+
+  #[test]
+  fn takes_expired_closes_with_kill() {
+    let mut state = state();
+    let config = UserConfig::from_value(ParsedConfig::default());
+
+    let expired_id = Uuid::new_v4();
+    let closing_id = Uuid::new_v4();
+    let no_kill_id = Uuid::new_v4();
+
+    state
+      .pending_closes
+      .insert(expired_id, pending_close(Duration::from_secs(10), true));
+    state
+      .pending_closes
+      .insert(closing_id, pending_close(Duration::from_secs(1), true));
+    state
+      .pending_closes
+      .insert(no_kill_id, pending_close(Duration::from_secs(10), false));
+
+    // The deadline is of the close that has already timed out.
+    assert!(state
+      .close_deadline(&config)
+      .is_some_and(|deadline| deadline <= Instant::now()));
+
+    let expired_ids = state
+      .take_expired_closes(&config)
+      .into_iter()
+      .map(|(id, _)| id)
+      .collect::<Vec<_>>();
+
+    assert_eq!(expired_ids, [expired_id]);
+    assert!(state
+      .close_deadline(&config)
+      .is_some_and(|deadline| deadline > Instant::now()));
+
+    // Closes without `--kill` are kept until the window is closed again.
+    assert!(state.pending_closes.contains_key(&no_kill_id));
+  }
+
+  #[test]
+  fn takes_title_changes_once_settled() {
+    let mut state = state();
+
+    let settled_id = Uuid::new_v4();
+    let changing_id = Uuid::new_v4();
+
+    state.pending_title_changes.insert(
+      settled_id,
+      Instant::now().checked_sub(Duration::from_secs(1)).unwrap(),
+    );
+    state
+      .pending_title_changes
+      .insert(changing_id, Instant::now());
+
+    assert!(state
+      .title_change_deadline()
+      .is_some_and(|deadline| deadline <= Instant::now()));
+
+    assert_eq!(state.take_settled_title_changes(), [settled_id]);
+
+    // The title that changed just now is taken once it settles.
+    assert!(state
+      .title_change_deadline()
+      .is_some_and(|deadline| deadline > Instant::now()));
+    assert!(state.take_settled_title_changes().is_empty());
+    assert!(state.pending_title_changes.contains_key(&changing_id));
+  }
+
+  #[test]
+  fn redraws_windows_once_when_ancestors_are_queued() {
+    let (mut state, _event_rx) = test_utils::state();
+
+    let monitor = test_utils::monitor(&state, 1, 0);
+    let workspace = test_utils::workspace(&monitor, "1");
+    let first = test_utils::tiling_window(&workspace.clone().into(), 1);
+    let second = test_utils::tiling_window(&workspace.clone().into(), 2);
+
+    state
+      .pending_sync
+      .queue_container_to_redraw(first.clone())
+      .queue_container_to_redraw(workspace)
+      .queue_container_to_redraw(monitor);
+
+    let mut ids = state
+      .windows_to_redraw()
+      .iter()
+      .map(CommonGetters::id)
+      .collect::<Vec<_>>();
+    ids.sort();
+
+    let mut expected_ids = vec![first.id(), second.id()];
+    expected_ids.sort();
+
+    assert_eq!(ids, expected_ids);
+  }
+  #[test]
+  fn unassigned_rects_go_to_primary_monitor() {
+    let (state, _event_rx) = test_utils::state();
+
+    // The primary monitor isn't the first one.
+    test_utils::monitor(&state, 1, -1920);
+    let primary = test_utils::monitor(&state, 2, 0);
+
+    assert!(state.is_unassigned_rect(&Rect::from_xy(-5000, 0, 400, 300)));
+    assert!(!state.is_unassigned_rect(&Rect::from_xy(-2000, 0, 400, 300)));
+    assert_eq!(
+      state.primary_monitor().map(|m| m.id()),
+      Some(primary.id())
+    );
   }
 }